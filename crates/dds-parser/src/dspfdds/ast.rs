@@ -0,0 +1,236 @@
+use super::Entry;
+use crate::field::FieldResult;
+use crate::line::{ContinuationLine, DDSLine};
+use crate::meta::{IHighlight, ISpan, Span};
+use crate::pfdds::{self, detail, resolve, Field, FileEntry, RecordFormat, References, Source, CST};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+
+// A conditioning indicator, ex: N03 is "indicator 03 is off"
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Indicator {
+    pub number: u8,
+    pub not: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AST {
+    pub entries: Vec<Entry>,
+    // row -> the indicators in columns 8-16 of that row, they condition what's on it
+    pub indicators: BTreeMap<usize, Vec<Indicator>>,
+}
+
+enum Phase {
+    FileEntry,
+    Main,
+}
+
+struct ParserState {
+    idx: usize,
+    phase: Phase,
+}
+
+struct Parser {
+    state: RefCell<ParserState>,
+    input: Vec<DDSLine>,
+}
+
+fn peek_n(parser: &Parser, n: usize) -> Option<&DDSLine> {
+    let idx = parser.state.borrow().idx;
+    parser.input.get(idx + n)
+}
+
+fn read_line(parser: &Parser) -> &DDSLine {
+    let out = peek_n(parser, 0).expect("read_line() requires a length check first");
+    parser.state.borrow_mut().idx += 1;
+    out
+}
+
+fn read_continuations(parser: &Parser) -> Vec<&ContinuationLine> {
+    let mut continuations = vec![];
+    while let Some(DDSLine::Continuation(peeked)) = peek_n(parser, 0) {
+        let _ = read_line(parser);
+        continuations.push(peeked);
+    }
+    continuations
+}
+
+fn read_entry(parser: &Parser) -> Option<Entry> {
+    let file_entry = matches!(parser.state.borrow().phase, Phase::FileEntry);
+    let entry = match read_line(parser) {
+        DDSLine::Continuation(cur) => {
+            let mut continuations = vec![cur];
+            continuations.append(&mut read_continuations(parser));
+            match file_entry {
+                true => Entry::FE(FileEntry::from(continuations)),
+                // nothing left to continue
+                false => return next_entry(parser),
+            }
+        }
+        DDSLine::RecordFormat(cur) => {
+            parser.state.borrow_mut().phase = Phase::Main;
+            Entry::R(RecordFormat::from((cur, read_continuations(parser))))
+        }
+        DDSLine::Field(cur) => Entry::F(Field::from((cur, read_continuations(parser)))),
+        DDSLine::Constant(cur) => Entry::C(Field::from((cur, read_continuations(parser)))),
+        // keys, joins and select/omit belong to database files
+        DDSLine::Key(_) | DDSLine::Join(_) | DDSLine::SelectOmit(_) => {
+            let _ = read_continuations(parser);
+            return next_entry(parser);
+        }
+        DDSLine::Comment(_) | DDSLine::Idk(_) => return next_entry(parser),
+    };
+    Some(entry)
+}
+
+fn next_entry(parser: &Parser) -> Option<Entry> {
+    match peek_n(parser, 0) {
+        Some(_) => read_entry(parser),
+        None => None,
+    }
+}
+
+// ex: " 03N40   " -> 03, N40
+fn parse_indicators(text: &str) -> Vec<Indicator> {
+    let chars = text.chars().collect::<Vec<char>>();
+    chars
+        .chunks(3)
+        .filter_map(|chunk| match chunk {
+            [not, a, b] if a.is_ascii_digit() && b.is_ascii_digit() => Some(Indicator {
+                number: format!("{}{}", a, b).parse::<u8>().ok()?,
+                not: *not == 'N',
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+// columns 8-16, what's left of a line with conditioning indicators
+fn indicators(line: &DDSLine) -> Vec<Indicator> {
+    let text = match line {
+        DDSLine::RecordFormat(x) => x.condition.to_string(),
+        DDSLine::Field(x) | DDSLine::Constant(x) => x.condition.to_string(),
+        DDSLine::Continuation(x) => x.nothing.to_string().chars().skip(7).take(9).collect(),
+        _ => return vec![],
+    };
+    parse_indicators(&text)
+}
+
+impl From<&CST> for AST {
+    fn from(value: &CST) -> Self {
+        let cst = value;
+        let state = ParserState {
+            idx: 0,
+            phase: Phase::FileEntry,
+        };
+        let parser = Parser {
+            state: RefCell::new(state),
+            input: cst
+                .lines
+                .iter()
+                .filter(|line| !matches!(line, DDSLine::Comment(_) | DDSLine::Idk(_)))
+                .cloned()
+                .collect::<Vec<DDSLine>>(),
+        };
+        let mut entries = vec![];
+        while let Some(entry) = next_entry(&parser) {
+            entries.push(entry);
+        }
+        let indicators = cst
+            .lines
+            .iter()
+            .map(|line| (line.span().start.row, indicators(line)))
+            .filter(|(_, x)| !x.is_empty())
+            .collect();
+        AST { entries, indicators }
+    }
+}
+
+type SpanShape = ((usize, usize), (usize, usize), String);
+pub fn highlight_ast(ast: AST) -> Vec<SpanShape> {
+    ast.entries
+        .iter()
+        .flat_map(|e| e.highlight())
+        .map(|tup| {
+            (
+                (tup.0.start.row, tup.0.start.col),
+                (tup.0.end.row, tup.0.end.col),
+                tup.1,
+            )
+        })
+        .collect::<Vec<_>>()
+}
+
+// a field of the display file, or one of its record formats
+pub fn query_definition(ast: &AST, pattern: &str) -> Option<Span> {
+    let pattern = pattern.to_uppercase();
+    for entry in ast.entries.iter() {
+        let name = match entry {
+            Entry::R(rf) => &rf.name,
+            Entry::F(fld) => &fld.name,
+            _ => continue,
+        };
+        if let FieldResult::Ok(namefield) = name {
+            if namefield.value.trim().to_uppercase() == pattern {
+                return Some(namefield.meta.span);
+            }
+        }
+    }
+    None
+}
+
+pub fn query_record_formats(ast: &AST) -> Vec<String> {
+    let mut out = vec![];
+    for entry in ast.entries.iter() {
+        if let Entry::R(rf) = entry {
+            if let FieldResult::Ok(namefield) = &rf.name {
+                out.push(namefield.value.clone());
+            }
+        }
+    }
+    out
+}
+
+// (record format, field, type and length) for every named field, ex: (PROMPT, CUSNO, 7S 0)
+pub fn query_fields(ast: &AST) -> Vec<(String, String, String)> {
+    let mut out = vec![];
+    let mut format = String::new();
+    for entry in ast.entries.iter() {
+        match entry {
+            Entry::R(rf) => {
+                if let FieldResult::Ok(namefield) = &rf.name {
+                    format = namefield.value.trim().to_string();
+                }
+            }
+            Entry::F(fld) => {
+                if let FieldResult::Ok(namefield) = &fld.name {
+                    out.push((format.clone(), namefield.value.trim().to_string(), detail(fld)));
+                }
+            }
+            Entry::FE(_) | Entry::C(_) => {}
+        }
+    }
+    out
+}
+
+/* The fields of the display or printer file `file` with their references
+ * followed through `files`, physical file names to parsed sources.
+ */
+pub fn resolve_references(file: &str, ast: &AST, files: &HashMap<String, pfdds::AST>) -> References {
+    let mut src = Source::new(file);
+    let mut format = String::new();
+    for entry in ast.entries.iter() {
+        match entry {
+            Entry::FE(fe) => src.file_entry(fe),
+            Entry::R(rf) => {
+                if let FieldResult::Ok(namefield) = &rf.name {
+                    format = namefield.value.trim().to_uppercase();
+                }
+            }
+            Entry::F(fld) => src.field(&format, fld),
+            Entry::C(_) => {}
+        }
+    }
+    resolve(&src, files)
+}
@@ -0,0 +1,358 @@
+use super::{Entry, Indicator, ScreenSize, AST};
+use crate::field::{FieldResult, Placement, Usage};
+use crate::free::{field_pieces, file_pieces, format_pieces, keywords, Keyword};
+use crate::meta::Span;
+use crate::pfdds::{detail, Field};
+use serde::{Deserialize, Serialize};
+
+// A display keyword with the indicators on its line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayKeyword {
+    pub name: String,
+    pub args: Vec<String>,
+    // ANDed, empty when the keyword is always in effect
+    pub conditions: Vec<Indicator>,
+    pub span: Span,
+}
+
+// CF03(03 'Exit') or CA12, a command key and the response indicator it sets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandKey {
+    pub key: usize,
+    // CA keys return without the input data, CF keys with it
+    pub cancel: bool,
+    pub indicator: Option<u8>,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FormatKind {
+    Record,
+    Subfile,
+    // the control record of the named subfile record
+    SubfileControl(String),
+}
+
+// A field or constant of a record format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenField {
+    // empty for constants
+    pub name: String,
+    pub span: Span,
+    // blank means B for a named field and O for a constant
+    pub usage: Usage,
+    pub length: Option<usize>,
+    // numeric fields have them
+    pub decimals: Option<usize>,
+    // type and length, ex: 7S 0
+    pub detail: String,
+    // the text of a constant, without its quotes
+    pub constant: Option<String>,
+    pub line: Option<usize>,
+    pub position: Option<Placement>,
+    // from the field's own line, ANDed
+    pub conditions: Vec<Indicator>,
+    pub keywords: Vec<DisplayKeyword>,
+}
+
+impl ScreenField {
+    pub fn is_constant(&self) -> bool {
+        self.name.is_empty()
+    }
+
+    pub fn keyword(&self, name: &str) -> Option<&DisplayKeyword> {
+        self.keywords.iter().find(|x| x.name == name)
+    }
+
+    // DSPATR(RI PC) -> RI, PC
+    pub fn attributes(&self) -> Vec<String> {
+        self.keywords
+            .iter()
+            .filter(|x| x.name == "DSPATR")
+            .flat_map(|x| x.args.iter().map(|a| a.to_uppercase()))
+            .collect()
+    }
+
+    pub fn color(&self) -> Option<String> {
+        self.keyword("COLOR")?.args.first().map(|x| x.to_uppercase())
+    }
+
+    // ERRMSG('Bad customer' 40) -> (Bad customer, 40)
+    pub fn messages(&self) -> Vec<(String, Option<u8>)> {
+        self.keywords
+            .iter()
+            .filter(|x| x.name == "ERRMSG")
+            .filter_map(|x| {
+                let text = unquote(x.args.first()?);
+                Some((text, x.args.get(1).and_then(|n| n.parse::<u8>().ok())))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayFormat {
+    pub name: String,
+    pub span: Span,
+    pub kind: FormatKind,
+    pub keywords: Vec<DisplayKeyword>,
+    pub command_keys: Vec<CommandKey>,
+    pub fields: Vec<ScreenField>,
+}
+
+impl DisplayFormat {
+    pub fn keyword(&self, name: &str) -> Option<&DisplayKeyword> {
+        self.keywords.iter().find(|x| x.name == name)
+    }
+
+    fn number(&self, name: &str) -> Option<usize> {
+        self.keyword(name)?.args.first()?.parse::<usize>().ok()
+    }
+
+    // SFLPAG, the subfile records shown at once
+    pub fn page(&self) -> Option<usize> {
+        self.number("SFLPAG")
+    }
+
+    // SFLSIZ, the subfile records kept
+    pub fn size(&self) -> Option<usize> {
+        self.number("SFLSIZ")
+    }
+
+    // OVERLAY, the screen isn't cleared before the format is written
+    pub fn overlay(&self) -> bool {
+        self.keyword("OVERLAY").is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplayFile {
+    pub keywords: Vec<DisplayKeyword>,
+    // the file level command keys, every format has them
+    pub command_keys: Vec<CommandKey>,
+    pub formats: Vec<DisplayFormat>,
+}
+
+impl DisplayFile {
+    pub fn format(&self, name: &str) -> Option<&DisplayFormat> {
+        let name = name.to_uppercase();
+        self.formats.iter().find(|x| x.name == name)
+    }
+
+    // the primary size from DSPSIZ, ex: DSPSIZ(27 132 *DS4)
+    pub fn screen_size(&self) -> ScreenSize {
+        let dspsiz = self.keywords.iter().find(|x| x.name == "DSPSIZ");
+        match dspsiz.and_then(|x| x.args.first()).map(|x| x.to_uppercase()).as_deref() {
+            Some("27") | Some("*DS4") => ScreenSize::Ds4,
+            _ => ScreenSize::Ds3,
+        }
+    }
+
+    // the subfile record of a subfile control record
+    pub fn subfile(&self, control: &DisplayFormat) -> Option<&DisplayFormat> {
+        match &control.kind {
+            FormatKind::SubfileControl(name) => self.format(name),
+            _ => None,
+        }
+    }
+}
+
+// 'It''s' -> It's
+pub(crate) fn unquote(text: &str) -> String {
+    let inner = text
+        .strip_prefix('\'')
+        .map(|x| x.strip_suffix('\'').unwrap_or(x))
+        .unwrap_or(text);
+    inner.replace("''", "'")
+}
+
+/* Keywords with the indicators of their line. Indicators on the first line
+ * condition the field or record format itself, not the keywords on it.
+ */
+pub(crate) fn conditioned(found: Vec<Keyword>, first_row: Option<usize>, ast: &AST) -> Vec<DisplayKeyword> {
+    found
+        .into_iter()
+        .map(|Keyword { name, args, span }| {
+            let row = span.start.row;
+            let conditions = match Some(row) == first_row {
+                true => vec![],
+                false => ast.indicators.get(&row).cloned().unwrap_or_default(),
+            };
+            DisplayKeyword { name, args, conditions, span }
+        })
+        .collect()
+}
+
+fn command_key(kw: &DisplayKeyword) -> Option<CommandKey> {
+    let cancel = match kw.name.get(..2)? {
+        "CA" => true,
+        "CF" => false,
+        _ => return None,
+    };
+    let key = kw.name.get(2..)?.parse::<usize>().ok().filter(|x| (1..=24).contains(x))?;
+    let indicator = kw.args.first().and_then(|x| x.parse::<u8>().ok());
+    let text = kw.args.iter().find(|x| x.starts_with('\'')).map(|x| unquote(x));
+    Some(CommandKey { key, cancel, indicator, text })
+}
+
+fn screen_field(fld: &Field, ast: &AST) -> ScreenField {
+    let (name, span) = match &fld.name {
+        FieldResult::Ok(x) => (x.value.trim().to_uppercase(), x.meta.span),
+        FieldResult::Idk(x) => (String::new(), x.meta.span),
+    };
+    let (line, position) = match &fld.location {
+        FieldResult::Ok(x) => (x.value.line, x.value.position),
+        FieldResult::Idk(_) => (None, None),
+    };
+    let row = span.start.row;
+    let found = match &fld.keywords {
+        FieldResult::Ok(kw) => keywords(field_pieces(&kw.tokens)),
+        FieldResult::Idk(_) => vec![],
+    };
+    let keywords = conditioned(found, Some(row), ast);
+    let constant = match name.is_empty() {
+        true => keywords
+            .iter()
+            .find(|x| x.name == "DFT")
+            .and_then(|x| x.args.first())
+            .map(|x| unquote(x)),
+        false => None,
+    };
+    ScreenField {
+        name,
+        span,
+        usage: match &fld.usage {
+            FieldResult::Ok(x) => x.value,
+            FieldResult::Idk(_) => Usage::Empty,
+        },
+        length: match &fld.length {
+            FieldResult::Ok(x) => Some(x.value),
+            FieldResult::Idk(_) => None,
+        },
+        decimals: match &fld.decimal_positions {
+            FieldResult::Ok(x) => Some(x.value),
+            FieldResult::Idk(_) => None,
+        },
+        detail: detail(fld),
+        constant,
+        line,
+        position,
+        conditions: ast.indicators.get(&row).cloned().unwrap_or_default(),
+        keywords,
+    }
+}
+
+/* The record formats of a display file with their keywords, command keys and
+ * the fields and constants on them, subfile records paired with their control
+ * records.
+ */
+pub fn query_display_file(ast: &AST) -> DisplayFile {
+    let mut file = DisplayFile { keywords: vec![], command_keys: vec![], formats: vec![] };
+    for entry in ast.entries.iter() {
+        match entry {
+            Entry::FE(fe) => {
+                if let FieldResult::Ok(kw) = &fe.keywords {
+                    let found = keywords(file_pieces(&kw.tokens));
+                    file.keywords.extend(conditioned(found, None, ast));
+                }
+            }
+            Entry::R(rf) => {
+                let (name, span) = match &rf.name {
+                    FieldResult::Ok(x) => (x.value.trim().to_uppercase(), x.meta.span),
+                    FieldResult::Idk(x) => (String::new(), x.meta.span),
+                };
+                let found = match &rf.keywords {
+                    FieldResult::Ok(kw) => keywords(format_pieces(&kw.tokens)),
+                    FieldResult::Idk(_) => vec![],
+                };
+                let keywords = conditioned(found, Some(span.start.row), ast);
+                let kind = keywords
+                    .iter()
+                    .find_map(|x| match (x.name.as_str(), x.args.first()) {
+                        ("SFL", _) => Some(FormatKind::Subfile),
+                        ("SFLCTL", Some(sfl)) => Some(FormatKind::SubfileControl(sfl.to_uppercase())),
+                        _ => None,
+                    })
+                    .unwrap_or(FormatKind::Record);
+                file.formats.push(DisplayFormat {
+                    name,
+                    span,
+                    kind,
+                    command_keys: keywords.iter().filter_map(command_key).collect(),
+                    keywords,
+                    fields: vec![],
+                });
+            }
+            Entry::F(fld) | Entry::C(fld) => {
+                if let Some(format) = file.formats.last_mut() {
+                    format.fields.push(screen_field(fld, ast));
+                }
+            }
+        }
+    }
+    file.command_keys = file.keywords.iter().filter_map(command_key).collect();
+    file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dspfdds::CST;
+
+    fn fixture() -> String {
+        r#"
+     A* customer inquiry
+     A                                      DSPSIZ(24 80 *DS3)
+     A                                      CA03(03 'Exit')
+     A          R PROMPT                    OVERLAY
+     A                                      CF12(12 'Cancel')
+     A                                  1  2'Customer Inquiry'
+     A                                      DSPATR(HI)
+     A                                  3  2'Customer:'
+     A            CUSNO          7S 0B  3 +1
+     A  40                                  DSPATR(RI PC)
+     A                                      ERRMSG('Customer not found' 40)
+     A          R CUSSFL                    SFL
+     A            ORDNO          9S 0O  6  2
+     A            ORDSTS         1   H
+     A N41        ORDAMT        11Y 2O  6 14COLOR(RED)
+     A          R CUSCTL                    SFLCTL(CUSSFL)
+     A                                      SFLPAG(10) SFLSIZ(50)
+     A  31                                  SFLDSP
+     A N31                                  SFLCLR
+     A                                  5  2'Order'
+"#[1..]
+            .to_string()
+    }
+
+    #[test]
+    fn test_display_file_snapshot() {
+        let cst = CST::try_from(fixture().as_str()).unwrap();
+        let ast = AST::from(&cst);
+        insta::assert_yaml_snapshot!(query_display_file(&ast));
+    }
+
+    #[test]
+    fn test_display_file_subfile() {
+        let cst = CST::try_from(fixture().as_str()).unwrap();
+        let file = query_display_file(&AST::from(&cst));
+        let control = file.format("cusctl").unwrap();
+        assert_eq!(file.subfile(control).map(|x| x.name.as_str()), Some("CUSSFL"));
+        assert_eq!((control.page(), control.size()), (Some(10), Some(50)));
+        let sfldsp = control.keyword("SFLDSP").unwrap();
+        assert_eq!(sfldsp.conditions, vec![Indicator { number: 31, not: false }]);
+        let prompt = file.format("PROMPT").unwrap();
+        assert!(prompt.overlay());
+        assert_eq!(file.command_keys[0].key, 3);
+        assert_eq!(prompt.command_keys[0].text.as_deref(), Some("Cancel"));
+        let title = &prompt.fields[0];
+        assert_eq!(title.constant.as_deref(), Some("Customer Inquiry"));
+        assert_eq!(title.attributes(), vec!["HI"]);
+        let cusno = &prompt.fields[2];
+        assert_eq!(cusno.position, Some(Placement::Relative(1)));
+        assert_eq!(cusno.messages(), vec![("Customer not found".to_string(), Some(40))]);
+        let ordamt = &file.format("CUSSFL").unwrap().fields[2];
+        assert_eq!(ordamt.conditions, vec![Indicator { number: 41, not: true }]);
+        assert_eq!(ordamt.color().as_deref(), Some("RED"));
+    }
+}
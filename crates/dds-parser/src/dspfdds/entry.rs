@@ -0,0 +1,22 @@
+use crate::pfdds::{Field, FileEntry, RecordFormat};
+use crate::meta::{IHighlight, Span};
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Entry {
+    FE(FileEntry),
+    R(RecordFormat),
+    F(Field),
+    // a constant, a field without a name
+    C(Field),
+}
+impl IHighlight for Entry {
+    fn highlight(&self) -> Vec<(Span, String)> {
+        match self {
+            Entry::FE(e) => e.highlight(),
+            Entry::R(e) => e.highlight(),
+            Entry::F(e) => e.highlight(),
+            Entry::C(e) => e.highlight(),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+// Display files have the same columns as physical files and parse to the same CST
+mod entry;
+mod ast;
+mod display_file;
+mod outline;
+mod render;
+pub use crate::pfdds::{CST, highlight_cst};
+pub use crate::field::{Location, Placement, Usage};
+pub use entry::Entry;
+pub use ast::{AST, Indicator, highlight_ast, query_definition, query_fields, query_record_formats,
+    resolve_references};
+pub use display_file::{
+    CommandKey, DisplayFile, DisplayFormat, DisplayKeyword, FormatKind, ScreenField,
+    query_display_file,
+};
+pub(crate) use display_file::{conditioned, unquote};
+pub use outline::{query_folds, query_outline};
+pub use render::{Cell, Color, Screen, ScreenSize, Style, render_format};
+pub(crate) use render::satisfied;
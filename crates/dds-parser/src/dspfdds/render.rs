@@ -0,0 +1,349 @@
+use super::{DisplayFile, DisplayFormat, DisplayKeyword, FormatKind, Indicator, ScreenField};
+use crate::field::{Placement, Usage};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScreenSize {
+    // 24 x 80
+    Ds3,
+    // 27 x 132
+    Ds4,
+}
+
+impl ScreenSize {
+    pub fn rows(&self) -> usize {
+        match self {
+            Self::Ds3 => 24,
+            Self::Ds4 => 27,
+        }
+    }
+
+    pub fn cols(&self) -> usize {
+        match self {
+            Self::Ds3 => 80,
+            Self::Ds4 => 132,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Color {
+    #[default]
+    Green,
+    White,
+    Red,
+    Blue,
+    Turquoise,
+    Yellow,
+    Pink,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Style {
+    pub color: Color,
+    pub high: bool,
+    pub reverse: bool,
+    pub underline: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub ch: char,
+    pub style: Style,
+}
+
+// A rendered screen, rows and columns are zero based
+#[derive(Debug, Clone, PartialEq)]
+pub struct Screen {
+    pub size: ScreenSize,
+    pub cells: Vec<Vec<Cell>>,
+}
+
+const BLANK: Cell = Cell { ch: ' ', style: Style { color: Color::Green, high: false, reverse: false, underline: false } };
+
+impl Screen {
+    fn new(size: ScreenSize) -> Self {
+        Self { size, cells: vec![vec![BLANK; size.cols()]; size.rows()] }
+    }
+
+    // `line` and `position` are one based like in the source
+    fn paint(&mut self, line: usize, position: usize, text: &[char], style: Style) {
+        let Some(row) = self.cells.get_mut(line.wrapping_sub(1)) else {
+            return;
+        };
+        for (i, ch) in text.iter().enumerate() {
+            if let Some(cell) = row.get_mut((position + i).wrapping_sub(1)) {
+                *cell = Cell { ch: *ch, style };
+            }
+        }
+    }
+
+    // trailing cells that wouldn't show are left out
+    fn visible(row: &[Cell]) -> &[Cell] {
+        let end = row
+            .iter()
+            .rposition(|c| c.ch != ' ' || c.style.reverse || c.style.underline)
+            .map(|x| x + 1)
+            .unwrap_or(0);
+        &row[..end]
+    }
+
+    pub fn plain(&self) -> String {
+        self.cells
+            .iter()
+            .map(|row| Self::visible(row).iter().map(|c| c.ch).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // like `plain`, with SGR escapes for the colors and attributes
+    pub fn ansi(&self) -> String {
+        let mut out = vec![];
+        for row in self.cells.iter() {
+            let mut line = String::new();
+            let mut current: Option<Style> = None;
+            for cell in Self::visible(row) {
+                if current != Some(cell.style) {
+                    line.push_str(&sgr(cell.style));
+                    current = Some(cell.style);
+                }
+                line.push(cell.ch);
+            }
+            if current.is_some() {
+                line.push_str("\x1b[0m");
+            }
+            out.push(line);
+        }
+        out.join("\n")
+    }
+}
+
+fn sgr(style: Style) -> String {
+    let color = match style.color {
+        Color::Green => 32,
+        Color::White => 37,
+        Color::Red => 31,
+        Color::Blue => 34,
+        Color::Turquoise => 36,
+        Color::Yellow => 33,
+        Color::Pink => 35,
+    };
+    let mut codes = vec!["0".to_string(), color.to_string()];
+    if style.high {
+        codes.push("1".to_string());
+    }
+    if style.underline {
+        codes.push("4".to_string());
+    }
+    if style.reverse {
+        codes.push("7".to_string());
+    }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+pub(crate) fn satisfied(conditions: &[Indicator], on: &[u8]) -> bool {
+    conditions.iter().all(|x| on.contains(&x.number) != x.not)
+}
+
+// the keywords in effect with the indicators that are on
+fn effective<'a>(keywords: &'a [DisplayKeyword], on: &'a [u8]) -> impl Iterator<Item = &'a DisplayKeyword> {
+    keywords.iter().filter(move |x| satisfied(&x.conditions, on))
+}
+
+// what a field shows, constants as text, input fields as underscores and output fields as 9s or Xs
+fn content(field: &ScreenField) -> Option<Vec<char>> {
+    if field.is_constant() {
+        let text = match (&field.constant, field.keywords.first().map(|x| x.name.as_str())) {
+            (Some(text), _) => text.clone(),
+            (None, Some("DATE")) => "99/99/99".to_string(),
+            (None, Some("TIME")) => "99:99:99".to_string(),
+            (None, Some("SYSNAME")) => "X".repeat(8),
+            (None, Some("USER")) => "X".repeat(10),
+            _ => return None,
+        };
+        return Some(text.chars().collect());
+    }
+    let length = field.length?;
+    let ch = match (field.usage, field.decimals) {
+        (Usage::H | Usage::P, _) => return None,
+        (Usage::Empty | Usage::B | Usage::I, _) => '_',
+        (_, Some(_)) => '9',
+        (_, None) => 'X',
+    };
+    Some(vec![ch; length])
+}
+
+fn style(field: &ScreenField, on: &[u8]) -> (Style, bool) {
+    let mut style = Style {
+        underline: matches!(field.usage, Usage::Empty | Usage::B | Usage::I) && !field.is_constant(),
+        ..Style::default()
+    };
+    let mut hidden = false;
+    for kw in effective(&field.keywords, on) {
+        match kw.name.as_str() {
+            "DSPATR" => {
+                for attr in kw.args.iter() {
+                    match attr.to_uppercase().as_str() {
+                        "HI" => style.high = true,
+                        "RI" => style.reverse = true,
+                        "UL" => style.underline = true,
+                        "ND" => hidden = true,
+                        _ => {}
+                    }
+                }
+            }
+            "COLOR" => {
+                style.color = match kw.args.first().map(|x| x.to_uppercase()).as_deref() {
+                    Some("WHT") => Color::White,
+                    Some("RED") => Color::Red,
+                    Some("BLU") => Color::Blue,
+                    Some("TRQ") => Color::Turquoise,
+                    Some("YLW") => Color::Yellow,
+                    Some("PNK") => Color::Pink,
+                    _ => Color::Green,
+                }
+            }
+            _ => {}
+        }
+    }
+    if style.high && style.color == Color::Green {
+        style.color = Color::White;
+    }
+    (style, hidden)
+}
+
+/* Paint the fields of a format, `offset` lines down. A field at +n starts n
+ * columns past the end of the field before it, shown or not.
+ */
+fn paint_format(screen: &mut Screen, format: &DisplayFormat, on: &[u8], offset: usize) {
+    let mut line = 1;
+    let mut end = 1;
+    for field in format.fields.iter() {
+        let Some(text) = content(field) else {
+            continue;
+        };
+        line = field.line.unwrap_or(line);
+        let position = match field.position {
+            Some(Placement::Absolute(x)) => x,
+            Some(Placement::Relative(x)) => end + x,
+            None => continue,
+        };
+        end = position + text.len();
+        if !satisfied(&field.conditions, on) {
+            continue;
+        }
+        let (style, hidden) = style(field, on);
+        let text = match hidden {
+            true => vec![' '; text.len()],
+            false => text,
+        };
+        screen.paint(line + offset, position, &text, style);
+    }
+}
+
+// the lines a subfile record takes
+fn height(format: &DisplayFormat) -> usize {
+    let lines = format.fields.iter().filter_map(|x| x.line);
+    match (lines.clone().min(), lines.max()) {
+        (Some(min), Some(max)) => max - min + 1,
+        _ => 1,
+    }
+}
+
+/* Lay out a record format with the indicators in `on` set. A subfile control
+ * record brings its subfile along, a page of records under it while SFLDSP is
+ * in effect.
+ */
+pub fn render_format(file: &DisplayFile, name: &str, on: &[u8], size: ScreenSize) -> Option<Screen> {
+    let format = file.format(name)?;
+    let mut screen = Screen::new(size);
+    paint_format(&mut screen, format, on, 0);
+    if let (FormatKind::SubfileControl(_), Some(subfile)) = (&format.kind, file.subfile(format)) {
+        let shown = effective(&format.keywords, on).any(|x| x.name == "SFLDSP");
+        if shown {
+            let height = height(subfile);
+            for record in 0..format.page().unwrap_or(1) {
+                paint_format(&mut screen, subfile, on, record * height);
+            }
+        }
+    }
+    Some(screen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dspfdds::{query_display_file, AST, CST};
+
+    fn fixture() -> DisplayFile {
+        let input = r#"
+     A                                      DSPSIZ(24 80 *DS3)
+     A          R CUSSFL                    SFL
+     A            ORDNO          5S 0O  6  2
+     A            ORDSTS         1   H
+     A N41        ORDAMT         7Y 2O  6 +2COLOR(RED)
+     A          R CUSCTL                    SFLCTL(CUSSFL)
+     A                                      SFLPAG(3) SFLSIZ(9)
+     A  31                                  SFLDSP
+     A                                  1  2'Orders'
+     A                                      DSPATR(HI)
+     A            CUSNO          7S 0B  3  2
+     A  40                                  DSPATR(RI)
+     A                                  5  2'Order'
+     A                                  5  9'Amount'
+"#[1..]
+            .to_string();
+        let cst = CST::try_from(input.as_str()).unwrap();
+        query_display_file(&AST::from(&cst))
+    }
+
+    fn expected(rows: &[(usize, &str)]) -> String {
+        let mut out = vec![String::new(); 24];
+        for (row, text) in rows {
+            out[row - 1] = text.to_string();
+        }
+        out.join("\n")
+    }
+
+    #[test]
+    fn test_render_subfile() {
+        let file = fixture();
+        assert_eq!(file.screen_size(), ScreenSize::Ds3);
+        let screen = render_format(&file, "CUSCTL", &[31], file.screen_size()).unwrap();
+        let observed = screen.plain();
+        let expected = expected(&[
+            (1, " Orders"),
+            (3, " _______"),
+            (5, " Order  Amount"),
+            (6, " 99999  9999999"),
+            (7, " 99999  9999999"),
+            (8, " 99999  9999999"),
+        ]);
+        assert_eq!(observed, expected);
+    }
+
+    #[test]
+    fn test_render_indicators() {
+        let file = fixture();
+        // no SFLDSP, no subfile
+        let screen = render_format(&file, "CUSCTL", &[], ScreenSize::Ds3).unwrap();
+        let expected_ctl = expected(&[(1, " Orders"), (3, " _______"), (5, " Order  Amount")]);
+        assert_eq!(screen.plain(), expected_ctl);
+        // ORDAMT is conditioned on N41
+        let screen = render_format(&file, "CUSCTL", &[31, 41], ScreenSize::Ds3).unwrap();
+        assert_eq!(screen.plain().lines().nth(5), Some(" 99999"));
+        assert!(render_format(&file, "NOTHERE", &[], ScreenSize::Ds3).is_none());
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let file = fixture();
+        let screen = render_format(&file, "CUSCTL", &[31, 40], ScreenSize::Ds4).unwrap();
+        let ansi = screen.ansi();
+        let lines = ansi.split('\n').collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 27);
+        assert_eq!(lines[0], "\x1b[0;32m \x1b[0;37;1mOrders\x1b[0m");
+        assert_eq!(lines[2], "\x1b[0;32m \x1b[0;32;4;7m_______\x1b[0m");
+        assert_eq!(lines[5], "\x1b[0;32m 99999  \x1b[0;31m9999999\x1b[0m");
+        assert_eq!(lines[1], "");
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt::Display;
+
+use super::result::FieldResult;
+use crate::meta::{Meta, IHighlight, ISpan, Position, Span};
+use crate::field::IdkField;
+use serde::{Deserialize, Serialize};
+
+// Where a display or printer file field goes, ex: "  5 20" or "   +2"
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Placement {
+    Absolute(usize),
+    // past the end of the field before it
+    Relative(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub line: Option<usize>,
+    pub position: Option<Placement>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationField {
+    pub value: Location,
+    pub meta: Meta,
+}
+fn placement(raw: &str) -> Option<Option<Placement>> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Some(None);
+    }
+    match raw.strip_prefix('+') {
+        Some(rest) => rest.trim().parse::<usize>().ok().map(|x| Some(Placement::Relative(x))),
+        None => raw.parse::<usize>().ok().map(|x| Some(Placement::Absolute(x))),
+    }
+}
+impl From<(Position, &[char; 6])> for FieldResult<LocationField> {
+    fn from(value: (Position, &[char; 6])) -> Self {
+        let raw = value.1.iter().collect::<String>();
+        // lines 39-41, positions 42-44
+        let line = value.1[..3].iter().collect::<String>();
+        let position = value.1[3..].iter().collect::<String>();
+        let line = match line.trim().is_empty() {
+            true => Some(None),
+            false => line.trim().parse::<usize>().ok().map(Some),
+        };
+        match (line, placement(&position)) {
+            (Some(line), Some(position)) => {
+                Self::Ok(LocationField { 
+                    value: Location { line, position },
+                    meta: Meta::from((value.0, value.1.as_slice()))
+                })
+            }
+            _ => {
+                Self::Idk(IdkField { 
+                    value: raw.trim().to_string(),
+                    meta: Meta::from((value.0, value.1.as_slice()))
+                })
+            }
+        }
+    }
+}
+impl Display for LocationField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let out = &self.meta.text;
+        write!(f, "{}", out)
+    }
+}
+impl IHighlight for LocationField {
+    fn highlight(&self) -> Vec<(Span, String)> {
+        vec![(self.meta.span, "@number".to_string())]
+    }
+}
+impl ISpan for LocationField {
+    fn span(&self) -> Span {
+        self.meta.span
+    }
+}
@@ -11,19 +11,21 @@ mod length_field;
 mod datatype_field;
 mod decimalpositions_field;
 mod usage_field;
+mod location_field;
 mod keywords_field;
 
 pub use result::FieldResult;
 pub use idk_field::IdkField;
 pub use sequence_field::SequenceField;
 pub use formtype_field::FormtypeField;
-pub use nametype_field::NametypeField;
+pub use nametype_field::{Nametype, NametypeField};
 pub use comment_field::CommentField;
 pub use ignored_field::IgnoredField;
 pub use name_field::NameField;
-pub use reference_field::ReferenceField;
+pub use reference_field::{Reference, ReferenceField};
 pub use length_field::LengthField;
-pub use datatype_field::DatatypeField;
+pub use datatype_field::{Datatype, DatatypeField};
 pub use decimalpositions_field::DecimalPositionsField;
-pub use usage_field::UsageField;
+pub use usage_field::{Usage, UsageField};
+pub use location_field::{Location, LocationField, Placement};
 pub use keywords_field::{RawKeywordsField, RFKeywordsField, FKeywordsField, KKeywordsField, FEKeywordsField};
@@ -10,6 +10,9 @@ pub enum Nametype {
     Empty,
     R,
     K,
+    J,
+    S,
+    O,
 }
 impl Display for Nametype {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -17,6 +20,9 @@ impl Display for Nametype {
             Self::Empty => " ".to_string(),
             Self::R => "R".to_string(),
             Self::K => "K".to_string(),
+            Self::J => "J".to_string(),
+            Self::S => "S".to_string(),
+            Self::O => "O".to_string(),
         };
         write!(f, "{}", msg)
     }
@@ -38,6 +44,9 @@ impl From<(Position, &[char; 1])> for FieldResult<NametypeField> {
             ' ' => Some(Nametype::Empty),
             'R' => Some(Nametype::R),
             'K' => Some(Nametype::K),
+            'J' => Some(Nametype::J),
+            'S' => Some(Nametype::S),
+            'O' => Some(Nametype::O),
             _ => None,
         };
         if let Some(x) = maybe {
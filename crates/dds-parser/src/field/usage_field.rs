@@ -9,12 +9,23 @@ use serde::{Deserialize, Serialize};
 pub enum Usage {
     Empty,
     B,
+    // display and printer files
+    I,
+    O,
+    H,
+    M,
+    P,
 }
 impl Display for Usage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let msg = match self {
             Self::Empty => " ".to_string(),
             Self::B => "B".to_string(),
+            Self::I => "I".to_string(),
+            Self::O => "O".to_string(),
+            Self::H => "H".to_string(),
+            Self::M => "M".to_string(),
+            Self::P => "P".to_string(),
         };
         write!(f, "{}", msg)
     }
@@ -36,6 +47,11 @@ impl From<(Position, &[char; 1])> for FieldResult<UsageField> {
         let maybe = match chars[0] {
             ' ' => Some(Usage::Empty),
             'B' => Some(Usage::B),
+            'I' => Some(Usage::I),
+            'O' => Some(Usage::O),
+            'H' => Some(Usage::H),
+            'M' => Some(Usage::M),
+            'P' => Some(Usage::P),
             _ => None,
         };
         if let Some(x) = maybe {
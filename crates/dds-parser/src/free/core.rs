@@ -67,7 +67,7 @@ pub fn ch(lexer: &Lexer) -> Option<&MetaChar> {
 }
 
 pub fn is_identifier_char(ch: &char) -> bool {
-    ch.is_alphanumeric() || *ch == '@' || *ch == '$' || *ch == '-' || *ch == '#'
+    ch.is_alphanumeric() || *ch == '@' || *ch == '$' || *ch == '-' || *ch == '#' || *ch == '_'
 }
 
 pub fn is_numeric(ch: &char) -> bool {
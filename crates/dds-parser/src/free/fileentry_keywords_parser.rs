@@ -18,6 +18,7 @@ pub enum FETokenKind {
     Indicator,
     Colon,
     StringLiteral,
+    Slash,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +38,7 @@ impl FEToken {
             FETokenKind::LParen => "Normal",
             FETokenKind::RParen => "Normal",
             FETokenKind::StringLiteral => "String",
+            FETokenKind::Slash => "Normal",
         };
         let mut out = vec![];
         for meta in self.metas.iter() {
@@ -102,6 +104,12 @@ fn next_token(lexer: &Lexer) -> Option<FEToken> {
                     }
                 }
             }
+            // slash
+            '/' => {
+                let chars = vec![read_char(lexer)];
+                let kind = FETokenKind::Slash;
+                (kind, chars)
+            }
             // quote
             '\'' => match peek_until(lexer, '\'') {
                 Some(MetaChar { .. }) => {
@@ -0,0 +1,126 @@
+use super::{FEToken, FETokenKind, FToken, FTokenKind, KToken, KTokenKind, RFToken, RFTokenKind};
+use crate::meta::{Meta, Span};
+
+// A keyword and its parameters, ex: SST(EVTDAT 1 4) -> SST, [EVTDAT, 1, 4]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyword {
+    // uppercased
+    pub name: String,
+    // parameters keep their quotes
+    pub args: Vec<String>,
+    // the name through the closing paren
+    pub span: Span,
+}
+
+pub enum Piece {
+    Word(String, Span),
+    Open,
+    Close(Span),
+    Space,
+}
+
+fn word(metas: &[Meta]) -> Piece {
+    let text = metas.iter().map(|m| m.text.as_str()).collect::<String>();
+    let span = match (metas.first(), metas.last()) {
+        (Some(first), Some(last)) => Span::from((first.span, last.span)),
+        _ => Span::empty(),
+    };
+    Piece::Word(text, span)
+}
+
+fn close(metas: &[Meta]) -> Piece {
+    match metas.first() {
+        Some(m) => Piece::Close(m.span),
+        None => Piece::Close(Span::empty()),
+    }
+}
+
+pub fn field_pieces(tokens: &[FToken]) -> Vec<Piece> {
+    tokens
+        .iter()
+        .map(|t| match t.kind {
+            FTokenKind::LParen => Piece::Open,
+            FTokenKind::RParen => close(&t.metas),
+            FTokenKind::Whitespace => Piece::Space,
+            _ => word(&t.metas),
+        })
+        .collect()
+}
+
+pub fn format_pieces(tokens: &[RFToken]) -> Vec<Piece> {
+    tokens
+        .iter()
+        .map(|t| match t.kind {
+            RFTokenKind::LParen => Piece::Open,
+            RFTokenKind::RParen => close(&t.metas),
+            RFTokenKind::Whitespace => Piece::Space,
+            _ => word(&t.metas),
+        })
+        .collect()
+}
+
+pub fn file_pieces(tokens: &[FEToken]) -> Vec<Piece> {
+    tokens
+        .iter()
+        .map(|t| match t.kind {
+            FETokenKind::LParen => Piece::Open,
+            FETokenKind::RParen => close(&t.metas),
+            FETokenKind::Whitespace => Piece::Space,
+            _ => word(&t.metas),
+        })
+        .collect()
+}
+
+pub fn key_pieces(tokens: &[KToken]) -> Vec<Piece> {
+    tokens
+        .iter()
+        .map(|t| match t.kind {
+            KTokenKind::LParen => Piece::Open,
+            KTokenKind::RParen => close(&t.metas),
+            KTokenKind::Whitespace => Piece::Space,
+            _ => word(&t.metas),
+        })
+        .collect()
+}
+
+/* The keywords in a run of tokens, parameters split on whitespace. A literal
+ * on its own is the value of an implied DFT, like the constants of display
+ * and printer files.
+ */
+pub fn keywords(pieces: Vec<Piece>) -> Vec<Keyword> {
+    let mut out: Vec<Keyword> = vec![];
+    let mut open = false;
+    let mut arg = String::new();
+    for piece in pieces {
+        match (piece, open) {
+            (Piece::Word(word, span), false) if word.starts_with('\'') => out.push(Keyword {
+                name: "DFT".to_string(),
+                args: vec![word],
+                span,
+            }),
+            (Piece::Word(word, span), false) => out.push(Keyword {
+                name: word.to_uppercase(),
+                args: vec![],
+                span,
+            }),
+            (Piece::Open, false) => open = true,
+            (Piece::Word(word, _), true) => arg.push_str(&word),
+            (Piece::Space, true) => {
+                if let (false, Some(last)) = (arg.is_empty(), out.last_mut()) {
+                    last.args.push(std::mem::take(&mut arg));
+                }
+            }
+            (Piece::Close(span), true) => {
+                if let Some(last) = out.last_mut() {
+                    if !arg.is_empty() {
+                        last.args.push(std::mem::take(&mut arg));
+                    }
+                    last.span = Span::from((last.span, span));
+                }
+                open = false;
+            }
+            _ => {}
+        }
+    }
+    out
+}
@@ -3,7 +3,9 @@ mod field_keywords_parser;
 mod fileentry_keywords_parser;
 mod keyfield_keywords_parser;
 mod recordformat_keywords_parser;
-pub use field_keywords_parser::{tokenize_fld_kw, FToken};
-pub use fileentry_keywords_parser::{tokenize_fe_kw, FEToken};
-pub use keyfield_keywords_parser::{tokenize_kf_kw, KToken};
-pub use recordformat_keywords_parser::{tokenize_rf_kw, RFToken};
+mod keywords;
+pub use field_keywords_parser::{tokenize_fld_kw, FToken, FTokenKind};
+pub use fileentry_keywords_parser::{tokenize_fe_kw, FEToken, FETokenKind};
+pub use keyfield_keywords_parser::{tokenize_kf_kw, KToken, KTokenKind};
+pub use recordformat_keywords_parser::{tokenize_rf_kw, RFToken, RFTokenKind};
+pub use keywords::{Keyword, keywords, field_pieces, file_pieces, format_pieces, key_pieces};
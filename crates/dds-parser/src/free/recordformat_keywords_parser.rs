@@ -18,6 +18,7 @@ pub enum RFTokenKind {
     Indicator,
     Colon,
     StringLiteral,
+    Slash,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +38,7 @@ impl RFToken {
             RFTokenKind::LParen => "Normal",
             RFTokenKind::RParen => "Normal",
             RFTokenKind::StringLiteral => "String",
+            RFTokenKind::Slash => "Normal",
         };
         let mut out = vec![];
         for meta in self.metas.iter() {
@@ -102,6 +104,12 @@ fn next_token(lexer: &Lexer) -> Option<RFToken> {
                     }
                 }
             }
+            // slash
+            '/' => {
+                let chars = vec![read_char(lexer)];
+                let kind = RFTokenKind::Slash;
+                (kind, chars)
+            }
             // quote
             '\'' => match peek_until(lexer, '\'') {
                 Some(MetaChar { .. }) => {
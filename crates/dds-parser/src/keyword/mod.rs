@@ -0,0 +1,225 @@
+// The keywords of physical and logical files with their parameters typed
+mod table;
+use crate::dspfdds::unquote;
+use crate::free::Keyword;
+use crate::meta::diagnostic::{Diagnostic, DiagnosticLevel};
+use crate::meta::Span;
+use serde::{Deserialize, Serialize};
+pub use table::{lookup, Known, KNOWN};
+
+// Where a keyword is written
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Level {
+    File,
+    Format,
+    Join,
+    Field,
+    Key,
+    SelectOmit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DdsKeyword {
+    Text(String),
+    // one to three lines
+    Colhdg(Vec<String>),
+    Alias(String),
+    // the code and the optional * or currency symbol
+    Edtcde(char, Option<String>),
+    Edtwrd(String),
+    // values keep their quotes, 'A' and 1 aren't the same
+    Values(Vec<String>),
+    Range(String, String),
+    // the operator, ex: GT, and the value, CMP is the same keyword
+    Comp(String, String),
+    Dft(String),
+    // the CCSID and the optional *CONVERT or *NOCONVERT
+    Ccsid(String, Option<String>),
+    // the allocated length
+    Varlen(Option<usize>),
+    Alwnull,
+    Datfmt(String),
+    Check(Vec<String>),
+    Refshift(String),
+    Unique,
+    // the file whose record format is shared, ex: LIB/FILE
+    Format(String),
+    // anything else, known or not, uppercased with its parameters
+    Other(String, Vec<String>),
+}
+
+impl DdsKeyword {
+    pub fn name(&self) -> &str {
+        match self {
+            DdsKeyword::Text(_) => "TEXT",
+            DdsKeyword::Colhdg(_) => "COLHDG",
+            DdsKeyword::Alias(_) => "ALIAS",
+            DdsKeyword::Edtcde(..) => "EDTCDE",
+            DdsKeyword::Edtwrd(_) => "EDTWRD",
+            DdsKeyword::Values(_) => "VALUES",
+            DdsKeyword::Range(..) => "RANGE",
+            DdsKeyword::Comp(..) => "COMP",
+            DdsKeyword::Dft(_) => "DFT",
+            DdsKeyword::Ccsid(..) => "CCSID",
+            DdsKeyword::Varlen(_) => "VARLEN",
+            DdsKeyword::Alwnull => "ALWNULL",
+            DdsKeyword::Datfmt(_) => "DATFMT",
+            DdsKeyword::Check(_) => "CHECK",
+            DdsKeyword::Refshift(_) => "REFSHIFT",
+            DdsKeyword::Unique => "UNIQUE",
+            DdsKeyword::Format(_) => "FORMAT",
+            DdsKeyword::Other(name, _) => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypedKeyword {
+    pub level: Level,
+    // the record format it's under, empty for file keywords
+    pub format: String,
+    // the field, key field or select/omit field it's on
+    pub field: Option<String>,
+    pub keyword: DdsKeyword,
+    // the name through the closing paren
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeywordModel {
+    pub keywords: Vec<TypedKeyword>,
+    // unknown keywords, wrong parameter counts and parameters that don't parse
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl KeywordModel {
+    // the keywords of a field, or of the record format itself without one, not key or select/omit lines
+    pub fn of<'a>(&'a self, format: &'a str, field: Option<&'a str>) -> impl Iterator<Item = &'a DdsKeyword> {
+        self.keywords
+            .iter()
+            .filter(|k| matches!(k.level, Level::Format | Level::Field))
+            .filter(move |k| k.format.eq_ignore_ascii_case(format))
+            .filter(move |k| match (&k.field, field) {
+                (Some(x), Some(y)) => x.eq_ignore_ascii_case(y),
+                (None, None) => true,
+                _ => false,
+            })
+            .map(|k| &k.keyword)
+    }
+
+    // the file level keywords
+    pub fn file(&self) -> impl Iterator<Item = &DdsKeyword> {
+        self.keywords.iter().filter(|k| k.level == Level::File).map(|k| &k.keyword)
+    }
+}
+
+const EDIT_CODES: &str = "1234ABCDJKLMNOPQWYZ";
+const OPERATORS: [&str; 8] = ["EQ", "NE", "LT", "NL", "GT", "NG", "LE", "GE"];
+
+fn arity(known: &Known) -> String {
+    let plural = |n: usize| if n == 1 { "parameter" } else { "parameters" };
+    match known.max {
+        Some(max) if max == known.min => format!("{} takes {} {}", known.name, max, plural(max)),
+        Some(max) => format!("{} takes {} to {} parameters", known.name, known.min, max),
+        None => format!("{} takes at least {} {}", known.name, known.min, plural(known.min)),
+    }
+}
+
+// the typed keyword, or why its parameters don't fit
+fn typed(name: &str, args: &[String]) -> Result<DdsKeyword, String> {
+    let first = || args[0].clone();
+    let upper = |x: &String| x.to_uppercase();
+    let out = match name {
+        "TEXT" => DdsKeyword::Text(unquote(&args[0])),
+        "COLHDG" => DdsKeyword::Colhdg(args.iter().map(|x| unquote(x)).collect()),
+        "ALIAS" => DdsKeyword::Alias(first()),
+        "EDTCDE" => {
+            let code = args[0].to_uppercase();
+            match (code.len(), code.chars().next()) {
+                (1, Some(c)) if EDIT_CODES.contains(c) => DdsKeyword::Edtcde(c, args.get(1).cloned()),
+                _ => return Err(format!("{} isn't an edit code", args[0])),
+            }
+        }
+        "EDTWRD" => DdsKeyword::Edtwrd(unquote(&args[0])),
+        "VALUES" => DdsKeyword::Values(args.to_vec()),
+        "RANGE" => DdsKeyword::Range(first(), args[1].clone()),
+        "COMP" | "CMP" => {
+            let op = args[0].to_uppercase();
+            if !OPERATORS.contains(&op.as_str()) {
+                return Err(format!("{} isn't a comparison operator", args[0]));
+            }
+            DdsKeyword::Comp(op, args[1].clone())
+        }
+        "DFT" => DdsKeyword::Dft(first()),
+        "CCSID" => DdsKeyword::Ccsid(upper(&args[0]), args.get(1).map(upper)),
+        "VARLEN" => match args.first() {
+            Some(x) => DdsKeyword::Varlen(Some(x.parse().map_err(|_| format!("{} isn't a length", x))?)),
+            None => DdsKeyword::Varlen(None),
+        },
+        "ALWNULL" => DdsKeyword::Alwnull,
+        "DATFMT" => DdsKeyword::Datfmt(upper(&args[0])),
+        "CHECK" => DdsKeyword::Check(args.iter().map(upper).collect()),
+        "REFSHIFT" => DdsKeyword::Refshift(upper(&args[0])),
+        "UNIQUE" => DdsKeyword::Unique,
+        "FORMAT" => DdsKeyword::Format(upper(&args[0])),
+        _ => DdsKeyword::Other(name.to_string(), args.to_vec()),
+    };
+    Ok(out)
+}
+
+// Types keywords as they're found, under the last record format seen
+pub(crate) struct Collector {
+    model: KeywordModel,
+    format: String,
+}
+
+impl Collector {
+    pub(crate) fn new() -> Self {
+        Self { model: KeywordModel { keywords: vec![], diagnostics: vec![] }, format: String::new() }
+    }
+
+    pub(crate) fn format(&mut self, name: &str) {
+        self.format = name.trim().to_uppercase();
+    }
+
+    fn report(&mut self, span: Span, level: DiagnosticLevel, code: &str, msg: String) {
+        self.model.diagnostics.push(Diagnostic { span, level, code: code.to_string(), msg });
+    }
+
+    pub(crate) fn add(&mut self, level: Level, field: Option<&str>, found: Vec<Keyword>) {
+        for Keyword { name, args, span } in found {
+            let keyword = match lookup(&name) {
+                None => {
+                    self.report(span, DiagnosticLevel::Warning, "unknown-keyword", format!("Unknown keyword {}", name));
+                    DdsKeyword::Other(name, args)
+                }
+                Some(known) if args.len() < known.min || known.max.is_some_and(|max| args.len() > max) => {
+                    let msg = format!("{}, found {}", arity(known), args.len());
+                    self.report(span, DiagnosticLevel::Error, "keyword-arity", msg);
+                    DdsKeyword::Other(name, args)
+                }
+                Some(_) => match typed(&name, &args) {
+                    Ok(x) => x,
+                    Err(msg) => {
+                        self.report(span, DiagnosticLevel::Error, "keyword-argument", msg);
+                        DdsKeyword::Other(name, args)
+                    }
+                },
+            };
+            self.model.keywords.push(TypedKeyword {
+                level,
+                format: match level {
+                    Level::File => String::new(),
+                    _ => self.format.clone(),
+                },
+                field: field.map(|x| x.trim().to_uppercase()),
+                keyword,
+                span,
+            });
+        }
+    }
+
+    pub(crate) fn finish(self) -> KeywordModel {
+        self.model
+    }
+}
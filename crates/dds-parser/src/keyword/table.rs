@@ -0,0 +1,77 @@
+use super::Level::{self, *};
+
+// A keyword physical and logical files know, where it goes and its parameter count
+pub struct Known {
+    pub name: &'static str,
+    pub levels: &'static [Level],
+    pub min: usize,
+    // None is as many as you like
+    pub max: Option<usize>,
+}
+
+const fn known(name: &'static str, levels: &'static [Level], min: usize, max: Option<usize>) -> Known {
+    Known { name, levels, min, max }
+}
+
+pub const KNOWN: &[Known] = &[
+    // file
+    known("ALTSEQ", &[File], 1, Some(1)),
+    known("DYNSLT", &[File], 0, Some(0)),
+    known("FCFO", &[File], 0, Some(0)),
+    known("FIFO", &[File], 0, Some(0)),
+    known("JDFTVAL", &[File], 0, Some(0)),
+    known("LIFO", &[File], 0, Some(0)),
+    known("REF", &[File], 1, Some(2)),
+    known("REFACCPTH", &[File], 1, Some(1)),
+    known("UNIQUE", &[File], 0, Some(0)),
+    // record format
+    known("FORMAT", &[Format], 1, Some(1)),
+    known("JFILE", &[Format], 2, Some(32)),
+    known("PFILE", &[Format], 1, Some(32)),
+    // join
+    known("JDUPSEQ", &[Join], 1, Some(2)),
+    known("JFLD", &[Join], 2, Some(2)),
+    known("JOIN", &[Join], 1, Some(2)),
+    // field
+    known("ALIAS", &[Field], 1, Some(1)),
+    known("ALWNULL", &[Field], 0, Some(0)),
+    known("CCSID", &[Field], 1, Some(2)),
+    known("CHECK", &[Field], 1, None),
+    known("CHKMSGID", &[Field], 1, Some(3)),
+    known("COLHDG", &[Field], 1, Some(3)),
+    known("CONCAT", &[Field], 2, None),
+    known("DATFMT", &[Field], 1, Some(1)),
+    known("DATSEP", &[Field], 1, Some(1)),
+    known("DFT", &[Field], 1, Some(1)),
+    known("EDTCDE", &[Field], 1, Some(2)),
+    known("EDTWRD", &[Field], 1, Some(1)),
+    known("FLTPCN", &[File, Field], 1, Some(1)),
+    known("JREF", &[Field], 1, Some(1)),
+    known("REFFLD", &[Field], 1, Some(2)),
+    known("REFSHIFT", &[Field], 1, Some(1)),
+    known("RENAME", &[Field], 1, Some(1)),
+    known("SST", &[Field], 2, Some(3)),
+    known("TEXT", &[Format, Field], 1, Some(1)),
+    known("TIMFMT", &[Field], 1, Some(1)),
+    known("TIMSEP", &[Field], 1, Some(1)),
+    known("TRNTBL", &[Field], 1, Some(1)),
+    known("VARLEN", &[Field], 0, Some(1)),
+    // validity checking, and select/omit tests on logical files
+    known("CMP", &[Field, SelectOmit], 2, Some(2)),
+    known("COMP", &[Field, SelectOmit], 2, Some(2)),
+    known("RANGE", &[Field, SelectOmit], 2, Some(2)),
+    known("VALUES", &[Field, SelectOmit], 1, Some(100)),
+    known("ALL", &[SelectOmit], 0, Some(0)),
+    // key field
+    known("ABSVAL", &[Key], 0, Some(0)),
+    known("DESCEND", &[Key], 0, Some(0)),
+    known("DIGIT", &[Key], 0, Some(0)),
+    known("NOALTSEQ", &[Key], 0, Some(0)),
+    known("SIGNED", &[Key], 0, Some(0)),
+    known("UNSIGNED", &[Key], 0, Some(0)),
+    known("ZONE", &[Key], 0, Some(0)),
+];
+
+pub fn lookup(name: &str) -> Option<&'static Known> {
+    KNOWN.iter().find(|k| k.name == name)
+}
@@ -0,0 +1,175 @@
+use super::Entry;
+use crate::field::{FieldResult, Nametype};
+use crate::line::{ContinuationLine, DDSLine};
+use crate::meta::{IHighlight, Span};
+use crate::pfdds::{Field, FileEntry, Keyfield, RecordFormat, CST};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AST {
+    pub entries: Vec<Entry>,
+}
+
+enum Phase {
+    FileEntry,
+    Main,
+    // the last entry was a select (true) or omit (false) line
+    SelectOmit(bool),
+}
+
+struct ParserState {
+    idx: usize,
+    phase: Phase,
+}
+
+struct Parser {
+    state: RefCell<ParserState>,
+    input: Vec<DDSLine>,
+}
+
+fn peek_n(parser: &Parser, n: usize) -> Option<&DDSLine> {
+    let idx = parser.state.borrow().idx;
+    parser.input.get(idx + n)
+}
+
+fn read_line(parser: &Parser) -> &DDSLine {
+    let out = peek_n(parser, 0).expect("read_line() requires a length check first");
+    parser.state.borrow_mut().idx += 1;
+    out
+}
+
+fn read_continuations(parser: &Parser) -> Vec<&ContinuationLine> {
+    let mut continuations = vec![];
+    while let Some(DDSLine::Continuation(peeked)) = peek_n(parser, 0) {
+        let _ = read_line(parser);
+        continuations.push(peeked);
+    }
+    continuations
+}
+
+fn read_entry(parser: &Parser) -> Option<Entry> {
+    let phase = match &parser.state.borrow().phase {
+        Phase::FileEntry => None,
+        Phase::Main => Some(None),
+        Phase::SelectOmit(select) => Some(Some(*select)),
+    };
+    let entry = match read_line(parser) {
+        DDSLine::Continuation(cur) => {
+            let mut continuations = vec![cur];
+            continuations.append(&mut read_continuations(parser));
+            match phase {
+                None => Entry::FE(FileEntry::from(continuations)),
+                // nothing left to continue
+                Some(_) => return next_entry(parser),
+            }
+        }
+        DDSLine::RecordFormat(cur) => {
+            parser.state.borrow_mut().phase = Phase::Main;
+            Entry::R(RecordFormat::from((cur, read_continuations(parser))))
+        }
+        DDSLine::Join(cur) => Entry::J(Field::from((cur, read_continuations(parser)))),
+        DDSLine::Key(cur) => Entry::K(Keyfield::from((cur, read_continuations(parser)))),
+        DDSLine::SelectOmit(cur) => {
+            let select = matches!(&cur.name_type, FieldResult::Ok(x) if x.value == Nametype::S);
+            parser.state.borrow_mut().phase = Phase::SelectOmit(select);
+            let field = Field::from((cur, read_continuations(parser)));
+            match select {
+                true => Entry::S(field),
+                false => Entry::O(field),
+            }
+        }
+        // after select/omit lines, a line without a name type is another comparison
+        DDSLine::Field(cur) => {
+            let field = Field::from((cur, read_continuations(parser)));
+            match phase {
+                Some(Some(true)) => Entry::S(field),
+                Some(Some(false)) => Entry::O(field),
+                _ => Entry::F(field),
+            }
+        }
+        // constants belong to display and printer files
+        DDSLine::Constant(_) => {
+            let _ = read_continuations(parser);
+            return next_entry(parser);
+        }
+        DDSLine::Comment(_) | DDSLine::Idk(_) => return next_entry(parser),
+    };
+    Some(entry)
+}
+
+fn next_entry(parser: &Parser) -> Option<Entry> {
+    match peek_n(parser, 0) {
+        Some(_) => read_entry(parser),
+        None => None,
+    }
+}
+
+impl From<&CST> for AST {
+    fn from(value: &CST) -> Self {
+        let cst = value;
+        let state = ParserState {
+            idx: 0,
+            phase: Phase::FileEntry,
+        };
+        let parser = Parser {
+            state: RefCell::new(state),
+            input: cst
+                .lines
+                .iter()
+                .filter(|line| !matches!(line, DDSLine::Comment(_) | DDSLine::Idk(_)))
+                .cloned()
+                .collect::<Vec<DDSLine>>(),
+        };
+        let mut entries = vec![];
+        while let Some(entry) = next_entry(&parser) {
+            entries.push(entry);
+        }
+        AST { entries }
+    }
+}
+
+type SpanShape = ((usize, usize), (usize, usize), String);
+pub fn highlight_ast(ast: AST) -> Vec<SpanShape> {
+    ast.entries
+        .iter()
+        .flat_map(|e| e.highlight())
+        .map(|tup| {
+            (
+                (tup.0.start.row, tup.0.start.col),
+                (tup.0.end.row, tup.0.end.col),
+                tup.1,
+            )
+        })
+        .collect::<Vec<_>>()
+}
+
+// a field of the logical file, or one of its record formats
+pub fn query_definition(ast: &AST, pattern: &str) -> Option<Span> {
+    let pattern = pattern.to_uppercase();
+    for entry in ast.entries.iter() {
+        let name = match entry {
+            Entry::R(rf) => &rf.name,
+            Entry::F(fld) => &fld.name,
+            _ => continue,
+        };
+        if let FieldResult::Ok(namefield) = name {
+            if namefield.value.trim().to_uppercase() == pattern {
+                return Some(namefield.meta.span);
+            }
+        }
+    }
+    None
+}
+
+pub fn query_record_formats(ast: &AST) -> Vec<String> {
+    let mut out = vec![];
+    for entry in ast.entries.iter() {
+        if let Entry::R(rf) = entry {
+            if let FieldResult::Ok(namefield) = &rf.name {
+                out.push(namefield.value.clone());
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,450 @@
+use super::{
+    query_keywords, query_logical_file, resolve_fields, Condition, FieldSource, LogicalField,
+    LogicalFormat, ResolvedField, SelectOmit, Test, AST,
+};
+use crate::keyword::{DdsKeyword, KeywordModel, Level};
+use crate::pfdds::{self, create_index, object, Script};
+use std::collections::HashMap;
+
+// the keywords a statement takes in, the rest only get a warning
+fn mapped(level: Level, keyword: &DdsKeyword) -> bool {
+    let name = keyword.name();
+    match level {
+        Level::File => ["UNIQUE", "DYNSLT", "JDFTVAL", "FIFO", "LIFO", "FCFO"].contains(&name),
+        Level::Format => ["PFILE", "JFILE", "TEXT"].contains(&name),
+        Level::Join => ["JOIN", "JFLD"].contains(&name),
+        Level::Field => ["RENAME", "SST", "CONCAT", "JREF"].contains(&name),
+        Level::Key => ["DESCEND", "ABSVAL", "SIGNED"].contains(&name),
+        Level::SelectOmit => ["COMP", "CMP", "VALUES", "RANGE", "ALL"].contains(&name),
+    }
+}
+
+fn unmapped(keywords: &KeywordModel, format: &str, script: &mut Script) {
+    for typed in keywords.keywords.iter() {
+        if typed.level != Level::File && typed.format != format
+            || mapped(typed.level, &typed.keyword)
+        {
+            continue;
+        }
+        let msg = match &typed.field {
+            Some(field) => format!(
+                "{} on {} has no SQL equivalent",
+                typed.keyword.name(),
+                field
+            ),
+            None => format!("{} has no SQL equivalent", typed.keyword.name()),
+        };
+        script.warn(msg);
+    }
+}
+
+// the value of a field in the based-on file, qualified with its table
+fn expression(field: &LogicalField, table: &str) -> String {
+    match &field.source {
+        FieldSource::Same => format!("{}{}", table, field.name),
+        FieldSource::Rename(x) => format!("{}{}", table, x),
+        FieldSource::Concat(fields) => fields
+            .iter()
+            .map(|x| format!("{}{}", table, x))
+            .collect::<Vec<String>>()
+            .join(" CONCAT "),
+        FieldSource::Substring {
+            field,
+            start,
+            length,
+        } if length.is_empty() => {
+            format!("SUBSTR({}{}, {})", table, field, start)
+        }
+        FieldSource::Substring {
+            field,
+            start,
+            length,
+        } => format!("SUBSTR({}{}, {}, {})", table, field, start, length),
+    }
+}
+
+fn column(field: &LogicalField, table: &str) -> String {
+    match field.source {
+        FieldSource::Same => expression(field, table),
+        _ => format!("{} AS {}", expression(field, table), field.name),
+    }
+}
+
+fn operator(op: &str) -> &str {
+    match op {
+        "EQ" => "=",
+        "NE" => "<>",
+        "LT" => "<",
+        "NL" | "GE" => ">=",
+        "GT" => ">",
+        "NG" | "LE" => "<=",
+        _ => op,
+    }
+}
+
+fn condition(condition: &Condition, expression: &dyn Fn(&str) -> String) -> String {
+    let field = expression(&condition.field);
+    match &condition.test {
+        Test::Comp { op, value } => format!("{} {} {}", field, operator(op), value),
+        Test::Values(values) => format!("{} IN ({})", field, values.join(", ")),
+        Test::Range { low, high } => format!("{} BETWEEN {} AND {}", field, low, high),
+        Test::All => "1 = 1".to_string(),
+    }
+}
+
+fn statement(statement: &SelectOmit, expression: &dyn Fn(&str) -> String) -> String {
+    let conditions = statement
+        .conditions
+        .iter()
+        .map(|c| condition(c, expression))
+        .collect::<Vec<String>>();
+    match conditions.len() {
+        1 => conditions[0].clone(),
+        _ => format!("({})", conditions.join(" AND ")),
+    }
+}
+
+/* Select/omit statements as a search condition. The first statement a record
+ * satisfies decides, and one that satisfies none is omitted when the last
+ * statement selects and selected when it omits.
+ */
+fn search_condition(
+    select_omit: &[SelectOmit],
+    expression: &dyn Fn(&str) -> String,
+) -> Option<String> {
+    let all = |x: &SelectOmit| x.conditions.iter().any(|c| c.test == Test::All);
+    if select_omit.is_empty() {
+        return None;
+    }
+    if select_omit.iter().all(|x| x.select && !all(x)) {
+        let statements = select_omit.iter().map(|x| statement(x, expression));
+        return Some(statements.collect::<Vec<String>>().join("\n     OR "));
+    }
+    if select_omit.iter().all(|x| !x.select && !all(x)) {
+        let statements = select_omit
+            .iter()
+            .map(|x| format!("NOT {}", statement(x, expression)));
+        return Some(statements.collect::<Vec<String>>().join("\n    AND "));
+    }
+    let mut cases = vec![];
+    let mut otherwise = !select_omit.last().map(|x| x.select).unwrap_or(true);
+    for x in select_omit.iter() {
+        if all(x) {
+            otherwise = x.select;
+            break;
+        }
+        cases.push(format!(
+            "WHEN {} THEN {}",
+            statement(x, expression),
+            x.select as u8
+        ));
+    }
+    Some(format!(
+        "CASE {} ELSE {} END = 1",
+        cases.join(" "),
+        otherwise as u8
+    ))
+}
+
+// the position of a file in JFILE, from JOIN(1 2) or JOIN(COWEVT BORNEVT)
+fn position(files: &[String], file: &str) -> Option<usize> {
+    file.parse::<usize>()
+        .ok()
+        .or_else(|| files.iter().position(|x| *x == object(file)).map(|x| x + 1))
+}
+
+fn join_view(
+    name: &str,
+    format: &LogicalFormat,
+    joined: bool,
+    resolved: &[ResolvedField],
+    script: &mut Script,
+) -> String {
+    let files = format
+        .jfile
+        .iter()
+        .map(|x| object(x))
+        .collect::<Vec<String>>();
+    // the table each field comes from, ex: T2.
+    let tables = format
+        .fields
+        .iter()
+        .map(|field| {
+            let from = field.jref.or_else(|| {
+                resolved
+                    .iter()
+                    .find(|x| x.format == format.name && x.name == field.name)
+                    .and_then(|x| x.file.as_ref())
+                    .and_then(|x| position(&files, x))
+            });
+            match from {
+                Some(n) => format!("T{}.", n),
+                None => {
+                    script.warn(format!(
+                        "the join file of {} isn't known, it's left unqualified",
+                        field.name
+                    ));
+                    String::new()
+                }
+            }
+        })
+        .collect::<Vec<String>>();
+    let columns = format
+        .fields
+        .iter()
+        .zip(tables.iter())
+        .map(|(x, table)| column(x, table))
+        .collect::<Vec<String>>();
+    let mut from = format!("{} T1", files.first().cloned().unwrap_or_default());
+    let kind = if joined {
+        "LEFT OUTER JOIN"
+    } else {
+        "INNER JOIN"
+    };
+    for join in format.joins.iter() {
+        let (Some(left), Some(right)) = (position(&files, &join.from), position(&files, &join.to))
+        else {
+            script.warn(format!(
+                "JOIN({} {}) doesn't name files of JFILE",
+                join.from, join.to
+            ));
+            continue;
+        };
+        let on = join
+            .fields
+            .iter()
+            .map(|(a, b)| format!("T{}.{} = T{}.{}", left, a, right, b))
+            .collect::<Vec<String>>();
+        let target = right
+            .checked_sub(1)
+            .and_then(|x| files.get(x))
+            .cloned()
+            .unwrap_or_default();
+        from.push_str(&format!(
+            "\n  {} {} T{}\n    ON {}",
+            kind,
+            target,
+            right,
+            on.join("\n   AND ")
+        ));
+    }
+    let expression = |name: &str| match format.fields.iter().position(|x| x.name == name) {
+        Some(n) => expression(&format.fields[n], &tables[n]),
+        None => name.to_string(),
+    };
+    let mut out = format!(
+        "CREATE VIEW {} AS\n  SELECT {}\n  FROM {}",
+        name,
+        columns.join(",\n         "),
+        from
+    );
+    if let Some(x) = search_condition(&format.select_omit, &expression) {
+        out.push_str(&format!("\n  WHERE {}", x));
+    }
+    out
+}
+
+/* Db2 for i SQL for the logical file `file`, an index when it's keyed over a
+ * single file and uses its fields as they are, a view otherwise, with its
+ * select/omit statements as the search condition. Join files are looked up in
+ * `physicals` to know which file a field comes from. What can't be converted
+ * is left as warning comments at the top.
+ */
+pub fn to_sql(file: &str, ast: &AST, physicals: &HashMap<String, pfdds::AST>) -> String {
+    let name = file.to_uppercase();
+    let mut script = Script::default();
+    let lf = query_logical_file(ast);
+    let keywords = query_keywords(ast);
+    let Some(format) = lf.formats.first() else {
+        script.warn("there's no record format to convert".to_string());
+        return script.render();
+    };
+    if lf.formats.len() > 1 {
+        script.warn(format!(
+            "SQL has no multiple format files, only {} is converted",
+            format.name
+        ));
+    }
+    unmapped(&keywords, &format.name, &mut script);
+    for field in format.fields.iter() {
+        if !field.detail.is_empty()
+            && matches!(field.source, FieldSource::Same | FieldSource::Rename(_))
+        {
+            script.warn(format!(
+                "{} redefines its type, the column keeps the type of the physical file",
+                field.name
+            ));
+        }
+    }
+    let path = format.access_path(&lf);
+    let same = format.fields.iter().all(|x| x.source == FieldSource::Same);
+    let index = format.jfile.is_empty() && path.is_keyed() && same;
+    let mut create = if !format.jfile.is_empty() {
+        let joined = keywords.file().any(|x| x.name() == "JDFTVAL");
+        let resolved = resolve_fields(&lf, physicals);
+        join_view(&name, format, joined, &resolved, &mut script)
+    } else {
+        if format.pfile.len() > 1 {
+            script.warn(format!(
+                "only the first of PFILE({}) is converted",
+                format.pfile.join(" ")
+            ));
+        }
+        let table = format.pfile.first().map(|x| object(x)).unwrap_or_default();
+        let expression = |name: &str| match format.fields.iter().find(|x| x.name == name) {
+            Some(field) => expression(field, ""),
+            None => name.to_string(),
+        };
+        let condition = search_condition(&format.select_omit, &expression);
+        if index {
+            let keys = path
+                .keys
+                .iter()
+                .map(|k| k.name.to_uppercase())
+                .collect::<Vec<String>>();
+            let mut out =
+                create_index(&name, &table, &path, &keys, &mut script).unwrap_or_default();
+            if let Some(x) = condition {
+                out.push_str(&format!("\n  WHERE {}", x));
+            }
+            out
+        } else {
+            let columns = match format.fields.is_empty() {
+                true => vec!["*".to_string()],
+                false => format.fields.iter().map(|x| column(x, "")).collect(),
+            };
+            let mut out = format!(
+                "CREATE VIEW {} AS\n  SELECT {}\n  FROM {}",
+                name,
+                columns.join(",\n         "),
+                table
+            );
+            if let Some(x) = condition {
+                out.push_str(&format!("\n  WHERE {}", x));
+            }
+            out
+        }
+    };
+    if !index && path.is_keyed() {
+        script.warn(format!(
+            "a view has no keys, the keys of {} aren't converted",
+            format.name
+        ));
+    }
+    create.push_str(&format!("\n  RCDFMT {}", format.name));
+    if index {
+        match format.fields.is_empty() {
+            true => create.push_str(" ADD ALL COLUMNS"),
+            false => {
+                let fields = format
+                    .fields
+                    .iter()
+                    .map(|x| x.name.clone())
+                    .collect::<Vec<String>>();
+                create.push_str(&format!(" ADD {}", fields.join(", ")));
+            }
+        }
+    }
+    script.statements.push(create);
+    if let Some(DdsKeyword::Text(text)) =
+        keywords.of(&format.name, None).find(|x| x.name() == "TEXT")
+    {
+        let kind = if index { "INDEX" } else { "TABLE" };
+        script.statements.push(format!(
+            "LABEL ON {} {} IS {}",
+            kind,
+            name,
+            pfdds::sql_string(text)
+        ));
+    }
+    for statement in script.statements.iter_mut() {
+        statement.push(';');
+    }
+    script.render()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pfdds::CST;
+
+    fn parse(input: &str) -> AST {
+        AST::from(&CST::try_from(input).unwrap())
+    }
+
+    fn physical(input: &str) -> pfdds::AST {
+        pfdds::AST::from(&CST::try_from(input).unwrap())
+    }
+
+    #[test]
+    fn test_to_sql_index() {
+        let input = r#"
+     A                                      UNIQUE
+     A          R EVTFMT                    PFILE(PRODLIB/COWEVT)
+     A                                      TEXT('Born events')
+     A          K EVTCOW
+     A          K EVTDAT                    DESCEND
+     A          S ETYP                      COMP(EQ 'BORN')
+     A          S ETYP                      VALUES('BOUGHT' 'SOLD')
+     A            EDAT                      RANGE(20240101 20241231)
+"#;
+        let expected = r#"CREATE UNIQUE INDEX COWBRN
+  ON COWEVT (EVTCOW, EVTDAT DESC)
+  WHERE ETYP = 'BORN'
+     OR (ETYP IN ('BOUGHT', 'SOLD') AND EDAT BETWEEN 20240101 AND 20241231)
+  RCDFMT EVTFMT ADD ALL COLUMNS;
+
+LABEL ON INDEX COWBRN IS 'Born events';
+"#;
+        assert_eq!(to_sql("cowbrn", &parse(input), &HashMap::new()), expected);
+    }
+
+    #[test]
+    fn test_to_sql_view_snapshot() {
+        let cowevt = physical(
+            r#"
+     A          R EVTFMT
+     A            ID             8  0
+     A            EDAT           8S 0
+     A            ETYP          10A
+"#,
+        );
+        let bornevt = physical(
+            r#"
+     A          R BORNFMT
+     A            EID            8  0
+     A            BNAME         10A
+"#,
+        );
+        let physicals = HashMap::from([
+            ("COWEVT".to_string(), cowevt),
+            ("BORNEVT".to_string(), bornevt),
+        ]);
+        let views = [
+            r#"
+     A          R EVTFMT                    PFILE(COWEVT)
+     A            ID
+     A            EVTDAY                    RENAME(EDAT)
+     A            EVTYEAR        4A         SST(EDAT 1 4)
+     A                                      EDTCDE(Z)
+     A          K ID
+     A          O ETYP                      COMP(EQ 'DEAD')
+     A          S EVTYEAR                   COMP(GE '2020')
+     A          O                           ALL
+"#,
+            r#"
+     A                                      JDFTVAL
+     A          R BRNJFMT                   JFILE(COWEVT BORNEVT)
+     A          J                           JOIN(COWEVT BORNEVT)
+     A                                      JFLD(ID EID)
+     A            ID                        JREF(1)
+     A            BNAME
+     A          S BNAME                     VALUES('Daisy' 'Rosie')
+"#,
+        ];
+        let observed = views
+            .map(|x| to_sql("COWVIEW", &parse(x), &physicals))
+            .join("\n");
+        insta::assert_snapshot!(observed);
+    }
+}
@@ -0,0 +1,28 @@
+use crate::pfdds::{Field, FileEntry, Keyfield, RecordFormat};
+use crate::meta::{IHighlight, Span};
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Entry {
+    FE(FileEntry),
+    R(RecordFormat),
+    J(Field),
+    F(Field),
+    K(Keyfield),
+    // select/omit, a line without a name type is ANDed with the one before it
+    S(Field),
+    O(Field),
+}
+impl IHighlight for Entry {
+    fn highlight(&self) -> Vec<(Span, String)> {
+        match self {
+            Entry::FE(e) => e.highlight(),
+            Entry::R(e) => e.highlight(),
+            Entry::J(e) => e.highlight(),
+            Entry::F(e) => e.highlight(),
+            Entry::K(e) => e.highlight(),
+            Entry::S(e) => e.highlight(),
+            Entry::O(e) => e.highlight(),
+        }
+    }
+}
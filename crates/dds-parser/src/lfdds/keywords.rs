@@ -0,0 +1,91 @@
+use super::{Entry, AST};
+use crate::field::FieldResult;
+use crate::free::{field_pieces, file_pieces, format_pieces, key_pieces, keywords};
+use crate::keyword::{Collector, KeywordModel, Level};
+use crate::pfdds::Field;
+
+fn name(fld: &Field) -> Option<&str> {
+    match &fld.name {
+        FieldResult::Ok(x) => Some(x.value.as_str()),
+        FieldResult::Idk(_) => None,
+    }
+}
+
+fn field(out: &mut Collector, level: Level, fld: &Field) {
+    if let FieldResult::Ok(kw) = &fld.keywords {
+        out.add(level, name(fld), keywords(field_pieces(&kw.tokens)));
+    }
+}
+
+// The typed keywords of a logical file with what's wrong with them
+pub fn query_keywords(ast: &AST) -> KeywordModel {
+    let mut out = Collector::new();
+    for entry in ast.entries.iter() {
+        match entry {
+            Entry::FE(fe) => {
+                if let FieldResult::Ok(kw) = &fe.keywords {
+                    out.add(Level::File, None, keywords(file_pieces(&kw.tokens)));
+                }
+            }
+            Entry::R(rf) => {
+                if let FieldResult::Ok(name) = &rf.name {
+                    out.format(&name.value);
+                }
+                if let FieldResult::Ok(kw) = &rf.keywords {
+                    out.add(Level::Format, None, keywords(format_pieces(&kw.tokens)));
+                }
+            }
+            Entry::J(fld) => field(&mut out, Level::Join, fld),
+            Entry::F(fld) => field(&mut out, Level::Field, fld),
+            Entry::S(fld) | Entry::O(fld) => field(&mut out, Level::SelectOmit, fld),
+            Entry::K(kf) => {
+                let name = match &kf.name {
+                    FieldResult::Ok(x) => Some(x.value.as_str()),
+                    FieldResult::Idk(_) => None,
+                };
+                if let FieldResult::Ok(kw) = &kf.keywords {
+                    out.add(Level::Key, name, keywords(key_pieces(&kw.tokens)));
+                }
+            }
+        }
+    }
+    out.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyword::DdsKeyword;
+    use crate::pfdds::CST;
+
+    #[test]
+    fn test_keywords() {
+        let input = r#"
+     A                                      DYNSLT
+     A          R BRNJFMT                   JFILE(PRODLIB/COWEVT BORNEVT)
+     A          J                           JOIN(1 2)
+     A                                      JFLD(ID EID)
+     A            ID                        JREF(1)
+     A            EVTYEAR                   SST(EDAT 1 4)
+     A          K ID
+     A          S EVTYEAR                   COMP(GT '2020')
+"#[1..]
+            .to_string();
+        let ast = AST::from(&CST::try_from(input.as_str()).unwrap());
+        let model = query_keywords(&ast);
+        assert_eq!(model.diagnostics, vec![]);
+        let levels = model.keywords.iter().map(|k| (k.level, k.keyword.name())).collect::<Vec<_>>();
+        let expected = [
+            (Level::File, "DYNSLT"),
+            (Level::Format, "JFILE"),
+            (Level::Join, "JOIN"),
+            (Level::Join, "JFLD"),
+            (Level::Field, "JREF"),
+            (Level::Field, "SST"),
+            (Level::SelectOmit, "COMP"),
+        ];
+        assert_eq!(levels, expected);
+        let comp = model.keywords.last().map(|k| &k.keyword);
+        assert_eq!(comp, Some(&DdsKeyword::Comp("GT".to_string(), "'2020'".to_string())));
+    }
+}
@@ -0,0 +1,312 @@
+use super::{Entry, AST};
+use crate::field::{FieldResult, Nametype};
+use crate::free::{field_pieces, format_pieces, keywords, Keyword};
+use crate::meta::Span;
+use crate::pfdds::{detail, file_keywords, key, AccessPath, DuplicateOrder, Field, Key};
+use serde::{Deserialize, Serialize};
+
+// Where the value of a logical file field comes from in the based-on file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FieldSource {
+    // the field with the same name
+    Same,
+    // RENAME(EVTDAT)
+    Rename(String),
+    // CONCAT(EVTDAT EVTTIM)
+    Concat(Vec<String>),
+    // SST(EVTDAT 1 4)
+    Substring {
+        field: String,
+        start: String,
+        length: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogicalField {
+    pub name: String,
+    pub span: Span,
+    pub source: FieldSource,
+    // JREF(2), the position of the join file it comes from
+    pub jref: Option<usize>,
+    // type and length when the line overrides them, ex: 9P 0
+    pub detail: String,
+}
+
+// JOIN(1 2) and its JFLD and JDUPSEQ keywords
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Join {
+    pub from: String,
+    pub to: String,
+    // (from field, to field)
+    pub fields: Vec<(String, String)>,
+    pub dupseq: Vec<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Test {
+    // COMP(EQ 'BORN')
+    Comp { op: String, value: String },
+    // VALUES('BORN' 'SOLD')
+    Values(Vec<String>),
+    // RANGE(20240101 20241231)
+    Range { low: String, high: String },
+    // ALL, every record not selected or omitted by an earlier statement
+    All,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Condition {
+    // empty for ALL
+    pub field: String,
+    pub test: Test,
+    pub span: Span,
+}
+
+// A select or omit statement, its conditions are ANDed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelectOmit {
+    pub select: bool,
+    pub conditions: Vec<Condition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogicalFormat {
+    pub name: String,
+    pub span: Span,
+    // PFILE(COWEVT), the based-on physical files
+    pub pfile: Vec<String>,
+    // JFILE(COWEVT BORNEVT), the joined physical files in order
+    pub jfile: Vec<String>,
+    pub joins: Vec<Join>,
+    // empty when every field of the based-on format is used
+    pub fields: Vec<LogicalField>,
+    pub keys: Vec<Key>,
+    pub select_omit: Vec<SelectOmit>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogicalFile {
+    pub unique: bool,
+    pub duplicates: Option<DuplicateOrder>,
+    pub formats: Vec<LogicalFormat>,
+}
+
+impl LogicalFormat {
+    // the files fields come from, JFILE for a join logical file
+    pub fn based_on(&self) -> &[String] {
+        match self.jfile.is_empty() {
+            true => &self.pfile,
+            false => &self.jfile,
+        }
+    }
+
+    pub fn access_path(&self, file: &LogicalFile) -> AccessPath {
+        AccessPath {
+            keys: self.keys.clone(),
+            unique: file.unique,
+            duplicates: file.duplicates,
+        }
+    }
+}
+
+fn field_keywords(fld: &Field) -> Vec<Keyword> {
+    match &fld.keywords {
+        FieldResult::Ok(kw) => keywords(field_pieces(&kw.tokens)),
+        FieldResult::Idk(_) => vec![],
+    }
+}
+
+fn name(fld: &Field) -> (String, Span) {
+    match &fld.name {
+        FieldResult::Ok(x) => (x.value.trim().to_uppercase(), x.meta.span),
+        FieldResult::Idk(x) => (String::new(), x.meta.span),
+    }
+}
+
+fn logical_field(fld: &Field) -> Option<LogicalField> {
+    let (name, span) = name(fld);
+    if name.is_empty() {
+        return None;
+    }
+    let mut source = FieldSource::Same;
+    let mut jref = None;
+    for Keyword { name: keyword, args, .. } in field_keywords(fld) {
+        match (keyword.as_str(), args.as_slice()) {
+            ("RENAME", [from]) => source = FieldSource::Rename(from.to_uppercase()),
+            ("CONCAT", fields) if !fields.is_empty() => {
+                source = FieldSource::Concat(fields.iter().map(|x| x.to_uppercase()).collect())
+            }
+            ("SST", [field, start, length]) => {
+                source = FieldSource::Substring {
+                    field: field.to_uppercase(),
+                    start: start.clone(),
+                    length: length.clone(),
+                }
+            }
+            ("SST", [field, start]) => {
+                source = FieldSource::Substring {
+                    field: field.to_uppercase(),
+                    start: start.clone(),
+                    length: String::new(),
+                }
+            }
+            ("JREF", [n]) => jref = n.parse::<usize>().ok(),
+            _ => {}
+        }
+    }
+    Some(LogicalField { name, span, source, jref, detail: detail(fld) })
+}
+
+fn join(fld: &Field) -> Join {
+    let mut out: Option<Join> = None;
+    let mut fields = vec![];
+    let mut dupseq = vec![];
+    for Keyword { name: keyword, args, .. } in field_keywords(fld) {
+        match (keyword.as_str(), args.as_slice()) {
+            ("JOIN", [from, to]) => {
+                out = Some(Join {
+                    from: from.to_uppercase(),
+                    to: to.to_uppercase(),
+                    fields: vec![],
+                    dupseq: vec![],
+                    span: name(fld).1,
+                })
+            }
+            ("JFLD", [from, to]) => fields.push((from.to_uppercase(), to.to_uppercase())),
+            ("JDUPSEQ", args) => dupseq.extend(args.iter().map(|x| x.to_uppercase())),
+            _ => {}
+        }
+    }
+    // a single join between two files can leave out JOIN
+    let mut out = out.unwrap_or_else(|| Join {
+        from: "1".to_string(),
+        to: "2".to_string(),
+        fields: vec![],
+        dupseq: vec![],
+        span: name(fld).1,
+    });
+    out.fields = fields;
+    out.dupseq = dupseq;
+    out
+}
+
+fn conditions(fld: &Field) -> Vec<Condition> {
+    let (field, span) = name(fld);
+    field_keywords(fld)
+        .into_iter()
+        .filter_map(|Keyword { name: keyword, args, .. }| {
+            let test = match (keyword.as_str(), args.as_slice()) {
+                ("COMP", [op, value]) => Test::Comp { op: op.to_uppercase(), value: value.clone() },
+                ("VALUES", values) => Test::Values(values.to_vec()),
+                ("RANGE", [low, high]) => Test::Range { low: low.clone(), high: high.clone() },
+                ("ALL", []) => Test::All,
+                _ => return None,
+            };
+            Some(Condition { field: field.clone(), test, span })
+        })
+        .collect()
+}
+
+// a select/omit line with a name type starts a statement, one without is ANDed to it
+fn starts_statement(fld: &Field) -> bool {
+    match &fld.name_type {
+        FieldResult::Ok(x) => x.value != Nametype::Empty,
+        FieldResult::Idk(_) => true,
+    }
+}
+
+/* The record formats of a logical file with what they're based on, their
+ * joins, fields, keys and select/omit statements.
+ */
+pub fn query_logical_file(ast: &AST) -> LogicalFile {
+    let mut path = AccessPath { keys: vec![], unique: false, duplicates: None };
+    let mut formats: Vec<LogicalFormat> = vec![];
+    for entry in ast.entries.iter() {
+        if let Entry::FE(fe) = entry {
+            file_keywords(fe, &mut path);
+            continue;
+        }
+        if let Entry::R(rf) = entry {
+            let (name, span) = match &rf.name {
+                FieldResult::Ok(x) => (x.value.trim().to_uppercase(), x.meta.span),
+                FieldResult::Idk(x) => (String::new(), x.meta.span),
+            };
+            let mut format = LogicalFormat {
+                name,
+                span,
+                pfile: vec![],
+                jfile: vec![],
+                joins: vec![],
+                fields: vec![],
+                keys: vec![],
+                select_omit: vec![],
+            };
+            if let FieldResult::Ok(kw) = &rf.keywords {
+                for Keyword { name: keyword, args, .. } in keywords(format_pieces(&kw.tokens)) {
+                    let files = args.iter().map(|x| x.to_uppercase());
+                    match keyword.as_str() {
+                        "PFILE" => format.pfile.extend(files),
+                        "JFILE" => format.jfile.extend(files),
+                        _ => {}
+                    }
+                }
+            }
+            formats.push(format);
+            continue;
+        }
+        let Some(format) = formats.last_mut() else {
+            continue;
+        };
+        match entry {
+            Entry::J(fld) => format.joins.push(join(fld)),
+            Entry::F(fld) => format.fields.extend(logical_field(fld)),
+            Entry::K(kf) => format.keys.extend(key(kf)),
+            Entry::S(fld) | Entry::O(fld) => {
+                let select = matches!(entry, Entry::S(_));
+                let conditions = conditions(fld);
+                match (starts_statement(fld), format.select_omit.last_mut()) {
+                    (false, Some(last)) => last.conditions.extend(conditions),
+                    _ => format.select_omit.push(SelectOmit { select, conditions }),
+                }
+            }
+            Entry::FE(_) | Entry::R(_) => {}
+        }
+    }
+    LogicalFile { unique: path.unique, duplicates: path.duplicates, formats }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lfdds::CST;
+
+    #[test]
+    fn test_logical_file_snapshot() {
+        let input = r#"
+     A* born events by cow, newest first
+     A                                      UNIQUE
+     A          R EVTFMT                    PFILE(COWEVT)
+     A            EVTCOW
+     A            EVTDAY                    RENAME(EDAT)
+     A            EVTYEAR        4A         SST(EDAT 1 4)
+     A            EVTSTAMP                  CONCAT(EDAT ETIM)
+     A          K EVTCOW
+     A          K EVTDAY                    DESCEND
+     A          S ETYP                      COMP(EQ 'BORN')
+     A            EDAT                      RANGE(20240101 20241231)
+     A          O ETYP                      VALUES('SOLD' 'DEAD')
+     A          R BRNJFMT                   JFILE(COWEVT BORNEVT)
+     A          J                           JOIN(COWEVT BORNEVT)
+     A                                      JFLD(ID EID)
+     A                                      JDUPSEQ(BDAT)
+     A            ID                        JREF(1)
+     A            BNAME
+"#[1..]
+            .to_string();
+        let cst = CST::try_from(input.as_str()).unwrap();
+        let ast = AST::from(&cst);
+        insta::assert_yaml_snapshot!(query_logical_file(&ast));
+    }
+}
@@ -0,0 +1,19 @@
+// Logical files have the same columns as physical files and parse to the same CST
+mod entry;
+mod ast;
+mod logical_file;
+mod resolve;
+mod outline;
+mod keywords;
+mod ddl;
+pub use crate::pfdds::{CST, highlight_cst};
+pub use entry::Entry;
+pub use ast::{AST, highlight_ast, query_definition, query_record_formats};
+pub use logical_file::{
+    Condition, FieldSource, Join, LogicalField, LogicalFile, LogicalFormat, SelectOmit, Test,
+    query_logical_file,
+};
+pub use resolve::{ResolvedField, resolve_fields};
+pub use outline::{query_folds, query_outline};
+pub use keywords::query_keywords;
+pub use ddl::to_sql;
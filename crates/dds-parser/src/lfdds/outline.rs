@@ -0,0 +1,27 @@
+use super::{Entry, AST};
+use crate::pfdds::{self, Fold, OutlineItem};
+
+// the entries a physical file could have, joins and select/omit are left out
+fn physical(ast: &AST) -> pfdds::AST {
+    let entries = ast
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            Entry::FE(x) => Some(pfdds::Entry::FE(x.clone())),
+            Entry::R(x) => Some(pfdds::Entry::R(x.clone())),
+            Entry::F(x) => Some(pfdds::Entry::F(x.clone())),
+            Entry::K(x) => Some(pfdds::Entry::K(x.clone())),
+            Entry::J(_) | Entry::S(_) | Entry::O(_) => None,
+        })
+        .collect();
+    pfdds::AST { entries }
+}
+
+// Each record format with its fields and key fields, like a physical file
+pub fn query_outline(ast: &AST) -> Vec<OutlineItem> {
+    pfdds::query_outline(&physical(ast))
+}
+
+pub fn query_folds(ast: &AST) -> Vec<Fold> {
+    pfdds::query_folds(&physical(ast))
+}
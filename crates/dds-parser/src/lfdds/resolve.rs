@@ -0,0 +1,173 @@
+use super::{FieldSource, LogicalFile, LogicalFormat};
+use crate::meta::Span;
+use crate::pfdds::{resolve_references, AST};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// A logical file field traced back to the physical file field it reads
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedField {
+    pub format: String,
+    pub name: String,
+    // None when the based-on file or its field isn't known
+    pub file: Option<String>,
+    pub field: Option<String>,
+    // the field's definition in the physical file
+    pub span: Option<Span>,
+    // type and length, ex: 9P 0
+    pub detail: String,
+}
+
+struct Definition {
+    name: String,
+    span: Span,
+    detail: String,
+}
+
+// the fields of a physical file, with the type and length they reference
+fn definitions(file: &str, ast: &AST, physicals: &HashMap<String, AST>) -> Vec<Definition> {
+    resolve_references(file, ast, physicals)
+        .fields
+        .into_iter()
+        .map(|fld| Definition {
+            detail: fld.detail(),
+            name: fld.name,
+            span: fld.span,
+        })
+        .collect()
+}
+
+fn resolve_format(
+    format: &LogicalFormat,
+    physicals: &HashMap<String, Vec<Definition>>,
+) -> Vec<ResolvedField> {
+    let files = format.based_on();
+    let lookup = |file: &String, field: &str| {
+        physicals
+            .get(file)?
+            .iter()
+            .find(|x| x.name == field)
+            .map(|x| (file.clone(), x))
+    };
+    // without field lines the format has every field of its physical file
+    if format.fields.is_empty() {
+        let Some(file) = files.first() else {
+            return vec![];
+        };
+        return physicals
+            .get(file)
+            .into_iter()
+            .flatten()
+            .map(|def| ResolvedField {
+                format: format.name.clone(),
+                name: def.name.clone(),
+                file: Some(file.clone()),
+                field: Some(def.name.clone()),
+                span: Some(def.span),
+                detail: def.detail.clone(),
+            })
+            .collect();
+    }
+    let mut out = vec![];
+    for field in format.fields.iter() {
+        let source = match &field.source {
+            FieldSource::Same => field.name.clone(),
+            FieldSource::Rename(x) => x.clone(),
+            FieldSource::Substring { field, .. } => field.clone(),
+            FieldSource::Concat(fields) => fields.first().cloned().unwrap_or_default(),
+        };
+        let found = match field.jref {
+            Some(n) => files.get(n.wrapping_sub(1)).and_then(|file| lookup(file, &source)),
+            None => files.iter().find_map(|file| lookup(file, &source)),
+        };
+        let detail = match (&field.source, field.detail.is_empty(), &found) {
+            (_, false, _) => field.detail.clone(),
+            (FieldSource::Substring { length, .. }, true, _) if !length.is_empty() => {
+                format!("{}A", length)
+            }
+            (FieldSource::Same | FieldSource::Rename(_), true, Some((_, def))) => {
+                def.detail.clone()
+            }
+            _ => String::new(),
+        };
+        out.push(ResolvedField {
+            format: format.name.clone(),
+            name: field.name.clone(),
+            file: found.as_ref().map(|x| x.0.clone()),
+            field: found.as_ref().map(|x| x.1.name.clone()),
+            span: found.as_ref().map(|x| x.1.span),
+            detail,
+        });
+    }
+    out
+}
+
+/* The fields of every record format of a logical file, traced back to the
+ * based-on physical files. `physicals` maps file names to parsed sources.
+ */
+pub fn resolve_fields(lf: &LogicalFile, physicals: &HashMap<String, AST>) -> Vec<ResolvedField> {
+    let definitions = physicals
+        .iter()
+        .map(|(file, ast)| (file.to_uppercase(), definitions(file, ast, physicals)))
+        .collect::<HashMap<String, Vec<Definition>>>();
+    lf.formats.iter().flat_map(|format| resolve_format(format, &definitions)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lfdds::{self, query_logical_file};
+    use crate::pfdds::CST;
+
+    fn physical(input: &str) -> AST {
+        AST::from(&CST::try_from(input).unwrap())
+    }
+
+    #[test]
+    fn test_resolve_fields() {
+        let cowevt = physical(
+            r#"
+     A          R EVTFMT
+     A            ID             8  0
+     A            EDAT           8S 0
+     A            ETIM           6S 0
+"#,
+        );
+        let bornevt = physical(
+            r#"
+     A          R BORNFMT
+     A            EID            8  0
+     A            BNAME         10A
+"#,
+        );
+        let physicals = HashMap::from([("COWEVT".to_string(), cowevt), ("BornEvt".to_string(), bornevt)]);
+        let input = r#"
+     A          R EVTFMT                    PFILE(COWEVT)
+     A            EVTDAY                    RENAME(EDAT)
+     A            EVTYEAR                   SST(EDAT 1 4)
+     A          R BRNJFMT                   JFILE(COWEVT BORNEVT)
+     A          J                           JOIN(1 2)
+     A                                      JFLD(ID EID)
+     A            ID                        JREF(1)
+     A            BNAME
+     A            NOTHERE
+     A          R ALLFMT                    PFILE(COWEVT)
+"#;
+        let ast = lfdds::AST::from(&CST::try_from(input).unwrap());
+        let observed = resolve_fields(&query_logical_file(&ast), &physicals)
+            .into_iter()
+            .map(|x| format!("{}.{} {:?}.{:?} {}", x.format, x.name, x.file, x.field, x.detail))
+            .collect::<Vec<String>>();
+        let expected = [
+            r#"EVTFMT.EVTDAY Some("COWEVT").Some("EDAT") 8S 0"#,
+            r#"EVTFMT.EVTYEAR Some("COWEVT").Some("EDAT") 4A"#,
+            r#"BRNJFMT.ID Some("COWEVT").Some("ID") 8 0"#,
+            r#"BRNJFMT.BNAME Some("BORNEVT").Some("BNAME") 10A"#,
+            "BRNJFMT.NOTHERE None.None ",
+            r#"ALLFMT.ID Some("COWEVT").Some("ID") 8 0"#,
+            r#"ALLFMT.EDAT Some("COWEVT").Some("EDAT") 8S 0"#,
+            r#"ALLFMT.ETIM Some("COWEVT").Some("ETIM") 6S 0"#,
+        ];
+        assert_eq!(observed, expected);
+    }
+}
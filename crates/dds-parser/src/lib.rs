@@ -1,6 +1,12 @@
 pub mod pfdds;
+pub mod lfdds;
+pub mod dspfdds;
+pub mod prtfdds;
+pub mod keyword;
+pub mod sqlddl;
 mod free;
 mod line;
 mod meta;
 mod field;
+pub use meta::diagnostic::{Diagnostic, DiagnosticLevel};
 
@@ -10,6 +10,11 @@ pub enum DDSLine {
     RecordFormat(RecordFormatLine),
     Field(FieldLine),
     Key(KeyLine),
+    // logical files, same columns as a field line
+    Join(FieldLine),
+    SelectOmit(FieldLine),
+    // display and printer files, a field line without a name but with a location
+    Constant(FieldLine),
     Continuation(ContinuationLine),
     Comment(CommentLine),
     Idk(IdkLine),
@@ -23,9 +28,15 @@ impl From<(usize, &[char; 80])> for DDSLine {
         match (p7, p17) {
             ('*', _) => DDSLine::Comment(CommentLine::from((idx, chars))),
             (_, 'R') => DDSLine::RecordFormat(RecordFormatLine::from((idx, chars))),
+            // key fields of the access path
+            (_, 'K') => DDSLine::Key(KeyLine::from((idx, chars))),
+            (_, 'J') => DDSLine::Join(FieldLine::from((idx, chars))),
+            (_, 'S') | (_, 'O') => DDSLine::SelectOmit(FieldLine::from((idx, chars))),
             _ => {
                 if chars[18].is_alphabetic() {
                     DDSLine::Field(FieldLine::from((idx, chars)))
+                } else if chars[38..44].iter().any(|c| *c != ' ') {
+                    DDSLine::Constant(FieldLine::from((idx, chars)))
                 } else {
                     let unique_chars = chars[44..].iter().collect::<HashSet<&char>>();
                     if unique_chars.len() == 1 && unique_chars.contains(&' ') {
@@ -44,6 +55,9 @@ impl Display for DDSLine {
             DDSLine::RecordFormat(line) => write!(f, "{}", line.to_string()),
             DDSLine::Field(line) => write!(f, "{}", line.to_string()),
             DDSLine::Key(line) => write!(f, "{}", line.to_string()),
+            DDSLine::Join(line) => write!(f, "{}", line),
+            DDSLine::SelectOmit(line) => write!(f, "{}", line),
+            DDSLine::Constant(line) => write!(f, "{}", line),
             DDSLine::Continuation(line) => write!(f, "{}", line.to_string()),
             DDSLine::Comment(line) => write!(f, "{}", line.to_string()),
             DDSLine::Idk(line) => write!(f, "{}", line.to_string()),
@@ -56,6 +70,9 @@ impl IHighlight for DDSLine {
             DDSLine::RecordFormat(line) => line.highlight(),
             DDSLine::Field(line) => line.highlight(),
             DDSLine::Key(line) => line.highlight(),
+            DDSLine::Join(line) => line.highlight(),
+            DDSLine::SelectOmit(line) => line.highlight(),
+            DDSLine::Constant(line) => line.highlight(),
             DDSLine::Continuation(line) => line.highlight(),
             DDSLine::Comment(line) => line.highlight(),
             DDSLine::Idk(line) => line.highlight(),
@@ -68,6 +85,9 @@ impl ISpan for DDSLine {
             DDSLine::RecordFormat(line) => line.span(),
             DDSLine::Field(line) => line.span(),
             DDSLine::Key(line) => line.span(),
+            DDSLine::Join(line) => line.span(),
+            DDSLine::SelectOmit(line) => line.span(),
+            DDSLine::Constant(line) => line.span(),
             DDSLine::Continuation(line) => line.span(),
             DDSLine::Comment(line) => line.span(),
             DDSLine::Idk(line) => line.span(),
@@ -3,7 +3,7 @@ use std::fmt;
 use std::fmt::Display;
 use crate::meta::{IHighlight, ISpan, Span, pos};
 use crate::field::{DatatypeField, FieldResult, FormtypeField, IgnoredField, LengthField, NameField, NametypeField, ReferenceField, SequenceField,
- DecimalPositionsField, UsageField, RawKeywordsField,
+ DecimalPositionsField, UsageField, LocationField, RawKeywordsField,
 };
 use crate::meta::pluck_array3 as pluck;
 
@@ -21,7 +21,7 @@ pub struct FieldLine {
     pub data_type: FieldResult<DatatypeField>,
     pub decimal_positions: FieldResult<DecimalPositionsField>,
     pub usage: FieldResult<UsageField>,
-    pub location: FieldResult<IgnoredField>,
+    pub location: FieldResult<LocationField>,
     pub keywords: FieldResult<RawKeywordsField>
 
 }
@@ -1,15 +1,18 @@
+use super::Span;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum DiagnosticLevel {
     Info,
     Warning,
     Error,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Diagnostic {
-    pub span: String,
+    pub span: Span,
     pub level: DiagnosticLevel,
+    // the check that produced this, ex: "missing-reference"
+    pub code: String,
     pub msg: String,
 }
@@ -0,0 +1,168 @@
+use super::{Entry, FileEntry, Keyfield, AST};
+use crate::field::FieldResult;
+use crate::free::{FETokenKind, KTokenKind};
+use crate::meta::{Meta, Span};
+use serde::{Deserialize, Serialize};
+
+// How records with the same key are read, without UNIQUE
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DuplicateOrder {
+    Fifo,
+    Lifo,
+    Fcfo,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Key {
+    pub name: String,
+    pub descend: bool,
+    pub absval: bool,
+    pub signed: bool,
+    // the key field's name
+    pub span: Span,
+}
+
+// The keyed access path of a physical file, no keys means arrival sequence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessPath {
+    // most significant first
+    pub keys: Vec<Key>,
+    pub unique: bool,
+    pub duplicates: Option<DuplicateOrder>,
+}
+
+impl AccessPath {
+    pub fn is_keyed(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    // a CHAIN or SETLL can search on any leading part of the key
+    pub fn accepts(&self, key_list: usize) -> bool {
+        key_list > 0 && key_list <= self.keys.len()
+    }
+}
+
+fn text(metas: &[Meta]) -> String {
+    metas.iter().map(|m| m.text.as_str()).collect::<String>().to_uppercase()
+}
+
+// UNIQUE and the duplicate key order among the file level keywords
+pub(crate) fn file_keywords(fe: &FileEntry, path: &mut AccessPath) {
+    let FieldResult::Ok(keywords) = &fe.keywords else {
+        return;
+    };
+    let names = keywords
+        .tokens
+        .iter()
+        .filter(|t| matches!(t.kind, FETokenKind::Identifier))
+        .map(|t| text(&t.metas));
+    for name in names {
+        match name.as_str() {
+            "UNIQUE" => path.unique = true,
+            "FIFO" => path.duplicates = Some(DuplicateOrder::Fifo),
+            "LIFO" => path.duplicates = Some(DuplicateOrder::Lifo),
+            "FCFO" => path.duplicates = Some(DuplicateOrder::Fcfo),
+            _ => {}
+        }
+    }
+}
+
+// a key field line and its key keywords
+pub(crate) fn key(kf: &Keyfield) -> Option<Key> {
+    let FieldResult::Ok(name) = &kf.name else {
+        return None;
+    };
+    let mut key = Key {
+        name: name.value.trim().to_string(),
+        descend: false,
+        absval: false,
+        signed: false,
+        span: name.meta.span,
+    };
+    if let FieldResult::Ok(keywords) = &kf.keywords {
+        let names = keywords
+            .tokens
+            .iter()
+            .filter(|t| matches!(t.kind, KTokenKind::Identifier))
+            .map(|t| text(&t.metas));
+        for name in names {
+            match name.as_str() {
+                "DESCEND" => key.descend = true,
+                "ABSVAL" => key.absval = true,
+                "SIGNED" => key.signed = true,
+                _ => {}
+            }
+        }
+    }
+    Some(key)
+}
+
+// The key fields in order with their keywords, and the file level keywords
+pub fn query_access_path(ast: &AST) -> AccessPath {
+    let mut path = AccessPath { keys: vec![], unique: false, duplicates: None };
+    for entry in ast.entries.iter() {
+        match entry {
+            Entry::FE(fe) => file_keywords(fe, &mut path),
+            Entry::K(kf) => path.keys.extend(key(kf)),
+            Entry::R(_) | Entry::F(_) => {}
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::line::DDSLine;
+    use crate::pfdds::CST;
+
+    #[test]
+    fn test_access_path_snapshot() {
+        let input = r#"
+     A                                      UNIQUE
+     A                                      LIFO
+     A          R EVTFMT                    TEXT('Cow events')
+     A            EVTCOW         9P 0
+     A            EVTDAT         8S 0
+     A            EVTAMT         9P 2
+     A          K EVTCOW
+     A          K EVTDAT                    DESCEND
+     A          K EVTAMT                    ABSVAL
+     A                                      SIGNED
+"#[1..]
+            .to_string();
+        let cst = CST::try_from(input.as_str()).unwrap();
+        let ast = AST::from(&cst);
+        let path = query_access_path(&ast);
+        assert!(path.accepts(2));
+        assert!(!path.accepts(4));
+        insta::assert_yaml_snapshot!(path);
+    }
+
+    #[test]
+    fn test_key_lines() {
+        // a K in position 17 is a key field, not a field named like one
+        let input = r#"
+     A          R EVTFMT
+     A            EVTCOW         9P 0
+     A          K EVTCOW
+"#[1..]
+            .to_string();
+        let cst = CST::try_from(input.as_str()).unwrap();
+        assert!(matches!(cst.lines[1], DDSLine::Field(_)));
+        assert!(matches!(cst.lines[2], DDSLine::Key(_)));
+    }
+
+    #[test]
+    fn test_arrival_sequence() {
+        let input = r#"
+     A          R EVTFMT
+     A            EVTCOW         9P 0
+"#[1..]
+            .to_string();
+        let cst = CST::try_from(input.as_str()).unwrap();
+        let path = query_access_path(&AST::from(&cst));
+        assert!(!path.is_keyed());
+        assert!(!path.accepts(1));
+    }
+}
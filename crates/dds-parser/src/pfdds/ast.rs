@@ -111,6 +111,16 @@ fn read_keyfield(parser: &Parser) -> Option<Entry> {
     unreachable!()
 }
 
+// join and select/omit lines belong to logical files and constants to display and printer
+// files, skip them with their continuations
+fn skip_line(parser: &Parser) -> Option<Entry> {
+    let _ = read_line(parser);
+    while let Some(DDSLine::Continuation(_)) = peek_n(parser, 0) {
+        let _ = read_line(parser);
+    }
+    next_entry(parser)
+}
+
 fn next_entry(parser: &Parser) -> Option<Entry> {
     match peek_n(parser, 0) {
         Some(line) => {
@@ -118,6 +128,9 @@ fn next_entry(parser: &Parser) -> Option<Entry> {
                 DDSLine::RecordFormat(_) => read_recordformat(parser),
                 DDSLine::Field(_) => read_field(parser),
                 DDSLine::Key(_) => read_keyfield(parser),
+                DDSLine::Join(_) => skip_line(parser),
+                DDSLine::SelectOmit(_) => skip_line(parser),
+                DDSLine::Constant(_) => skip_line(parser),
                 DDSLine::Comment(_) => None, // ignore
                 DDSLine::Idk(_) => None,     // ignore
                 DDSLine::Continuation(_) => {
@@ -154,6 +167,9 @@ impl From<&CST> for AST {
                     DDSLine::RecordFormat(_) => true,
                     DDSLine::Field(_) => true,
                     DDSLine::Key(_) => true,
+                    DDSLine::Join(_) => true,
+                    DDSLine::SelectOmit(_) => true,
+                    DDSLine::Constant(_) => true,
                     DDSLine::Continuation(_) => true,
                     DDSLine::Comment(_) => false,
                     DDSLine::Idk(_) => false,
@@ -200,3 +216,55 @@ pub fn query_definition(ast: &AST, pattern: &str) -> Option<Span> {
     }
     None
 }
+
+pub fn query_record_formats(ast: &AST) -> Vec<String> {
+    let mut out = vec![];
+    for entry in ast.entries.iter() {
+        if let Entry::R(rf) = entry {
+            if let FieldResult::Ok(namefield) = &rf.name {
+                out.push(namefield.value.clone());
+            }
+        }
+    }
+    out
+}
+
+// type and length of a field, ex: 9P 0
+pub(crate) fn detail(fld: &Field) -> String {
+    let length = match &fld.length {
+        FieldResult::Ok(x) => x.value.to_string(),
+        FieldResult::Idk(_) => String::new(),
+    };
+    let datatype = match &fld.data_type {
+        FieldResult::Ok(x) => x.value.to_string(),
+        FieldResult::Idk(_) => String::new(),
+    };
+    let decimals = match &fld.decimal_positions {
+        FieldResult::Ok(x) => x.value.to_string(),
+        FieldResult::Idk(_) => String::new(),
+    };
+    format!("{}{} {}", length, datatype.trim(), decimals).trim().to_string()
+}
+
+// (record format, field, type and length) for every field, ex: (EVTFMT, EVTID, 9P 0)
+pub fn query_fields(ast: &AST) -> Vec<(String, String, String)> {
+    let mut out = vec![];
+    let mut format = String::new();
+    for entry in ast.entries.iter() {
+        match entry {
+            Entry::R(rf) => {
+                if let FieldResult::Ok(namefield) = &rf.name {
+                    format = namefield.value.trim().to_string();
+                }
+            }
+            Entry::F(fld) => {
+                let FieldResult::Ok(namefield) = &fld.name else {
+                    continue;
+                };
+                out.push((format.clone(), namefield.value.trim().to_string(), detail(fld)));
+            }
+            _ => {}
+        }
+    }
+    out
+}
@@ -0,0 +1,222 @@
+use super::{query_keywords, resolve_references, Entry, Referenced, AST};
+use crate::field::FieldResult;
+use crate::keyword::DdsKeyword;
+use crate::meta::Span;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Where a field sits in the record buffer, positions are one based like DSPFFD shows them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldLayout {
+    pub name: String,
+    // the field's name
+    pub span: Span,
+    // type and length, ex: 9P 0, a blank type is filled in
+    pub detail: String,
+    // None from the field whose size isn't known on, ex: a REFFLD that can't be followed
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+    pub bytes: Option<usize>,
+    // the 2 byte length prefix is in `bytes`
+    pub varlen: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordLayout {
+    pub format: String,
+    // the record format's name
+    pub span: Span,
+    pub fields: Vec<FieldLayout>,
+    // None when a field's size isn't known
+    pub length: Option<usize>,
+}
+
+impl RecordLayout {
+    // a column per field, like DSPFFD, ex: EVTID 9P 0 1 5 5
+    pub fn table(&self) -> String {
+        let known = |x: Option<usize>| x.map(|x| x.to_string()).unwrap_or("?".to_string());
+        let width = self
+            .fields
+            .iter()
+            .map(|x| x.name.len())
+            .max()
+            .unwrap_or(0)
+            .max(5);
+        let mut out = format!(
+            "{:<width$}  {:<8}  {:>5}  {:>5}  {:>5}\n",
+            "Field", "Type", "From", "To", "Bytes"
+        );
+        for fld in self.fields.iter() {
+            let detail = match fld.varlen {
+                true => format!("{} V", fld.detail),
+                false => fld.detail.clone(),
+            };
+            out.push_str(&format!(
+                "{:<width$}  {:<8}  {:>5}  {:>5}  {:>5}\n",
+                fld.name,
+                detail,
+                known(fld.start),
+                known(fld.end),
+                known(fld.bytes)
+            ));
+        }
+        out.push_str(&format!(
+            "{} is {} bytes long\n",
+            self.format,
+            known(self.length)
+        ));
+        out
+    }
+}
+
+/* The bytes a field takes in the buffer: packed is half its digits and a
+ * sign, binary 2, 4 or 8 by its digits, a float 4 or 8 by FLTPCN, a date 6 to
+ * 10 by DATFMT, a time 8 and a timestamp 26. VARLEN adds a 2 byte prefix.
+ */
+fn storage(data_type: char, length: Option<usize>, keywords: &[&DdsKeyword]) -> Option<usize> {
+    let double = keywords.iter().any(|k| match k {
+        DdsKeyword::Other(name, args) => {
+            name == "FLTPCN" && args.iter().any(|x| x.eq_ignore_ascii_case("*DOUBLE"))
+        }
+        _ => false,
+    });
+    let datfmt = keywords.iter().find_map(|k| match k {
+        DdsKeyword::Datfmt(x) => Some(x.as_str()),
+        _ => None,
+    });
+    let varlen = keywords.iter().any(|k| matches!(k, DdsKeyword::Varlen(_)));
+    let bytes = match data_type {
+        'P' => length? / 2 + 1,
+        'B' => match length? {
+            1..=4 => 2,
+            5..=9 => 4,
+            _ => 8,
+        },
+        'F' if double => 8,
+        'F' => 4,
+        'G' => length? * 2,
+        'L' => match datfmt {
+            Some("*MDY" | "*DMY" | "*YMD") => 8,
+            Some("*JUL") => 6,
+            _ => 10,
+        },
+        'T' => 8,
+        'Z' => 26,
+        // character, zoned, hexadecimal and the DBCS ones are a byte a position
+        _ => length?,
+    };
+    Some(bytes + if varlen { 2 } else { 0 })
+}
+
+/* The buffer layout of each record format of the physical file `file`, the
+ * fields in order with their positions and the record length. References are
+ * followed through `files`, a map of file names to parsed sources like the
+ * manifest's.
+ */
+pub fn query_record_layouts(
+    file: &str,
+    ast: &AST,
+    files: &HashMap<String, AST>,
+) -> Vec<RecordLayout> {
+    let references = resolve_references(file, ast, files);
+    let keywords = query_keywords(ast);
+    let mut out: Vec<RecordLayout> = vec![];
+    for entry in ast.entries.iter() {
+        if let Entry::R(rf) = entry {
+            if let FieldResult::Ok(name) = &rf.name {
+                out.push(RecordLayout {
+                    format: name.value.trim().to_uppercase(),
+                    span: name.meta.span,
+                    fields: vec![],
+                    length: Some(0),
+                });
+            }
+        }
+    }
+    for fld in references.fields.iter() {
+        let Some(layout) = out
+            .iter_mut()
+            .find(|x| x.format.eq_ignore_ascii_case(&fld.format))
+        else {
+            continue;
+        };
+        let found = keywords
+            .of(&fld.format, Some(&fld.name))
+            .collect::<Vec<&DdsKeyword>>();
+        // blank is packed with decimal positions, character without
+        let data_type = fld
+            .data_type
+            .unwrap_or(if fld.decimals.is_some() { 'P' } else { 'A' });
+        let bytes = storage(data_type, fld.length, &found);
+        let start = layout.length.map(|x| x + 1);
+        layout.length = layout.length.zip(bytes).map(|(x, y)| x + y);
+        layout.fields.push(FieldLayout {
+            name: fld.name.clone(),
+            span: fld.span,
+            detail: match fld.length {
+                Some(_) => Referenced {
+                    data_type: Some(data_type),
+                    ..fld.clone()
+                }
+                .detail(),
+                None => fld.detail(),
+            },
+            start: start.filter(|_| bytes.is_some()),
+            end: layout.length,
+            bytes,
+            varlen: found.iter().any(|k| matches!(k, DdsKeyword::Varlen(_))),
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pfdds::CST;
+
+    #[test]
+    fn test_record_layouts() {
+        let input = r#"
+     A          R EVTFMT                    TEXT('Cow events')
+     A            EVTID          9P 0
+     A            EVTCOW         8S 0
+     A            EVTTYP         1
+     A            EVTQTY         5B 0
+     A            EVTCNT         4B 0
+     A            EVTBIG        18B 0
+     A            EVTNOTE      200A         VARLEN(50)
+     A            EVTDAT          L
+     A            EVTOLD          L         DATFMT(*MDY)
+     A            EVTTIM          T
+     A            EVTSTAMP        Z
+     A            EVTWGT         9F 2       FLTPCN(*DOUBLE)
+     A            EVTWHO    R               REFFLD(USER NOPE)
+     A            EVTEND         2A
+     A          K EVTID
+"#;
+        let ast = AST::from(&CST::try_from(input).unwrap());
+        let layouts = query_record_layouts("COWEVT", &ast, &HashMap::new());
+        let expected = &r#"
+Field     Type       From     To  Bytes
+EVTID     9P 0          1      5      5
+EVTCOW    8S 0          6     13      8
+EVTTYP    1A           14     14      1
+EVTQTY    5B 0         15     18      4
+EVTCNT    4B 0         19     20      2
+EVTBIG    18B 0        21     28      8
+EVTNOTE   200A V       29    230    202
+EVTDAT    L           231    240     10
+EVTOLD    L           241    248      8
+EVTTIM    T           249    256      8
+EVTSTAMP  Z           257    282     26
+EVTWGT    9F 2        283    290      8
+EVTWHO                  ?      ?      ?
+EVTEND    2A            ?      ?      2
+EVTFMT is ? bytes long
+"#[1..];
+        assert_eq!(layouts.len(), 1);
+        assert_eq!(layouts[0].table(), expected);
+        assert_eq!(layouts[0].fields[0].span, Span::from(((1, 18), (1, 28))));
+    }
+}
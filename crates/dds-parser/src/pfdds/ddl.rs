@@ -0,0 +1,528 @@
+use super::{
+    query_access_path, query_keywords, query_record_formats, resolve_references, AccessPath,
+    Referenced, AST,
+};
+use crate::dspfdds::unquote;
+use crate::keyword::DdsKeyword;
+use std::collections::HashMap;
+
+// Statements and the warnings about what they leave out
+#[derive(Default)]
+pub(crate) struct Script {
+    pub(crate) warnings: Vec<String>,
+    pub(crate) statements: Vec<String>,
+}
+
+impl Script {
+    pub(crate) fn warn(&mut self, msg: String) {
+        if !self.warnings.contains(&msg) {
+            self.warnings.push(msg);
+        }
+    }
+
+    // warnings first, as comments, then the statements
+    pub(crate) fn render(&self) -> String {
+        let warnings = self.warnings.iter().map(|w| format!("-- WARNING: {}\n", w));
+        let mut out = warnings.collect::<String>();
+        if !out.is_empty() && !self.statements.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&self.statements.join("\n\n"));
+        out.push('\n');
+        out
+    }
+}
+
+// 'It's' -> 'It''s'
+pub(crate) fn sql_string(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+// LIB/FILE -> FILE, the library is left to the job's current schema
+pub(crate) fn object(text: &str) -> String {
+    text.rsplit('/')
+        .next()
+        .unwrap_or(text)
+        .trim()
+        .to_uppercase()
+}
+
+// CREATE [UNIQUE] INDEX over the keys, None when there aren't any
+pub(crate) fn create_index(
+    index: &str,
+    table: &str,
+    path: &AccessPath,
+    keys: &[String],
+    script: &mut Script,
+) -> Option<String> {
+    if !path.is_keyed() {
+        return None;
+    }
+    let columns = path
+        .keys
+        .iter()
+        .zip(keys)
+        .map(|(key, column)| {
+            if key.absval || key.signed {
+                script.warn(format!(
+                    "ABSVAL and SIGNED on key {} have no SQL equivalent",
+                    key.name
+                ));
+            }
+            match key.descend {
+                true => format!("{} DESC", column),
+                false => column.clone(),
+            }
+        })
+        .collect::<Vec<String>>();
+    if path.duplicates.is_some() {
+        script.warn(
+            "FIFO, LIFO and FCFO have no SQL equivalent, duplicate keys come back in any order"
+                .to_string(),
+        );
+    }
+    let unique = if path.unique { "UNIQUE " } else { "" };
+    Some(format!(
+        "CREATE {}INDEX {}\n  ON {} ({})",
+        unique,
+        index,
+        table,
+        columns.join(", ")
+    ))
+}
+
+// blank is packed with decimal positions, character without
+fn data_type(fld: &Referenced) -> char {
+    fld.data_type
+        .unwrap_or(if fld.decimals.is_some() { 'P' } else { 'A' })
+}
+
+// the SQL type of a field, or why it has none
+fn column_type(fld: &Referenced, keywords: &[&DdsKeyword]) -> Result<String, String> {
+    let varlen = keywords.iter().find_map(|k| match k {
+        DdsKeyword::Varlen(x) => Some(*x),
+        _ => None,
+    });
+    let double = keywords.iter().any(|k| match k {
+        DdsKeyword::Other(name, args) => {
+            name == "FLTPCN" && args.iter().any(|x| x.eq_ignore_ascii_case("*DOUBLE"))
+        }
+        _ => false,
+    });
+    let data_type = data_type(fld);
+    let length = || {
+        fld.length
+            .ok_or(format!("the length of {} isn't known", fld.name))
+    };
+    let decimals = fld.decimals.unwrap_or(0);
+    let varying = |fixed: &str, varying: &str, length: usize| match varlen {
+        Some(Some(allocate)) => format!("{}({}) ALLOCATE({})", varying, length, allocate),
+        Some(None) => format!("{}({})", varying, length),
+        None => format!("{}({})", fixed, length),
+    };
+    let out = match data_type {
+        'P' => format!("DECIMAL({}, {})", length()?, decimals),
+        'S' => format!("NUMERIC({}, {})", length()?, decimals),
+        'A' => varying("CHAR", "VARCHAR", length()?),
+        'H' => varying("BINARY", "VARBINARY", length()?),
+        'B' if decimals > 0 => format!("DECIMAL({}, {})", length()?, decimals),
+        'B' => match length()? {
+            1..=4 => "SMALLINT".to_string(),
+            5..=9 => "INTEGER".to_string(),
+            _ => "BIGINT".to_string(),
+        },
+        'F' if double || length()? > 9 => "DOUBLE".to_string(),
+        'F' => "REAL".to_string(),
+        'L' => "DATE".to_string(),
+        'T' => "TIME".to_string(),
+        'Z' => "TIMESTAMP".to_string(),
+        x => {
+            return Err(format!(
+                "data type {} of {} has no SQL equivalent",
+                x, fld.name
+            ))
+        }
+    };
+    Ok(out)
+}
+
+/* The DFT value as SQL for the field's type. Character, hexadecimal, date and
+ * time values are quoted again, numbers have to be numbers and *NULL needs
+ * ALWNULL.
+ */
+fn default_value(fld: &Referenced, raw: &str, alwnull: bool) -> Result<String, String> {
+    let raw = raw.trim();
+    if raw.eq_ignore_ascii_case("*NULL") {
+        return match alwnull {
+            true => Ok("NULL".to_string()),
+            false => Err(format!("DFT(*NULL) on {} needs ALWNULL", fld.name)),
+        };
+    }
+    let hex = raw.len() > 2 && raw.starts_with(['X', 'x']) && raw[1..].starts_with('\'');
+    match data_type(fld) {
+        'A' | 'H' if hex => Ok(format!("X{}", sql_string(&unquote(&raw[1..])))),
+        'A' | 'H' | 'L' | 'T' | 'Z' => Ok(sql_string(&unquote(raw))),
+        _ => {
+            let number = unquote(raw);
+            let digits = number.trim_start_matches(['+', '-']);
+            match !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                true => Ok(number),
+                false => Err(format!("DFT({}) on {} isn't a number", raw, fld.name)),
+            }
+        }
+    }
+}
+
+// the field keywords a column takes in, the rest only get a warning
+fn mapped(keyword: &DdsKeyword) -> bool {
+    match keyword {
+        DdsKeyword::Text(_)
+        | DdsKeyword::Colhdg(_)
+        | DdsKeyword::Alias(_)
+        | DdsKeyword::Dft(_)
+        | DdsKeyword::Ccsid(..)
+        | DdsKeyword::Varlen(_)
+        | DdsKeyword::Alwnull => true,
+        DdsKeyword::Datfmt(x) => x == "*ISO" || x == "*JIS",
+        DdsKeyword::Other(name, _) => name == "REFFLD" || name == "FLTPCN",
+        _ => false,
+    }
+}
+
+fn column(fld: &Referenced, keywords: &[&DdsKeyword], script: &mut Script) -> Option<String> {
+    for keyword in keywords.iter().filter(|k| !mapped(k)) {
+        script.warn(format!(
+            "{} on {} has no SQL equivalent",
+            keyword.name(),
+            fld.name
+        ));
+    }
+    let data_type = match column_type(fld, keywords) {
+        Ok(x) => x,
+        Err(msg) => {
+            script.warn(format!("{}, the column is left out", msg));
+            return None;
+        }
+    };
+    let mut out = String::new();
+    for keyword in keywords.iter() {
+        if let DdsKeyword::Alias(alias) = keyword {
+            out.push_str(&format!("{} FOR COLUMN ", alias.to_uppercase()));
+        }
+    }
+    out.push_str(&format!("{} {}", fld.name, data_type));
+    for keyword in keywords.iter() {
+        if let DdsKeyword::Ccsid(ccsid, _) = keyword {
+            out.push_str(&format!(" CCSID {}", ccsid));
+        }
+    }
+    let alwnull = keywords.contains(&&DdsKeyword::Alwnull);
+    let default = keywords
+        .iter()
+        .find_map(|k| match k {
+            DdsKeyword::Dft(x) => Some(x),
+            _ => None,
+        })
+        .and_then(|x| match default_value(fld, x, alwnull) {
+            Ok(x) => Some(x),
+            Err(msg) => {
+                script.warn(format!("{}, the default is left out", msg));
+                None
+            }
+        });
+    // DDS fields can't be null and default to blanks or zeros unless told otherwise
+    match (alwnull, default) {
+        (true, Some(x)) => out.push_str(&format!(" DEFAULT {}", x)),
+        (true, None) => {}
+        (false, Some(x)) => out.push_str(&format!(" NOT NULL WITH DEFAULT {}", x)),
+        (false, None) => out.push_str(" NOT NULL WITH DEFAULT"),
+    }
+    Some(out)
+}
+
+// LABEL ON COLUMN for the column headings and the text of the fields
+fn labels(table: &str, fields: &[&Referenced]) -> Vec<String> {
+    let mut out = vec![];
+    // each heading line is 20 wide
+    let headings = fields
+        .iter()
+        .filter(|x| !x.colhdg.is_empty())
+        .map(|x| {
+            let lines = x
+                .colhdg
+                .iter()
+                .map(|line| format!("{:<20}", line))
+                .collect::<String>();
+            format!("  {} IS {}", x.name, sql_string(lines.trim_end()))
+        })
+        .collect::<Vec<String>>();
+    if !headings.is_empty() {
+        out.push(format!(
+            "LABEL ON COLUMN {} (\n{}\n)",
+            table,
+            headings.join(",\n")
+        ));
+    }
+    let texts = fields
+        .iter()
+        .filter_map(|x| {
+            Some(format!(
+                "  {} TEXT IS {}",
+                x.name,
+                sql_string(x.text.as_ref()?)
+            ))
+        })
+        .collect::<Vec<String>>();
+    if !texts.is_empty() {
+        out.push(format!(
+            "LABEL ON COLUMN {} (\n{}\n)",
+            table,
+            texts.join(",\n")
+        ));
+    }
+    out
+}
+
+/* Db2 for i SQL for the physical file `file`, a CREATE TABLE with its labels
+ * and the keys as a primary key or an index. References are followed through
+ * `files`, a map of file names to parsed sources like the manifest's. What
+ * can't be converted is left as warning comments at the top.
+ */
+pub fn to_sql(file: &str, ast: &AST, files: &HashMap<String, AST>) -> String {
+    let table = file.to_uppercase();
+    let mut script = Script::default();
+    let references = resolve_references(file, ast, files);
+    for diagnostic in references.diagnostics.iter() {
+        script.warn(diagnostic.msg.clone());
+    }
+    let keywords = query_keywords(ast);
+    for keyword in keywords.file() {
+        // the duplicate key order is warned about with the index
+        match keyword.name() {
+            "UNIQUE" | "REF" | "FIFO" | "LIFO" | "FCFO" => {}
+            name => script.warn(format!("{} has no SQL equivalent", name)),
+        }
+    }
+    let formats = query_record_formats(ast)
+        .iter()
+        .map(|x| x.trim().to_uppercase())
+        .collect::<Vec<String>>();
+    let format = formats.first().cloned().unwrap_or_default();
+    if formats.len() > 1 {
+        script.warn(format!(
+            "a table has one record format, only {} is converted",
+            format
+        ));
+    }
+    let fields = references
+        .fields
+        .iter()
+        .filter(|x| x.format == format)
+        .collect::<Vec<&Referenced>>();
+    let mut columns = fields
+        .iter()
+        .filter_map(|fld| {
+            let found = keywords
+                .of(&format, Some(&fld.name))
+                .collect::<Vec<&DdsKeyword>>();
+            column(fld, &found, &mut script)
+        })
+        .collect::<Vec<String>>();
+    let path = query_access_path(ast);
+    let keys = path
+        .keys
+        .iter()
+        .map(|k| k.name.to_uppercase())
+        .collect::<Vec<String>>();
+    let plain = path
+        .keys
+        .iter()
+        .all(|k| !k.descend && !k.absval && !k.signed);
+    // a primary key can't be null, a unique index can
+    let nullable = path
+        .keys
+        .iter()
+        .filter(|k| {
+            keywords
+                .of(&format, Some(&k.name))
+                .any(|x| *x == DdsKeyword::Alwnull)
+        })
+        .map(|k| k.name.to_uppercase())
+        .collect::<Vec<String>>();
+    if path.is_keyed() && path.unique && !nullable.is_empty() {
+        let msg = format!(
+            "{} can be null, the keys are a unique index, not a primary key",
+            nullable.join(", ")
+        );
+        script.warn(msg);
+    }
+    let primary = path.is_keyed() && path.unique && plain && nullable.is_empty();
+    if primary {
+        columns.push(format!("PRIMARY KEY ({})", keys.join(", ")));
+    }
+    let mut create = format!("CREATE TABLE {} (\n  {}\n)", table, columns.join(",\n  "));
+    if !format.is_empty() {
+        create.push_str(&format!(" RCDFMT {}", format));
+    }
+    script.statements.push(create);
+    let mut text = None;
+    for keyword in keywords.of(&format, None) {
+        match keyword {
+            DdsKeyword::Text(x) => text = Some(x.clone()),
+            x => script.warn(format!(
+                "{} on record format {} has no SQL equivalent",
+                x.name(),
+                format
+            )),
+        }
+    }
+    if let Some(text) = text {
+        script
+            .statements
+            .push(format!("LABEL ON TABLE {} IS {}", table, sql_string(&text)));
+    }
+    script.statements.extend(labels(&table, &fields));
+    if !primary {
+        let index = format!("{}_IX", table);
+        let statement = create_index(&index, &table, &path, &keys, &mut script);
+        script.statements.extend(statement);
+    }
+    for statement in script.statements.iter_mut() {
+        statement.push(';');
+    }
+    script.render()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pfdds::CST;
+
+    fn physical(input: &str) -> AST {
+        AST::from(&CST::try_from(input).unwrap())
+    }
+
+    #[test]
+    fn test_to_sql_snapshot() {
+        let input = r#"
+     A                                      UNIQUE
+     A          R EVTFMT                    TEXT('Cow''s events')
+     A            EVTCOW         9P 0       COLHDG('Cow' 'Id')
+     A                                      ALIAS(EVENT_COW)
+     A            EVTDAT          L         TEXT('Event date')
+     A            EVTTYP         1A         DFT('B')
+     A                                      VALUES('B' 'D' 'S')
+     A            EVTAMT         9S 2       EDTCDE(J)
+     A            EVTQTY         5B 0
+     A            EVTNOTE      200A         VARLEN(50) ALWNULL CCSID(37)
+     A            EVTSTAMP        Z
+     A            EVTWHO    R               REFFLD(USER NOPE)
+     A          K EVTCOW
+     A          K EVTDAT
+"#;
+        insta::assert_snapshot!(to_sql("cowevt", &physical(input), &HashMap::new()));
+    }
+
+    #[test]
+    fn test_to_sql_index() {
+        let input = r#"
+     A                                      FIFO
+     A          R EVTFMT
+     A            EVTCOW         9P 0
+     A            EVTDAT         8S 0
+     A          K EVTCOW
+     A          K EVTDAT                    DESCEND
+"#;
+        let expected = r#"-- WARNING: FIFO, LIFO and FCFO have no SQL equivalent, duplicate keys come back in any order
+
+CREATE TABLE COWEVT (
+  EVTCOW DECIMAL(9, 0) NOT NULL WITH DEFAULT,
+  EVTDAT NUMERIC(8, 0) NOT NULL WITH DEFAULT
+) RCDFMT EVTFMT;
+
+CREATE INDEX COWEVT_IX
+  ON COWEVT (EVTCOW, EVTDAT DESC);
+"#;
+        assert_eq!(
+            to_sql("COWEVT", &physical(input), &HashMap::new()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_to_sql_defaults() {
+        let input = r#"
+     A          R EVTFMT
+     A            EVTNAME       10A         DFT('Bessie''s')
+     A            EVTHEX         2A         DFT(X'C1C2')
+     A            EVTQTY         5P 0       DFT('12')
+     A            EVTAMT         7S 2       DFT(150)
+     A            EVTBAD         5P 0       DFT('ABC')
+     A            EVTDAT          L         DFT('2024-01-01')
+     A            EVTNUL        10A         DFT(*NULL) ALWNULL
+"#;
+        let expected = r#"-- WARNING: DFT('ABC') on EVTBAD isn't a number, the default is left out
+
+CREATE TABLE COWEVT (
+  EVTNAME CHAR(10) NOT NULL WITH DEFAULT 'Bessie''s',
+  EVTHEX CHAR(2) NOT NULL WITH DEFAULT X'C1C2',
+  EVTQTY DECIMAL(5, 0) NOT NULL WITH DEFAULT 12,
+  EVTAMT NUMERIC(7, 2) NOT NULL WITH DEFAULT 150,
+  EVTBAD DECIMAL(5, 0) NOT NULL WITH DEFAULT,
+  EVTDAT DATE NOT NULL WITH DEFAULT '2024-01-01',
+  EVTNUL CHAR(10) DEFAULT NULL
+) RCDFMT EVTFMT;
+"#;
+        assert_eq!(
+            to_sql("COWEVT", &physical(input), &HashMap::new()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_to_sql_nullable_key() {
+        let input = r#"
+     A                                      UNIQUE
+     A          R EVTFMT
+     A            EVTCOW         9P 0
+     A            EVTTAG        10A         ALWNULL
+     A          K EVTCOW
+     A          K EVTTAG
+"#;
+        let expected = r#"-- WARNING: EVTTAG can be null, the keys are a unique index, not a primary key
+
+CREATE TABLE COWEVT (
+  EVTCOW DECIMAL(9, 0) NOT NULL WITH DEFAULT,
+  EVTTAG CHAR(10)
+) RCDFMT EVTFMT;
+
+CREATE UNIQUE INDEX COWEVT_IX
+  ON COWEVT (EVTCOW, EVTTAG);
+"#;
+        assert_eq!(
+            to_sql("COWEVT", &physical(input), &HashMap::new()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_to_sql_first_format() {
+        // only the second format has keywords, the first is still the table
+        let input = r#"
+     A          R EVTFMT
+     A            EVTCOW         9P 0
+     A          R OLDFMT                    TEXT('Old events')
+     A            OLDCOW         9P 0
+"#;
+        let expected = r#"-- WARNING: a table has one record format, only EVTFMT is converted
+
+CREATE TABLE COWEVT (
+  EVTCOW DECIMAL(9, 0) NOT NULL WITH DEFAULT
+) RCDFMT EVTFMT;
+"#;
+        assert_eq!(
+            to_sql("COWEVT", &physical(input), &HashMap::new()),
+            expected
+        );
+    }
+}
@@ -2,7 +2,7 @@ use super::{RecordFormat, Field, Keyfield, FileEntry};
 use crate::meta::{IHighlight, Span};
 use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Entry {
     FE(FileEntry),
     R(RecordFormat),
@@ -1,12 +1,12 @@
 use serde::{Deserialize, Serialize};
 use crate::meta::{IHighlight, Span};
 use crate::field::{DatatypeField, FieldResult, FormtypeField, IgnoredField, LengthField, NameField, NametypeField, ReferenceField, SequenceField,
- DecimalPositionsField, UsageField, FKeywordsField,
+ DecimalPositionsField, UsageField, LocationField, FKeywordsField,
 };
 use crate::free::tokenize_fld_kw;
 use crate::line::{FieldLine, ContinuationLine};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Field {
     pub sequence: FieldResult<SequenceField>,
     pub form_type: FieldResult<FormtypeField>,
@@ -20,7 +20,7 @@ pub struct Field {
     pub data_type: FieldResult<DatatypeField>,
     pub decimal_positions: FieldResult<DecimalPositionsField>,
     pub usage: FieldResult<UsageField>,
-    pub location: FieldResult<IgnoredField>,
+    pub location: FieldResult<LocationField>,
     pub keywords: FieldResult<FKeywordsField>
 }
 impl From<(&FieldLine, Vec<&ContinuationLine>)> for Field {
@@ -4,7 +4,7 @@ use crate::field::{FieldResult, FEKeywordsField};
 use crate::free::tokenize_fe_kw;
 use crate::line::ContinuationLine;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileEntry {
     pub keywords: FieldResult<FEKeywordsField>
 }
@@ -0,0 +1,41 @@
+use super::{OutlineKind, AST, query_outline};
+use serde::{Deserialize, Serialize};
+
+// Rows are zero based and inclusive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fold {
+    pub start_row: usize,
+    pub end_row: usize,
+}
+
+// Each record format through its last field or key field
+pub fn query_folds(ast: &AST) -> Vec<Fold> {
+    query_outline(ast)
+        .into_iter()
+        .filter(|item| item.kind == OutlineKind::RecordFormat)
+        .filter(|item| item.span.end.row > item.span.start.row)
+        .map(|item| Fold { start_row: item.span.start.row, end_row: item.span.end.row })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pfdds::CST;
+
+    #[test]
+    fn test_folds() {
+        let input = r#"
+     A                                      UNIQUE
+     A          R EVTFMT                    TEXT('Cow events')
+     A            EVTID          9P 0       COLHDG('Event' 'Id')
+     A            EVTNAME       20A
+     A          K EVTID
+     A          R SUMFMT
+"#[1..]
+            .to_string();
+        let cst = CST::try_from(input.as_str()).unwrap();
+        let ast = AST::from(&cst);
+        assert_eq!(query_folds(&ast), vec![Fold { start_row: 1, end_row: 4 }]);
+    }
+}
@@ -0,0 +1,101 @@
+use super::{Entry, AST};
+use crate::field::FieldResult;
+use crate::free::{field_pieces, file_pieces, format_pieces, key_pieces, keywords};
+use crate::keyword::{Collector, KeywordModel, Level};
+
+// The typed keywords of a physical file with what's wrong with them
+pub fn query_keywords(ast: &AST) -> KeywordModel {
+    let mut out = Collector::new();
+    for entry in ast.entries.iter() {
+        match entry {
+            Entry::FE(fe) => {
+                if let FieldResult::Ok(kw) = &fe.keywords {
+                    out.add(Level::File, None, keywords(file_pieces(&kw.tokens)));
+                }
+            }
+            Entry::R(rf) => {
+                if let FieldResult::Ok(name) = &rf.name {
+                    out.format(&name.value);
+                }
+                if let FieldResult::Ok(kw) = &rf.keywords {
+                    out.add(Level::Format, None, keywords(format_pieces(&kw.tokens)));
+                }
+            }
+            Entry::F(fld) => {
+                let name = match &fld.name {
+                    FieldResult::Ok(x) => Some(x.value.as_str()),
+                    FieldResult::Idk(_) => None,
+                };
+                if let FieldResult::Ok(kw) = &fld.keywords {
+                    out.add(Level::Field, name, keywords(field_pieces(&kw.tokens)));
+                }
+            }
+            Entry::K(kf) => {
+                let name = match &kf.name {
+                    FieldResult::Ok(x) => Some(x.value.as_str()),
+                    FieldResult::Idk(_) => None,
+                };
+                if let FieldResult::Ok(kw) = &kf.keywords {
+                    out.add(Level::Key, name, keywords(key_pieces(&kw.tokens)));
+                }
+            }
+        }
+    }
+    out.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyword::DdsKeyword;
+    use crate::pfdds::CST;
+
+    #[test]
+    fn test_keywords_snapshot() {
+        let input = r#"
+     A                                      UNIQUE
+     A          R EVTFMT                    TEXT('Cow events')
+     A            EVTCOW         9P 0       COLHDG('Cow' 'Id')
+     A                                      ALIAS(EVENT_COW)
+     A            EVTAMT         9P 2       EDTCDE(J $) COMP(GT 0)
+     A            EVTTYP         1A         VALUES('B' 'D' 'S')
+     A                                      DFT('B')
+     A            EVTNOTE      200A         VARLEN(50) ALWNULL CCSID(37)
+     A            EVTDAT          L         DATFMT(*ISO)
+     A          K EVTCOW                    DESCEND
+"#[1..]
+            .to_string();
+        let ast = AST::from(&CST::try_from(input.as_str()).unwrap());
+        let model = query_keywords(&ast);
+        assert_eq!(model.diagnostics, vec![]);
+        let colhdg = model.of("EVTFMT", Some("EVTCOW")).find(|k| k.name() == "COLHDG");
+        assert_eq!(colhdg, Some(&DdsKeyword::Colhdg(vec!["Cow".to_string(), "Id".to_string()])));
+        insta::assert_yaml_snapshot!(model);
+    }
+
+    #[test]
+    fn test_keyword_diagnostics() {
+        let input = r#"
+     A          R EVTFMT                    TEXT('Cow' 'events')
+     A            EVTCOW         9P 0       COLHDG('A' 'B' 'C' 'D')
+     A            EVTAMT         9P 2       EDTCDE(X) COMP(XX 0)
+     A            EVTNOTE      200A         VARLEN(ABC) MOO
+"#[1..]
+            .to_string();
+        let ast = AST::from(&CST::try_from(input.as_str()).unwrap());
+        let observed = query_keywords(&ast)
+            .diagnostics
+            .into_iter()
+            .map(|d| format!("{} {} {}", d.span.start.row, d.code, d.msg))
+            .collect::<Vec<String>>();
+        let expected = [
+            "0 keyword-arity TEXT takes 1 parameter, found 2",
+            "1 keyword-arity COLHDG takes 1 to 3 parameters, found 4",
+            "2 keyword-argument X isn't an edit code",
+            "2 keyword-argument XX isn't a comparison operator",
+            "3 keyword-argument ABC isn't a length",
+            "3 unknown-keyword Unknown keyword MOO",
+        ];
+        assert_eq!(observed, expected);
+    }
+}
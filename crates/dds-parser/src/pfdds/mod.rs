@@ -5,11 +5,30 @@ mod field;
 mod keyfield;
 mod cst;
 mod ast;
+mod outline;
+mod folding;
+mod access_path;
+mod reference;
+mod keywords;
+mod ddl;
+mod buffer;
+mod validate;
 pub use record_format::RecordFormat;
 pub use field::Field;
 pub use keyfield::Keyfield;
 pub use fileentry::FileEntry;
 pub use entry::Entry;
 pub use cst::{CST, highlight_cst};
-pub use ast::{AST, highlight_ast, query_definition};
-
+pub use ast::{AST, highlight_ast, query_definition, query_fields, query_record_formats};
+pub(crate) use ast::detail;
+pub(crate) use access_path::{file_keywords, key};
+pub use outline::{OutlineItem, OutlineKind, query_outline};
+pub use folding::{Fold, query_folds};
+pub use access_path::{AccessPath, DuplicateOrder, Key, query_access_path};
+pub use reference::{FIELD_REFERENCE_FILE, Referenced, References, resolve_references};
+pub(crate) use reference::{resolve, Source};
+pub use keywords::query_keywords;
+pub use ddl::to_sql;
+pub use buffer::{FieldLayout, RecordLayout, query_record_layouts};
+pub use validate::validate;
+pub(crate) use ddl::{create_index, object, sql_string, Script};
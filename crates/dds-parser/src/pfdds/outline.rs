@@ -0,0 +1,104 @@
+use super::{Entry, AST};
+use crate::field::FieldResult;
+use crate::meta::{IHighlight, Span};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OutlineKind {
+    FileEntry,
+    RecordFormat,
+    Field,
+    Keyfield,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutlineItem {
+    pub name: String,
+    pub kind: OutlineKind,
+    // the whole entry, continuation lines included
+    pub span: Span,
+    // just the name, or the keywords of a file level entry
+    pub selection: Span,
+    pub children: Vec<OutlineItem>,
+}
+
+fn cover(spans: Vec<Span>) -> Option<Span> {
+    spans.into_iter().reduce(Span::to_cover_both)
+}
+
+/* The file level entry, then each record format with its fields and the key
+ * fields that follow it.
+ */
+pub fn query_outline(ast: &AST) -> Vec<OutlineItem> {
+    let mut out: Vec<OutlineItem> = vec![];
+    for entry in ast.entries.iter() {
+        let Some(span) = cover(entry.highlight().into_iter().map(|x| x.0).collect()) else {
+            continue;
+        };
+        let (name, kind) = match entry {
+            Entry::FE(fe) => {
+                let FieldResult::Ok(keywords) = &fe.keywords else {
+                    continue;
+                };
+                let metas = keywords.tokens.iter().flat_map(|t| t.metas.iter());
+                let text = metas.clone().map(|m| m.text.as_str()).collect::<String>();
+                let Some(selection) = cover(metas.map(|m| m.span).collect()) else {
+                    continue;
+                };
+                let name = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+                out.push(OutlineItem {
+                    name,
+                    kind: OutlineKind::FileEntry,
+                    span,
+                    selection,
+                    children: vec![],
+                });
+                continue;
+            }
+            Entry::R(rf) => (&rf.name, OutlineKind::RecordFormat),
+            Entry::F(fld) => (&fld.name, OutlineKind::Field),
+            Entry::K(kf) => (&kf.name, OutlineKind::Keyfield),
+        };
+        let FieldResult::Ok(name) = name else {
+            continue;
+        };
+        let item = OutlineItem {
+            name: name.value.trim().to_string(),
+            kind,
+            span,
+            selection: name.meta.span,
+            children: vec![],
+        };
+        match (kind, out.last_mut()) {
+            (OutlineKind::Field | OutlineKind::Keyfield, Some(parent))
+                if parent.kind == OutlineKind::RecordFormat =>
+            {
+                parent.span = Span::to_cover_both(parent.span, item.span);
+                parent.children.push(item);
+            }
+            _ => out.push(item),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pfdds::CST;
+
+    #[test]
+    fn test_outline_snapshot() {
+        let input = r#"
+     A                                      UNIQUE
+     A          R EVTFMT                    TEXT('Cow events')
+     A            EVTID          9P 0       COLHDG('Event' 'Id')
+     A            EVTNAME       20A
+     A          K EVTID
+"#[1..]
+            .to_string();
+        let cst = CST::try_from(input.as_str()).unwrap();
+        let ast = AST::from(&cst);
+        insta::assert_yaml_snapshot!(query_outline(&ast));
+    }
+}
@@ -6,7 +6,7 @@ use crate::field::{DatatypeField, FieldResult, FormtypeField, IgnoredField, Leng
 use crate::free::tokenize_rf_kw;
 use crate::line::{RecordFormatLine, ContinuationLine};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordFormat {
     pub sequence: FieldResult<SequenceField>,
     pub form_type: FieldResult<FormtypeField>,
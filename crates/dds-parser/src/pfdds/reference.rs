@@ -0,0 +1,430 @@
+use super::{Entry, Field, FileEntry, AST};
+use crate::dspfdds::unquote;
+use crate::field::{FieldResult, Reference};
+use crate::free::{field_pieces, file_pieces, keywords, Keyword};
+use crate::meta::diagnostic::{Diagnostic, DiagnosticLevel};
+use crate::meta::Span;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+// The file searched when a reference names none and the source has no REF
+pub const FIELD_REFERENCE_FILE: &str = "FLDREF";
+
+// A field with what it inherits through REF and REFFLD filled in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Referenced {
+    pub format: String,
+    pub name: String,
+    // the field's name
+    pub span: Span,
+    pub length: Option<usize>,
+    pub data_type: Option<char>,
+    pub decimals: Option<usize>,
+    pub text: Option<String>,
+    pub colhdg: Vec<String>,
+    // the (file, field) it was referenced from, None when defined in place
+    pub from: Option<(String, String)>,
+}
+
+impl Referenced {
+    // type and length, ex: 9P 0
+    pub fn detail(&self) -> String {
+        let length = self.length.map(|x| x.to_string()).unwrap_or_default();
+        let data_type = self.data_type.map(|x| x.to_string()).unwrap_or_default();
+        let decimals = self.decimals.map(|x| x.to_string()).unwrap_or_default();
+        format!("{}{} {}", length, data_type, decimals).trim().to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct References {
+    pub fields: Vec<Referenced>,
+    // missing files and fields, and cycles
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+// A length or decimal positions column, +n and -n adjust the referenced one
+#[derive(Clone, Copy)]
+enum Size {
+    Blank,
+    Exactly(usize),
+    Plus(isize),
+}
+
+impl Size {
+    fn parse(text: &str) -> Self {
+        let text = text.trim();
+        if text.is_empty() {
+            Size::Blank
+        } else if text.starts_with(['+', '-']) {
+            text.parse::<isize>().map(Size::Plus).unwrap_or(Size::Blank)
+        } else {
+            text.parse::<usize>().map(Size::Exactly).unwrap_or(Size::Blank)
+        }
+    }
+
+    fn apply(self, inherited: Option<usize>) -> Option<usize> {
+        match self {
+            Size::Blank => inherited,
+            Size::Exactly(x) => Some(x),
+            Size::Plus(x) => inherited.map(|y| y.saturating_add_signed(x)),
+        }
+    }
+}
+
+// What a field says about itself, before following its reference
+#[derive(Clone)]
+struct Definition {
+    format: String,
+    name: String,
+    span: Span,
+    reference: bool,
+    length: Size,
+    data_type: Option<char>,
+    decimals: Size,
+    keywords: Vec<Keyword>,
+}
+
+impl Definition {
+    fn keyword(&self, name: &str) -> Option<&Keyword> {
+        self.keywords.iter().find(|k| k.name == name)
+    }
+}
+
+fn size<T: ToString>(result: &FieldResult<T>) -> Size {
+    match result {
+        FieldResult::Ok(x) => Size::parse(&x.to_string()),
+        FieldResult::Idk(x) => Size::parse(&x.value),
+    }
+}
+
+fn definition(format: &str, fld: &Field) -> Option<Definition> {
+    let FieldResult::Ok(name) = &fld.name else {
+        return None;
+    };
+    let data_type = match &fld.data_type {
+        FieldResult::Ok(x) => x.to_string().trim().chars().next(),
+        FieldResult::Idk(_) => None,
+    };
+    Some(Definition {
+        format: format.to_string(),
+        name: name.value.trim().to_uppercase(),
+        span: name.meta.span,
+        reference: matches!(&fld.reference, FieldResult::Ok(x) if x.value == Reference::R),
+        length: size(&fld.length),
+        data_type,
+        decimals: size(&fld.decimal_positions),
+        keywords: match &fld.keywords {
+            FieldResult::Ok(kw) => keywords(field_pieces(&kw.tokens)),
+            FieldResult::Idk(_) => vec![],
+        },
+    })
+}
+
+// The fields of one source in order and its REF file
+pub(crate) struct Source {
+    file: String,
+    reference: Option<String>,
+    definitions: Vec<Definition>,
+}
+
+impl Source {
+    pub(crate) fn new(file: &str) -> Self {
+        Self { file: file.to_uppercase(), reference: None, definitions: vec![] }
+    }
+
+    // REF(LIB/FILE RECFMT), the library and record format don't matter here
+    pub(crate) fn file_entry(&mut self, fe: &FileEntry) {
+        let FieldResult::Ok(kw) = &fe.keywords else {
+            return;
+        };
+        let found = keywords(file_pieces(&kw.tokens));
+        if let Some(file) = found.iter().find(|k| k.name == "REF").and_then(|k| k.args.first()) {
+            self.reference = Some(object(file));
+        }
+    }
+
+    pub(crate) fn field(&mut self, format: &str, fld: &Field) {
+        self.definitions.extend(definition(format, fld));
+    }
+
+    fn find(&self, field: &str, before: Option<usize>) -> Option<usize> {
+        let end = before.unwrap_or(self.definitions.len());
+        self.definitions[..end].iter().rposition(|d| d.name == field)
+    }
+}
+
+// LIB/FILE -> FILE
+fn object(text: &str) -> String {
+    text.rsplit('/').next().unwrap_or(text).trim().to_uppercase()
+}
+
+pub(crate) fn source(file: &str, ast: &AST) -> Source {
+    let mut out = Source::new(file);
+    let mut format = String::new();
+    for entry in ast.entries.iter() {
+        match entry {
+            Entry::FE(fe) => out.file_entry(fe),
+            Entry::R(rf) => {
+                if let FieldResult::Ok(name) = &rf.name {
+                    format = name.value.trim().to_uppercase();
+                }
+            }
+            Entry::F(fld) => out.field(&format, fld),
+            Entry::K(_) => {}
+        }
+    }
+    out
+}
+
+struct Resolver<'a> {
+    current: &'a Source,
+    files: &'a HashMap<String, Source>,
+}
+
+// Why a reference can't be followed
+enum Unresolved {
+    // the (file, field) pairs around the loop, the first one repeated at the end
+    Cycle(Vec<(String, String)>),
+    MissingFile(String),
+    MissingField { field: String, file: String },
+}
+
+impl Unresolved {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Cycle(_) => "reference-cycle",
+            Self::MissingFile(_) | Self::MissingField { .. } => "missing-reference",
+        }
+    }
+}
+
+impl fmt::Display for Unresolved {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle(path) => {
+                let path = path.iter().map(|(f, x)| format!("{}.{}", f, x));
+                write!(f, "Reference cycle {}", path.collect::<Vec<_>>().join(" -> "))
+            }
+            Self::MissingFile(file) => {
+                write!(f, "The reference file {} isn't in the manifest", file)
+            }
+            Self::MissingField { field, file } => write!(f, "{} isn't a field of {}", field, file),
+        }
+    }
+}
+
+type Resolved = Result<Referenced, Unresolved>;
+
+impl Resolver<'_> {
+    fn source(&self, file: &str) -> Option<&Source> {
+        match file == self.current.file {
+            true => Some(self.current),
+            false => self.files.get(file),
+        }
+    }
+
+    /* The file and position of the field a reference points to. REFFLD names
+     * the field, optionally qualified with its record format, and the file,
+     * or *SRC for the fields above it in the same source. Without a file the
+     * REF file is searched, then the field reference file.
+     */
+    fn target(&self, src: &Source, idx: usize) -> Result<(String, usize), Unresolved> {
+        let def = &src.definitions[idx];
+        let (field, file) = match def.keyword("REFFLD") {
+            Some(kw) => {
+                let field = kw.args.first().map(|x| object(x)).unwrap_or(def.name.clone());
+                (field, kw.args.get(1).map(|x| x.to_uppercase()))
+            }
+            None => (def.name.clone(), None),
+        };
+        let file = match file.as_deref() {
+            Some("*SRC") => src.file.clone(),
+            Some(x) => object(x),
+            None => match &src.reference {
+                Some(x) => x.clone(),
+                None if src.find(&field, Some(idx)).is_some() => src.file.clone(),
+                None => FIELD_REFERENCE_FILE.to_string(),
+            },
+        };
+        let target = self.source(&file).ok_or_else(|| Unresolved::MissingFile(file.clone()))?;
+        let before = (file == src.file).then_some(idx);
+        let found = target
+            .find(&field, before)
+            .ok_or_else(|| Unresolved::MissingField { field: field.clone(), file: file.clone() })?;
+        Ok((file, found))
+    }
+
+    // `seen` holds the (file, field) pairs on the way here, for cycles
+    fn resolve(&self, file: &str, idx: usize, seen: &mut Vec<(String, String)>) -> Resolved {
+        let src = self.source(file).expect("resolve() requires a known file");
+        let def = &src.definitions[idx];
+        let text = |name: &str| {
+            def.keyword(name).map(|k| k.args.iter().map(|x| unquote(x)).collect::<Vec<String>>())
+        };
+        let mut out = Referenced {
+            format: def.format.clone(),
+            name: def.name.clone(),
+            span: def.span,
+            length: def.length.apply(None),
+            data_type: def.data_type,
+            decimals: def.decimals.apply(None),
+            text: text("TEXT").and_then(|x| x.first().cloned()),
+            colhdg: text("COLHDG").unwrap_or_default(),
+            from: None,
+        };
+        if !def.reference {
+            return Ok(out);
+        }
+        let (target_file, target_idx) = self.target(src, idx)?;
+        let target_name = self.source(&target_file).unwrap().definitions[target_idx].name.clone();
+        let key = (target_file.clone(), target_name.clone());
+        seen.push((src.file.clone(), def.name.clone()));
+        if seen.contains(&key) {
+            return Err(Unresolved::Cycle(seen.iter().chain([&key]).cloned().collect()));
+        }
+        let inherited = self.resolve(&target_file, target_idx, seen)?;
+        seen.pop();
+        out.length = def.length.apply(inherited.length);
+        out.data_type = def.data_type.or(inherited.data_type);
+        out.decimals = def.decimals.apply(inherited.decimals);
+        out.text = out.text.or(inherited.text);
+        if out.colhdg.is_empty() {
+            out.colhdg = inherited.colhdg;
+        }
+        out.from = Some(key);
+        Ok(out)
+    }
+}
+
+pub(crate) fn resolve(current: &Source, files: &HashMap<String, AST>) -> References {
+    let files = files
+        .iter()
+        .map(|(file, ast)| (file.to_uppercase(), source(file, ast)))
+        .collect::<HashMap<String, Source>>();
+    let resolver = Resolver { current, files: &files };
+    let mut out = References { fields: vec![], diagnostics: vec![] };
+    for (idx, def) in current.definitions.iter().enumerate() {
+        match resolver.resolve(&current.file, idx, &mut vec![]) {
+            Ok(referenced) => out.fields.push(referenced),
+            Err(unresolved) => {
+                out.diagnostics.push(Diagnostic {
+                    span: def.keyword("REFFLD").map(|k| k.span).unwrap_or(def.span),
+                    level: DiagnosticLevel::Error,
+                    code: unresolved.code().to_string(),
+                    msg: unresolved.to_string(),
+                });
+                out.fields.push(Referenced {
+                    format: def.format.clone(),
+                    name: def.name.clone(),
+                    span: def.span,
+                    length: def.length.apply(None),
+                    data_type: def.data_type,
+                    decimals: def.decimals.apply(None),
+                    text: None,
+                    colhdg: vec![],
+                    from: None,
+                });
+            }
+        }
+    }
+    out
+}
+
+/* The fields of the physical file `file` with their references followed
+ * through `files`, a map of file names to parsed sources like the manifest's.
+ */
+pub fn resolve_references(file: &str, ast: &AST, files: &HashMap<String, AST>) -> References {
+    resolve(&source(file, ast), files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pfdds::CST;
+
+    fn physical(input: &str) -> AST {
+        AST::from(&CST::try_from(input).unwrap())
+    }
+
+    #[test]
+    fn test_resolve_references() {
+        let fldref = physical(
+            r#"
+     A          R FLDREFR
+     A            COWID          9P 0       TEXT('Cow id')
+     A                                      COLHDG('Cow' 'Id')
+     A            NAME          30A         TEXT('Name')
+     A            AMOUNT        11P 2
+"#,
+        );
+        let cowmst = physical(
+            r#"
+     A          R COWFMT
+     A            CMID      R               REFFLD(COWID FLDREF)
+     A            CMNAME    R  -10          REFFLD(NAME)
+     A                                      TEXT('Cow name')
+"#,
+        );
+        let files = HashMap::from([("FLDREF".to_string(), fldref), ("cowmst".to_string(), cowmst)]);
+        let input = r#"
+     A                                      REF(PRODLIB/COWMST)
+     A          R EVTFMT
+     A            CMID      R
+     A            EVTAMT    R   +2          REFFLD(AMOUNT FLDREF)
+     A            EVTNAME   R               REFFLD(COWFMT/CMNAME)
+     A            EVTCOW    R               REFFLD(EVTCOW *SRC)
+     A            EVTCMT    R               REFFLD(COMMENT)
+     A            EVTNOTE   R               REFFLD(NOTE NOPE)
+     A            EVTDAT         8S 0
+     A            EVTDAY    R               REFFLD(EVTDAT *SRC)
+"#;
+        let refs = resolve_references("COWEVT", &physical(input), &files);
+        let observed = refs
+            .fields
+            .iter()
+            .map(|x| format!("{} {} {:?} {:?} {:?}", x.name, x.detail(), x.text, x.colhdg, x.from))
+            .collect::<Vec<String>>();
+        let expected = [
+            r#"CMID 9P 0 Some("Cow id") ["Cow", "Id"] Some(("COWMST", "CMID"))"#,
+            r#"EVTAMT 13P 2 None [] Some(("FLDREF", "AMOUNT"))"#,
+            r#"EVTNAME 20A Some("Cow name") [] Some(("COWMST", "CMNAME"))"#,
+            "EVTCOW  None [] None",
+            "EVTCMT  None [] None",
+            "EVTNOTE  None [] None",
+            "EVTDAT 8S 0 None [] None",
+            r#"EVTDAY 8S 0 None [] Some(("COWEVT", "EVTDAT"))"#,
+        ];
+        assert_eq!(observed, expected);
+        let observed = refs.diagnostics.iter().map(|d| (d.span.start.row, d.msg.as_str())).collect::<Vec<_>>();
+        let expected = [
+            (5, "EVTCOW isn't a field of COWEVT"),
+            (6, "COMMENT isn't a field of COWMST"),
+            (7, "The reference file NOPE isn't in the manifest"),
+        ];
+        assert_eq!(observed, expected);
+        assert!(refs.diagnostics.iter().all(|d| d.code == "missing-reference"));
+    }
+
+    #[test]
+    fn test_reference_cycle() {
+        let a = physical(
+            r#"
+     A          R AFMT
+     A            AFLD      R               REFFLD(BFLD B)
+"#,
+        );
+        let b = physical(
+            r#"
+     A          R BFMT
+     A            BFLD      R               REFFLD(AFLD A)
+"#,
+        );
+        let files = HashMap::from([("A".to_string(), a), ("B".to_string(), b)]);
+        let refs = resolve_references("A", &files["A"], &files);
+        assert_eq!(refs.diagnostics.len(), 1);
+        assert_eq!(refs.diagnostics[0].code, "reference-cycle");
+        assert_eq!(refs.diagnostics[0].msg, "Reference cycle A.AFLD -> B.BFLD -> A.AFLD");
+    }
+}
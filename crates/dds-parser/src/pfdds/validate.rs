@@ -0,0 +1,303 @@
+use super::{query_keywords, Entry, Field, AST};
+use crate::field::{Datatype, FieldResult, Reference};
+use crate::keyword::{lookup, DdsKeyword, Level};
+use crate::meta::diagnostic::{Diagnostic, DiagnosticLevel};
+use crate::meta::{ISpan, Span};
+
+fn error(span: Span, code: &str, msg: String) -> Diagnostic {
+    Diagnostic {
+        span,
+        level: DiagnosticLevel::Error,
+        code: code.to_string(),
+        msg,
+    }
+}
+
+fn place(level: Level) -> &'static str {
+    match level {
+        Level::File => "the file",
+        Level::Format => "a record format",
+        Level::Join => "a join",
+        Level::Field => "a field",
+        Level::Key => "a key field",
+        Level::SelectOmit => "a select/omit line",
+    }
+}
+
+/* The length and decimal positions a field's type allows. A blank type is
+ * packed with decimal positions and character without, dates, times and
+ * timestamps have no length at all.
+ */
+fn check_size(fld: &Field, name: &str, double: bool, out: &mut Vec<Diagnostic>) {
+    let data_type = match &fld.data_type {
+        FieldResult::Ok(x) => x.value,
+        FieldResult::Idk(_) => return,
+    };
+    let length = match &fld.length {
+        FieldResult::Ok(x) => Some(x.value),
+        FieldResult::Idk(_) => None,
+    };
+    let decimals = match &fld.decimal_positions {
+        FieldResult::Ok(x) => Some(x.value),
+        FieldResult::Idk(_) => None,
+    };
+    let data_type = match data_type {
+        Datatype::Empty if decimals.is_some() => Datatype::P,
+        Datatype::Empty => Datatype::A,
+        x => x,
+    };
+    let (kind, max) = match data_type {
+        Datatype::P => ("packed", 63),
+        Datatype::S => ("zoned", 63),
+        Datatype::B => ("binary", 18),
+        Datatype::F if double => ("double precision float", 17),
+        Datatype::F => ("single precision float", 9),
+        Datatype::A => ("character", 32766),
+        Datatype::H => ("hexadecimal", 32766),
+        Datatype::L | Datatype::T | Datatype::Z => {
+            let kind = match data_type {
+                Datatype::L => "date",
+                Datatype::T => "time",
+                _ => "timestamp",
+            };
+            if length.is_some() {
+                out.push(error(
+                    fld.length.span(),
+                    "length-range",
+                    format!("{} is a {}, it has no length", name, kind),
+                ));
+            }
+            if decimals.is_some() {
+                let msg = format!("{} is a {}, it has no decimal positions", name, kind);
+                out.push(error(
+                    fld.decimal_positions.span(),
+                    "decimals-not-allowed",
+                    msg,
+                ));
+            }
+            return;
+        }
+        Datatype::Empty | Datatype::Five => return,
+    };
+    if let Some(length) = length.filter(|x| !(1..=max).contains(x)) {
+        let msg = format!(
+            "{} is {} long, a {} field is 1 to {}",
+            name, length, kind, max
+        );
+        out.push(error(fld.length.span(), "length-range", msg));
+    }
+    let Some(decimals) = decimals else {
+        return;
+    };
+    match data_type {
+        Datatype::A | Datatype::H => {
+            let msg = format!("{} is {}, it has no decimal positions", name, kind);
+            out.push(error(
+                fld.decimal_positions.span(),
+                "character-decimals",
+                msg,
+            ));
+        }
+        _ => {
+            if let Some(length) = length.filter(|x| decimals > *x) {
+                let msg = format!(
+                    "{} has {} decimal positions but only {} digits",
+                    name, decimals, length
+                );
+                out.push(error(fld.decimal_positions.span(), "length-range", msg));
+            }
+        }
+    }
+}
+
+/* What fits the columns but isn't a valid physical file: lengths and decimal
+ * positions the type doesn't allow, a field or record format named twice,
+ * fields after the key fields, key fields that aren't fields of their record
+ * format and keywords where they don't go.
+ */
+pub fn validate(ast: &AST) -> Vec<Diagnostic> {
+    let keywords = query_keywords(ast);
+    let mut out = vec![];
+    let mut formats: Vec<String> = vec![];
+    let mut fields: Vec<String> = vec![];
+    let mut keyed = false;
+    for entry in ast.entries.iter() {
+        match entry {
+            Entry::R(rf) => {
+                let FieldResult::Ok(namefield) = &rf.name else {
+                    continue;
+                };
+                let name = namefield.value.trim().to_uppercase();
+                if formats.contains(&name) {
+                    out.push(error(
+                        namefield.meta.span,
+                        "duplicate-format",
+                        format!("{} is already a record format", name),
+                    ));
+                }
+                formats.push(name);
+                fields.clear();
+                keyed = false;
+            }
+            Entry::F(fld) => {
+                let FieldResult::Ok(namefield) = &fld.name else {
+                    continue;
+                };
+                let name = namefield.value.trim().to_uppercase();
+                let format = formats.last().cloned().unwrap_or_default();
+                if keyed {
+                    let msg = format!("{} comes after the key fields, fields go before them", name);
+                    out.push(error(namefield.meta.span, "field-after-key", msg));
+                }
+                if fields.contains(&name) {
+                    out.push(error(
+                        namefield.meta.span,
+                        "duplicate-field",
+                        format!("{} is already a field of {}", name, format),
+                    ));
+                }
+                fields.push(name.clone());
+                // a reference takes the referenced size, only what's written over it is checked
+                let referenced =
+                    matches!(&fld.reference, FieldResult::Ok(x) if x.value == Reference::R);
+                if !referenced || matches!(fld.length, FieldResult::Ok(_)) {
+                    let double = keywords.of(&format, Some(&name)).any(|k| match k {
+                        DdsKeyword::Other(kw, args) => {
+                            kw == "FLTPCN" && args.iter().any(|x| x.eq_ignore_ascii_case("*DOUBLE"))
+                        }
+                        _ => false,
+                    });
+                    check_size(fld, &name, double, &mut out);
+                }
+            }
+            Entry::K(kf) => {
+                keyed = true;
+                let FieldResult::Ok(namefield) = &kf.name else {
+                    continue;
+                };
+                let name = namefield.value.trim().to_uppercase();
+                if !name.starts_with('*') && !fields.contains(&name) {
+                    let format = formats.last().cloned().unwrap_or_default();
+                    out.push(error(
+                        namefield.meta.span,
+                        "unknown-key",
+                        format!("{} isn't a field of {}", name, format),
+                    ));
+                }
+            }
+            Entry::FE(_) => {}
+        }
+    }
+    for typed in keywords.keywords.iter() {
+        let Some(known) = lookup(typed.keyword.name()) else {
+            continue;
+        };
+        if !known.levels.contains(&typed.level) {
+            let allowed = known
+                .levels
+                .iter()
+                .map(|x| place(*x))
+                .collect::<Vec<_>>()
+                .join(" or ");
+            let msg = format!(
+                "{} goes on {}, not {}",
+                known.name,
+                allowed,
+                place(typed.level)
+            );
+            out.push(error(typed.span, "keyword-level", msg));
+        }
+    }
+    out.sort_by_key(|d| (d.span.start.row, d.span.start.col));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pfdds::CST;
+
+    #[test]
+    fn test_validate() {
+        let input = r#"
+     A                                      DESCEND
+     A          R EVTFMT                    UNIQUE
+     A            EVTID          9P 0
+     A            EVTNAME       20A 2
+     A            EVTID          5S 0
+     A            EVTBIG        70P 0
+     A            EVTQTY         3B 5
+     A            EVTDAT         8L
+     A            EVTWGT        12F 2
+     A            EVTDBL        12F 2       FLTPCN(*DOUBLE)
+     A            EVTWHO    R     +2        REFFLD(USER NOPE) TEXT('Who')
+     A          K EVTID
+     A          K EVTNOPE
+     A            EVTLATE        1A         DESCEND
+     A          R EVTFMT
+     A            EVTTIM          T 2
+"#[1..]
+            .to_string();
+        let ast = AST::from(&CST::try_from(input.as_str()).unwrap());
+        let observed = validate(&ast)
+            .into_iter()
+            .map(|d| (d.span.start.row, d.code, d.msg))
+            .collect::<Vec<_>>();
+        let expected = vec![
+            (
+                0,
+                "keyword-level",
+                "DESCEND goes on a key field, not the file",
+            ),
+            (
+                1,
+                "keyword-level",
+                "UNIQUE goes on the file, not a record format",
+            ),
+            (
+                3,
+                "character-decimals",
+                "EVTNAME is character, it has no decimal positions",
+            ),
+            (4, "duplicate-field", "EVTID is already a field of EVTFMT"),
+            (
+                5,
+                "length-range",
+                "EVTBIG is 70 long, a packed field is 1 to 63",
+            ),
+            (
+                6,
+                "length-range",
+                "EVTQTY has 5 decimal positions but only 3 digits",
+            ),
+            (7, "length-range", "EVTDAT is a date, it has no length"),
+            (
+                8,
+                "length-range",
+                "EVTWGT is 12 long, a single precision float field is 1 to 9",
+            ),
+            (12, "unknown-key", "EVTNOPE isn't a field of EVTFMT"),
+            (
+                13,
+                "field-after-key",
+                "EVTLATE comes after the key fields, fields go before them",
+            ),
+            (
+                13,
+                "keyword-level",
+                "DESCEND goes on a key field, not a field",
+            ),
+            (14, "duplicate-format", "EVTFMT is already a record format"),
+            (
+                15,
+                "decimals-not-allowed",
+                "EVTTIM is a time, it has no decimal positions",
+            ),
+        ];
+        let expected = expected
+            .into_iter()
+            .map(|(row, code, msg)| (row, code.to_string(), msg.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(observed, expected);
+    }
+}
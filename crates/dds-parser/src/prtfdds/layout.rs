@@ -0,0 +1,273 @@
+use super::{Edit, PrinterField, PrinterFile, PrinterFormat, Placement, Spacing};
+use crate::dspfdds::satisfied;
+
+// A write of a record format with the indicators in `on` set
+#[derive(Debug, Clone, PartialEq)]
+pub struct Write {
+    pub format: String,
+    pub on: Vec<u8>,
+}
+
+struct Printer {
+    // the lines printed on each page, one based
+    pages: Vec<Vec<Vec<char>>>,
+    line: usize,
+    width: usize,
+    overflow: usize,
+}
+
+impl Printer {
+    fn new_page(&mut self) {
+        self.pages.push(vec![]);
+        self.line = 1;
+    }
+
+    // a line behind the current one is on the next page
+    fn skip(&mut self, to: usize) {
+        if to < self.line {
+            self.new_page();
+        }
+        self.line = to.max(1);
+        self.overflowed();
+    }
+
+    fn space(&mut self, lines: usize) {
+        self.line += lines;
+        self.overflowed();
+    }
+
+    // past the overflow line, the page is done
+    fn overflowed(&mut self) {
+        if self.line > self.overflow {
+            self.new_page();
+        }
+    }
+
+    fn before(&mut self, spacing: Spacing) {
+        if let Some(to) = spacing.skip_before {
+            self.skip(to);
+        }
+        if let Some(lines) = spacing.space_before {
+            self.space(lines);
+        }
+    }
+
+    fn after(&mut self, spacing: Spacing) {
+        if let Some(lines) = spacing.space_after {
+            self.space(lines);
+        }
+        if let Some(to) = spacing.skip_after {
+            self.skip(to);
+        }
+    }
+
+    // `position` is one based like in the source
+    fn print(&mut self, position: usize, text: &[char]) {
+        let page = self.pages.last_mut().expect("a printer always has a page");
+        if page.len() < self.line {
+            page.resize(self.line, vec![]);
+        }
+        let line = &mut page[self.line - 1];
+        for (i, ch) in text.iter().enumerate() {
+            let col = position + i;
+            if col == 0 || col > self.width {
+                continue;
+            }
+            if line.len() < col {
+                line.resize(col, ' ');
+            }
+            line[col - 1] = *ch;
+        }
+    }
+
+    fn text(&self) -> String {
+        self.pages
+            .iter()
+            .map(|page| {
+                page.iter()
+                    .map(|line| line.iter().collect::<String>().trim_end().to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            })
+            .collect::<Vec<String>>()
+            .join("\n\u{c}\n")
+    }
+}
+
+// 1234567 -> 1,234,567
+fn grouped(digits: usize) -> String {
+    let mut out = vec![];
+    for i in 0..digits {
+        if i > 0 && (digits - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push('9');
+    }
+    out.into_iter().collect()
+}
+
+// what an edited number looks like, 9s for the digits
+fn edited(length: usize, decimals: usize, edit: &Edit) -> String {
+    let digits = length.saturating_sub(decimals);
+    let fraction = match decimals {
+        0 => String::new(),
+        _ => format!(".{}", "9".repeat(decimals)),
+    };
+    match edit {
+        Edit::Word(word) => word
+            .chars()
+            .map(|c| match c {
+                ' ' | '0' => '9',
+                '&' => ' ',
+                c => c,
+            })
+            .collect(),
+        Edit::Code('Y', _) => match length {
+            8 => "99/99/9999".to_string(),
+            _ => "99/99/99".to_string(),
+        },
+        Edit::Code('Z', _) => "9".repeat(digits),
+        Edit::Code(code, _) => {
+            let integer = match "12ABJKNO".contains(*code) {
+                true => grouped(digits),
+                false => "9".repeat(digits),
+            };
+            match code {
+                'A'..='D' => format!("{}{}CR", integer, fraction),
+                'J'..='M' => format!("{}{}-", integer, fraction),
+                'N'..='Q' => format!("-{}{}", integer, fraction),
+                _ => format!("{}{}", integer, fraction),
+            }
+        }
+    }
+}
+
+// constants as text, character fields as Xs and numbers as 9s, edited
+fn content(field: &PrinterField) -> Option<Vec<char>> {
+    if field.is_constant() {
+        return field.constant.as_ref().map(|x| x.chars().collect());
+    }
+    let length = field.length?;
+    let text = match (field.decimals, &field.edit) {
+        (Some(decimals), Some(edit)) => edited(length, decimals, edit),
+        (Some(_), None) => "9".repeat(length),
+        (None, _) => "X".repeat(length),
+    };
+    Some(text.chars().collect())
+}
+
+fn write(printer: &mut Printer, format: &PrinterFormat, on: &[u8]) {
+    printer.before(format.spacing(on));
+    let mut end = 1;
+    for field in format.fields.iter() {
+        let Some(text) = content(field) else {
+            continue;
+        };
+        let position = match field.position {
+            Some(Placement::Absolute(x)) => x,
+            Some(Placement::Relative(x)) => end + x,
+            None => continue,
+        };
+        end = position + text.len();
+        if !satisfied(&field.conditions, on) {
+            continue;
+        }
+        printer.before(field.spacing(on));
+        printer.print(position, &text);
+        printer.after(field.spacing(on));
+    }
+    printer.after(format.spacing(on));
+}
+
+/* Print a sequence of record writes to pages `width` columns wide. Like a
+ * program that handles overflow, a new page starts once the overflow line is
+ * passed. Pages are separated by a form feed on its own line.
+ */
+pub fn simulate(file: &PrinterFile, writes: &[Write], width: usize, overflow: usize) -> Result<String, String> {
+    let mut printer = Printer { pages: vec![vec![]], line: 1, width, overflow: overflow.max(1) };
+    for w in writes.iter() {
+        let format = file
+            .format(&w.format)
+            .ok_or(format!("No record format named {}", w.format))?;
+        write(&mut printer, format, &w.on);
+    }
+    Ok(printer.text())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prtfdds::{query_printer_file, AST, CST};
+
+    fn fixture() -> PrinterFile {
+        let input = r#"
+     A          R HEADER                    SKIPB(1) SPACEA(2)
+     A                                     2'Cow report'
+     A                                    30'Page'
+     A            PAGNBR         4S 0     +1EDTCDE(Z)
+     A          R DETAIL                    SPACEA(1)
+     A  90                                  SPACEB(1)
+     A            COWID          6S 0      2
+     A            COWNAME       10        +2
+     A            WEIGHT         7P 2     +2EDTCDE(J)
+     A N91        FLAG           1        +1
+     A          R TOTAL                     SPACEB(1)
+     A                                     2'Total'
+     A            TOTWGT         9P 2     +2EDTWRD('   ,   ,   .  -')
+"#[1..]
+            .to_string();
+        let cst = CST::try_from(input.as_str()).unwrap();
+        query_printer_file(&AST::from(&cst))
+    }
+
+    fn writes(formats: &[(&str, &[u8])]) -> Vec<Write> {
+        formats
+            .iter()
+            .map(|(format, on)| Write { format: format.to_string(), on: on.to_vec() })
+            .collect()
+    }
+
+    #[test]
+    fn test_simulate_report() {
+        let file = fixture();
+        let writes = writes(&[
+            ("HEADER", &[]),
+            ("DETAIL", &[]),
+            ("DETAIL", &[90]),
+            ("DETAIL", &[91]),
+            ("TOTAL", &[]),
+        ]);
+        let observed = simulate(&file, &writes, 132, 60).unwrap();
+        let expected = [
+            " Cow report                  Page 9999",
+            "",
+            " 999999  XXXXXXXXXX  99,999.99- X",
+            "",
+            " 999999  XXXXXXXXXX  99,999.99- X",
+            " 999999  XXXXXXXXXX  99,999.99-",
+            "",
+            " Total  999,999,999.99-",
+        ]
+        .join("\n");
+        assert_eq!(observed, expected);
+    }
+
+    #[test]
+    fn test_simulate_overflow() {
+        let file = fixture();
+        let writes = writes(&[("HEADER", &[]), ("DETAIL", &[]), ("DETAIL", &[]), ("DETAIL", &[])]);
+        let observed = simulate(&file, &writes, 20, 4).unwrap();
+        let expected = [
+            " Cow report",
+            "",
+            " 999999  XXXXXXXXXX",
+            " 999999  XXXXXXXXXX",
+            "\u{c}",
+            " 999999  XXXXXXXXXX",
+        ]
+        .join("\n");
+        assert_eq!(observed, expected);
+        let unknown = vec![Write { format: "NOTHERE".to_string(), on: vec![] }];
+        assert!(simulate(&file, &unknown, 80, 60).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+// Printer files have the same columns as display files and parse to the same AST
+mod printer_file;
+mod layout;
+pub use crate::dspfdds::{
+    AST, CST, DisplayKeyword, Entry, Indicator, Placement, highlight_ast, highlight_cst,
+    query_definition, query_fields, query_folds, query_outline, query_record_formats,
+    resolve_references,
+};
+pub use printer_file::{Edit, PrinterField, PrinterFile, PrinterFormat, Spacing, query_printer_file};
+pub use layout::{Write, simulate};
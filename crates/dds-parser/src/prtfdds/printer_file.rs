@@ -0,0 +1,217 @@
+use super::{DisplayKeyword, Entry, Indicator, Placement, AST};
+use crate::dspfdds::{conditioned, satisfied, unquote};
+use crate::field::FieldResult;
+use crate::free::{field_pieces, file_pieces, format_pieces, keywords};
+use crate::meta::Span;
+use crate::pfdds::{detail, Field};
+use serde::{Deserialize, Serialize};
+
+// SPACEB/SPACEA move down lines, SKIPB/SKIPA go to a line, on the next page when it's behind
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Spacing {
+    pub space_before: Option<usize>,
+    pub space_after: Option<usize>,
+    pub skip_before: Option<usize>,
+    pub skip_after: Option<usize>,
+}
+
+impl Spacing {
+    // from the keywords in effect with the indicators in `on`
+    pub fn of(keywords: &[DisplayKeyword], on: &[u8]) -> Self {
+        let mut out = Self::default();
+        for kw in keywords.iter().filter(|x| satisfied(&x.conditions, on)) {
+            let n = kw.args.first().and_then(|x| x.parse::<usize>().ok());
+            match kw.name.as_str() {
+                "SPACEB" => out.space_before = n,
+                "SPACEA" => out.space_after = n,
+                "SKIPB" => out.skip_before = n,
+                "SKIPA" => out.skip_after = n,
+                _ => {}
+            }
+        }
+        out
+    }
+}
+
+// How a numeric field is edited
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Edit {
+    // EDTCDE(J) or EDTCDE(1 *), the code and the fill or currency symbol
+    Code(char, Option<String>),
+    // EDTWRD('  /  /    '), without the quotes
+    Word(String),
+}
+
+// A field or constant of a printer record format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrinterField {
+    // empty for constants
+    pub name: String,
+    pub span: Span,
+    pub length: Option<usize>,
+    // numeric fields have them
+    pub decimals: Option<usize>,
+    // type and length, ex: 9P 2
+    pub detail: String,
+    // the text of a constant, without its quotes
+    pub constant: Option<String>,
+    pub position: Option<Placement>,
+    pub conditions: Vec<Indicator>,
+    pub keywords: Vec<DisplayKeyword>,
+    pub edit: Option<Edit>,
+}
+
+impl PrinterField {
+    pub fn is_constant(&self) -> bool {
+        self.name.is_empty()
+    }
+
+    pub fn spacing(&self, on: &[u8]) -> Spacing {
+        Spacing::of(&self.keywords, on)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrinterFormat {
+    pub name: String,
+    pub span: Span,
+    pub keywords: Vec<DisplayKeyword>,
+    pub fields: Vec<PrinterField>,
+}
+
+impl PrinterFormat {
+    pub fn spacing(&self, on: &[u8]) -> Spacing {
+        Spacing::of(&self.keywords, on)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrinterFile {
+    pub keywords: Vec<DisplayKeyword>,
+    pub formats: Vec<PrinterFormat>,
+}
+
+impl PrinterFile {
+    pub fn format(&self, name: &str) -> Option<&PrinterFormat> {
+        let name = name.to_uppercase();
+        self.formats.iter().find(|x| x.name == name)
+    }
+}
+
+fn edit(keywords: &[DisplayKeyword]) -> Option<Edit> {
+    keywords.iter().find_map(|kw| match (kw.name.as_str(), kw.args.as_slice()) {
+        ("EDTCDE", [code, rest @ ..]) => Some(Edit::Code(
+            code.to_uppercase().chars().next()?,
+            rest.first().cloned(),
+        )),
+        ("EDTWRD", [word, ..]) => Some(Edit::Word(unquote(word))),
+        _ => None,
+    })
+}
+
+fn printer_field(fld: &Field, ast: &AST) -> PrinterField {
+    let (name, span) = match &fld.name {
+        FieldResult::Ok(x) => (x.value.trim().to_uppercase(), x.meta.span),
+        FieldResult::Idk(x) => (String::new(), x.meta.span),
+    };
+    let row = span.start.row;
+    let found = match &fld.keywords {
+        FieldResult::Ok(kw) => keywords(field_pieces(&kw.tokens)),
+        FieldResult::Idk(_) => vec![],
+    };
+    let keywords = conditioned(found, Some(row), ast);
+    let constant = match name.is_empty() {
+        true => keywords
+            .iter()
+            .find(|x| x.name == "DFT")
+            .and_then(|x| x.args.first())
+            .map(|x| unquote(x)),
+        false => None,
+    };
+    PrinterField {
+        name,
+        span,
+        length: match &fld.length {
+            FieldResult::Ok(x) => Some(x.value),
+            FieldResult::Idk(_) => None,
+        },
+        decimals: match &fld.decimal_positions {
+            FieldResult::Ok(x) => Some(x.value),
+            FieldResult::Idk(_) => None,
+        },
+        detail: detail(fld),
+        constant,
+        position: match &fld.location {
+            FieldResult::Ok(x) => x.value.position,
+            FieldResult::Idk(_) => None,
+        },
+        conditions: ast.indicators.get(&row).cloned().unwrap_or_default(),
+        edit: edit(&keywords),
+        keywords,
+    }
+}
+
+/* The record formats of a printer file with their spacing and the fields and
+ * constants on them.
+ */
+pub fn query_printer_file(ast: &AST) -> PrinterFile {
+    let mut file = PrinterFile { keywords: vec![], formats: vec![] };
+    for entry in ast.entries.iter() {
+        match entry {
+            Entry::FE(fe) => {
+                if let FieldResult::Ok(kw) = &fe.keywords {
+                    file.keywords.extend(conditioned(keywords(file_pieces(&kw.tokens)), None, ast));
+                }
+            }
+            Entry::R(rf) => {
+                let (name, span) = match &rf.name {
+                    FieldResult::Ok(x) => (x.value.trim().to_uppercase(), x.meta.span),
+                    FieldResult::Idk(x) => (String::new(), x.meta.span),
+                };
+                let found = match &rf.keywords {
+                    FieldResult::Ok(kw) => keywords(format_pieces(&kw.tokens)),
+                    FieldResult::Idk(_) => vec![],
+                };
+                let keywords = conditioned(found, Some(span.start.row), ast);
+                file.formats.push(PrinterFormat {
+                    name,
+                    span,
+                    keywords,
+                    fields: vec![],
+                });
+            }
+            Entry::F(fld) | Entry::C(fld) => {
+                if let Some(format) = file.formats.last_mut() {
+                    format.fields.push(printer_field(fld, ast));
+                }
+            }
+        }
+    }
+    file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prtfdds::CST;
+
+    #[test]
+    fn test_printer_file_snapshot() {
+        let input = r#"
+     A                                      REF(COWEVT)
+     A          R DETAIL                    SPACEA(1)
+     A  90                                  SKIPB(3)
+     A            COWID          6S 0      2
+     A            WEIGHT         7P 2     +2EDTCDE(J)
+     A            BORN           8S 0     +2EDTWRD('    /  /  ')
+     A                                    +2'kg'
+"#[1..]
+            .to_string();
+        let cst = CST::try_from(input.as_str()).unwrap();
+        let file = query_printer_file(&AST::from(&cst));
+        let detail = file.format("detail").unwrap();
+        assert_eq!(detail.spacing(&[]).skip_before, None);
+        assert_eq!(detail.spacing(&[90]).skip_before, Some(3));
+        insta::assert_yaml_snapshot!(file);
+    }
+}
@@ -0,0 +1,1033 @@
+use super::cst::{Token, TokenKind, CST};
+use super::resolve::resolve_objects;
+use crate::meta::Span;
+use crate::pfdds::Key;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ObjectKind {
+    Table,
+    View,
+    Index,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    // the system name, what RPG sees, * stands for every column of `origin`
+    pub name: String,
+    // the SQL name when FOR COLUMN gives a shorter system name
+    pub alias: Option<String>,
+    // the system name, or the SQL name without one
+    pub span: Span,
+    // as written, ex: DECIMAL(9, 0), empty when a view or index takes it from its table
+    pub sql_type: String,
+    // the DDS equivalent, None for types DDS doesn't have, ex: CLOB
+    pub length: Option<usize>,
+    pub data_type: Option<char>,
+    pub decimals: Option<usize>,
+    pub nullable: bool,
+    // from LABEL ON, TEXT IS and IS
+    pub text: Option<String>,
+    pub colhdg: Vec<String>,
+    // the table, or correlation name, and column a view or index column is,
+    // the table is empty when the select doesn't qualify the column
+    pub origin: Option<(String, String)>,
+}
+
+impl Column {
+    // type and length like DDS has them, ex: 9P 0, or the SQL type
+    pub fn detail(&self) -> String {
+        if self.data_type.is_none() {
+            return self.sql_type.clone();
+        }
+        let length = self.length.map(|x| x.to_string()).unwrap_or_default();
+        let data_type = self.data_type.map(|x| x.to_string()).unwrap_or_default();
+        let decimals = self.decimals.map(|x| x.to_string()).unwrap_or_default();
+        format!("{}{} {}", length, data_type, decimals)
+            .trim()
+            .to_string()
+    }
+
+    fn new(name: String, alias: Option<String>, span: Span) -> Self {
+        Self {
+            name,
+            alias,
+            span,
+            sql_type: String::new(),
+            length: None,
+            data_type: None,
+            decimals: None,
+            nullable: true,
+            text: None,
+            colhdg: vec![],
+            origin: None,
+        }
+    }
+
+    pub fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self
+                .alias
+                .as_deref()
+                .is_some_and(|x| x.eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SqlObject {
+    pub kind: ObjectKind,
+    // the system name, the object name RPG sees
+    pub name: String,
+    // the SQL name when FOR SYSTEM NAME gives a shorter system name
+    pub alias: Option<String>,
+    pub span: Span,
+    // the RCDFMT, or the object name without one, an index without one is its table's
+    pub format: String,
+    pub format_span: Option<Span>,
+    pub text: Option<String>,
+    pub columns: Vec<Column>,
+    // the primary key of a table, the columns of an index
+    pub keys: Vec<Key>,
+    pub unique: bool,
+    // the tables a view selects from, the table of an index, with their correlation names
+    pub from: Vec<(String, Option<String>)>,
+    // CREATE through the last token
+    pub statement: Span,
+}
+
+impl SqlObject {
+    pub fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self
+                .alias
+                .as_deref()
+                .is_some_and(|x| x.eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AST {
+    pub objects: Vec<SqlObject>,
+}
+
+// the words that end a column's data type, what comes after are its clauses
+const CLAUSES: &[&str] = &[
+    "NOT",
+    "NULL",
+    "WITH",
+    "DEFAULT",
+    "CCSID",
+    "ALLOCATE",
+    "PRIMARY",
+    "UNIQUE",
+    "CHECK",
+    "CONSTRAINT",
+    "GENERATED",
+    "REFERENCES",
+    "FOR",
+    "IMPLICITLY",
+    "AS",
+    "HIDDEN",
+];
+
+// the table elements that aren't columns
+const CONSTRAINTS: &[&str] = &[
+    "CONSTRAINT",
+    "PRIMARY",
+    "UNIQUE",
+    "FOREIGN",
+    "CHECK",
+    "PERIOD",
+];
+
+// the words that end a FROM clause, or a table reference in it
+const FROM_ENDS: &[&str] = &[
+    "WHERE",
+    "GROUP",
+    "HAVING",
+    "ORDER",
+    "UNION",
+    "EXCEPT",
+    "INTERSECT",
+    "FETCH",
+    "LIMIT",
+    "OFFSET",
+    "WITH",
+    "RCDFMT",
+];
+const JOINS: &[&str] = &[
+    "JOIN",
+    "INNER",
+    "LEFT",
+    "RIGHT",
+    "FULL",
+    "CROSS",
+    "EXCEPTION",
+    "OUTER",
+    "ON",
+];
+
+// the length, type and decimals a DDS field of this SQL type has
+fn dds_type(words: &str, args: &[String]) -> (Option<usize>, Option<char>, Option<usize>) {
+    let arg =
+        |i: usize, default: Option<usize>| args.get(i).and_then(|x| x.parse().ok()).or(default);
+    match words {
+        "DECIMAL" | "DEC" => (arg(0, Some(5)), Some('P'), arg(1, Some(0))),
+        "NUMERIC" | "NUM" => (arg(0, Some(5)), Some('S'), arg(1, Some(0))),
+        "CHAR" | "CHARACTER" | "VARCHAR" | "CHAR VARYING" | "CHARACTER VARYING" => {
+            (arg(0, Some(1)), Some('A'), None)
+        }
+        "GRAPHIC" | "VARGRAPHIC" | "NCHAR" | "NVARCHAR" => (arg(0, Some(1)), Some('G'), None),
+        "BINARY" | "VARBINARY" => (arg(0, Some(1)), Some('H'), None),
+        "SMALLINT" => (Some(4), Some('B'), Some(0)),
+        "INTEGER" | "INT" => (Some(9), Some('B'), Some(0)),
+        "BIGINT" => (Some(18), Some('B'), Some(0)),
+        "REAL" | "FLOAT" | "DOUBLE" | "DOUBLE PRECISION" => (None, Some('F'), None),
+        "DATE" => (None, Some('L'), None),
+        "TIME" => (None, Some('T'), None),
+        "TIMESTAMP" => (None, Some('Z'), None),
+        _ => (None, None, None),
+    }
+}
+
+// 'It''s' -> It's
+fn unquote(text: &str) -> String {
+    text.replace("''", "'")
+}
+
+// the source text of some tokens, spaced the way SQL is usually written
+fn text(tokens: &[Token]) -> String {
+    let mut out = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let joined = match i.checked_sub(1).map(|x| &tokens[x]) {
+            None => true,
+            Some(prev) if prev.is_punct('(') || prev.is_punct('.') => true,
+            // 1M, 1K and such are a number and a word with nothing between them
+            Some(prev) if prev.kind == TokenKind::Number && prev.span.end == token.span.start => {
+                true
+            }
+            Some(_) => token.is_punct(',') || token.is_punct(')') || token.is_punct('.'),
+        };
+        if !joined {
+            out.push(' ');
+        }
+        match token.kind {
+            TokenKind::Str => out.push_str(&format!("'{}'", token.text)),
+            TokenKind::Quoted => out.push_str(&format!("\"{}\"", token.text)),
+            _ => out.push_str(&token.text),
+        }
+    }
+    out
+}
+
+// split on `c` outside of parens
+fn split(tokens: &[Token], c: char) -> Vec<&[Token]> {
+    let mut out = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, token) in tokens.iter().enumerate() {
+        if token.is_punct('(') {
+            depth += 1;
+        } else if token.is_punct(')') {
+            depth -= 1;
+        } else if depth == 0 && token.is_punct(c) {
+            out.push(&tokens[start..i]);
+            start = i + 1;
+        }
+    }
+    out.push(&tokens[start..]);
+    out.into_iter().filter(|x| !x.is_empty()).collect()
+}
+
+fn is_name(token: &Token) -> bool {
+    matches!(token.kind, TokenKind::Word | TokenKind::Quoted)
+}
+
+struct Cursor<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(tokens: &'a [Token]) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let out = self.tokens.get(self.pos);
+        self.pos += 1;
+        out
+    }
+
+    fn rest(&self) -> &'a [Token] {
+        &self.tokens[self.pos.min(self.tokens.len())..]
+    }
+
+    // consumes the word if it's next
+    fn eat(&mut self, word: &str) -> bool {
+        match self.peek() {
+            Some(token) if token.is(word) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // consumes the words if they're all next
+    fn eat_all(&mut self, words: &[&str]) -> bool {
+        let found = words
+            .iter()
+            .enumerate()
+            .all(|(i, word)| self.tokens.get(self.pos + i).is_some_and(|x| x.is(word)));
+        if found {
+            self.pos += words.len();
+        }
+        found
+    }
+
+    // a name, qualified or not, ex: LIB.TABLE or LIB/TABLE, the last part of it
+    fn name(&mut self) -> Option<&'a Token> {
+        let mut out = self.next().filter(|x| is_name(x))?;
+        while self
+            .peek()
+            .is_some_and(|x| x.is_punct('.') || x.is_punct('/'))
+            && self.tokens.get(self.pos + 1).is_some_and(is_name)
+        {
+            out = &self.tokens[self.pos + 1];
+            self.pos += 2;
+        }
+        Some(out)
+    }
+
+    // the tokens inside the parens that are next, and past them
+    fn parens(&mut self) -> Option<&'a [Token]> {
+        if !self.peek()?.is_punct('(') {
+            return None;
+        }
+        let start = self.pos + 1;
+        let mut depth = 0;
+        while let Some(token) = self.next() {
+            if token.is_punct('(') {
+                depth += 1;
+            } else if token.is_punct(')') {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&self.tokens[start..self.pos - 1]);
+                }
+            }
+        }
+        Some(&self.tokens[start..])
+    }
+
+    // a name, the system name after FOR [COLUMN] when there's one
+    fn system_name(&mut self, first: &Token) -> (String, Option<String>, Span) {
+        if self.eat("FOR") {
+            self.eat("COLUMN");
+            if let Some(sys) = self.next().filter(|x| is_name(x)) {
+                return (sys.name(), Some(first.name()), sys.span);
+            }
+        }
+        (first.name(), None, first.span)
+    }
+
+    // a RCDFMT anywhere after, outside of parens, and what follows it
+    fn rcdfmt(&mut self) -> Option<&'a Token> {
+        while let Some(token) = self.peek() {
+            if token.is_punct('(') {
+                self.parens();
+                continue;
+            }
+            self.pos += 1;
+            if token.is("RCDFMT") {
+                return self.next().filter(|x| is_name(x));
+            }
+        }
+        None
+    }
+}
+
+fn object(kind: ObjectKind, cur: &mut Cursor, statement: Span) -> Option<SqlObject> {
+    let first = cur.name()?;
+    let (name, alias, span) = match cur.eat_all(&["FOR", "SYSTEM", "NAME"]) {
+        true => {
+            let sys = cur.name()?;
+            (sys.name(), Some(first.name()), sys.span)
+        }
+        false => (first.name(), None, first.span),
+    };
+    Some(SqlObject {
+        kind,
+        format: name.clone(),
+        name,
+        alias,
+        span,
+        format_span: None,
+        text: None,
+        columns: vec![],
+        keys: vec![],
+        unique: false,
+        from: vec![],
+        statement,
+    })
+}
+
+fn set_format(object: &mut SqlObject, token: Option<&Token>) {
+    if let Some(token) = token {
+        object.format = token.name();
+        object.format_span = Some(token.span);
+    }
+}
+
+// the columns of a key, ex: (EVTID, EDAT DESC)
+fn keys(tokens: &[Token]) -> Vec<Key> {
+    split(tokens, ',')
+        .into_iter()
+        .filter_map(|key| {
+            let name = key.first().filter(|x| is_name(x))?;
+            Some(Key {
+                name: name.name(),
+                descend: key.iter().any(|x| x.is("DESC")),
+                absval: false,
+                signed: false,
+                span: name.span,
+            })
+        })
+        .collect()
+}
+
+// a column definition, None for a constraint
+fn column(tokens: &[Token]) -> Option<Column> {
+    let mut cur = Cursor::new(tokens);
+    let first = cur.next().filter(|x| is_name(x))?;
+    if CONSTRAINTS.iter().any(|x| first.is(x)) {
+        return None;
+    }
+    let (name, alias, span) = cur.system_name(first);
+    let mut column = Column::new(name, alias, span);
+    let mut words = vec![];
+    while let Some(token) = cur
+        .peek()
+        .filter(|x| x.kind == TokenKind::Word && !CLAUSES.iter().any(|c| x.is(c)))
+    {
+        words.push(token.text.to_uppercase());
+        cur.pos += 1;
+    }
+    let words = words.join(" ");
+    let args = cur
+        .parens()
+        .map(|x| split(x, ',').into_iter().map(text).collect::<Vec<_>>())
+        .unwrap_or_default();
+    column.sql_type = match args.is_empty() {
+        true => words.clone(),
+        false => format!("{}({})", words, args.join(", ")),
+    };
+    (column.length, column.data_type, column.decimals) = dds_type(&words, &args);
+    column.nullable = !cur
+        .rest()
+        .windows(2)
+        .any(|x| x[0].is("NOT") && x[1].is("NULL"));
+    Some(column)
+}
+
+fn create_table(cur: &mut Cursor, statement: Span) -> Option<SqlObject> {
+    let mut table = object(ObjectKind::Table, cur, statement)?;
+    if cur.eat("LIKE") {
+        let like = cur.name()?;
+        table.from.push((like.name(), None));
+        let mut all = Column::new("*".to_string(), None, like.span);
+        all.origin = Some((like.name(), "*".to_string()));
+        table.columns.push(all);
+    } else if cur.eat("AS") {
+        let select = cur.parens()?;
+        select_columns(&mut table, select);
+    } else {
+        for element in split(cur.parens()?, ',') {
+            match column(element) {
+                Some(column) => {
+                    // a primary key on the column itself
+                    if element
+                        .windows(2)
+                        .any(|x| x[0].is("PRIMARY") && x[1].is("KEY"))
+                    {
+                        table.keys = vec![Key {
+                            name: column.name.clone(),
+                            descend: false,
+                            absval: false,
+                            signed: false,
+                            span: column.span,
+                        }];
+                        table.unique = true;
+                    }
+                    table.columns.push(column);
+                }
+                None => {
+                    let mut inner = Cursor::new(element);
+                    while inner.peek().is_some() && !inner.eat_all(&["PRIMARY", "KEY"]) {
+                        inner.pos += 1;
+                    }
+                    if let Some(found) = inner.parens() {
+                        table.keys = keys(found);
+                        table.unique = true;
+                    }
+                }
+            }
+        }
+    }
+    // a primary key names the columns by their SQL names
+    for key in table.keys.iter_mut() {
+        if let Some(column) = table.columns.iter().find(|x| x.is(&key.name)) {
+            key.name = column.name.clone();
+        }
+    }
+    let format = cur.rcdfmt();
+    set_format(&mut table, format);
+    Some(table)
+}
+
+/* The columns a select has, named by AS or after the column they are. Those
+ * that are just a column, qualified or not, keep where they come from.
+ */
+fn select_columns(object: &mut SqlObject, tokens: &[Token]) {
+    let mut depth = 0;
+    let mut found = None;
+    for (i, token) in tokens.iter().enumerate() {
+        if token.is_punct('(') {
+            depth += 1;
+        } else if token.is_punct(')') {
+            depth -= 1;
+        } else if depth == 0 && token.is("SELECT") {
+            found = Some(i);
+            break;
+        }
+    }
+    let Some(start) = found else {
+        return;
+    };
+    let mut cur = Cursor::new(&tokens[start + 1..]);
+    let _ = cur.eat("DISTINCT") || cur.eat("ALL");
+    let list_start = cur.pos;
+    depth = 0;
+    while let Some(token) = cur.peek() {
+        if token.is_punct('(') {
+            depth += 1;
+        } else if token.is_punct(')') {
+            depth -= 1;
+        } else if depth == 0 && token.is("FROM") {
+            break;
+        }
+        cur.pos += 1;
+    }
+    let list = &cur.tokens[list_start..cur.pos];
+    cur.eat("FROM");
+    object.from = from_tables(&mut cur);
+    for item in split(list, ',') {
+        let origin = match item {
+            [all] if all.is_punct('*') => Some((String::new(), "*".to_string())),
+            [table, dot, all] if dot.is_punct('.') && all.is_punct('*') => {
+                Some((table.name(), "*".to_string()))
+            }
+            [col, ..]
+                if is_name(col)
+                    && (item.len() == 1 || !item[1].is_punct('.') && !item[1].is_punct('(')) =>
+            {
+                Some((String::new(), col.name()))
+            }
+            [table, dot, col, ..]
+                if is_name(table)
+                    && dot.is_punct('.')
+                    && is_name(col)
+                    && (item.len() == 3 || !item[3].is_punct('(')) =>
+            {
+                Some((table.name(), col.name()))
+            }
+            _ => None,
+        };
+        let last = &item[item.len() - 1];
+        let named = item.len() > 1 && is_name(last) && !item[item.len() - 2].is_punct('.');
+        let (name, span) = match (named, &origin) {
+            (true, _) => (last.name(), last.span),
+            (false, Some((_, col))) => (col.clone(), last.span),
+            (false, None) => (String::new(), last.span),
+        };
+        let mut column = Column::new(name, None, span);
+        // just the column, maybe renamed, not an expression with it
+        column.origin = origin.filter(|_| {
+            item.len() <= 5
+                && !item
+                    .iter()
+                    .any(|x| x.kind == TokenKind::Punct && !x.is_punct('.'))
+        });
+        object.columns.push(column);
+    }
+}
+
+// the tables of a FROM clause, with their correlation names, not the nested selects
+fn from_tables(cur: &mut Cursor) -> Vec<(String, Option<String>)> {
+    let mut out = vec![];
+    let mut expecting = true;
+    while let Some(token) = cur.peek() {
+        if FROM_ENDS.iter().any(|x| token.is(x)) || token.is_punct(')') {
+            break;
+        }
+        if token.is_punct('(') {
+            cur.parens();
+            expecting = false;
+            continue;
+        }
+        if token.is_punct(',') || token.is("JOIN") {
+            cur.pos += 1;
+            expecting = true;
+            continue;
+        }
+        if expecting && is_name(token) {
+            let Some(table) = cur.name() else { break };
+            cur.eat("AS");
+            let correlation = cur
+                .peek()
+                .filter(|x| {
+                    is_name(x)
+                        && !JOINS.iter().any(|j| x.is(j))
+                        && !FROM_ENDS.iter().any(|e| x.is(e))
+                })
+                .map(|x| x.name());
+            if correlation.is_some() {
+                cur.pos += 1;
+            }
+            out.push((table.name(), correlation));
+            expecting = false;
+            continue;
+        }
+        cur.pos += 1;
+    }
+    out
+}
+
+fn create_view(cur: &mut Cursor, statement: Span) -> Option<SqlObject> {
+    let mut view = object(ObjectKind::View, cur, statement)?;
+    let names = cur.parens().map(|list| {
+        split(list, ',')
+            .into_iter()
+            .filter_map(|item| {
+                let mut inner = Cursor::new(item);
+                let first = inner.next().filter(|x| is_name(x))?;
+                Some(inner.system_name(first))
+            })
+            .collect::<Vec<_>>()
+    });
+    cur.eat("AS");
+    let rest = cur.rest();
+    select_columns(&mut view, rest);
+    // the column list names them in order
+    for (column, (name, alias, span)) in view.columns.iter_mut().zip(names.into_iter().flatten()) {
+        (column.name, column.alias, column.span) = (name, alias, span);
+    }
+    view.columns.retain(|x| !x.name.is_empty());
+    // the select might have a RCDFMT of its own in a nested select, only the last one is the view's
+    let mut format = None;
+    while let Some(found) = cur.rcdfmt() {
+        format = Some(found);
+    }
+    set_format(&mut view, format);
+    Some(view)
+}
+
+fn create_index(cur: &mut Cursor, statement: Span, unique: bool) -> Option<SqlObject> {
+    let mut index = object(ObjectKind::Index, cur, statement)?;
+    index.unique = unique;
+    // without RCDFMT an index has the record format of its table
+    index.format = String::new();
+    if !cur.eat("ON") {
+        return None;
+    }
+    let table = cur.name()?;
+    index.from.push((table.name(), None));
+    index.keys = keys(cur.parens()?);
+    let origin = |column: &str, span: Span| {
+        let mut out = Column::new(column.to_string(), None, span);
+        out.origin = Some((table.name(), column.to_string()));
+        out
+    };
+    let format = cur.rcdfmt();
+    match format {
+        None => index.columns.push(origin("*", table.span)),
+        Some(_) if cur.eat_all(&["ADD", "ALL", "COLUMNS"]) => {
+            index.columns.push(origin("*", table.span))
+        }
+        Some(_) => {
+            // the keys, then the columns added to them
+            let mut names = index
+                .keys
+                .iter()
+                .map(|x| (x.name.clone(), x.span))
+                .collect::<Vec<_>>();
+            if cur.eat("ADD") {
+                for item in split(cur.rest(), ',') {
+                    match item.first().filter(|x| is_name(x)) {
+                        Some(name) if !names.iter().any(|x| x.0 == name.name()) => {
+                            names.push((name.name(), name.span))
+                        }
+                        Some(_) => {}
+                        None => break,
+                    }
+                    if item.len() > 1 {
+                        break;
+                    }
+                }
+            }
+            index.columns = names
+                .into_iter()
+                .map(|(name, span)| origin(&name, span))
+                .collect();
+        }
+    }
+    set_format(&mut index, format);
+    Some(index)
+}
+
+// a column heading is up to three lines of 20
+fn colhdg(text: &str) -> Vec<String> {
+    let chars = text.chars().collect::<Vec<char>>();
+    chars
+        .chunks(20)
+        .map(|x| x.iter().collect::<String>().trim().to_string())
+        .filter(|x| !x.is_empty())
+        .take(3)
+        .collect()
+}
+
+fn label(objects: &mut [SqlObject], table: &str, column: Option<&str>, text: bool, value: String) {
+    let Some(object) = objects.iter_mut().find(|x| x.is(table)) else {
+        return;
+    };
+    match column {
+        None => object.text = Some(value),
+        Some(column) => {
+            let Some(column) = object.columns.iter_mut().find(|x| x.is(column)) else {
+                return;
+            };
+            match text {
+                true => column.text = Some(value),
+                false => column.colhdg = colhdg(&value),
+            }
+        }
+    }
+}
+
+/* LABEL ON TABLE x IS 'text', LABEL ON COLUMN x.y [TEXT] IS 'text' and
+ * LABEL ON COLUMN x (y [TEXT] IS 'text', ...), for the objects created before
+ */
+fn label_on(cur: &mut Cursor, objects: &mut [SqlObject]) {
+    let Some(kind) = cur.next() else {
+        return;
+    };
+    let mut names = vec![];
+    while let Some(token) = cur.peek() {
+        if is_name(token) && !token.is("TEXT") && !token.is("IS") {
+            names.push(token.name());
+        } else if !token.is_punct('.') && !token.is_punct('/') {
+            break;
+        }
+        cur.pos += 1;
+    }
+    let value = |cur: &mut Cursor| -> Option<(bool, String)> {
+        let text = cur.eat("TEXT");
+        if !cur.eat("IS") {
+            return None;
+        }
+        let value = cur.next().filter(|x| x.kind == TokenKind::Str)?;
+        Some((text, unquote(&value.text)))
+    };
+    if let Some(list) = cur.parens() {
+        let Some(table) = names.last() else {
+            return;
+        };
+        for item in split(list, ',') {
+            let mut inner = Cursor::new(item);
+            let Some(column) = inner.next() else {
+                continue;
+            };
+            if let Some((text, found)) = value(&mut inner) {
+                label(objects, table, Some(&column.name()), text, found);
+            }
+        }
+        return;
+    }
+    let Some((text, found)) = value(cur) else {
+        return;
+    };
+    match kind.is("COLUMN") {
+        true if names.len() >= 2 => label(
+            objects,
+            &names[names.len() - 2],
+            names.last().map(|x| x.as_str()),
+            text,
+            found,
+        ),
+        true => {}
+        false => {
+            if let Some(table) = names.last() {
+                label(objects, table, None, text, found)
+            }
+        }
+    }
+}
+
+impl From<&CST> for AST {
+    fn from(value: &CST) -> Self {
+        let tokens = value
+            .tokens
+            .iter()
+            .filter(|x| x.kind != TokenKind::Comment)
+            .cloned()
+            .collect::<Vec<Token>>();
+        let mut objects = vec![];
+        for statement in split(&tokens, ';') {
+            let span = Span::to_cover_both(statement[0].span, statement[statement.len() - 1].span);
+            let mut cur = Cursor::new(statement);
+            if cur.eat("LABEL") && cur.eat("ON") {
+                label_on(&mut cur, &mut objects);
+                continue;
+            }
+            if !cur.eat("CREATE") {
+                continue;
+            }
+            cur.eat_all(&["OR", "REPLACE"]);
+            let unique = cur.eat("UNIQUE");
+            cur.eat_all(&["WHERE", "NOT", "NULL"]);
+            let created = match cur.next() {
+                Some(x) if x.is("TABLE") => create_table(&mut cur, span),
+                Some(x) if x.is("VIEW") => create_view(&mut cur, span),
+                Some(x) if x.is("INDEX") => create_index(&mut cur, span, unique),
+                _ => None,
+            };
+            objects.extend(created);
+        }
+        Self { objects }
+    }
+}
+
+// a table, view or index, or a record format or column of one
+pub fn query_definition(ast: &AST, pattern: &str) -> Option<Span> {
+    for object in ast.objects.iter() {
+        if object.is(pattern) {
+            return Some(object.span);
+        }
+        if let Some(span) = object
+            .format_span
+            .filter(|_| object.format.eq_ignore_ascii_case(pattern))
+        {
+            return Some(span);
+        }
+        if let Some(column) = object
+            .columns
+            .iter()
+            .find(|x| x.origin.is_none() && x.is(pattern))
+        {
+            return Some(column.span);
+        }
+    }
+    None
+}
+
+pub fn query_record_formats(ast: &AST) -> Vec<String> {
+    resolve_objects(ast, &HashMap::new())
+        .into_iter()
+        .map(|x| x.format)
+        .collect()
+}
+
+// the record format, column and its type and length of every object, ex: 9P 0
+pub fn query_fields(ast: &AST) -> Vec<(String, String, String)> {
+    let mut out = vec![];
+    for object in resolve_objects(ast, &HashMap::new()) {
+        for column in object.columns.iter().filter(|x| x.name != "*") {
+            out.push((object.format.clone(), column.name.clone(), column.detail()));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> AST {
+        AST::from(&CST::try_from(input).unwrap())
+    }
+
+    #[test]
+    fn test_create_table() {
+        let ast = parse(
+            r#"
+CREATE OR REPLACE TABLE DAIRY.COW_EVENTS FOR SYSTEM NAME COWEVT (
+    EVENT_ID FOR COLUMN EVTID DECIMAL(9, 0) NOT NULL,
+    EVENT_DATE FOR EDAT DATE NOT NULL WITH DEFAULT,
+    EVENT_NAME VARCHAR(20) ALLOCATE(10) CCSID 37,
+    NOTES CLOB(1M),
+    WEIGHT NUMERIC(7, 2),
+    COUNT INTEGER,
+    PRIMARY KEY (EVENT_ID)
+) RCDFMT EVTFMT;
+
+LABEL ON TABLE COW_EVENTS IS 'Cow events';
+LABEL ON COLUMN COW_EVENTS (
+    EVENT_ID IS 'Event               Id',
+    EVENT_ID TEXT IS 'Event id'
+);
+LABEL ON COLUMN DAIRY.COWEVT.WEIGHT TEXT IS 'Weight in kg';
+"#,
+        );
+        let table = &ast.objects[0];
+        assert_eq!(
+            (table.name.as_str(), table.alias.as_deref()),
+            ("COWEVT", Some("COW_EVENTS"))
+        );
+        assert_eq!(table.format, "EVTFMT");
+        assert_eq!(table.text.as_deref(), Some("Cow events"));
+        assert_eq!(
+            table
+                .keys
+                .iter()
+                .map(|x| x.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["EVTID"]
+        );
+        assert!(table.unique);
+        let evtid = &table.columns[0];
+        assert_eq!(
+            (evtid.name.as_str(), evtid.alias.as_deref()),
+            ("EVTID", Some("EVENT_ID"))
+        );
+        assert_eq!(evtid.colhdg, vec!["Event", "Id"]);
+        assert_eq!(evtid.text.as_deref(), Some("Event id"));
+        assert!(!evtid.nullable);
+        assert_eq!(table.columns[4].text.as_deref(), Some("Weight in kg"));
+        assert_eq!(
+            query_fields(&ast),
+            vec![
+                (
+                    "EVTFMT".to_string(),
+                    "EVTID".to_string(),
+                    "9P 0".to_string()
+                ),
+                ("EVTFMT".to_string(), "EDAT".to_string(), "L".to_string()),
+                (
+                    "EVTFMT".to_string(),
+                    "EVENT_NAME".to_string(),
+                    "20A".to_string()
+                ),
+                (
+                    "EVTFMT".to_string(),
+                    "NOTES".to_string(),
+                    "CLOB(1M)".to_string()
+                ),
+                (
+                    "EVTFMT".to_string(),
+                    "WEIGHT".to_string(),
+                    "7S 2".to_string()
+                ),
+                (
+                    "EVTFMT".to_string(),
+                    "COUNT".to_string(),
+                    "9B 0".to_string()
+                ),
+            ]
+        );
+        assert_eq!(query_definition(&ast, "evtfmt"), table.format_span);
+        assert_eq!(
+            query_definition(&ast, "EVENT_DATE"),
+            Some(Span::from(((3, 19), (3, 23))))
+        );
+        assert_eq!(query_definition(&ast, "COW_EVENTS"), Some(table.span));
+    }
+
+    #[test]
+    fn test_create_view_and_index() {
+        let ast = parse(
+            r#"
+CREATE TABLE COWEVT (
+    EVTID DEC(9, 0) NOT NULL,
+    COWID CHAR(8),
+    EDAT DATE
+);
+CREATE TABLE COWS (COWID CHAR(8), COWNAME VARCHAR(30));
+
+-- the events with the name of the cow
+CREATE VIEW COWEVTV (EVTID, COW, NAME FOR COLUMN COWNAM) AS
+    SELECT E.EVTID, E.COWID, C.COWNAME
+    FROM COWEVT E
+    LEFT JOIN COWS AS C ON C.COWID = E.COWID
+    WHERE E.EDAT > CURRENT DATE - 1 YEAR
+    RCDFMT COWEVTVR;
+
+CREATE VIEW RECENT AS SELECT EVTID, YEAR(EDAT) AS EYEAR FROM COWEVT;
+
+CREATE UNIQUE INDEX COWEVTX ON COWEVT (COWID, EDAT DESC) RCDFMT COWEVTXR ADD EVTID;
+CREATE INDEX COWEVTY ON COWEVT (EDAT);
+"#,
+        );
+        assert_eq!(
+            query_record_formats(&ast),
+            vec!["COWEVT", "COWS", "COWEVTVR", "RECENT", "COWEVTXR", "COWEVT"]
+        );
+        let fields = query_fields(&ast)
+            .into_iter()
+            .filter(|x| x.0 != "COWEVT" && x.0 != "COWS")
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                (
+                    "COWEVTVR".to_string(),
+                    "EVTID".to_string(),
+                    "9P 0".to_string()
+                ),
+                ("COWEVTVR".to_string(), "COW".to_string(), "8A".to_string()),
+                (
+                    "COWEVTVR".to_string(),
+                    "COWNAM".to_string(),
+                    "30A".to_string()
+                ),
+                (
+                    "RECENT".to_string(),
+                    "EVTID".to_string(),
+                    "9P 0".to_string()
+                ),
+                ("RECENT".to_string(), "EYEAR".to_string(), "".to_string()),
+                (
+                    "COWEVTXR".to_string(),
+                    "COWID".to_string(),
+                    "8A".to_string()
+                ),
+                ("COWEVTXR".to_string(), "EDAT".to_string(), "L".to_string()),
+                (
+                    "COWEVTXR".to_string(),
+                    "EVTID".to_string(),
+                    "9P 0".to_string()
+                ),
+            ]
+        );
+        let index = &ast.objects[4];
+        assert!(index.unique);
+        assert_eq!(
+            index
+                .keys
+                .iter()
+                .map(|x| (x.name.as_str(), x.descend))
+                .collect::<Vec<_>>(),
+            vec![("COWID", false), ("EDAT", true)]
+        );
+        assert_eq!(
+            ast.objects[2].from,
+            vec![
+                ("COWEVT".to_string(), Some("E".to_string())),
+                ("COWS".to_string(), Some("C".to_string()))
+            ]
+        );
+    }
+}
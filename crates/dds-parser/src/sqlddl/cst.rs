@@ -0,0 +1,327 @@
+use crate::meta::{IHighlight, Position, Span};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug)]
+pub enum ParserException {
+    UnterminatedException(String),
+}
+
+impl ParserException {
+    pub fn unterminated(what: &str, start: Position) -> Self {
+        let msg = format!("Unterminated {} starting at {}", what, start);
+        Self::UnterminatedException(msg)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TokenKind {
+    // a name or a reserved word, they look the same until parsed
+    Word,
+    // a delimited name, "like this", case is kept
+    Quoted,
+    Str,
+    Number,
+    Punct,
+    Comment,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Token {
+    pub kind: TokenKind,
+    // without the quotes of a delimited name or a string, doubled quotes are kept
+    pub text: String,
+    pub span: Span,
+}
+
+impl Token {
+    // a word, case doesn't matter
+    pub fn is(&self, word: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(word)
+    }
+
+    pub fn is_punct(&self, c: char) -> bool {
+        self.kind == TokenKind::Punct && self.text.starts_with(c)
+    }
+
+    // a name as the system keeps it, ordinary names are uppercase
+    pub fn name(&self) -> String {
+        match self.kind {
+            TokenKind::Quoted => self.text.clone(),
+            _ => self.text.to_uppercase(),
+        }
+    }
+}
+
+// SQL is free form, the source is just tokens
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CST {
+    pub tokens: Vec<Token>,
+}
+
+impl IHighlight for CST {
+    fn highlight(&self) -> Vec<(Span, String)> {
+        self.tokens
+            .iter()
+            .filter_map(|token| {
+                let group = match token.kind {
+                    TokenKind::Word if KEYWORDS.iter().any(|x| token.is(x)) => "@keyword",
+                    TokenKind::Word if TYPES.iter().any(|x| token.is(x)) => "@type.qualifier",
+                    TokenKind::Word | TokenKind::Quoted => "Identifier",
+                    TokenKind::Str => "@string",
+                    TokenKind::Number => "@number",
+                    TokenKind::Comment => "@comment",
+                    TokenKind::Punct => return None,
+                };
+                Some((token.span, group.to_string()))
+            })
+            .collect()
+    }
+}
+
+// the words painted as keywords, not every reserved word
+const KEYWORDS: &[&str] = &[
+    "ADD",
+    "ALL",
+    "ALLOCATE",
+    "AND",
+    "AS",
+    "ASC",
+    "BY",
+    "CCSID",
+    "CHECK",
+    "COLUMN",
+    "COLUMNS",
+    "CONSTRAINT",
+    "CREATE",
+    "DEFAULT",
+    "DESC",
+    "DISTINCT",
+    "FOR",
+    "FOREIGN",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "HAVING",
+    "IN",
+    "INDEX",
+    "INNER",
+    "IS",
+    "JOIN",
+    "KEY",
+    "LABEL",
+    "LEFT",
+    "LIKE",
+    "NAME",
+    "NOT",
+    "NULL",
+    "ON",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "PRIMARY",
+    "RCDFMT",
+    "REFERENCES",
+    "REPLACE",
+    "RIGHT",
+    "SELECT",
+    "SYSTEM",
+    "TABLE",
+    "TEXT",
+    "UNION",
+    "UNIQUE",
+    "VIEW",
+    "WHERE",
+    "WITH",
+];
+
+const TYPES: &[&str] = &[
+    "BIGINT",
+    "BINARY",
+    "BLOB",
+    "CHAR",
+    "CHARACTER",
+    "CLOB",
+    "DATE",
+    "DBCLOB",
+    "DEC",
+    "DECFLOAT",
+    "DECIMAL",
+    "DOUBLE",
+    "FLOAT",
+    "GRAPHIC",
+    "INT",
+    "INTEGER",
+    "NCHAR",
+    "NUMERIC",
+    "NVARCHAR",
+    "REAL",
+    "SMALLINT",
+    "TIME",
+    "TIMESTAMP",
+    "VARBINARY",
+    "VARCHAR",
+    "VARGRAPHIC",
+];
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '#' | '@' | '$')
+}
+
+impl TryFrom<&str> for CST {
+    type Error = ParserException;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let chars = value.chars().collect::<Vec<char>>();
+        let mut tokens = vec![];
+        let (mut i, mut row, mut col) = (0, 0, 0);
+        while i < chars.len() {
+            let c = chars[i];
+            if c == '\n' {
+                (i, row, col) = (i + 1, row + 1, 0);
+                continue;
+            }
+            if c.is_whitespace() {
+                (i, col) = (i + 1, col + 1);
+                continue;
+            }
+            let start = Position::from((row, col));
+            let begin = i;
+            let kind = match c {
+                '-' if chars.get(i + 1) == Some(&'-') => {
+                    while i < chars.len() && chars[i] != '\n' {
+                        (i, col) = (i + 1, col + 1);
+                    }
+                    TokenKind::Comment
+                }
+                '/' if chars.get(i + 1) == Some(&'*') => {
+                    (i, col) = (i + 2, col + 2);
+                    loop {
+                        match chars.get(i) {
+                            None => return Err(ParserException::unterminated("comment", start)),
+                            Some('*') if chars.get(i + 1) == Some(&'/') => {
+                                (i, col) = (i + 2, col + 2);
+                                break;
+                            }
+                            Some('\n') => (i, row, col) = (i + 1, row + 1, 0),
+                            Some(_) => (i, col) = (i + 1, col + 1),
+                        }
+                    }
+                    TokenKind::Comment
+                }
+                '\'' | '"' => {
+                    (i, col) = (i + 1, col + 1);
+                    loop {
+                        match chars.get(i) {
+                            None | Some('\n') => {
+                                let what = if c == '\'' {
+                                    "string"
+                                } else {
+                                    "delimited name"
+                                };
+                                return Err(ParserException::unterminated(what, start));
+                            }
+                            // a doubled quote is one quote
+                            Some(x) if *x == c && chars.get(i + 1) == Some(&c) => {
+                                (i, col) = (i + 2, col + 2)
+                            }
+                            Some(x) if *x == c => {
+                                (i, col) = (i + 1, col + 1);
+                                break;
+                            }
+                            Some(_) => (i, col) = (i + 1, col + 1),
+                        }
+                    }
+                    if c == '\'' {
+                        TokenKind::Str
+                    } else {
+                        TokenKind::Quoted
+                    }
+                }
+                _ if c.is_ascii_digit() => {
+                    while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                        (i, col) = (i + 1, col + 1);
+                    }
+                    TokenKind::Number
+                }
+                _ if is_name_char(c) => {
+                    while i < chars.len() && is_name_char(chars[i]) {
+                        (i, col) = (i + 1, col + 1);
+                    }
+                    TokenKind::Word
+                }
+                _ => {
+                    (i, col) = (i + 1, col + 1);
+                    TokenKind::Punct
+                }
+            };
+            let text = chars[begin..i].iter().collect::<String>();
+            let text = match kind {
+                TokenKind::Str | TokenKind::Quoted => text[1..text.len() - 1].to_string(),
+                _ => text,
+            };
+            let span = Span {
+                start,
+                end: Position::from((row, col)),
+            };
+            tokens.push(Token { kind, text, span });
+        }
+        Ok(Self { tokens })
+    }
+}
+
+// start, end and highlight group
+type Highlight = ((usize, usize), (usize, usize), String);
+
+pub fn highlight_cst(cst: &CST) -> Vec<Highlight> {
+    cst.highlight()
+        .into_iter()
+        .map(|tup| {
+            (
+                (tup.0.start.row, tup.0.start.col),
+                (tup.0.end.row, tup.0.end.col),
+                tup.1,
+            )
+        })
+        .collect::<Vec<_>>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens() {
+        let input =
+            "CREATE TABLE \"CowEvt\" ( -- events\n  EVTID DEC(9, 0) /* id */ DEFAULT 'It''s' );";
+        let cst = CST::try_from(input).unwrap();
+        let kinds = cst
+            .tokens
+            .iter()
+            .map(|x| (x.kind, x.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (TokenKind::Word, "CREATE"),
+                (TokenKind::Word, "TABLE"),
+                (TokenKind::Quoted, "CowEvt"),
+                (TokenKind::Punct, "("),
+                (TokenKind::Comment, "-- events"),
+                (TokenKind::Word, "EVTID"),
+                (TokenKind::Word, "DEC"),
+                (TokenKind::Punct, "("),
+                (TokenKind::Number, "9"),
+                (TokenKind::Punct, ","),
+                (TokenKind::Number, "0"),
+                (TokenKind::Punct, ")"),
+                (TokenKind::Comment, "/* id */"),
+                (TokenKind::Word, "DEFAULT"),
+                (TokenKind::Str, "It''s"),
+                (TokenKind::Punct, ")"),
+                (TokenKind::Punct, ";"),
+            ]
+        );
+        assert_eq!(cst.tokens[5].span, Span::from(((1, 2), (1, 7))));
+        assert!(CST::try_from("LABEL ON TABLE X IS 'oops").is_err());
+    }
+}
@@ -0,0 +1,11 @@
+// Tables, views and indexes created with SQL, the same files DDS describes
+mod ast;
+mod cst;
+mod outline;
+mod resolve;
+pub use ast::{
+    query_definition, query_fields, query_record_formats, Column, ObjectKind, SqlObject, AST,
+};
+pub use cst::{highlight_cst, ParserException, Token, TokenKind, CST};
+pub use outline::{query_folds, query_outline};
+pub use resolve::resolve_objects;
@@ -0,0 +1,62 @@
+use super::{resolve_objects, AST};
+use crate::pfdds::{Fold, OutlineItem, OutlineKind};
+use std::collections::HashMap;
+
+/* Each table, view and index like a file of its own, its record format with
+ * the columns it defines and its keys.
+ */
+pub fn query_outline(ast: &AST) -> Vec<OutlineItem> {
+    let mut out = vec![];
+    // an index without RCDFMT has its table's
+    let formats = resolve_objects(ast, &HashMap::new())
+        .into_iter()
+        .map(|x| x.format);
+    for (object, name) in ast.objects.iter().zip(formats) {
+        let mut children = object
+            .columns
+            .iter()
+            .filter(|x| x.name != "*" && !x.name.is_empty())
+            .map(|column| OutlineItem {
+                name: column.name.clone(),
+                kind: OutlineKind::Field,
+                span: column.span,
+                selection: column.span,
+                children: vec![],
+            })
+            .collect::<Vec<_>>();
+        children.extend(object.keys.iter().map(|key| OutlineItem {
+            name: key.name.clone(),
+            kind: OutlineKind::Keyfield,
+            span: key.span,
+            selection: key.span,
+            children: vec![],
+        }));
+        let format = object.format_span.unwrap_or(object.span);
+        out.push(OutlineItem {
+            name: object.name.clone(),
+            kind: OutlineKind::FileEntry,
+            span: object.statement,
+            selection: object.span,
+            children: vec![OutlineItem {
+                name,
+                kind: OutlineKind::RecordFormat,
+                span: object.statement,
+                selection: format,
+                children,
+            }],
+        });
+    }
+    out
+}
+
+// Each statement over more than one line
+pub fn query_folds(ast: &AST) -> Vec<Fold> {
+    ast.objects
+        .iter()
+        .filter(|x| x.statement.end.row > x.statement.start.row)
+        .map(|x| Fold {
+            start_row: x.statement.start.row,
+            end_row: x.statement.end.row,
+        })
+        .collect()
+}
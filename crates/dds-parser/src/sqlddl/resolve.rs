@@ -0,0 +1,98 @@
+use super::{Column, SqlObject, AST};
+use std::collections::HashMap;
+
+// views over views over views, deep enough without going around a cycle forever
+const MAX_DEPTH: usize = 8;
+
+fn find<'a>(
+    name: &str,
+    ast: &'a AST,
+    others: &'a HashMap<String, SqlObject>,
+) -> Option<&'a SqlObject> {
+    ast.objects
+        .iter()
+        .find(|x| x.is(name))
+        .or_else(|| others.get(&name.to_uppercase()))
+        .or_else(|| others.values().find(|x| x.is(name)))
+}
+
+fn resolve(
+    object: &SqlObject,
+    ast: &AST,
+    others: &HashMap<String, SqlObject>,
+    depth: usize,
+) -> SqlObject {
+    let mut out = object.clone();
+    if depth > MAX_DEPTH {
+        return out;
+    }
+    // the objects it's over, with the names a select can qualify their columns by
+    let sources = object
+        .from
+        .iter()
+        .filter_map(|(table, correlation)| {
+            let found = find(table, ast, others)?;
+            Some((table, correlation, resolve(found, ast, others, depth + 1)))
+        })
+        .collect::<Vec<_>>();
+    if out.format.is_empty() {
+        out.format = match sources.first() {
+            Some((_, _, source)) => source.format.clone(),
+            None => object
+                .from
+                .first()
+                .map(|x| x.0.clone())
+                .unwrap_or(object.name.clone()),
+        };
+    }
+    out.columns = vec![];
+    for column in object.columns.iter() {
+        let Some((table, name)) = &column.origin else {
+            out.columns.push(column.clone());
+            continue;
+        };
+        let mut candidates = sources.iter().filter(|(t, c, _)| {
+            table.is_empty()
+                || t.eq_ignore_ascii_case(table)
+                || c.as_deref().is_some_and(|c| c.eq_ignore_ascii_case(table))
+        });
+        if name == "*" {
+            let all = candidates
+                .flat_map(|x| x.2.columns.clone())
+                .collect::<Vec<Column>>();
+            match all.is_empty() {
+                true => out.columns.push(column.clone()),
+                false => out.columns.extend(all),
+            }
+            continue;
+        }
+        match candidates.find_map(|x| x.2.columns.iter().find(|c| c.is(name))) {
+            Some(found) => out.columns.push(Column {
+                name: column.name.clone(),
+                alias: column.alias.clone(),
+                span: column.span,
+                text: column.text.clone().or(found.text.clone()),
+                colhdg: match column.colhdg.is_empty() {
+                    true => found.colhdg.clone(),
+                    false => column.colhdg.clone(),
+                },
+                origin: column.origin.clone(),
+                ..found.clone()
+            }),
+            None => out.columns.push(column.clone()),
+        }
+    }
+    out
+}
+
+/* The objects of a source with the columns of its views and indexes taken
+ * from the tables they're over, looked up in the source first and then in
+ * `others`, ex: every table of a project by name. An index without RCDFMT
+ * gets the record format of its table.
+ */
+pub fn resolve_objects(ast: &AST, others: &HashMap<String, SqlObject>) -> Vec<SqlObject> {
+    ast.objects
+        .iter()
+        .map(|x| resolve(x, ast, others, 0))
+        .collect()
+}
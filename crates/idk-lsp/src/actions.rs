@@ -0,0 +1,26 @@
+use crate::document::{Document, range};
+use serde_json::{Value, json};
+
+// textDocument/codeAction, the rewrites offered at the start of the requested range
+pub fn code_actions(doc: &Document, row: usize, col: usize) -> Value {
+    idk::code_actions(doc.lang, &doc.text, row, col)
+        .into_iter()
+        .map(|action| {
+            let edits = action
+                .edits
+                .iter()
+                .map(|x| {
+                    json!({
+                        "range": range((x.start_row, x.start_col), (x.end_row, x.end_col)),
+                        "newText": x.text,
+                    })
+                })
+                .collect::<Vec<Value>>();
+            json!({
+                "title": action.title,
+                "kind": "refactor.rewrite",
+                "edit": { "changes": { doc.uri.clone(): edits } },
+            })
+        })
+        .collect()
+}
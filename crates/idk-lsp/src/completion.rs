@@ -0,0 +1,40 @@
+use crate::document::{Document, range};
+use idk::Lang;
+use serde_json::{Value, json};
+
+// completion kind -> lsp CompletionItemKind
+fn item_kind(kind: &str) -> u8 {
+    match kind {
+        "opcode" => 14,
+        "builtin" | "procedure" => 3,
+        "subroutine" => 2,
+        "field" => 5,
+        "file" => 17,
+        "data-structure" => 22,
+        "constant" => 21,
+        _ => 6,
+    }
+}
+
+// textDocument/completion, the candidates of the editor's omnifunc
+pub fn completion(doc: &Document, row: usize, col: usize) -> Value {
+    if doc.lang != Lang::Rpgle {
+        return json!([]);
+    }
+    let found = idk::complete_rpgle(&doc.text, doc.path.as_deref(), row, col);
+    let edit = range((row, found.start_col), (row, col));
+    Value::Array(
+        found
+            .items
+            .iter()
+            .map(|item| {
+                json!({
+                    "label": item.label,
+                    "kind": item_kind(&item.kind),
+                    "detail": item.detail,
+                    "textEdit": { "range": edit, "newText": item.label },
+                })
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,22 @@
+use crate::document::{Document, range};
+use serde_json::{Value, json};
+
+/* textDocument/definition, the same lookup as jump to definition in the editor:
+ * the document first, then the other sources of its project manifest.
+ */
+pub fn definition(doc: &Document, row: usize, col: usize) -> Value {
+    let Some(word) = doc.word_at(row, col) else {
+        return Value::Null;
+    };
+    let found = idk::find_definition(doc.lang, &doc.text, &word, doc.path.as_deref(), Some(row));
+    match found {
+        Some(def) => json!({
+            "uri": def.uri.unwrap_or(doc.uri.clone()),
+            "range": range(
+                (def.start_line, def.start_char),
+                (def.end_line, def.end_char)
+            ),
+        }),
+        None => Value::Null,
+    }
+}
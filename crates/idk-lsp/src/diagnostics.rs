@@ -0,0 +1,66 @@
+use crate::document::{Document, range};
+use idk::Lang;
+use serde_json::{Value, json};
+
+// (start, end, severity, code, message)
+type Item = ((usize, usize), (usize, usize), u8, String, String);
+
+fn rpgle(d: rpgle_parser::Diagnostic) -> Item {
+    let severity = match d.level {
+        rpgle_parser::DiagnosticLevel::Error => 1,
+        rpgle_parser::DiagnosticLevel::Warning => 2,
+        rpgle_parser::DiagnosticLevel::Info => 3,
+    };
+    (
+        (d.span.start.row, d.span.start.col),
+        (d.span.end.row, d.span.end.col),
+        severity,
+        d.code,
+        d.msg,
+    )
+}
+
+fn dds(d: dds_parser::Diagnostic) -> Item {
+    let severity = match d.level {
+        dds_parser::DiagnosticLevel::Error => 1,
+        dds_parser::DiagnosticLevel::Warning => 2,
+        dds_parser::DiagnosticLevel::Info => 3,
+    };
+    (
+        (d.span.start.row, d.span.start.col),
+        (d.span.end.row, d.span.end.col),
+        severity,
+        d.code,
+        d.msg,
+    )
+}
+
+// textDocument/publishDiagnostics, the lint report of the editor
+pub fn diagnostics(doc: &Document) -> Value {
+    let diagnostics = match doc.lang {
+        Lang::Rpgle => idk::lint_rpgle(&doc.text, doc.path.as_deref())
+            .into_iter()
+            .map(rpgle)
+            .collect::<Vec<Item>>(),
+        Lang::Pfdds | Lang::Lfdds | Lang::Dspfdds | Lang::Prtfdds => {
+            idk::lint_dds(&doc.text, doc.lang, doc.path.as_deref())
+                .into_iter()
+                .map(dds)
+                .collect()
+        }
+        Lang::Sqlddl => vec![],
+    };
+    let items = diagnostics
+        .into_iter()
+        .map(|(start, end, severity, code, msg)| {
+            json!({
+                "range": range(start, end),
+                "severity": severity,
+                "code": code,
+                "source": "idk",
+                "message": msg,
+            })
+        })
+        .collect::<Vec<Value>>();
+    json!({ "uri": doc.uri, "diagnostics": items })
+}
@@ -0,0 +1,84 @@
+use idk::Lang;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+
+// An open text document, synced in full on every change
+#[derive(Debug)]
+pub struct Document {
+    pub uri: String,
+    pub lang: Lang,
+    pub text: String,
+    // None for anything but file:// uris, ex: an unsaved buffer
+    pub path: Option<PathBuf>,
+}
+
+impl Document {
+    pub fn new(uri: &str, language_id: Option<&str>, text: String) -> Self {
+        let path = uri_to_path(uri);
+        let lang = language_id
+            .and_then(Lang::from_name)
+            .or(path.as_deref().and_then(Lang::from_path))
+            .unwrap_or(Lang::Rpgle);
+        Self {
+            uri: uri.to_string(),
+            lang,
+            text,
+            path,
+        }
+    }
+
+    // the name under the cursor, ex: `$SetLstId` in `exsr $SetLstId;`
+    pub fn word_at(&self, row: usize, col: usize) -> Option<String> {
+        let chars = self.text.lines().nth(row)?.chars().collect::<Vec<char>>();
+        let is_name = |c: &char| c.is_alphanumeric() || "@$#_".contains(*c);
+        let col = match chars.get(col).is_some_and(is_name) {
+            true => col,
+            false => col
+                .checked_sub(1)
+                .filter(|c| chars.get(*c).is_some_and(is_name))?,
+        };
+        let start = chars[..col]
+            .iter()
+            .rposition(|c| !is_name(c))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let end = chars[col..]
+            .iter()
+            .position(|c| !is_name(c))
+            .map(|i| col + i)
+            .unwrap_or(chars.len());
+        Some(chars[start..end].iter().collect())
+    }
+}
+
+// file:///home/me/src/zevt.rpgle -> /home/me/src/zevt.rpgle
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut bytes = vec![];
+    let mut i = 0;
+    while i < encoded.len() {
+        let escaped = encoded
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (encoded[i], escaped) {
+            (b'%', Some(byte)) => {
+                bytes.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                bytes.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+// an lsp Range, rows and columns are zero based like the parsers
+pub fn range(start: (usize, usize), end: (usize, usize)) -> Value {
+    json!({
+        "start": { "line": start.0, "character": start.1 },
+        "end": { "line": end.0, "character": end.1 },
+    })
+}
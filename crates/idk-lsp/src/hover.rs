@@ -0,0 +1,13 @@
+use crate::document::{Document, range};
+use serde_json::{Value, json};
+
+// textDocument/hover, docs for the built-in function or opcode, or the field layout, under the cursor
+pub fn hover(doc: &Document, row: usize, col: usize) -> Value {
+    match idk::hover(doc.lang, &doc.text, doc.path.as_deref(), row, col) {
+        Some(hover) => json!({
+            "contents": { "kind": "markdown", "value": hover.contents },
+            "range": range((hover.row, hover.start_col), (hover.row, hover.end_col)),
+        }),
+        None => Value::Null,
+    }
+}
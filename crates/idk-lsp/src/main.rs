@@ -0,0 +1,27 @@
+mod actions;
+mod completion;
+mod definition;
+mod diagnostics;
+mod document;
+mod hover;
+mod rpc;
+mod server;
+mod signature;
+mod symbols;
+mod tokens;
+
+use std::io;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let mut writer = io::stdout();
+    match server::serve(&mut reader, &mut writer) {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
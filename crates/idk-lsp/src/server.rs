@@ -0,0 +1,417 @@
+use crate::actions::code_actions;
+use crate::completion::completion;
+use crate::definition::definition;
+use crate::diagnostics::diagnostics;
+use crate::document::Document;
+use crate::hover::hover;
+use crate::rpc::{read_message, write_message};
+use crate::signature::signature_help;
+use crate::symbols::document_symbols;
+use crate::tokens::{TOKEN_TYPES, semantic_tokens};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+// json-rpc error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Debug, Default)]
+pub struct Server {
+    documents: HashMap<String, Document>,
+    shutdown: bool,
+}
+
+fn capabilities() -> Value {
+    json!({
+        "textDocumentSync": { "openClose": true, "change": 1 },
+        "definitionProvider": true,
+        "completionProvider": { "triggerCharacters": ["%"] },
+        "signatureHelpProvider": { "triggerCharacters": ["(", ":"] },
+        "hoverProvider": true,
+        "codeActionProvider": true,
+        "documentSymbolProvider": true,
+        "semanticTokensProvider": {
+            "legend": { "tokenTypes": TOKEN_TYPES, "tokenModifiers": [] },
+            "full": true,
+        },
+    })
+}
+
+fn response(id: &Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error(id: &Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+fn position(params: &Value) -> Option<(usize, usize)> {
+    at(params.get("position")?)
+}
+
+fn at(position: &Value) -> Option<(usize, usize)> {
+    let row = position.get("line")?.as_u64()?;
+    let col = position.get("character")?.as_u64()?;
+    Some((row as usize, col as usize))
+}
+
+impl Server {
+    fn document(&self, params: &Value) -> Option<&Document> {
+        let uri = params.get("textDocument")?.get("uri")?.as_str()?;
+        self.documents.get(uri)
+    }
+
+    // the diagnostics to publish after a document was opened or changed
+    fn sync(&mut self, params: &Value, text: Option<&str>) -> Option<Value> {
+        let item = params.get("textDocument")?;
+        let uri = item.get("uri")?.as_str()?;
+        let language_id = item.get("languageId").and_then(|x| x.as_str());
+        let doc = match (text, self.documents.remove(uri)) {
+            // a change only has the text, the language stays the one it was opened with
+            (Some(text), Some(mut doc)) => {
+                doc.text = text.to_string();
+                doc
+            }
+            (text, _) => {
+                let text = text.or(item.get("text").and_then(|x| x.as_str()))?;
+                Document::new(uri, language_id, text.to_string())
+            }
+        };
+        let out = notification("textDocument/publishDiagnostics", diagnostics(&doc));
+        self.documents.insert(uri.to_string(), doc);
+        Some(out)
+    }
+
+    fn notify(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        match method {
+            "textDocument/didOpen" => self.sync(params, None).into_iter().collect(),
+            "textDocument/didChange" => {
+                // full sync, the last change holds the whole text
+                let text = params
+                    .get("contentChanges")
+                    .and_then(|x| x.as_array())
+                    .and_then(|x| x.last())
+                    .and_then(|x| x.get("text"))
+                    .and_then(|x| x.as_str());
+                match text {
+                    Some(text) => self.sync(params, Some(text)).into_iter().collect(),
+                    None => vec![],
+                }
+            }
+            "textDocument/didClose" => {
+                let uri = params
+                    .get("textDocument")
+                    .and_then(|x| x.get("uri"))
+                    .and_then(|x| x.as_str());
+                match uri.and_then(|uri| self.documents.remove(uri)) {
+                    Some(doc) => vec![notification(
+                        "textDocument/publishDiagnostics",
+                        json!({ "uri": doc.uri, "diagnostics": [] }),
+                    )],
+                    None => vec![],
+                }
+            }
+            // initialized, $/cancelRequest, ...
+            _ => vec![],
+        }
+    }
+
+    fn request(&mut self, id: &Value, method: &str, params: &Value) -> Value {
+        if self.shutdown {
+            return error(id, INVALID_REQUEST, "The server is shutting down");
+        }
+        match method {
+            "initialize" => response(
+                id,
+                json!({
+                    "capabilities": capabilities(),
+                    "serverInfo": { "name": "idk-lsp", "version": env!("CARGO_PKG_VERSION") },
+                }),
+            ),
+            "shutdown" => {
+                self.shutdown = true;
+                response(id, Value::Null)
+            }
+            "textDocument/definition" => match (self.document(params), position(params)) {
+                (Some(doc), Some((row, col))) => response(id, definition(doc, row, col)),
+                (None, _) => response(id, Value::Null),
+                (_, None) => error(id, INVALID_PARAMS, "Missing position"),
+            },
+            "textDocument/completion" => match (self.document(params), position(params)) {
+                (Some(doc), Some((row, col))) => response(id, completion(doc, row, col)),
+                (None, _) => response(id, json!([])),
+                (_, None) => error(id, INVALID_PARAMS, "Missing position"),
+            },
+            "textDocument/signatureHelp" => match (self.document(params), position(params)) {
+                (Some(doc), Some((row, col))) => response(id, signature_help(doc, row, col)),
+                (None, _) => response(id, Value::Null),
+                (_, None) => error(id, INVALID_PARAMS, "Missing position"),
+            },
+            "textDocument/hover" => match (self.document(params), position(params)) {
+                (Some(doc), Some((row, col))) => response(id, hover(doc, row, col)),
+                (None, _) => response(id, Value::Null),
+                (_, None) => error(id, INVALID_PARAMS, "Missing position"),
+            },
+            "textDocument/codeAction" => {
+                let start = params
+                    .get("range")
+                    .and_then(|x| x.get("start"))
+                    .and_then(at);
+                match (self.document(params), start) {
+                    (Some(doc), Some((row, col))) => response(id, code_actions(doc, row, col)),
+                    (None, _) => response(id, json!([])),
+                    (_, None) => error(id, INVALID_PARAMS, "Missing range"),
+                }
+            }
+            "textDocument/documentSymbol" => match self.document(params) {
+                Some(doc) => response(id, document_symbols(doc)),
+                None => response(id, json!([])),
+            },
+            "textDocument/semanticTokens/full" => match self.document(params) {
+                Some(doc) => response(id, semantic_tokens(doc)),
+                None => response(id, json!({ "data": [] })),
+            },
+            _ => error(
+                id,
+                METHOD_NOT_FOUND,
+                &format!("Method not found: {}", method),
+            ),
+        }
+    }
+
+    // everything to send back for one client message, requests get exactly one response
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let method = message.get("method").and_then(|x| x.as_str());
+        match (message.get("id"), method) {
+            (Some(id), Some(method)) => vec![self.request(id, method, &params)],
+            (None, Some(method)) => self.notify(method, &params),
+            // a response to a request we never send
+            (_, None) => vec![],
+        }
+    }
+}
+
+// Serve one client until it sends `exit` or closes the stream
+pub fn serve(reader: &mut impl BufRead, writer: &mut impl Write) -> io::Result<()> {
+    let mut server = Server::default();
+    while let Some(body) = read_message(reader)? {
+        let out = match serde_json::from_str::<Value>(&body) {
+            Ok(message) if message.get("method").and_then(|x| x.as_str()) == Some("exit") => {
+                break;
+            }
+            Ok(message) => server.handle(&message),
+            Err(e) => vec![error(&Value::Null, PARSE_ERROR, &e.to_string())],
+        };
+        for message in out {
+            write_message(writer, &message.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn fixture() -> String {
+        r#"
+     D LastId          S              8  0
+     C                   EXSR      $SetLstId
+     C                   GOTO      Done
+     C     $SetLstId     BEGSR
+     C                   Z-ADD     1             LastId
+     C                   ENDSR
+     C     Done          TAG
+"#[1..]
+            .to_string()
+    }
+
+    // scripted client, every message framed and sent in one go
+    fn session(messages: &[Value]) -> Vec<Value> {
+        let mut input = vec![];
+        for message in messages {
+            write_message(&mut input, &message.to_string()).unwrap();
+        }
+        let mut output = vec![];
+        serve(&mut Cursor::new(input), &mut output).unwrap();
+        let mut reader = Cursor::new(output);
+        let mut out = vec![];
+        while let Some(body) = read_message(&mut reader).unwrap() {
+            out.push(serde_json::from_str(&body).unwrap());
+        }
+        out
+    }
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    fn result(out: &[Value], id: u64) -> &Value {
+        out.iter()
+            .find(|x| x.get("id") == Some(&json!(id)))
+            .and_then(|x| x.get("result"))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_change_keeps_language() {
+        // an unsaved buffer, only the client knows it's a physical file
+        let uri = "untitled:Untitled-1";
+        let text = |name: &str| {
+            format!(
+                "     A          R {}\n     A            EVTCOW         9P 0\n",
+                name
+            )
+        };
+        let out = session(&[
+            request(1, "initialize", json!({ "capabilities": {} })),
+            notification(
+                "textDocument/didOpen",
+                json!({ "textDocument": {
+                    "uri": uri, "languageId": "pfdds", "version": 1, "text": text("EVTFMT")
+                }}),
+            ),
+            notification(
+                "textDocument/didChange",
+                json!({
+                    "textDocument": { "uri": uri, "version": 2 },
+                    "contentChanges": [{ "text": text("COWFMT") }],
+                }),
+            ),
+            request(
+                2,
+                "textDocument/documentSymbol",
+                json!({ "textDocument": { "uri": uri } }),
+            ),
+        ]);
+        let symbols = result(&out, 2).as_array().unwrap();
+        assert_eq!(symbols[0]["name"], json!("COWFMT"));
+    }
+
+    #[test]
+    fn test_session() {
+        let uri = "file:///tmp/idk-lsp-test/zevt.rpgle";
+        let doc = json!({ "textDocument": { "uri": uri } });
+        let out = session(&[
+            request(1, "initialize", json!({ "capabilities": {} })),
+            notification("initialized", json!({})),
+            notification(
+                "textDocument/didOpen",
+                json!({ "textDocument": {
+                    "uri": uri, "languageId": "rpgle", "version": 1, "text": fixture()
+                }}),
+            ),
+            request(
+                2,
+                "textDocument/definition",
+                json!({ "textDocument": { "uri": uri }, "position": { "line": 1, "character": 40 } }),
+            ),
+            request(3, "textDocument/documentSymbol", doc.clone()),
+            request(4, "textDocument/semanticTokens/full", doc.clone()),
+            request(
+                5,
+                "textDocument/hover",
+                json!({ "textDocument": { "uri": uri }, "position": { "line": 1, "character": 27 } }),
+            ),
+            request(9, "textDocument/rename", doc.clone()),
+            request(
+                10,
+                "textDocument/codeAction",
+                json!({
+                    "textDocument": { "uri": uri },
+                    "range": {
+                        "start": { "line": 4, "character": 27 },
+                        "end": { "line": 4, "character": 27 }
+                    },
+                    "context": { "diagnostics": [] }
+                }),
+            ),
+            request(
+                8,
+                "textDocument/completion",
+                json!({ "textDocument": { "uri": uri }, "position": { "line": 1, "character": 38 } }),
+            ),
+            request(6, "shutdown", Value::Null),
+            notification("exit", Value::Null),
+            request(7, "shutdown", Value::Null),
+        ]);
+
+        let init = result(&out, 1);
+        assert_eq!(init["capabilities"]["definitionProvider"], json!(true));
+
+        let published = out
+            .iter()
+            .find(|x| x["method"] == json!("textDocument/publishDiagnostics"))
+            .unwrap();
+        let codes = published["params"]["diagnostics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d["code"].as_str().unwrap())
+            .collect::<Vec<&str>>();
+        assert!(codes.contains(&"no-goto"));
+
+        let location = result(&out, 2);
+        assert_eq!(location["uri"], json!(uri));
+        assert_eq!(location["range"]["start"]["line"], json!(3));
+
+        let names = result(&out, 3)
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["name"].as_str().unwrap().to_uppercase())
+            .collect::<Vec<String>>();
+        assert_eq!(names, vec!["$SETLSTID", "DONE"]);
+        assert_eq!(result(&out, 3)[0]["range"]["end"]["line"], json!(5));
+
+        let data = result(&out, 4)["data"].as_array().unwrap();
+        assert!(!data.is_empty());
+        assert_eq!(data.len() % 5, 0);
+
+        let hover = result(&out, 5);
+        assert_eq!(hover["contents"]["kind"], json!("markdown"));
+        assert!(
+            hover["contents"]["value"]
+                .as_str()
+                .unwrap()
+                .starts_with("`EXSR`")
+        );
+        assert_eq!(hover["range"]["end"]["character"], json!(29));
+
+        let action = &result(&out, 10)[0];
+        assert_eq!(
+            action["title"],
+            json!("Convert Z-ADD to EVAL, Z-ADD truncated on overflow, this raises an error")
+        );
+        let edit = &action["edit"]["changes"][uri][0];
+        assert_eq!(edit["range"]["start"]["line"], json!(4));
+        assert_eq!(
+            edit["newText"],
+            json!("     C                   EVAL      LastId = 1\n")
+        );
+
+        let rename = out.iter().find(|x| x["id"] == json!(9)).unwrap();
+        assert_eq!(rename["error"]["code"], json!(METHOD_NOT_FOUND));
+
+        let labels = result(&out, 8)
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["label"].as_str().unwrap())
+            .collect::<Vec<&str>>();
+        assert_eq!(labels, vec!["$SetLstId"]);
+
+        assert_eq!(result(&out, 6), &Value::Null);
+        // nothing is read after exit
+        assert!(out.iter().all(|x| x["id"] != json!(7)));
+    }
+}
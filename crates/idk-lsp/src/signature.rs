@@ -0,0 +1,25 @@
+use crate::document::Document;
+use idk::Lang;
+use serde_json::{Value, json};
+
+// textDocument/signatureHelp, the prototype of the call under the cursor
+pub fn signature_help(doc: &Document, row: usize, col: usize) -> Value {
+    if doc.lang != Lang::Rpgle {
+        return Value::Null;
+    }
+    match idk::signature_help(&doc.text, row, col) {
+        Some(help) => json!({
+            "signatures": [{
+                "label": help.label,
+                "parameters": help
+                    .parameters
+                    .iter()
+                    .map(|x| json!({ "label": x }))
+                    .collect::<Vec<Value>>(),
+            }],
+            "activeSignature": 0,
+            "activeParameter": help.active_parameter,
+        }),
+        None => Value::Null,
+    }
+}
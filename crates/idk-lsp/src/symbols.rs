@@ -0,0 +1,40 @@
+use crate::document::{Document, range};
+use idk::Outline;
+use serde_json::{Value, json};
+
+// outline kind -> lsp SymbolKind
+fn symbol_kind(kind: &str) -> u8 {
+    match kind {
+        "file" => 1,
+        "options" | "file-entry" => 2,
+        "subroutine" => 6,
+        "subfield" | "field" => 8,
+        "procedure-interface" => 11,
+        "prototype" | "procedure" => 12,
+        "parameter" => 13,
+        "key-field" | "tag" => 20,
+        "data-structure" | "record-format" => 23,
+        _ => 13,
+    }
+}
+
+fn symbol(item: &Outline) -> Value {
+    json!({
+        "name": item.name,
+        "detail": item.kind,
+        "kind": symbol_kind(&item.kind),
+        "range": range(item.range.0, item.range.1),
+        "selectionRange": range(item.selection.0, item.selection.1),
+        "children": item.children.iter().map(symbol).collect::<Vec<Value>>(),
+    })
+}
+
+// textDocument/documentSymbol, the outline as nested DocumentSymbols
+pub fn document_symbols(doc: &Document) -> Value {
+    Value::Array(
+        idk::outline(doc.lang, &doc.text)
+            .iter()
+            .map(symbol)
+            .collect(),
+    )
+}
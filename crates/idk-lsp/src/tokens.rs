@@ -0,0 +1,138 @@
+use crate::document::Document;
+use idk::{HighlightMeta, Lang};
+use serde_json::{Value, json};
+
+// the semantic token legend, a token type is its index here
+pub const TOKEN_TYPES: [&str; 14] = [
+    "comment",
+    "keyword",
+    "type",
+    "number",
+    "string",
+    "function",
+    "variable",
+    "enumMember",
+    "operator",
+    "parameter",
+    "property",
+    "namespace",
+    "struct",
+    // not a standard type, a name that resolves to nothing
+    "undefined",
+];
+
+// neovim highlight group -> token type, ex: @keyword.directive -> keyword
+fn token_type(hl_group: &str) -> Option<usize> {
+    let group = hl_group.trim_start_matches('@').to_lowercase();
+    let mut parts = group.split('.');
+    let name = match (parts.next()?, parts.next()) {
+        ("variable", Some("parameter")) => "parameter",
+        ("variable", Some("member")) => "property",
+        ("variable", Some("structure")) | ("type", Some("record")) => "struct",
+        ("variable", Some("undefined")) => "undefined",
+        ("module", _) => "namespace",
+        ("comment", _) => "comment",
+        (
+            "keyword" | "statement" | "conditional" | "repeat" | "label" | "include" | "define"
+            | "preproc",
+            _,
+        ) => "keyword",
+        ("type" | "storageclass" | "structure" | "typedef", _) => "type",
+        ("number" | "float", _) => "number",
+        ("string" | "character", _) => "string",
+        ("function", _) => "function",
+        ("variable" | "identifier", _) => "variable",
+        ("constant" | "boolean", _) => "enumMember",
+        ("operator", _) => "operator",
+        _ => return None,
+    };
+    TOKEN_TYPES.iter().position(|t| *t == name)
+}
+
+/* Paint the highlights over a grid of the document, in order, so the semantic
+ * (AST) pass wins over the naive (CST) one like it does in the editor. Runs of
+ * the same type on a line become one token.
+ */
+fn paint(metas: &[HighlightMeta], lines: &[usize]) -> Vec<Vec<Option<usize>>> {
+    let mut grid = lines
+        .iter()
+        .map(|len| vec![None; *len])
+        .collect::<Vec<Vec<Option<usize>>>>();
+    for meta in metas {
+        let kind = token_type(&meta.hl_group);
+        for row in meta.start_row..=meta.end_row {
+            let Some(cells) = grid.get_mut(row) else {
+                break;
+            };
+            let from = if row == meta.start_row {
+                meta.start_col
+            } else {
+                0
+            };
+            let to = if row == meta.end_row {
+                meta.end_col
+            } else {
+                cells.len()
+            };
+            for cell in cells.iter_mut().take(to).skip(from) {
+                *cell = kind;
+            }
+        }
+    }
+    grid
+}
+
+// textDocument/semanticTokens/full, the highlights of the editor
+pub fn semantic_tokens(doc: &Document) -> Value {
+    let metas = match doc.lang {
+        Lang::Rpgle => idk::highlight_rpgle(&doc.text, doc.path.as_deref()),
+        Lang::Pfdds => idk::highlight_pfdds(&doc.text),
+        Lang::Lfdds => idk::highlight_lfdds(&doc.text),
+        Lang::Dspfdds => idk::highlight_dspfdds(&doc.text),
+        Lang::Prtfdds => idk::highlight_prtfdds(&doc.text),
+        Lang::Sqlddl => idk::highlight_sqlddl(&doc.text),
+    };
+    let lines = doc
+        .text
+        .lines()
+        .map(|line| line.chars().count())
+        .collect::<Vec<usize>>();
+    let mut data = vec![];
+    let (mut last_row, mut last_col) = (0, 0);
+    for (row, cells) in paint(&metas, &lines).iter().enumerate() {
+        let mut col = 0;
+        while col < cells.len() {
+            let Some(kind) = cells[col] else {
+                col += 1;
+                continue;
+            };
+            let length = cells[col..]
+                .iter()
+                .take_while(|c| **c == Some(kind))
+                .count();
+            if row != last_row {
+                last_col = 0;
+            }
+            data.extend([row - last_row, col - last_col, length, kind, 0]);
+            (last_row, last_col) = (row, col);
+            col += length;
+        }
+    }
+    json!({ "data": data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_type() {
+        assert_eq!(token_type("@keyword.directive.define"), Some(1));
+        assert_eq!(token_type("@comment"), Some(0));
+        assert_eq!(token_type("Identifier"), Some(6));
+        assert_eq!(token_type("Normal"), None);
+        assert_eq!(token_type("@variable.parameter"), Some(9));
+        assert_eq!(token_type("@variable.member.external"), Some(10));
+        assert_eq!(token_type("@variable.undefined"), Some(13));
+    }
+}
@@ -1,13 +1,11 @@
-mod graph;
-mod highlight;
-use dds_parser;
-use highlight::{HighlightMeta, highlight_pfdds, highlight_rpgle};
+use idk::{
+    HighlightMeta, IRenderable, IdkGraph, Lang, Manifest, highlight_dspfdds, highlight_lfdds,
+    highlight_pfdds, highlight_prtfdds, highlight_rpgle, highlight_sqlddl,
+};
 use nvim_oxi::{self as oxi};
 use rpgle_parser;
-use std::path::PathBuf;
-use std::{env, fs};
+use std::env;
 
-use graph::{IRenderable, IdkGraph};
 use nvim_oxi::conversion::{Error as ConversionError, ToObject};
 use nvim_oxi::serde::Serializer;
 use nvim_oxi::{Object, lua};
@@ -71,7 +69,8 @@ impl Highlighter {
             input.push_str(&line.to_string());
             input.push_str("\n");
         }
-        let metas = highlight_rpgle(&input);
+        let bufname = self.buf.get_name().ok();
+        let metas = highlight_rpgle(&input, bufname.as_deref());
         if env::var("DEBUG").is_ok() {
             let _ = std::fs::write(
                 "/tmp/highlights.txt",
@@ -91,7 +90,11 @@ impl Highlighter {
         Ok(())
     }
 
-    fn apply_pfdds_highlights(&mut self) -> oxi::Result<()> {
+    // dds and sql sources, `highlight` picks the parser
+    fn apply_dds_highlights(
+        &mut self,
+        highlight: fn(&str) -> Vec<HighlightMeta>,
+    ) -> oxi::Result<()> {
         let count = self.buf.line_count()?;
         let lines = self.buf.get_lines(0..count, true)?;
         let mut input = String::new();
@@ -99,7 +102,7 @@ impl Highlighter {
             input.push_str(&line.to_string());
             input.push_str("\n");
         }
-        let metas = highlight_pfdds(&input);
+        let metas = highlight(&input);
         if env::var("DEBUG").is_ok() {
             let _ = std::fs::write(
                 "/tmp/highlights.txt",
@@ -129,6 +132,18 @@ struct TagItem {
     end_char: usize,
     uri: Option<String>,
 }
+impl From<idk::Definition> for TagItem {
+    fn from(value: idk::Definition) -> Self {
+        Self {
+            name: value.name,
+            start_line: value.start_line,
+            start_char: value.start_char,
+            end_line: value.end_line,
+            end_char: value.end_char,
+            uri: value.uri,
+        }
+    }
+}
 impl ToObject for TagItem {
     fn to_object(self) -> Result<Object, ConversionError> {
         self.serialize(Serializer::new()).map_err(Into::into)
@@ -144,89 +159,251 @@ impl lua::Pushable for TagItem {
     }
 }
 
-#[derive(Debug)]
-struct Manifest {
-    uri: String,
-}
-
-impl Manifest {
-    fn uri_filepath(&self) -> String {
-        self.uri.replace("file://", "").to_string()
-    }
-
-    fn get_source_files(&self) -> Option<Vec<String>> {
-        if let Ok(raw_manifest) = fs::read_to_string(self.uri_filepath()) {
-            if let Some(maybebp) = PathBuf::from(&self.uri_filepath()).parent() {
-                let mut relpaths = raw_manifest
-                    .replace("\n", "")
-                    .replace("[", "")
-                    .replace("]", "")
-                    .replace('"', "")
-                    .replace('\\', "")
-                    .replace(' ', "")
-                    .split(",")
-                    .map(|x| x.to_string())
-                    .collect::<Vec<String>>();
-                relpaths.reverse();
-                if let Ok(bp) = maybebp.canonicalize() {
-                    if let Some(basepath) = bp.to_str() {
-                        let out = relpaths
-                            .iter()
-                            .map(|rp| {
-                                let mut out = basepath.to_string();
-                                out.push_str("/");
-                                out.push_str(rp);
-                                format!("{}", out)
-                            })
-                            .collect::<Vec<String>>();
-                        return Some(out);
-                    }
-                }
-            }
+#[derive(Debug, Serialize, Deserialize)]
+struct DiagnosticItem {
+    lnum: usize,
+    col: usize,
+    end_lnum: usize,
+    end_col: usize,
+    severity: u8,
+    message: String,
+    code: String,
+    source: String,
+}
+impl From<&rpgle_parser::Diagnostic> for DiagnosticItem {
+    fn from(value: &rpgle_parser::Diagnostic) -> Self {
+        // matches vim.diagnostic.severity
+        let severity = match value.level {
+            rpgle_parser::DiagnosticLevel::Error => 1,
+            rpgle_parser::DiagnosticLevel::Warning => 2,
+            rpgle_parser::DiagnosticLevel::Info => 3,
+        };
+        Self {
+            lnum: value.span.start.row,
+            col: value.span.start.col,
+            end_lnum: value.span.end.row,
+            end_col: value.span.end.col,
+            severity,
+            message: value.msg.clone(),
+            code: value.code.clone(),
+            source: "idk".to_string(),
+        }
+    }
+}
+impl ToObject for DiagnosticItem {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+impl lua::Pushable for DiagnosticItem {
+    unsafe fn push(self, lstate: *mut lua::ffi::State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
         }
-        None
     }
 }
 
 fn get_manifest() -> Option<Manifest> {
     let buf = oxi::api::Buffer::current();
-    if let Ok(bufname) = buf.get_name() {
-        if let Some(parent) = bufname.parent() {
-            if let Ok(entries) = parent.read_dir() {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        if entry.file_name().to_ascii_lowercase() == "manifest.json" {
-                            if let Ok(fp) = entry.path().canonicalize() {
-                                if let Some(s) = fp.to_str() {
-                                    return Some(Manifest {
-                                        uri: format!("file://{}", s),
-                                    });
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            if let Some(grandparent) = parent.parent() {
-                if let Ok(entries) = grandparent.read_dir() {
-                    for entry in entries {
-                        if let Ok(entry) = entry {
-                            if entry.file_name().to_ascii_lowercase() == "manifest.json" {
-                                if let Ok(fp) = entry.path().canonicalize() {
-                                    if let Some(s) = fp.to_str() {
-                                        return Some(Manifest {
-                                            uri: format!("file://{}", s),
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    let bufname = buf.get_name().ok()?;
+    Manifest::find(&bufname)
+}
+
+// one outline entry, flattened in source order with its nesting depth
+#[derive(Debug, Serialize, Deserialize)]
+struct OutlineItem {
+    name: String,
+    kind: String,
+    depth: usize,
+    lnum: usize,
+    col: usize,
+    end_lnum: usize,
+    end_col: usize,
+}
+impl OutlineItem {
+    fn flatten(items: &[idk::Outline], depth: usize, out: &mut Vec<Self>) {
+        for item in items {
+            out.push(Self {
+                name: item.name.clone(),
+                kind: item.kind.clone(),
+                depth,
+                lnum: item.selection.0.0,
+                col: item.selection.0.1,
+                end_lnum: item.range.1.0,
+                end_col: item.range.1.1,
+            });
+            Self::flatten(&item.children, depth + 1, out);
+        }
+    }
+}
+impl ToObject for OutlineItem {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+impl lua::Pushable for OutlineItem {
+    unsafe fn push(self, lstate: *mut lua::ffi::State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+// a vim complete-item, ex: { word = "LastId", kind = "variable", menu = "8 0" }
+#[derive(Debug, Serialize, Deserialize)]
+struct CompleteItem {
+    word: String,
+    kind: String,
+    menu: String,
+}
+
+// candidates for the word starting at `start_col`, for an omnifunc
+#[derive(Debug, Serialize, Deserialize)]
+struct CompleteOutcome {
+    start_col: usize,
+    items: Vec<CompleteItem>,
+}
+impl From<idk::Completions> for CompleteOutcome {
+    fn from(value: idk::Completions) -> Self {
+        Self {
+            start_col: value.start_col,
+            items: value
+                .items
+                .into_iter()
+                .map(|x| CompleteItem {
+                    word: x.label,
+                    kind: x.kind,
+                    menu: x.detail,
+                })
+                .collect(),
+        }
+    }
+}
+impl ToObject for CompleteOutcome {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+impl lua::Pushable for CompleteOutcome {
+    unsafe fn push(self, lstate: *mut lua::ffi::State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+// the signature of the call under the cursor
+#[derive(Debug, Serialize, Deserialize)]
+struct SignatureItem {
+    label: String,
+    parameters: Vec<String>,
+    // one based like lua tables
+    active: usize,
+}
+impl From<idk::SignatureHelp> for SignatureItem {
+    fn from(value: idk::SignatureHelp) -> Self {
+        Self {
+            label: value.label,
+            parameters: value.parameters,
+            active: value.active_parameter + 1,
+        }
+    }
+}
+impl ToObject for SignatureItem {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+impl lua::Pushable for SignatureItem {
+    unsafe fn push(self, lstate: *mut lua::ffi::State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+// markdown docs for the word under the cursor
+#[derive(Debug, Serialize, Deserialize)]
+struct HoverItem {
+    contents: String,
+}
+impl From<idk::Hover> for HoverItem {
+    fn from(value: idk::Hover) -> Self {
+        Self {
+            contents: value.contents,
+        }
+    }
+}
+impl ToObject for HoverItem {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+impl lua::Pushable for HoverItem {
+    unsafe fn push(self, lstate: *mut lua::ffi::State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
+        }
+    }
+}
+
+// one edit of a code action, `lines` replace the text between the two positions
+#[derive(Debug, Serialize, Deserialize)]
+struct EditItem {
+    start_row: usize,
+    start_col: usize,
+    end_row: usize,
+    end_col: usize,
+    lines: Vec<String>,
+}
+
+// a code action for vim.ui.select, edits are sorted by position
+#[derive(Debug, Serialize, Deserialize)]
+struct ActionItem {
+    title: String,
+    edits: Vec<EditItem>,
+}
+impl From<idk::CodeAction> for ActionItem {
+    fn from(value: idk::CodeAction) -> Self {
+        Self {
+            title: value.title,
+            edits: value
+                .edits
+                .into_iter()
+                .map(|x| EditItem {
+                    start_row: x.start_row,
+                    start_col: x.start_col,
+                    end_row: x.end_row,
+                    end_col: x.end_col,
+                    lines: x.text.split('\n').map(|x| x.to_string()).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+impl ToObject for ActionItem {
+    fn to_object(self) -> Result<Object, ConversionError> {
+        self.serialize(Serializer::new()).map_err(Into::into)
+    }
+}
+impl lua::Pushable for ActionItem {
+    unsafe fn push(self, lstate: *mut lua::ffi::State) -> Result<std::ffi::c_int, lua::Error> {
+        unsafe {
+            self.to_object()
+                .map_err(lua::Error::push_error_from_err::<Self, _>)?
+                .push(lstate)
         }
     }
-    None
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -316,23 +493,8 @@ fn json_dump_current_buffer(path: String) -> DumpOutcome {
     }
 }
 
-fn getdef(pattern: String) -> Option<TagItem> {
+fn diagnostics_rpgle(_: ()) -> Vec<DiagnosticItem> {
     let buf = oxi::api::Buffer::current();
-    let current_file: String = match buf.get_name() {
-        Ok(pb) => pb
-            .as_path()
-            .file_name()
-            .expect("path from pathbuf from ok result should always unwrap")
-            .to_str()
-            .expect("should always be able to convert osstr to str in this context")
-            .to_string()
-            .to_uppercase(),
-        Err(_) => "".to_string(),
-    };
-    let current_row = match oxi::api::get_current_win().get_cursor() {
-        Ok((row1, _)) => row1 - 1,
-        Err(_) => 0,
-    };
     if let Ok(count) = buf.line_count() {
         if let Ok(lines) = buf.get_lines(0..count, true) {
             let mut input = String::new();
@@ -340,107 +502,220 @@ fn getdef(pattern: String) -> Option<TagItem> {
                 input.push_str(&line.to_string());
                 input.push_str("\n");
             }
-            if let Ok(cst) = rpgle_parser::parse_cst(input.as_str()) {
-                let ast_rs = rpgle_parser::parse_ast(&cst);
-                if let Ok(ast) = ast_rs {
-                    if let Some(((strow, stcol), (endrow, endcol))) =
-                        ast.try_get_definition(&pattern)
-                    {
-                        if strow != current_row {
-                            let ti = TagItem {
-                                name: pattern.clone(),
-                                uri: None,
-                                start_line: strow,
-                                start_char: stcol,
-                                end_line: endrow,
-                                end_char: endcol,
-                            };
-                            if env::var("DEBUG").is_ok() {
-                                let _ = std::fs::write("/tmp/getdef.txt", format!("{:#?}", ti));
-                            }
-                            return Some(ti);
-                        }
-                    }
-                    // else
-                    if let Some(man) = get_manifest() {
-                        if let Some(srcs) = man.get_source_files() {
-                            let mut sources = srcs
-                                .into_iter()
-                                .filter(|x| !x.to_uppercase().ends_with(&current_file))
-                                .collect::<Vec<String>>();
-                            sources.sort_by_key(|x| {
-                                match x.to_uppercase().contains(&pattern.to_uppercase()) {
-                                    true => 0,
-                                    false => 1,
-                                }
-                            });
-                            for source in sources {
-                                if source.ends_with("rpgle") {
-                                    if let Ok(input) = fs::read_to_string(source.clone()) {
-                                        if let Ok(cst) = rpgle_parser::parse_cst(input.as_str()) {
-                                            let ast_rs = rpgle_parser::parse_ast(&cst);
-                                            if let Ok(ast) = ast_rs {
-                                                if let Some(((strow, stcol), (endrow, endcol))) =
-                                                    ast.try_get_definition(&pattern)
-                                                {
-                                                    let uri = format!("file://{}", source);
-                                                    let ti = TagItem {
-                                                        name: pattern.clone(),
-                                                        uri: Some(uri),
-                                                        start_line: strow,
-                                                        start_char: stcol,
-                                                        end_line: endrow,
-                                                        end_char: endcol,
-                                                    };
-                                                    if env::var("DEBUG").is_ok() {
-                                                        let _ = std::fs::write(
-                                                            "/tmp/getdef.txt",
-                                                            format!("{:#?}", ti),
-                                                        );
-                                                    }
-                                                    return Some(ti);
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                                if source.ends_with("pfdds") {
-                                    if let Ok(input) = fs::read_to_string(&source) {
-                                        if let Ok(cst) =
-                                            dds_parser::pfdds::CST::try_from(input.as_str())
-                                        {
-                                            let ast = dds_parser::pfdds::AST::from(&cst);
-                                            if let Some(def) =
-                                                dds_parser::pfdds::query_definition(&ast, &pattern)
-                                            {
-                                                let uri = format!("file://{}", source);
-                                                let ti = TagItem {
-                                                    name: pattern.clone(),
-                                                    uri: Some(uri),
-                                                    start_line: def.start.row,
-                                                    start_char: def.start.col,
-                                                    end_line: def.end.row,
-                                                    end_char: def.end.col,
-                                                };
-                                                if env::var("DEBUG").is_ok() {
-                                                    let _ = std::fs::write(
-                                                        "/tmp/getdef.txt",
-                                                        format!("{:#?}", ti),
-                                                    );
-                                                }
-                                                return Some(ti);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+            let bufname = buf.get_name().ok();
+            return idk::lint_rpgle(&input, bufname.as_deref())
+                .iter()
+                .map(DiagnosticItem::from)
+                .collect();
+        }
+    }
+    vec![]
+}
+
+fn outline(_: ()) -> Vec<OutlineItem> {
+    let buf = oxi::api::Buffer::current();
+    let Ok(count) = buf.line_count() else {
+        return vec![];
+    };
+    let mut input = String::new();
+    if let Ok(lines) = buf.get_lines(0..count, true) {
+        for line in lines {
+            input.push_str(&line.to_string());
+            input.push('\n');
+        }
+    }
+    let lang = buf
+        .get_name()
+        .ok()
+        .and_then(|name| Lang::from_path(&name))
+        .unwrap_or(Lang::Rpgle);
+    let mut out = vec![];
+    OutlineItem::flatten(&idk::outline(lang, &input), 0, &mut out);
+    out
+}
+
+// the 'foldexpr' value of every line of the current buffer
+fn fold_levels(_: ()) -> Vec<String> {
+    let buf = oxi::api::Buffer::current();
+    let Ok(count) = buf.line_count() else {
+        return vec![];
+    };
+    let mut input = String::new();
+    if let Ok(lines) = buf.get_lines(0..count, true) {
+        for line in lines {
+            input.push_str(&line.to_string());
+            input.push('\n');
+        }
+    }
+    let lang = buf
+        .get_name()
+        .ok()
+        .and_then(|name| Lang::from_path(&name))
+        .unwrap_or(Lang::Rpgle);
+    idk::fold_levels(&idk::folds(lang, &input), count)
+}
+
+fn format_rpgle(_: ()) -> DumpOutcome {
+    let mut buf = oxi::api::Buffer::current();
+    let Ok(count) = buf.line_count() else {
+        return DumpOutcome {
+            ok: false,
+            msg: Some("Unable to read current buffer!".to_string()),
+        };
+    };
+    let mut input = String::new();
+    if let Ok(lines) = buf.get_lines(0..count, true) {
+        for line in lines {
+            input.push_str(&line.to_string());
+            input.push_str("\n");
+        }
+    }
+    let options = get_manifest()
+        .and_then(|man| man.get_format_options())
+        .unwrap_or_default();
+    let outcome = match rpgle_parser::format(&input, &options) {
+        Ok(output) if output == input => Ok(()),
+        Ok(output) => buf
+            .set_lines(0..count, true, output.lines())
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    match outcome {
+        Ok(_) => DumpOutcome {
+            ok: true,
+            msg: None,
+        },
+        Err(msg) => DumpOutcome {
+            ok: false,
+            msg: Some(msg),
+        },
+    }
+}
+
+fn convert_rpgle((start, end): (usize, usize)) -> DumpOutcome {
+    let mut buf = oxi::api::Buffer::current();
+    let Ok(count) = buf.line_count() else {
+        return DumpOutcome {
+            ok: false,
+            msg: Some("Unable to read current buffer!".to_string()),
+        };
+    };
+    let mut input = String::new();
+    if let Ok(lines) = buf.get_lines(0..count, true) {
+        for line in lines {
+            input.push_str(&line.to_string());
+            input.push_str("\n");
+        }
+    }
+    let outcome = match rpgle_parser::convert_range(&input, start..end.min(count)) {
+        Ok(output) if output == input => Ok(()),
+        Ok(output) => buf
+            .set_lines(0..count, true, output.lines())
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    match outcome {
+        Ok(_) => DumpOutcome {
+            ok: true,
+            msg: None,
+        },
+        Err(msg) => DumpOutcome {
+            ok: false,
+            msg: Some(msg),
+        },
+    }
+}
+
+// completion candidates at a zero based row and column of the current buffer
+fn complete_rpgle((row, col): (usize, usize)) -> CompleteOutcome {
+    let buf = oxi::api::Buffer::current();
+    let mut input = String::new();
+    if let Ok(count) = buf.line_count() {
+        if let Ok(lines) = buf.get_lines(0..count, true) {
+            for line in lines {
+                input.push_str(&line.to_string());
+                input.push('\n');
             }
         }
     }
-    None
+    let bufname = buf.get_name().ok();
+    CompleteOutcome::from(idk::complete_rpgle(&input, bufname.as_deref(), row, col))
+}
+
+// signature help at a zero based row and column of the current buffer
+fn signature_help((row, col): (usize, usize)) -> Option<SignatureItem> {
+    let buf = oxi::api::Buffer::current();
+    let count = buf.line_count().ok()?;
+    let mut input = String::new();
+    for line in buf.get_lines(0..count, true).ok()? {
+        input.push_str(&line.to_string());
+        input.push('\n');
+    }
+    idk::signature_help(&input, row, col).map(SignatureItem::from)
+}
+
+// hover docs at a zero based row and column of the current buffer
+fn hover((row, col): (usize, usize)) -> Option<HoverItem> {
+    let buf = oxi::api::Buffer::current();
+    let count = buf.line_count().ok()?;
+    let mut input = String::new();
+    for line in buf.get_lines(0..count, true).ok()? {
+        input.push_str(&line.to_string());
+        input.push('\n');
+    }
+    let bufname = buf.get_name().ok();
+    let lang = bufname
+        .as_deref()
+        .and_then(Lang::from_path)
+        .unwrap_or(Lang::Rpgle);
+    idk::hover(lang, &input, bufname.as_deref(), row, col).map(HoverItem::from)
+}
+
+// code actions at a zero based row and column of the current buffer
+fn code_actions((row, col): (usize, usize)) -> Vec<ActionItem> {
+    let buf = oxi::api::Buffer::current();
+    let Ok(count) = buf.line_count() else {
+        return vec![];
+    };
+    let mut input = String::new();
+    if let Ok(lines) = buf.get_lines(0..count, true) {
+        for line in lines {
+            input.push_str(&line.to_string());
+            input.push('\n');
+        }
+    }
+    let lang = buf
+        .get_name()
+        .ok()
+        .and_then(|name| Lang::from_path(&name))
+        .unwrap_or(Lang::Rpgle);
+    idk::code_actions(lang, &input, row, col)
+        .into_iter()
+        .map(ActionItem::from)
+        .collect()
+}
+
+fn getdef(pattern: String) -> Option<TagItem> {
+    let buf = oxi::api::Buffer::current();
+    let current_row = match oxi::api::get_current_win().get_cursor() {
+        Ok((row1, _)) => row1 - 1,
+        Err(_) => 0,
+    };
+    let count = buf.line_count().ok()?;
+    let mut input = String::new();
+    for line in buf.get_lines(0..count, true).ok()? {
+        input.push_str(&line.to_string());
+        input.push('\n');
+    }
+    let bufname = buf.get_name().ok();
+    idk::find_definition(
+        Lang::Rpgle,
+        &input,
+        &pattern,
+        bufname.as_deref(),
+        Some(current_row),
+    )
+    .map(TagItem::from)
 }
 
 #[nvim_oxi::plugin]
@@ -463,7 +738,59 @@ fn libidk() -> oxi::Result<oxi::Dictionary> {
             buf: oxi::api::Buffer::current(),
             namespace_id: oxi::api::create_namespace("PFDDSNamespace"),
         };
-        if let Err(e) = highlighter.apply_pfdds_highlights() {
+        if let Err(e) = highlighter.apply_dds_highlights(highlight_pfdds) {
+            oxi::print!("ERROR");
+            oxi::print!("\n");
+            oxi::print!("{}", e);
+            oxi::print!("\n");
+        }
+    });
+
+    let highlight_lfdds = oxi::Function::from_fn(move |(): ()| {
+        let mut highlighter = Highlighter {
+            buf: oxi::api::Buffer::current(),
+            namespace_id: oxi::api::create_namespace("LFDDSNamespace"),
+        };
+        if let Err(e) = highlighter.apply_dds_highlights(highlight_lfdds) {
+            oxi::print!("ERROR");
+            oxi::print!("\n");
+            oxi::print!("{}", e);
+            oxi::print!("\n");
+        }
+    });
+
+    let highlight_dspfdds = oxi::Function::from_fn(move |(): ()| {
+        let mut highlighter = Highlighter {
+            buf: oxi::api::Buffer::current(),
+            namespace_id: oxi::api::create_namespace("DSPFDDSNamespace"),
+        };
+        if let Err(e) = highlighter.apply_dds_highlights(highlight_dspfdds) {
+            oxi::print!("ERROR");
+            oxi::print!("\n");
+            oxi::print!("{}", e);
+            oxi::print!("\n");
+        }
+    });
+
+    let highlight_prtfdds = oxi::Function::from_fn(move |(): ()| {
+        let mut highlighter = Highlighter {
+            buf: oxi::api::Buffer::current(),
+            namespace_id: oxi::api::create_namespace("PRTFDDSNamespace"),
+        };
+        if let Err(e) = highlighter.apply_dds_highlights(highlight_prtfdds) {
+            oxi::print!("ERROR");
+            oxi::print!("\n");
+            oxi::print!("{}", e);
+            oxi::print!("\n");
+        }
+    });
+
+    let highlight_sqlddl = oxi::Function::from_fn(move |(): ()| {
+        let mut highlighter = Highlighter {
+            buf: oxi::api::Buffer::current(),
+            namespace_id: oxi::api::create_namespace("SQLDDLNamespace"),
+        };
+        if let Err(e) = highlighter.apply_dds_highlights(highlight_sqlddl) {
             oxi::print!("ERROR");
             oxi::print!("\n");
             oxi::print!("{}", e);
@@ -472,6 +799,15 @@ fn libidk() -> oxi::Result<oxi::Dictionary> {
     });
 
     let getdef = oxi::Function::from_fn(getdef);
+    let diagnostics_rpgle = oxi::Function::from_fn(diagnostics_rpgle);
+    let format_rpgle = oxi::Function::from_fn(format_rpgle);
+    let convert_rpgle = oxi::Function::from_fn(convert_rpgle);
+    let outline = oxi::Function::from_fn(outline);
+    let fold_levels = oxi::Function::from_fn(fold_levels);
+    let complete_rpgle = oxi::Function::from_fn(complete_rpgle);
+    let signature_help = oxi::Function::from_fn(signature_help);
+    let hover = oxi::Function::from_fn(hover);
+    let code_actions = oxi::Function::from_fn(code_actions);
 
     let json_dump_current_buffer = oxi::Function::from_fn(json_dump_current_buffer);
     let dot_dump_current_buffer = oxi::Function::from_fn(dot_dump_current_buffer);
@@ -479,7 +815,20 @@ fn libidk() -> oxi::Result<oxi::Dictionary> {
     Ok(oxi::Dictionary::from_iter([
         ("highlight_rpgle", oxi::Object::from(highlight_rpgle)),
         ("highlight_pfdds", oxi::Object::from(highlight_pfdds)),
+        ("highlight_lfdds", oxi::Object::from(highlight_lfdds)),
+        ("highlight_dspfdds", oxi::Object::from(highlight_dspfdds)),
+        ("highlight_prtfdds", oxi::Object::from(highlight_prtfdds)),
+        ("highlight_sqlddl", oxi::Object::from(highlight_sqlddl)),
         ("getdef", oxi::Object::from(getdef)),
+        ("diagnostics_rpgle", oxi::Object::from(diagnostics_rpgle)),
+        ("format_rpgle", oxi::Object::from(format_rpgle)),
+        ("convert_rpgle", oxi::Object::from(convert_rpgle)),
+        ("outline", oxi::Object::from(outline)),
+        ("fold_levels", oxi::Object::from(fold_levels)),
+        ("complete_rpgle", oxi::Object::from(complete_rpgle)),
+        ("signature_help", oxi::Object::from(signature_help)),
+        ("hover", oxi::Object::from(hover)),
+        ("code_actions", oxi::Object::from(code_actions)),
         (
             "json_dump_current_buffer",
             oxi::Object::from(json_dump_current_buffer),
@@ -0,0 +1,49 @@
+use crate::Lang;
+use serde::{Deserialize, Serialize};
+
+// Replace from (start_row, start_col) up to (end_row, end_col) with `text`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Edit {
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    pub text: String,
+}
+
+// A named set of edits, ex: Convert Z-ADD to EVAL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeAction {
+    pub title: String,
+    pub edits: Vec<Edit>,
+}
+
+impl From<rpgle_parser::CodeAction> for CodeAction {
+    fn from(value: rpgle_parser::CodeAction) -> Self {
+        Self {
+            title: value.title,
+            edits: value
+                .edits
+                .into_iter()
+                .map(|x| Edit {
+                    start_row: x.span.start.row,
+                    start_col: x.span.start.col,
+                    end_row: x.span.end.row,
+                    end_col: x.span.end.col,
+                    text: x.new_text,
+                })
+                .collect(),
+        }
+    }
+}
+
+// Code actions at a zero based `row` and `col`, only rpgle has any for now
+pub fn code_actions(lang: Lang, input: &str, row: usize, col: usize) -> Vec<CodeAction> {
+    match lang {
+        Lang::Rpgle => rpgle_parser::query_code_actions(input, row, col)
+            .into_iter()
+            .map(CodeAction::from)
+            .collect(),
+        Lang::Pfdds | Lang::Lfdds | Lang::Dspfdds | Lang::Prtfdds | Lang::Sqlddl => vec![],
+    }
+}
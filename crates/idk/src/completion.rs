@@ -0,0 +1,173 @@
+use crate::Manifest;
+use rpgle_parser::{Completion, CompletionKind, SymbolKind, SymbolTable};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompletionItem {
+    pub label: String,
+    // ex: variable, field, procedure, opcode, builtin
+    pub kind: String,
+    // type and length, ex: 9P 0, packed(9:2), or a BIF signature
+    pub detail: String,
+}
+
+// The candidates for a cursor, each replaces `start_col` up to the cursor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Completions {
+    pub start_col: usize,
+    pub items: Vec<CompletionItem>,
+}
+
+impl From<&Completion> for CompletionItem {
+    fn from(value: &Completion) -> Self {
+        let kind = match value.kind {
+            CompletionKind::Variable => "variable",
+            CompletionKind::Constant => "constant",
+            CompletionKind::DataStructure => "data-structure",
+            CompletionKind::Field => "field",
+            CompletionKind::Parameter => "parameter",
+            CompletionKind::File => "file",
+            CompletionKind::Procedure => "procedure",
+            CompletionKind::Subroutine => "subroutine",
+            CompletionKind::Opcode => "opcode",
+            CompletionKind::Builtin => "builtin",
+        };
+        Self {
+            label: value.label.clone(),
+            kind: kind.to_string(),
+            detail: value.detail.clone(),
+        }
+    }
+}
+
+// the fields of the externally described files the source declares
+fn external_fields(input: &str, manifest: &Manifest) -> Vec<Completion> {
+    let Ok(cst) = rpgle_parser::parse_cst(input) else {
+        return vec![];
+    };
+    let files = SymbolTable::from(&cst)
+        .definitions
+        .into_iter()
+        .filter(|x| x.kind == SymbolKind::File { external: true })
+        .map(|x| x.name.to_uppercase())
+        .collect::<Vec<String>>();
+    let mut out = vec![];
+    for (file, fields) in manifest.get_fields() {
+        if !files.contains(&file) {
+            continue;
+        }
+        for (format, name, detail) in fields {
+            out.push(Completion {
+                label: name,
+                kind: CompletionKind::Field,
+                detail: format!("{} {}", format, detail).trim().to_string(),
+            });
+        }
+    }
+    out
+}
+
+/* Completion candidates for rpgle source at a zero based `row` and `col`,
+ * with the fields of externally described files when there is a project
+ * manifest near `source`.
+ */
+pub fn complete_rpgle(input: &str, source: Option<&Path>, row: usize, col: usize) -> Completions {
+    let external = source
+        .and_then(Manifest::find)
+        .map(|man| external_fields(input, &man))
+        .unwrap_or_default();
+    let found = rpgle_parser::query_completions(input, row, col, &external);
+    Completions {
+        start_col: found.start_col,
+        items: found.items.iter().map(CompletionItem::from).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_complete_external_fields() {
+        let dir = std::env::temp_dir().join("idk-completion-test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("manifest.json"),
+            r#"["cowevt.pfdds", "zevt.rpgle"]"#,
+        )
+        .unwrap();
+        let dds = r#"
+     A          R EVTFMT
+     A            EVTID          9P 0
+     A            EVTNAME       20A
+"#[1..]
+            .to_string();
+        fs::write(dir.join("cowevt.pfdds"), dds).unwrap();
+        let input = r#"
+     FCowEvt    UF A E           K DISK
+      /free
+         x = evt
+      /end-free
+"#[1..]
+            .to_string();
+        let source = dir.join("zevt.rpgle");
+        fs::write(&source, &input).unwrap();
+        let found = complete_rpgle(&input, Some(&source), 2, 16);
+        assert_eq!(found.start_col, 13);
+        let items = found
+            .items
+            .iter()
+            .map(|x| (x.label.as_str(), x.kind.as_str(), x.detail.as_str()))
+            .collect::<Vec<(&str, &str, &str)>>();
+        assert_eq!(
+            items,
+            vec![
+                ("EVTID", "field", "EVTFMT 9P 0"),
+                ("EVTNAME", "field", "EVTFMT 20A")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_complete_logical_file_fields() {
+        let dir = std::env::temp_dir().join("idk-completion-lf-test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("manifest.json"),
+            r#"["cowevt.pfdds", "cowevtl2.lfdds", "zevt.rpgle"]"#,
+        )
+        .unwrap();
+        let pf = r#"
+     A          R EVTFMT
+     A            EVTID          9P 0
+     A            EVTNAME       20A
+"#[1..]
+            .to_string();
+        fs::write(dir.join("cowevt.pfdds"), pf).unwrap();
+        let lf = r#"
+     A          R EVTFMT                    PFILE(COWEVT)
+     A            EVTNAME
+     A          K EVTNAME
+"#[1..]
+            .to_string();
+        fs::write(dir.join("cowevtl2.lfdds"), lf).unwrap();
+        let input = r#"
+     FCowEvtL2  IF   E           K DISK
+      /free
+         x = evt
+      /end-free
+"#[1..]
+            .to_string();
+        let source = dir.join("zevt.rpgle");
+        fs::write(&source, &input).unwrap();
+        let found = complete_rpgle(&input, Some(&source), 2, 16);
+        let items = found
+            .items
+            .iter()
+            .map(|x| (x.label.as_str(), x.kind.as_str(), x.detail.as_str()))
+            .collect::<Vec<(&str, &str, &str)>>();
+        assert_eq!(items, vec![("EVTNAME", "field", "EVTFMT 20A")]);
+    }
+}
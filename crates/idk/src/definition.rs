@@ -0,0 +1,195 @@
+use crate::{Lang, Manifest};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Definition {
+    pub name: String,
+    pub start_line: usize,
+    pub start_char: usize,
+    pub end_line: usize,
+    pub end_char: usize,
+    // None when the definition is in the searched source itself
+    pub uri: Option<String>,
+}
+
+type Range = ((usize, usize), (usize, usize));
+
+fn query(lang: Lang, input: &str, pattern: &str) -> Option<Range> {
+    match lang {
+        Lang::Rpgle => {
+            let cst = rpgle_parser::parse_cst(input).ok()?;
+            let ast = rpgle_parser::parse_ast(&cst).ok()?;
+            ast.try_get_definition(pattern)
+        }
+        Lang::Pfdds => {
+            let cst = dds_parser::pfdds::CST::try_from(input).ok()?;
+            let ast = dds_parser::pfdds::AST::from(&cst);
+            let def = dds_parser::pfdds::query_definition(&ast, pattern)?;
+            Some(((def.start.row, def.start.col), (def.end.row, def.end.col)))
+        }
+        Lang::Lfdds => {
+            let cst = dds_parser::lfdds::CST::try_from(input).ok()?;
+            let ast = dds_parser::lfdds::AST::from(&cst);
+            let def = dds_parser::lfdds::query_definition(&ast, pattern)?;
+            Some(((def.start.row, def.start.col), (def.end.row, def.end.col)))
+        }
+        Lang::Dspfdds => {
+            let cst = dds_parser::dspfdds::CST::try_from(input).ok()?;
+            let ast = dds_parser::dspfdds::AST::from(&cst);
+            let def = dds_parser::dspfdds::query_definition(&ast, pattern)?;
+            Some(((def.start.row, def.start.col), (def.end.row, def.end.col)))
+        }
+        Lang::Prtfdds => {
+            let cst = dds_parser::prtfdds::CST::try_from(input).ok()?;
+            let ast = dds_parser::prtfdds::AST::from(&cst);
+            let def = dds_parser::prtfdds::query_definition(&ast, pattern)?;
+            Some(((def.start.row, def.start.col), (def.end.row, def.end.col)))
+        }
+        Lang::Sqlddl => {
+            let cst = dds_parser::sqlddl::CST::try_from(input).ok()?;
+            let ast = dds_parser::sqlddl::AST::from(&cst);
+            let def = dds_parser::sqlddl::query_definition(&ast, pattern)?;
+            Some(((def.start.row, def.start.col), (def.end.row, def.end.col)))
+        }
+    }
+}
+
+/* Where `pattern` is defined. The source itself is searched first, then every
+ * other rpgle, dds and sql source in the project manifest found from `source`. A
+ * definition on `current_row` doesn't count, that's where the lookup started.
+ */
+pub fn find_definition(
+    lang: Lang,
+    input: &str,
+    pattern: &str,
+    source: Option<&Path>,
+    current_row: Option<usize>,
+) -> Option<Definition> {
+    let found = |range: Range, uri: Option<String>| {
+        let ((strow, stcol), (endrow, endcol)) = range;
+        let def = Definition {
+            name: pattern.to_string(),
+            start_line: strow,
+            start_char: stcol,
+            end_line: endrow,
+            end_char: endcol,
+            uri,
+        };
+        if env::var("DEBUG").is_ok() {
+            let _ = std::fs::write("/tmp/getdef.txt", format!("{:#?}", def));
+        }
+        def
+    };
+    if let Some(range) = query(lang, input, pattern) {
+        if Some(range.0.0) != current_row {
+            return Some(found(range, None));
+        }
+    }
+    let source = source?;
+    let current_file = source
+        .file_name()
+        .and_then(|x| x.to_str())
+        .map(|x| x.to_uppercase())
+        .unwrap_or_default();
+    let mut sources = Manifest::find(source)?
+        .get_source_files()?
+        .into_iter()
+        .filter(|x| !x.to_uppercase().ends_with(&current_file))
+        .collect::<Vec<String>>();
+    sources.sort_by_key(
+        |x| match x.to_uppercase().contains(&pattern.to_uppercase()) {
+            true => 0,
+            false => 1,
+        },
+    );
+    sources.into_iter().find_map(|source| {
+        let lang = Lang::from_path(Path::new(&source))?;
+        let input = fs::read_to_string(&source).ok()?;
+        let range = query(lang, &input, pattern)?;
+        Some(found(range, Some(format!("file://{}", source))))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_definition_in_display_file() {
+        let dir = std::env::temp_dir().join("idk-definition-dspf-test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("manifest.json"),
+            r#"["cusinq.dspfdds", "cusinq.rpgle"]"#,
+        )
+        .unwrap();
+        let dds = r#"
+     A          R PROMPT
+     A                                  3  2'Customer:'
+     A            CUSNO          7S 0B  3 12
+"#[1..]
+            .to_string();
+        fs::write(dir.join("cusinq.dspfdds"), dds).unwrap();
+        let input = r#"
+     FCusInq    CF   E             WORKSTN
+      /free
+         Exfmt Prompt;
+         x = CusNo;
+      /end-free
+"#[1..]
+            .to_string();
+        let source = dir.join("cusinq.rpgle");
+        fs::write(&source, &input).unwrap();
+        let def = find_definition(Lang::Rpgle, &input, "CusNo", Some(&source), None).unwrap();
+        assert!(def.uri.unwrap().ends_with("cusinq.dspfdds"));
+        assert_eq!((def.start_line, def.start_char), (2, 18));
+    }
+
+    #[test]
+    fn test_find_definition_in_sql_table() {
+        let dir = std::env::temp_dir().join("idk-definition-sql-test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("manifest.json"),
+            r#"["cowevt.table", "zevt.rpgle"]"#,
+        )
+        .unwrap();
+        let sql = r#"
+CREATE TABLE COW_EVENTS FOR SYSTEM NAME COWEVT (
+    EVENT_ID FOR COLUMN EVTID DECIMAL(9, 0) NOT NULL,
+    EVENT_DATE FOR COLUMN EDAT DATE
+) RCDFMT EVTFMT;
+"#[1..]
+            .to_string();
+        fs::write(dir.join("cowevt.table"), sql).unwrap();
+        let input = r#"
+     FCowEvt    IF   E           K DISK
+      /free
+         Read EvtFmt;
+         x = EvtId;
+      /end-free
+"#[1..]
+            .to_string();
+        let source = dir.join("zevt.rpgle");
+        fs::write(&source, &input).unwrap();
+        let def = find_definition(Lang::Rpgle, &input, "EvtId", Some(&source), None).unwrap();
+        assert!(def.uri.unwrap().ends_with("cowevt.table"));
+        assert_eq!((def.start_line, def.start_char), (1, 24));
+        let manifest = Manifest::find(&source).unwrap();
+        assert_eq!(manifest.get_record_formats()["COWEVT"], vec!["EVTFMT"]);
+        assert_eq!(
+            manifest.get_fields()["COWEVT"],
+            vec![
+                (
+                    "EVTFMT".to_string(),
+                    "EVTID".to_string(),
+                    "9P 0".to_string()
+                ),
+                ("EVTFMT".to_string(), "EDAT".to_string(), "L".to_string()),
+            ]
+        );
+    }
+}
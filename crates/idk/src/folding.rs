@@ -0,0 +1,149 @@
+use crate::Lang;
+use serde::{Deserialize, Serialize};
+
+// A foldable range of either language, rows are zero based and inclusive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fold {
+    pub start_row: usize,
+    pub end_row: usize,
+    // comment, region or block
+    pub kind: String,
+}
+
+// Foldable ranges of a source member, empty when it can't be parsed
+pub fn folds(lang: Lang, input: &str) -> Vec<Fold> {
+    match lang {
+        Lang::Rpgle => match rpgle_parser::parse_cst(input) {
+            Ok(cst) => rpgle_parser::query_folds(&cst)
+                .iter()
+                .map(|f| {
+                    use rpgle_parser::FoldKind;
+                    let kind = match f.kind {
+                        FoldKind::Comment => "comment",
+                        FoldKind::Region => "region",
+                        FoldKind::Block => "block",
+                    };
+                    Fold {
+                        start_row: f.start_row,
+                        end_row: f.end_row,
+                        kind: kind.to_string(),
+                    }
+                })
+                .collect(),
+            Err(_) => vec![],
+        },
+        Lang::Pfdds => match dds_parser::pfdds::CST::try_from(input) {
+            Ok(cst) => {
+                let ast = dds_parser::pfdds::AST::from(&cst);
+                dds_parser::pfdds::query_folds(&ast)
+                    .iter()
+                    .map(|f| Fold {
+                        start_row: f.start_row,
+                        end_row: f.end_row,
+                        kind: "region".to_string(),
+                    })
+                    .collect()
+            }
+            Err(_) => vec![],
+        },
+        Lang::Lfdds => match dds_parser::lfdds::CST::try_from(input) {
+            Ok(cst) => {
+                let ast = dds_parser::lfdds::AST::from(&cst);
+                dds_parser::lfdds::query_folds(&ast)
+                    .iter()
+                    .map(|f| Fold {
+                        start_row: f.start_row,
+                        end_row: f.end_row,
+                        kind: "region".to_string(),
+                    })
+                    .collect()
+            }
+            Err(_) => vec![],
+        },
+        Lang::Dspfdds => match dds_parser::dspfdds::CST::try_from(input) {
+            Ok(cst) => {
+                let ast = dds_parser::dspfdds::AST::from(&cst);
+                dds_parser::dspfdds::query_folds(&ast)
+                    .iter()
+                    .map(|f| Fold {
+                        start_row: f.start_row,
+                        end_row: f.end_row,
+                        kind: "region".to_string(),
+                    })
+                    .collect()
+            }
+            Err(_) => vec![],
+        },
+        Lang::Prtfdds => match dds_parser::prtfdds::CST::try_from(input) {
+            Ok(cst) => {
+                let ast = dds_parser::prtfdds::AST::from(&cst);
+                dds_parser::prtfdds::query_folds(&ast)
+                    .iter()
+                    .map(|f| Fold {
+                        start_row: f.start_row,
+                        end_row: f.end_row,
+                        kind: "region".to_string(),
+                    })
+                    .collect()
+            }
+            Err(_) => vec![],
+        },
+        Lang::Sqlddl => match dds_parser::sqlddl::CST::try_from(input) {
+            Ok(cst) => {
+                let ast = dds_parser::sqlddl::AST::from(&cst);
+                dds_parser::sqlddl::query_folds(&ast)
+                    .iter()
+                    .map(|f| Fold {
+                        start_row: f.start_row,
+                        end_row: f.end_row,
+                        kind: "region".to_string(),
+                    })
+                    .collect()
+            }
+            Err(_) => vec![],
+        },
+    }
+}
+
+/* The 'foldexpr' value of every line: the number of folds it sits in, with a
+ * `>` on the first line of a fold so folds that touch stay apart.
+ */
+pub fn fold_levels(folds: &[Fold], line_count: usize) -> Vec<String> {
+    (0..line_count)
+        .map(|row| {
+            let level = folds
+                .iter()
+                .filter(|f| f.start_row <= row && row <= f.end_row)
+                .count();
+            match folds.iter().any(|f| f.start_row == row) {
+                true => format!(">{}", level),
+                false => level.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_levels() {
+        let input = r#"
+      * Banner
+      * ---------
+     C     $Init         BEGSR
+     C                   IF        *in99
+     C                   EVAL      *in99 = *off
+     C                   ENDIF
+     C                   ENDSR
+     C                   RETURN
+"#[1..]
+            .to_string();
+        let folds = folds(Lang::Rpgle, &input);
+        assert_eq!(
+            fold_levels(&folds, input.lines().count()),
+            vec![">1", "1", ">1", ">2", "2", "2", "1", "0"]
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use rpgle_parser;
-use rpgle_parser::{FieldResult, Op, Spec, AST};
+use rpgle_parser::{AST, FieldResult, Op, Spec};
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 
@@ -123,7 +123,9 @@ digraph g {{
 }
 impl From<Model> for IdkGraph {
     fn from(value: Model) -> Self {
-        let _ = std::fs::write("/tmp/model.txt", format!("{:#?}", &value));
+        if std::env::var("DEBUG").is_ok() {
+            let _ = std::fs::write("/tmp/model.txt", format!("{:#?}", &value));
+        }
         let mut graph = Self { components: vec![] };
         // root node
         graph.components.push(Component::Node(Node {
@@ -0,0 +1,189 @@
+use crate::Manifest;
+use dds_parser;
+use rpgle_parser;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::path::Path;
+
+#[derive(Serialize, Deserialize)]
+pub struct HighlightMeta {
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    pub hl_group: String,
+    pub src: String,
+}
+impl fmt::Display for HighlightMeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = format!(
+            "({}, {}) -> ({}, {}): {}, src={}",
+            self.start_row, self.start_col, self.end_row, self.end_col, self.hl_group, self.src,
+        );
+        write!(f, "{}", s)
+    }
+}
+impl From<((usize, usize), (usize, usize), &str, &str)> for HighlightMeta {
+    fn from(value: ((usize, usize), (usize, usize), &str, &str)) -> Self {
+        Self {
+            start_row: value.0.0,
+            start_col: value.0.1,
+            end_row: value.1.0,
+            end_col: value.1.1,
+            hl_group: value.2.to_string(),
+            src: value.3.to_string(),
+        }
+    }
+}
+
+/* Names classified by what they refer to, painted last so they win over the
+ * generic identifier group. Record formats and fields of externally described
+ * files need a project manifest near `source`.
+ */
+fn semantic_highlights(cst: &rpgle_parser::CST, source: Option<&Path>) -> Vec<HighlightMeta> {
+    let manifest = source.and_then(Manifest::find);
+    let formats = manifest
+        .as_ref()
+        .map(|man| man.get_record_formats())
+        .unwrap_or_default();
+    let fields = manifest
+        .map(|man| {
+            man.get_fields()
+                .into_iter()
+                .map(|(file, fields)| (file, fields.into_iter().map(|x| x.1).collect()))
+                .collect()
+        })
+        .unwrap_or_default();
+    rpgle_parser::query_semantic_highlights(cst, &formats, &fields)
+        .into_iter()
+        .map(|(span, kind)| {
+            let start = (span.start.row, span.start.col);
+            let end = (span.end.row, span.end.col);
+            HighlightMeta::from((start, end, kind.hl_group(), "SEM"))
+        })
+        .collect()
+}
+
+// main
+pub fn highlight_rpgle(txt: &str, source: Option<&Path>) -> Vec<HighlightMeta> {
+    if let Ok(cst) = rpgle_parser::parse_cst(txt) {
+        if env::var("DEBUG").is_ok() {
+            let _ = std::fs::write("/tmp/cst.txt", format!("{:#?}", &cst));
+        }
+        let mut out = cst
+            .get_highlights()
+            .into_iter()
+            .map(|tup| HighlightMeta::from((tup.0.0, tup.0.1, tup.1.as_str(), "CST")))
+            .collect::<Vec<HighlightMeta>>();
+
+        if let Ok(ast) = rpgle_parser::parse_ast(&cst) {
+            if env::var("DEBUG").is_ok() {
+                let _ = std::fs::write("/tmp/ast.txt", format!("{:#?}", &ast));
+            }
+            out.append(
+                &mut ast
+                    .get_highlights()
+                    .into_iter()
+                    .map(|tup| HighlightMeta::from((tup.0.0, tup.0.1, tup.1.as_str(), "AST")))
+                    .collect::<Vec<HighlightMeta>>(),
+            );
+        }
+        out.append(&mut semantic_highlights(&cst, source));
+        out
+    } else {
+        vec![]
+    }
+}
+
+pub fn highlight_pfdds(txt: &str) -> Vec<HighlightMeta> {
+    if let Ok(cst) = dds_parser::pfdds::CST::try_from(txt) {
+        if env::var("DEBUG").is_ok() {
+            let _ = std::fs::write("/tmp/cst.txt", format!("{:#?}", &cst));
+        }
+        let mut out = dds_parser::pfdds::highlight_cst(&cst)
+            .into_iter()
+            .map(|tup| HighlightMeta::from((tup.0, tup.1, tup.2.as_str(), "CST")))
+            .collect::<Vec<HighlightMeta>>();
+        let ast = dds_parser::pfdds::AST::from(&cst);
+        if env::var("DEBUG").is_ok() {
+            let _ = std::fs::write("/tmp/ast.txt", format!("{:#?}", &ast));
+        }
+        out.append(
+            &mut dds_parser::pfdds::highlight_ast(ast)
+                .into_iter()
+                .map(|tup| HighlightMeta::from((tup.0, tup.1, tup.2.as_str(), "AST")))
+                .collect::<Vec<HighlightMeta>>(),
+        );
+        out
+    } else {
+        vec![]
+    }
+}
+
+pub fn highlight_lfdds(txt: &str) -> Vec<HighlightMeta> {
+    if let Ok(cst) = dds_parser::lfdds::CST::try_from(txt) {
+        let mut out = dds_parser::lfdds::highlight_cst(&cst)
+            .into_iter()
+            .map(|tup| HighlightMeta::from((tup.0, tup.1, tup.2.as_str(), "CST")))
+            .collect::<Vec<HighlightMeta>>();
+        let ast = dds_parser::lfdds::AST::from(&cst);
+        out.append(
+            &mut dds_parser::lfdds::highlight_ast(ast)
+                .into_iter()
+                .map(|tup| HighlightMeta::from((tup.0, tup.1, tup.2.as_str(), "AST")))
+                .collect::<Vec<HighlightMeta>>(),
+        );
+        out
+    } else {
+        vec![]
+    }
+}
+
+pub fn highlight_dspfdds(txt: &str) -> Vec<HighlightMeta> {
+    if let Ok(cst) = dds_parser::dspfdds::CST::try_from(txt) {
+        let mut out = dds_parser::dspfdds::highlight_cst(&cst)
+            .into_iter()
+            .map(|tup| HighlightMeta::from((tup.0, tup.1, tup.2.as_str(), "CST")))
+            .collect::<Vec<HighlightMeta>>();
+        let ast = dds_parser::dspfdds::AST::from(&cst);
+        out.append(
+            &mut dds_parser::dspfdds::highlight_ast(ast)
+                .into_iter()
+                .map(|tup| HighlightMeta::from((tup.0, tup.1, tup.2.as_str(), "AST")))
+                .collect::<Vec<HighlightMeta>>(),
+        );
+        out
+    } else {
+        vec![]
+    }
+}
+
+pub fn highlight_prtfdds(txt: &str) -> Vec<HighlightMeta> {
+    if let Ok(cst) = dds_parser::prtfdds::CST::try_from(txt) {
+        let mut out = dds_parser::prtfdds::highlight_cst(&cst)
+            .into_iter()
+            .map(|tup| HighlightMeta::from((tup.0, tup.1, tup.2.as_str(), "CST")))
+            .collect::<Vec<HighlightMeta>>();
+        let ast = dds_parser::prtfdds::AST::from(&cst);
+        out.append(
+            &mut dds_parser::prtfdds::highlight_ast(ast)
+                .into_iter()
+                .map(|tup| HighlightMeta::from((tup.0, tup.1, tup.2.as_str(), "AST")))
+                .collect::<Vec<HighlightMeta>>(),
+        );
+        out
+    } else {
+        vec![]
+    }
+}
+
+pub fn highlight_sqlddl(txt: &str) -> Vec<HighlightMeta> {
+    match dds_parser::sqlddl::CST::try_from(txt) {
+        Ok(cst) => dds_parser::sqlddl::highlight_cst(&cst)
+            .into_iter()
+            .map(|tup| HighlightMeta::from((tup.0, tup.1, tup.2.as_str(), "CST")))
+            .collect(),
+        Err(_) => vec![],
+    }
+}
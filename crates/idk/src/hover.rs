@@ -0,0 +1,130 @@
+use crate::{Lang, Manifest};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// Markdown docs for the word under the cursor, on a single row
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hover {
+    pub row: usize,
+    pub start_col: usize,
+    pub end_col: usize,
+    pub contents: String,
+}
+
+/* The buffer positions of the field, or the layout of the record format,
+ * under the cursor of a physical file. References are followed through the
+ * physical files of the project manifest found from `source`.
+ */
+fn hover_pfdds(input: &str, source: Option<&Path>, row: usize, col: usize) -> Option<Hover> {
+    let cst = dds_parser::pfdds::CST::try_from(input).ok()?;
+    let ast = dds_parser::pfdds::AST::from(&cst);
+    let physicals = source
+        .and_then(Manifest::find)
+        .map(|man| man.get_physicals())
+        .unwrap_or_default();
+    let file = source
+        .and_then(|x| x.file_stem())
+        .and_then(|x| x.to_str())
+        .unwrap_or_default();
+    let known = |x: Option<usize>| x.map(|x| x.to_string()).unwrap_or("?".to_string());
+    // the name without the blanks after it
+    let at = |start: (usize, usize), name: &str| {
+        let end_col = start.1 + name.chars().count();
+        match start.0 == row && (start.1..end_col).contains(&col) {
+            true => Some((start.1, end_col)),
+            false => None,
+        }
+    };
+    for layout in dds_parser::pfdds::query_record_layouts(file, &ast, &physicals) {
+        let start = (layout.span.start.row, layout.span.start.col);
+        if let Some((start_col, end_col)) = at(start, &layout.format) {
+            let contents = format!(
+                "`{}` {} bytes\n\n```\n{}```",
+                layout.format,
+                known(layout.length),
+                layout.table()
+            );
+            return Some(Hover {
+                row,
+                start_col,
+                end_col,
+                contents,
+            });
+        }
+        for fld in layout.fields.iter() {
+            let start = (fld.span.start.row, fld.span.start.col);
+            let Some((start_col, end_col)) = at(start, &fld.name) else {
+                continue;
+            };
+            let prefix = match fld.varlen {
+                true => ", the 2 byte VARLEN prefix included",
+                false => "",
+            };
+            let contents = format!(
+                "`{}` {}\n\nBuffer positions {} to {}, {} bytes{}",
+                fld.name,
+                fld.detail,
+                known(fld.start),
+                known(fld.end),
+                known(fld.bytes),
+                prefix
+            );
+            return Some(Hover {
+                row,
+                start_col,
+                end_col,
+                contents,
+            });
+        }
+    }
+    None
+}
+
+/* Hover docs at a zero based `row` and `col`, the built-in functions and
+ * opcodes of rpgle and the buffer layout of physical files
+ */
+pub fn hover(
+    lang: Lang,
+    input: &str,
+    source: Option<&Path>,
+    row: usize,
+    col: usize,
+) -> Option<Hover> {
+    match lang {
+        Lang::Rpgle => {
+            let hover = rpgle_parser::query_hover(input, row, col)?;
+            Some(Hover {
+                row: hover.span.start.row,
+                start_col: hover.span.start.col,
+                end_col: hover.span.end.col,
+                contents: hover.contents,
+            })
+        }
+        Lang::Pfdds => hover_pfdds(input, source, row, col),
+        Lang::Lfdds | Lang::Dspfdds | Lang::Prtfdds | Lang::Sqlddl => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hover_pfdds() {
+        let input = r#"
+     A          R EVTFMT
+     A            EVTID          9P 0
+     A            EVTNOTE      200A         VARLEN
+"#[1..]
+            .to_string();
+        let found = hover(Lang::Pfdds, &input, None, 2, 19).unwrap();
+        assert_eq!((found.row, found.start_col, found.end_col), (2, 18, 25));
+        assert_eq!(
+            found.contents,
+            "`EVTNOTE` 200A\n\nBuffer positions 6 to 207, 202 bytes, the 2 byte VARLEN prefix included"
+        );
+        let found = hover(Lang::Pfdds, &input, None, 0, 20).unwrap();
+        assert!(found.contents.starts_with("`EVTFMT` 207 bytes"));
+        assert_eq!(hover(Lang::Pfdds, &input, None, 1, 24), None);
+    }
+}
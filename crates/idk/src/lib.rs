@@ -0,0 +1,106 @@
+mod actions;
+mod completion;
+mod definition;
+mod folding;
+mod graph;
+mod highlight;
+mod hover;
+mod lint;
+mod manifest;
+mod outline;
+mod signature;
+
+use std::path::Path;
+
+pub use actions::{CodeAction, Edit, code_actions};
+pub use completion::{CompletionItem, Completions, complete_rpgle};
+pub use definition::{Definition, find_definition};
+pub use folding::{Fold, fold_levels, folds};
+pub use graph::{IRenderable, IdkGraph};
+pub use highlight::{
+    HighlightMeta, highlight_dspfdds, highlight_lfdds, highlight_pfdds, highlight_prtfdds,
+    highlight_rpgle, highlight_sqlddl,
+};
+pub use hover::{Hover, hover};
+pub use lint::{lint_dds, lint_rpgle};
+pub use manifest::Manifest;
+pub use outline::{Outline, outline};
+pub use signature::{SignatureHelp, signature_help};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lang {
+    Rpgle,
+    Pfdds,
+    Lfdds,
+    Dspfdds,
+    Prtfdds,
+    Sqlddl,
+}
+
+impl Lang {
+    // by file extension, ex: zevt.rpgle, cowevt.pfdds, cowevtl2.lfdds, cusinq.dspfdds, cowevt.table
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        Self::from_name(&ext)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "rpgle" | "sqlrpgle" | "rpg" => Some(Self::Rpgle),
+            "pfdds" | "dds" => Some(Self::Pfdds),
+            "lfdds" | "lf" => Some(Self::Lfdds),
+            "dspfdds" | "dspf" => Some(Self::Dspfdds),
+            "prtfdds" | "prtf" => Some(Self::Prtfdds),
+            "sqlddl" | "sql" | "table" | "view" | "index" => Some(Self::Sqlddl),
+            _ => None,
+        }
+    }
+
+    // every source line is exactly this wide, None for free form SQL
+    pub fn width(&self) -> Option<usize> {
+        match self {
+            Self::Rpgle => Some(100),
+            Self::Pfdds | Self::Lfdds | Self::Dspfdds | Self::Prtfdds => Some(80),
+            Self::Sqlddl => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lang_from_path() {
+        assert_eq!(
+            Lang::from_path(Path::new("src/ZEVT.RPGLE")),
+            Some(Lang::Rpgle)
+        );
+        assert_eq!(
+            Lang::from_path(Path::new("zevt.sqlrpgle")),
+            Some(Lang::Rpgle)
+        );
+        assert_eq!(
+            Lang::from_path(Path::new("cowevt.pfdds")),
+            Some(Lang::Pfdds)
+        );
+        assert_eq!(
+            Lang::from_path(Path::new("cowevtl2.lfdds")),
+            Some(Lang::Lfdds)
+        );
+        assert_eq!(
+            Lang::from_path(Path::new("cusinq.dspf")),
+            Some(Lang::Dspfdds)
+        );
+        assert_eq!(
+            Lang::from_path(Path::new("cowrpt.prtf")),
+            Some(Lang::Prtfdds)
+        );
+        assert_eq!(
+            Lang::from_path(Path::new("cowevt.table")),
+            Some(Lang::Sqlddl)
+        );
+        assert_eq!(Lang::from_path(Path::new("qcmdexc.clle")), None);
+        assert_eq!(Lang::from_path(Path::new("Makefile")), None);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{Lang, Manifest};
+use std::path::Path;
+
+/* Lint rpgle source with the `idk-lint.json` config and the pfdds record
+ * formats of the project manifest found from `source`, or the defaults when
+ * there isn't one.
+ */
+pub fn lint_rpgle(input: &str, source: Option<&Path>) -> Vec<rpgle_parser::Diagnostic> {
+    let Ok(cst) = rpgle_parser::parse_cst(input) else {
+        return vec![];
+    };
+    let manifest = source.and_then(Manifest::find);
+    let formats = manifest
+        .as_ref()
+        .map(|man| man.get_record_formats())
+        .unwrap_or_default();
+    let config = manifest
+        .and_then(|man| man.get_lint_config())
+        .unwrap_or_default();
+    rpgle_parser::Linter::new(config).lint(&cst, &formats)
+}
+
+/* Lint dds source, the keywords of physical and logical files that are
+ * unknown or have the wrong parameters, what a physical file's columns allow
+ * but the system doesn't, and the REF and REFFLD references that can't be
+ * followed through the physical files of the project manifest. Without a
+ * manifest there's nothing to follow them through.
+ */
+pub fn lint_dds(input: &str, lang: Lang, source: Option<&Path>) -> Vec<dds_parser::Diagnostic> {
+    let manifest = source.and_then(Manifest::find);
+    let file = source
+        .and_then(|x| x.file_stem())
+        .and_then(|x| x.to_str())
+        .unwrap_or_default();
+    let physicals = manifest.map(|man| man.get_physicals());
+    let mut out = vec![];
+    match lang {
+        Lang::Pfdds => {
+            if let Ok(cst) = dds_parser::pfdds::CST::try_from(input) {
+                let ast = dds_parser::pfdds::AST::from(&cst);
+                out.extend(dds_parser::pfdds::query_keywords(&ast).diagnostics);
+                out.extend(dds_parser::pfdds::validate(&ast));
+                if let Some(physicals) = &physicals {
+                    let refs = dds_parser::pfdds::resolve_references(file, &ast, physicals);
+                    out.extend(refs.diagnostics);
+                }
+            }
+        }
+        Lang::Lfdds => {
+            if let Ok(cst) = dds_parser::lfdds::CST::try_from(input) {
+                let ast = dds_parser::lfdds::AST::from(&cst);
+                out.extend(dds_parser::lfdds::query_keywords(&ast).diagnostics);
+            }
+        }
+        Lang::Dspfdds => {
+            if let (Ok(cst), Some(physicals)) =
+                (dds_parser::dspfdds::CST::try_from(input), &physicals)
+            {
+                let ast = dds_parser::dspfdds::AST::from(&cst);
+                let refs = dds_parser::dspfdds::resolve_references(file, &ast, physicals);
+                out.extend(refs.diagnostics);
+            }
+        }
+        Lang::Prtfdds => {
+            if let (Ok(cst), Some(physicals)) =
+                (dds_parser::prtfdds::CST::try_from(input), &physicals)
+            {
+                let ast = dds_parser::prtfdds::AST::from(&cst);
+                let refs = dds_parser::prtfdds::resolve_references(file, &ast, physicals);
+                out.extend(refs.diagnostics);
+            }
+        }
+        Lang::Rpgle | Lang::Sqlddl => {}
+    }
+    out.sort_by_key(|d| (d.span.start.row, d.span.start.col));
+    out
+}
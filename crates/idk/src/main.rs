@@ -0,0 +1,486 @@
+use idk::{IRenderable, IdkGraph, Lang, Manifest};
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::{env, fs};
+
+const USAGE: &str = r#"idk v0.11.4
+
+IBM i development tools for the command line
+
+Usage:
+  idk parse [--ast] [file]     print the CST, or the AST, as json
+  idk graph [file]             print the subroutine and program call graph as a dot file
+  idk def <name> [file]        print where <name> is defined as json
+  idk highlight [file]         print highlight spans as json
+  idk outline [file]           print the outline, procedures, subroutines, record formats, ..., as json
+  idk fmt [--check] [file]     format source, rpgle to 100 columns and dds to 80, sql as is
+  idk preview <format> [file]  print a display file record format as it would look on screen,
+                               or printer file writes, ex: HEADER,DETAIL*3,TOTAL, as a report
+  idk sql [file]               print the Db2 for i SQL of a physical or logical file
+  idk lint [file...]           print the diagnostics of each source, exit 1 if any is an error
+
+Args:
+  file (string): OPTIONAL - the source file. If not supplied, or `-`, stdin is assumed
+
+Options:
+  --lang <rpgle|dds|lf|dspf|prtf|sql>  the source language, detected from the file extension otherwise
+  --check                              fmt only, exit 1 instead of printing when the source isn't formatted
+  --on <01,31,...>                     preview only, the indicators that are on
+  --ansi                               preview only, display files with colors and attributes
+  --width <132>                        preview only, the printer page width
+  --overflow <60>                      preview only, the printer overflow line
+  -h, --help                           print this message
+
+Exit codes:
+  0 = ok
+  1 = error
+
+Example:
+  $ DSN=AS400 idk-get "ZWAITE/QRPGLESRC(ZEVT)" | idk fmt --lang rpgle
+"#;
+
+#[derive(Debug)]
+struct Args {
+    command: String,
+    positional: Vec<String>,
+    lang: Option<Lang>,
+    ast: bool,
+    check: bool,
+    on: Vec<u8>,
+    ansi: bool,
+    width: usize,
+    overflow: usize,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            positional: vec![],
+            lang: None,
+            ast: false,
+            check: false,
+            on: vec![],
+            ansi: false,
+            width: 132,
+            overflow: 60,
+        }
+    }
+}
+
+impl Args {
+    fn parse(mut raw: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut args = Self::default();
+        while let Some(arg) = raw.next() {
+            match arg.as_str() {
+                "--lang" => {
+                    let name = raw.next().ok_or("--lang needs a value")?;
+                    args.lang =
+                        Some(Lang::from_name(&name).ok_or(format!("Unknown language: {}", name))?);
+                }
+                "--ast" => args.ast = true,
+                "--check" => args.check = true,
+                "--on" => {
+                    let value = raw.next().ok_or("--on needs a value")?;
+                    args.on = value
+                        .split(',')
+                        .map(|x| {
+                            x.trim()
+                                .parse::<u8>()
+                                .map_err(|_| format!("Not an indicator: {}", x))
+                        })
+                        .collect::<Result<Vec<u8>, String>>()?;
+                }
+                "--ansi" => args.ansi = true,
+                "--width" | "--overflow" => {
+                    let value = raw.next().ok_or(format!("{} needs a value", arg))?;
+                    let value = value
+                        .parse::<usize>()
+                        .map_err(|_| format!("Not a number: {}", value))?;
+                    match arg.as_str() {
+                        "--width" => args.width = value,
+                        _ => args.overflow = value,
+                    }
+                }
+                "-" => args.positional.push(arg),
+                flag if flag.starts_with('-') => return Err(format!("Unknown option: {}", flag)),
+                _ if args.command.is_empty() => args.command = arg,
+                _ => args.positional.push(arg),
+            }
+        }
+        Ok(args)
+    }
+}
+
+struct Source {
+    path: Option<PathBuf>,
+    lang: Lang,
+    text: String,
+}
+
+fn read_source(file: Option<&String>, lang: Option<Lang>) -> Result<Source, String> {
+    let path = file.filter(|x| *x != "-").map(PathBuf::from);
+    let text = match &path {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read {}: {}", path.display(), e))?,
+        None => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("Unable to read stdin: {}", e))?;
+            text
+        }
+    };
+    let lang = match (lang, &path) {
+        (Some(lang), _) => lang,
+        (None, Some(path)) => Lang::from_path(path).ok_or(format!(
+            "Unable to detect the language of {}, use --lang",
+            path.display()
+        ))?,
+        (None, None) => Lang::Rpgle,
+    };
+    Ok(Source { path, lang, text })
+}
+
+// a closed pipe, ex: `idk parse | head`, isn't an error
+fn output(text: &str) {
+    let _ = io::stdout().write_all(text.as_bytes());
+}
+
+fn json<T: serde::Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| e.to_string())
+}
+
+fn parse(source: &Source, ast: bool) -> Result<String, String> {
+    match source.lang {
+        Lang::Rpgle => {
+            let cst = rpgle_parser::parse_cst(&source.text)
+                .map_err(|e| format!("Unable to parse CST: {:?}", e))?;
+            match ast {
+                true => json(
+                    &rpgle_parser::parse_ast(&cst)
+                        .map_err(|e| format!("Unable to parse AST: {:?}", e))?,
+                ),
+                false => json(&cst),
+            }
+        }
+        Lang::Pfdds => {
+            let cst = dds_parser::pfdds::CST::try_from(source.text.as_str())
+                .map_err(|e| format!("Unable to parse CST: {:?}", e))?;
+            match ast {
+                true => json(&dds_parser::pfdds::AST::from(&cst)),
+                false => json(&cst),
+            }
+        }
+        Lang::Lfdds => {
+            let cst = dds_parser::lfdds::CST::try_from(source.text.as_str())
+                .map_err(|e| format!("Unable to parse CST: {:?}", e))?;
+            match ast {
+                true => json(&dds_parser::lfdds::AST::from(&cst)),
+                false => json(&cst),
+            }
+        }
+        Lang::Dspfdds => {
+            let cst = dds_parser::dspfdds::CST::try_from(source.text.as_str())
+                .map_err(|e| format!("Unable to parse CST: {:?}", e))?;
+            match ast {
+                true => json(&dds_parser::dspfdds::AST::from(&cst)),
+                false => json(&cst),
+            }
+        }
+        Lang::Prtfdds => {
+            let cst = dds_parser::prtfdds::CST::try_from(source.text.as_str())
+                .map_err(|e| format!("Unable to parse CST: {:?}", e))?;
+            match ast {
+                true => json(&dds_parser::prtfdds::AST::from(&cst)),
+                false => json(&cst),
+            }
+        }
+        Lang::Sqlddl => {
+            let cst = dds_parser::sqlddl::CST::try_from(source.text.as_str())
+                .map_err(|e| format!("Unable to parse CST: {:?}", e))?;
+            match ast {
+                true => json(&dds_parser::sqlddl::AST::from(&cst)),
+                false => json(&cst),
+            }
+        }
+    }
+}
+
+fn graph(source: &Source) -> Result<String, String> {
+    if source.lang != Lang::Rpgle {
+        return Err("Call graphs are only available for rpgle sources".to_string());
+    }
+    let cst = rpgle_parser::parse_cst(&source.text)
+        .map_err(|e| format!("Unable to parse CST: {:?}", e))?;
+    let ast = rpgle_parser::parse_ast(&cst).map_err(|e| format!("Unable to parse AST: {:?}", e))?;
+    Ok(IdkGraph::from(&ast).render())
+}
+
+fn def(source: &Source, pattern: &str) -> Result<String, String> {
+    let found = idk::find_definition(
+        source.lang,
+        &source.text,
+        pattern,
+        source.path.as_deref(),
+        None,
+    );
+    match found {
+        Some(def) => json(&def),
+        None => Err(format!("No definition found for {}", pattern)),
+    }
+}
+
+fn highlight(source: &Source) -> Result<String, String> {
+    match source.lang {
+        Lang::Rpgle => json(&idk::highlight_rpgle(&source.text, source.path.as_deref())),
+        Lang::Pfdds => json(&idk::highlight_pfdds(&source.text)),
+        Lang::Lfdds => json(&idk::highlight_lfdds(&source.text)),
+        Lang::Dspfdds => json(&idk::highlight_dspfdds(&source.text)),
+        Lang::Prtfdds => json(&idk::highlight_prtfdds(&source.text)),
+        Lang::Sqlddl => json(&idk::highlight_sqlddl(&source.text)),
+    }
+}
+
+fn preview_screen(source: &Source, format: &str, args: &Args) -> Result<String, String> {
+    let cst = dds_parser::dspfdds::CST::try_from(source.text.as_str())
+        .map_err(|e| format!("Unable to parse CST: {:?}", e))?;
+    let file = dds_parser::dspfdds::query_display_file(&dds_parser::dspfdds::AST::from(&cst));
+    let screen = dds_parser::dspfdds::render_format(&file, format, &args.on, file.screen_size())
+        .ok_or(format!("No record format named {}", format))?;
+    match args.ansi {
+        true => Ok(screen.ansi()),
+        false => Ok(screen.plain()),
+    }
+}
+
+// the writes are record formats separated by commas, ex: HEADER,DETAIL*3,TOTAL
+fn preview_report(source: &Source, formats: &str, args: &Args) -> Result<String, String> {
+    let cst = dds_parser::prtfdds::CST::try_from(source.text.as_str())
+        .map_err(|e| format!("Unable to parse CST: {:?}", e))?;
+    let file = dds_parser::prtfdds::query_printer_file(&dds_parser::prtfdds::AST::from(&cst));
+    let mut writes = vec![];
+    for item in formats.split(',') {
+        let (format, count) = match item.split_once('*') {
+            Some((format, count)) => (
+                format,
+                count
+                    .parse::<usize>()
+                    .map_err(|_| format!("Not a count: {}", count))?,
+            ),
+            None => (item, 1),
+        };
+        let write = dds_parser::prtfdds::Write {
+            format: format.trim().to_string(),
+            on: args.on.clone(),
+        };
+        writes.extend(std::iter::repeat_n(write, count));
+    }
+    dds_parser::prtfdds::simulate(&file, &writes, args.width, args.overflow)
+}
+
+fn preview(source: &Source, format: &str, args: &Args) -> Result<String, String> {
+    match source.lang {
+        Lang::Dspfdds => preview_screen(source, format, args),
+        Lang::Prtfdds => preview_report(source, format, args),
+        _ => Err("Previews are only available for dspfdds and prtfdds sources".to_string()),
+    }
+}
+
+/* The SQL of a physical or logical file, named after the file. References
+ * and join files are looked up in the manifest.
+ */
+fn sql(source: &Source) -> Result<String, String> {
+    let name = source
+        .path
+        .as_deref()
+        .and_then(|x| x.file_stem())
+        .and_then(|x| x.to_str())
+        .ok_or("sql needs a file, the table is named after it")?;
+    let physicals = source
+        .path
+        .as_deref()
+        .and_then(Manifest::find)
+        .map(|man| man.get_physicals())
+        .unwrap_or_default();
+    match source.lang {
+        Lang::Pfdds => {
+            let cst = dds_parser::pfdds::CST::try_from(source.text.as_str())
+                .map_err(|e| format!("Unable to parse CST: {:?}", e))?;
+            let ast = dds_parser::pfdds::AST::from(&cst);
+            Ok(dds_parser::pfdds::to_sql(name, &ast, &physicals))
+        }
+        Lang::Lfdds => {
+            let cst = dds_parser::lfdds::CST::try_from(source.text.as_str())
+                .map_err(|e| format!("Unable to parse CST: {:?}", e))?;
+            let ast = dds_parser::lfdds::AST::from(&cst);
+            Ok(dds_parser::lfdds::to_sql(name, &ast, &physicals))
+        }
+        _ => Err("SQL is only available for pfdds and lfdds sources".to_string()),
+    }
+}
+
+/* One line per diagnostic, ex: `zevt.rpgle:12:8: error[unknown-builtin] ...`,
+ * and whether any of them is an error.
+ */
+fn lint(source: &Source, name: &str) -> (String, bool) {
+    let mut out = String::new();
+    let mut failed = false;
+    match source.lang {
+        Lang::Rpgle => {
+            for d in idk::lint_rpgle(&source.text, source.path.as_deref()) {
+                failed |= d.level == rpgle_parser::DiagnosticLevel::Error;
+                out.push_str(&format!("{}:{}\n", name, d));
+            }
+        }
+        lang => {
+            for d in idk::lint_dds(&source.text, lang, source.path.as_deref()) {
+                let level = match d.level {
+                    dds_parser::DiagnosticLevel::Info => "info",
+                    dds_parser::DiagnosticLevel::Warning => "warning",
+                    dds_parser::DiagnosticLevel::Error => "error",
+                };
+                failed |= d.level == dds_parser::DiagnosticLevel::Error;
+                out.push_str(&format!(
+                    "{}:{}:{}: {}[{}] {}\n",
+                    name,
+                    d.span.start.row + 1,
+                    d.span.start.col + 1,
+                    level,
+                    d.code,
+                    d.msg
+                ));
+            }
+        }
+    }
+    (out, failed)
+}
+
+// lint takes any number of files, stdin when there are none
+fn lint_all(args: &Args) -> Result<ExitCode, String> {
+    let stdin = ["-".to_string()];
+    let files = match args.positional.is_empty() {
+        true => &stdin[..],
+        false => &args.positional[..],
+    };
+    let mut failed = false;
+    for file in files {
+        let source = read_source(Some(file), args.lang)?;
+        let name = if file == "-" { "stdin" } else { file.as_str() };
+        let (out, errors) = lint(&source, name);
+        output(&out);
+        failed |= errors;
+    }
+    match failed {
+        true => Ok(ExitCode::FAILURE),
+        false => Ok(ExitCode::SUCCESS),
+    }
+}
+
+/* Like the editor, a source that can't be formatted safely is still padded and
+ * trimmed to width, with the reason on stderr. Free form SQL is left as is.
+ */
+fn fmt(source: &Source) -> String {
+    let Some(width) = source.lang.width() else {
+        return source.text.clone();
+    };
+    let normalized = rpgle_parser::normalize_width(&source.text, width);
+    if source.lang != Lang::Rpgle {
+        return normalized;
+    }
+    let options = source
+        .path
+        .as_deref()
+        .and_then(Manifest::find)
+        .and_then(|man| man.get_format_options())
+        .unwrap_or_default();
+    match rpgle_parser::format(&source.text, &options) {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("{}", e);
+            normalized
+        }
+    }
+}
+
+const COMMANDS: [&str; 9] = [
+    "parse",
+    "graph",
+    "def",
+    "highlight",
+    "outline",
+    "fmt",
+    "preview",
+    "sql",
+    "lint",
+];
+
+fn run(args: &Args) -> Result<ExitCode, String> {
+    if !COMMANDS.contains(&args.command.as_str()) {
+        return Err(format!("Unknown command: {}, see idk --help", args.command));
+    }
+    if args.command == "lint" {
+        return lint_all(args);
+    }
+    let (file, pattern) = match args.command.as_str() {
+        "def" => (
+            args.positional.get(1),
+            args.positional.first().ok_or("def needs a name")?.as_str(),
+        ),
+        "preview" => (
+            args.positional.get(1),
+            args.positional
+                .first()
+                .ok_or("preview needs a record format")?
+                .as_str(),
+        ),
+        _ => (args.positional.first(), ""),
+    };
+    let source = read_source(file, args.lang)?;
+    let out = match args.command.as_str() {
+        "parse" => parse(&source, args.ast)?,
+        "graph" => graph(&source)?,
+        "def" => def(&source, pattern)?,
+        "highlight" => highlight(&source)?,
+        "outline" => json(&idk::outline(source.lang, &source.text))?,
+        "preview" => preview(&source, pattern, args)?,
+        "fmt" if args.check => {
+            return match fmt(&source) == source.text {
+                true => Ok(ExitCode::SUCCESS),
+                false => {
+                    let name = file.map(|x| x.as_str()).unwrap_or("stdin");
+                    eprintln!("{} is not formatted", name);
+                    Ok(ExitCode::FAILURE)
+                }
+            };
+        }
+        "fmt" => {
+            output(&fmt(&source));
+            return Ok(ExitCode::SUCCESS);
+        }
+        "sql" => {
+            output(&sql(&source)?);
+            return Ok(ExitCode::SUCCESS);
+        }
+        _ => unreachable!(),
+    };
+    output(&format!("{}\n", out));
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    let raw = env::args().skip(1).collect::<Vec<String>>();
+    if raw.is_empty() || raw.iter().any(|x| x == "-h" || x == "--help") {
+        output(USAGE);
+        return ExitCode::SUCCESS;
+    }
+    let result = Args::parse(raw.into_iter()).and_then(|args| run(&args));
+    match result {
+        Ok(code) => code,
+        Err(msg) => {
+            eprintln!("{}", msg);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use crate::Lang;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/* A project manifest, a `manifest.json` listing the source files of a project
+ * relative to itself. Optional `idk-lint.json` and `idk-fmt.json` configs sit
+ * next to it.
+ */
+#[derive(Debug)]
+pub struct Manifest {
+    pub uri: String,
+}
+
+impl Manifest {
+    // looks for a manifest.json next to the source file, then one directory up
+    pub fn find(source: &Path) -> Option<Self> {
+        let source = source.canonicalize().ok()?;
+        let parent = source.parent()?;
+        [Some(parent), parent.parent()]
+            .into_iter()
+            .flatten()
+            .find_map(|dir| {
+                dir.read_dir().ok()?.flatten().find_map(|entry| {
+                    if !entry.file_name().eq_ignore_ascii_case("manifest.json") {
+                        return None;
+                    }
+                    let fp = entry.path().canonicalize().ok()?;
+                    Some(Self {
+                        uri: format!("file://{}", fp.to_str()?),
+                    })
+                })
+            })
+    }
+
+    pub fn uri_filepath(&self) -> String {
+        self.uri.replace("file://", "").to_string()
+    }
+
+    pub fn get_source_files(&self) -> Option<Vec<String>> {
+        if let Ok(raw_manifest) = fs::read_to_string(self.uri_filepath()) {
+            if let Some(maybebp) = PathBuf::from(&self.uri_filepath()).parent() {
+                let mut relpaths = raw_manifest
+                    .replace("\n", "")
+                    .replace("[", "")
+                    .replace("]", "")
+                    .replace('"', "")
+                    .replace('\\', "")
+                    .replace(' ', "")
+                    .split(",")
+                    .map(|x| x.to_string())
+                    .collect::<Vec<String>>();
+                relpaths.reverse();
+                if let Ok(bp) = maybebp.canonicalize() {
+                    if let Some(basepath) = bp.to_str() {
+                        let out = relpaths
+                            .iter()
+                            .map(|rp| {
+                                let mut out = basepath.to_string();
+                                out.push_str("/");
+                                out.push_str(rp);
+                                format!("{}", out)
+                            })
+                            .collect::<Vec<String>>();
+                        return Some(out);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // optional idk-lint.json next to the manifest
+    pub fn get_lint_config(&self) -> Option<rpgle_parser::LintConfig> {
+        let dir = PathBuf::from(self.uri_filepath()).parent()?.to_path_buf();
+        let raw = fs::read_to_string(dir.join("idk-lint.json")).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    // optional idk-fmt.json next to the manifest
+    pub fn get_format_options(&self) -> Option<rpgle_parser::FormatOptions> {
+        let dir = PathBuf::from(self.uri_filepath()).parent()?.to_path_buf();
+        let raw = fs::read_to_string(dir.join("idk-fmt.json")).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    // file name -> record format names, for every dds source in the manifest
+    pub fn get_record_formats(&self) -> HashMap<String, Vec<String>> {
+        let mut out = HashMap::new();
+        for (stem, ast) in self.get_pfdds() {
+            out.insert(stem, dds_parser::pfdds::query_record_formats(&ast));
+        }
+        for (stem, ast) in self.get_lfdds() {
+            out.insert(stem, dds_parser::lfdds::query_record_formats(&ast));
+        }
+        for (stem, ast) in self.get_dspfdds() {
+            out.insert(stem, dds_parser::dspfdds::query_record_formats(&ast));
+        }
+        for (stem, ast) in self.get_prtfdds() {
+            out.insert(stem, dds_parser::prtfdds::query_record_formats(&ast));
+        }
+        for object in self.get_sql_objects() {
+            out.insert(object.name, vec![object.format]);
+        }
+        out
+    }
+
+    /* file name -> (record format, field, type and length) for every dds
+     * source in the manifest, logical file fields take the type and length of
+     * the physical file field they read and fields defined with REF or REFFLD
+     * that of the field they reference, and for every table, view and index
+     * created by an sql source
+     */
+    pub fn get_fields(&self) -> HashMap<String, Vec<(String, String, String)>> {
+        let mut out = HashMap::new();
+        let physicals = self.get_physicals();
+        let details = |refs: dds_parser::pfdds::References| {
+            refs.fields
+                .into_iter()
+                .map(|x| {
+                    let detail = x.detail();
+                    (x.format, x.name, detail)
+                })
+                .collect::<Vec<_>>()
+        };
+        for (stem, ast) in physicals.iter() {
+            let refs = dds_parser::pfdds::resolve_references(stem, ast, &physicals);
+            out.insert(stem.clone(), details(refs));
+        }
+        for (stem, ast) in self.get_lfdds() {
+            let lf = dds_parser::lfdds::query_logical_file(&ast);
+            let fields = dds_parser::lfdds::resolve_fields(&lf, &physicals)
+                .into_iter()
+                .map(|x| (x.format, x.name, x.detail))
+                .collect();
+            out.insert(stem, fields);
+        }
+        for (stem, ast) in self.get_dspfdds() {
+            let refs = dds_parser::dspfdds::resolve_references(&stem, &ast, &physicals);
+            out.insert(stem, details(refs));
+        }
+        for (stem, ast) in self.get_prtfdds() {
+            let refs = dds_parser::prtfdds::resolve_references(&stem, &ast, &physicals);
+            out.insert(stem, details(refs));
+        }
+        for object in self.get_sql_objects() {
+            let fields = object
+                .columns
+                .iter()
+                .filter(|x| x.name != "*")
+                .map(|x| (object.format.clone(), x.name.clone(), x.detail()))
+                .collect();
+            out.insert(object.name, fields);
+        }
+        out
+    }
+
+    /* every table, view and index the sql sources create, by their system
+     * names, views and indexes with the columns of the tables they're over
+     */
+    fn get_sql_objects(&self) -> Vec<dds_parser::sqlddl::SqlObject> {
+        let asts = self
+            .get_source_files()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|x| Lang::from_path(Path::new(x)) == Some(Lang::Sqlddl))
+            .filter_map(|source| {
+                let input = fs::read_to_string(source).ok()?;
+                let cst = dds_parser::sqlddl::CST::try_from(input.as_str()).ok()?;
+                Some(dds_parser::sqlddl::AST::from(&cst))
+            })
+            .collect::<Vec<_>>();
+        let tables = asts
+            .iter()
+            .flat_map(|ast| ast.objects.iter())
+            .map(|x| (x.name.clone(), x.clone()))
+            .collect::<HashMap<_, _>>();
+        asts.iter()
+            .flat_map(|ast| dds_parser::sqlddl::resolve_objects(ast, &tables))
+            .collect()
+    }
+
+    // file name -> parsed pfdds source, what REF and REFFLD can point to
+    pub fn get_physicals(&self) -> HashMap<String, dds_parser::pfdds::AST> {
+        self.get_pfdds().into_iter().collect()
+    }
+
+    // file name -> parsed pfdds source
+    fn get_pfdds(&self) -> Vec<(String, dds_parser::pfdds::AST)> {
+        self.get_dds("pfdds")
+            .into_iter()
+            .filter_map(|(stem, input)| {
+                let cst = dds_parser::pfdds::CST::try_from(input.as_str()).ok()?;
+                Some((stem, dds_parser::pfdds::AST::from(&cst)))
+            })
+            .collect()
+    }
+
+    // file name -> parsed lfdds source
+    fn get_lfdds(&self) -> Vec<(String, dds_parser::lfdds::AST)> {
+        self.get_dds("lfdds")
+            .into_iter()
+            .filter_map(|(stem, input)| {
+                let cst = dds_parser::lfdds::CST::try_from(input.as_str()).ok()?;
+                Some((stem, dds_parser::lfdds::AST::from(&cst)))
+            })
+            .collect()
+    }
+
+    // file name -> parsed dspfdds source
+    fn get_dspfdds(&self) -> Vec<(String, dds_parser::dspfdds::AST)> {
+        self.get_dds("dspfdds")
+            .into_iter()
+            .filter_map(|(stem, input)| {
+                let cst = dds_parser::dspfdds::CST::try_from(input.as_str()).ok()?;
+                Some((stem, dds_parser::dspfdds::AST::from(&cst)))
+            })
+            .collect()
+    }
+
+    // file name -> parsed prtfdds source
+    fn get_prtfdds(&self) -> Vec<(String, dds_parser::prtfdds::AST)> {
+        self.get_dds("prtfdds")
+            .into_iter()
+            .filter_map(|(stem, input)| {
+                let cst = dds_parser::prtfdds::CST::try_from(input.as_str()).ok()?;
+                Some((stem, dds_parser::prtfdds::AST::from(&cst)))
+            })
+            .collect()
+    }
+
+    // file name -> text, for the sources with extension `ext`
+    fn get_dds(&self, ext: &str) -> Vec<(String, String)> {
+        let mut out = vec![];
+        if let Some(srcs) = self.get_source_files() {
+            for source in srcs.iter().filter(|x| x.ends_with(ext)) {
+                let Some(stem) = PathBuf::from(source)
+                    .file_stem()
+                    .and_then(|x| x.to_str())
+                    .map(|x| x.to_uppercase())
+                else {
+                    continue;
+                };
+                if let Ok(input) = fs::read_to_string(source) {
+                    out.push((stem, input));
+                }
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,134 @@
+use crate::Lang;
+use serde::{Deserialize, Serialize};
+
+type Range = ((usize, usize), (usize, usize));
+
+// An outline entry of either language, rows and columns are zero based
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Outline {
+    pub name: String,
+    // ex: subroutine, data-structure, record-format
+    pub kind: String,
+    // the whole body
+    pub range: Range,
+    // just the name
+    pub selection: Range,
+    pub children: Vec<Outline>,
+}
+
+fn from_rpgle(item: &rpgle_parser::OutlineItem) -> Outline {
+    use rpgle_parser::OutlineKind;
+    let kind = match item.kind {
+        OutlineKind::Options => "options",
+        OutlineKind::File => "file",
+        OutlineKind::DataStructure => "data-structure",
+        OutlineKind::Subfield => "subfield",
+        OutlineKind::Prototype => "prototype",
+        OutlineKind::ProcedureInterface => "procedure-interface",
+        OutlineKind::Parameter => "parameter",
+        OutlineKind::Procedure => "procedure",
+        OutlineKind::Subroutine => "subroutine",
+        OutlineKind::Tag => "tag",
+    };
+    let (span, selection) = (item.span, item.selection);
+    Outline {
+        name: item.name.clone(),
+        kind: kind.to_string(),
+        range: (
+            (span.start.row, span.start.col),
+            (span.end.row, span.end.col),
+        ),
+        selection: (
+            (selection.start.row, selection.start.col),
+            (selection.end.row, selection.end.col),
+        ),
+        children: item.children.iter().map(from_rpgle).collect(),
+    }
+}
+
+fn from_pfdds(item: &dds_parser::pfdds::OutlineItem) -> Outline {
+    use dds_parser::pfdds::OutlineKind;
+    let kind = match item.kind {
+        OutlineKind::FileEntry => "file-entry",
+        OutlineKind::RecordFormat => "record-format",
+        OutlineKind::Field => "field",
+        OutlineKind::Keyfield => "key-field",
+    };
+    let (span, selection) = (item.span, item.selection);
+    Outline {
+        name: item.name.clone(),
+        kind: kind.to_string(),
+        range: (
+            (span.start.row, span.start.col),
+            (span.end.row, span.end.col),
+        ),
+        selection: (
+            (selection.start.row, selection.start.col),
+            (selection.end.row, selection.end.col),
+        ),
+        children: item.children.iter().map(from_pfdds).collect(),
+    }
+}
+
+// A hierarchical outline of a source member, empty when it can't be parsed
+pub fn outline(lang: Lang, input: &str) -> Vec<Outline> {
+    match lang {
+        Lang::Rpgle => match rpgle_parser::parse_cst(input) {
+            Ok(cst) => rpgle_parser::query_outline(&cst)
+                .iter()
+                .map(from_rpgle)
+                .collect(),
+            Err(_) => vec![],
+        },
+        Lang::Pfdds => match dds_parser::pfdds::CST::try_from(input) {
+            Ok(cst) => {
+                let ast = dds_parser::pfdds::AST::from(&cst);
+                dds_parser::pfdds::query_outline(&ast)
+                    .iter()
+                    .map(from_pfdds)
+                    .collect()
+            }
+            Err(_) => vec![],
+        },
+        Lang::Lfdds => match dds_parser::lfdds::CST::try_from(input) {
+            Ok(cst) => {
+                let ast = dds_parser::lfdds::AST::from(&cst);
+                dds_parser::lfdds::query_outline(&ast)
+                    .iter()
+                    .map(from_pfdds)
+                    .collect()
+            }
+            Err(_) => vec![],
+        },
+        Lang::Dspfdds => match dds_parser::dspfdds::CST::try_from(input) {
+            Ok(cst) => {
+                let ast = dds_parser::dspfdds::AST::from(&cst);
+                dds_parser::dspfdds::query_outline(&ast)
+                    .iter()
+                    .map(from_pfdds)
+                    .collect()
+            }
+            Err(_) => vec![],
+        },
+        Lang::Prtfdds => match dds_parser::prtfdds::CST::try_from(input) {
+            Ok(cst) => {
+                let ast = dds_parser::prtfdds::AST::from(&cst);
+                dds_parser::prtfdds::query_outline(&ast)
+                    .iter()
+                    .map(from_pfdds)
+                    .collect()
+            }
+            Err(_) => vec![],
+        },
+        Lang::Sqlddl => match dds_parser::sqlddl::CST::try_from(input) {
+            Ok(cst) => {
+                let ast = dds_parser::sqlddl::AST::from(&cst);
+                dds_parser::sqlddl::query_outline(&ast)
+                    .iter()
+                    .map(from_pfdds)
+                    .collect()
+            }
+            Err(_) => vec![],
+        },
+    }
+}
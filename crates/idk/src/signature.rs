@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+// The signature of the call under the cursor, parameter labels are substrings of `label`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureHelp {
+    // ex: QCmdExc(Command 2000A const : Length 15P 5 const)
+    pub label: String,
+    pub parameters: Vec<String>,
+    // zero based, can be past the last parameter while typing
+    pub active_parameter: usize,
+}
+
+// Signature help for rpgle source at a zero based `row` and `col`
+pub fn signature_help(input: &str, row: usize, col: usize) -> Option<SignatureHelp> {
+    let help = rpgle_parser::query_signature_help(input, row, col)?;
+    Some(SignatureHelp {
+        label: help.signature.label(),
+        parameters: help
+            .signature
+            .parameters
+            .iter()
+            .map(|x| x.label())
+            .collect(),
+        active_parameter: help.active_parameter,
+    })
+}
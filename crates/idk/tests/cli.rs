@@ -0,0 +1,85 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// runs idk with `input` on stdin, the exit code and stdout
+fn idk(args: &[&str], input: &str) -> (i32, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_idk"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // idk may exit before it reads stdin, ex: an unknown --lang
+    let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    (output.status.code().unwrap(), stdout)
+}
+
+fn source() -> String {
+    let lines = [
+        "     D LastId          S              8  0",
+        "     C                   EVAL      LastId = 1",
+    ];
+    lines.map(|x| format!("{:<100}\n", x)).concat()
+}
+
+#[test]
+fn test_parse_exit_code() {
+    let (code, stdout) = idk(&["parse", "-"], &source());
+    assert_eq!(code, 0);
+    assert!(stdout.starts_with('{'));
+    let (code, _) = idk(&["parse", "--ast", "-"], &source());
+    assert_eq!(code, 0);
+    let (code, stdout) = idk(&["parse", "--lang", "cobol", "-"], &source());
+    assert_eq!(code, 1);
+    assert!(stdout.is_empty());
+    let (code, _) = idk(&["parse", "nope.rpgle"], "");
+    assert_eq!(code, 1);
+}
+
+#[test]
+fn test_def_exit_code() {
+    let (code, stdout) = idk(&["def", "LastId", "-"], &source());
+    assert_eq!(code, 0);
+    assert!(stdout.starts_with('{'));
+    let (code, stdout) = idk(&["def", "Nope", "-"], &source());
+    assert_eq!(code, 1);
+    assert!(stdout.is_empty());
+    let (code, _) = idk(&["def"], &source());
+    assert_eq!(code, 1);
+}
+
+#[test]
+fn test_fmt_check_exit_code() {
+    let (code, formatted) = idk(&["fmt", "-"], &source());
+    assert_eq!(code, 0);
+    let (code, stdout) = idk(&["fmt", "--check", "-"], &formatted);
+    assert_eq!(code, 0);
+    assert!(stdout.is_empty());
+    // trailing blanks trimmed away, the lines are no longer 100 wide
+    let trimmed = source()
+        .lines()
+        .map(|x| format!("{}\n", x.trim_end()))
+        .collect::<String>();
+    let (code, stdout) = idk(&["fmt", "--check", "-"], &trimmed);
+    assert_eq!(code, 1);
+    assert!(stdout.is_empty());
+}
+
+#[test]
+fn test_lint_exit_code() {
+    let (code, _) = idk(&["lint", "-"], &source());
+    assert_eq!(code, 0);
+    let input = format!(
+        "{:<100}\n",
+        "     C                   EVAL      LastId = %nope(1)"
+    );
+    let (code, stdout) = idk(&["lint", "-"], &input);
+    assert_eq!(code, 1);
+    assert!(stdout.starts_with("stdin:1:"));
+    assert!(stdout.contains("error[unknown-builtin]"));
+    let (code, _) = idk(&["lint", "nope.rpgle"], "");
+    assert_eq!(code, 1);
+}
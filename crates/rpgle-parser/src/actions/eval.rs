@@ -0,0 +1,150 @@
+use super::{extf2, replace_lines, CodeAction, Source};
+use crate::convert::calc::{caveat, Calc};
+use crate::cst::{CSrcline, Srcline};
+use crate::field::{Datatype, Decimals, FieldResult, ResultLength};
+use crate::lint::{field_lengths, operand_length};
+use std::collections::HashSet;
+
+// uppercased names of the character fields, a blank type without decimals is character
+fn character_fields(lines: &[Srcline]) -> HashSet<String> {
+    let mut out = HashSet::new();
+    for line in lines.iter() {
+        match line {
+            Srcline::D {
+                name: FieldResult::Ok(name),
+                datatype: FieldResult::Ok(datatype),
+                decimals: FieldResult::Ok(decimals),
+                ..
+            } => {
+                let character = match datatype.value {
+                    Datatype::A => true,
+                    Datatype::Empty => decimals.value == Decimals::Empty,
+                    _ => false,
+                };
+                if character && !name.value.is_empty() {
+                    out.insert(name.value.to_uppercase());
+                }
+            }
+            Srcline::C(CSrcline::Traditional {
+                result: FieldResult::Ok(result),
+                result_length: FieldResult::Ok(length),
+                decimals: FieldResult::Ok(decimals),
+                ..
+            }) => {
+                if let (ResultLength::Value(_), Decimals::Empty) = (length.value, decimals.value) {
+                    out.insert(result.value.to_uppercase());
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+// literals, *BLANKS and the fields known to be character
+fn is_character(operand: &str, fields: &HashSet<String>) -> bool {
+    let operand = operand.trim().to_uppercase();
+    operand.starts_with('\'')
+        || operand == "*BLANK"
+        || operand == "*BLANKS"
+        || fields.contains(&operand)
+}
+
+// the expression a traditional arithmetic opcode assigns to its result field
+fn arithmetic(calc: &Calc, symbol: &str) -> String {
+    let left = match calc.factor1.is_empty() {
+        true => &calc.result,
+        false => &calc.factor1,
+    };
+    format!("{} {} {}", left, symbol, calc.factor2)
+}
+
+fn negated(operand: &str) -> String {
+    match operand.strip_prefix('-') {
+        Some(rest) => rest.to_string(),
+        None => format!("-{}", operand),
+    }
+}
+
+/* The EVAL opcode and expression for a calc spec. MOVE and MOVEL only pad
+ * like EVALR and EVAL do with (P), or when both sides are the same length,
+ * and only convert between character operands, numbers move by digits.
+ */
+fn eval(source: &Source, calc: &Calc, next: Option<&Calc>) -> Option<(String, String)> {
+    let half_adjust = match calc.extender.contains('h') {
+        true => "(H)",
+        false => "",
+    };
+    let expr = match calc.opcode.as_str() {
+        "Z-ADD" => calc.factor2.clone(),
+        "Z-SUB" => negated(&calc.factor2),
+        "ADD" => arithmetic(calc, "+"),
+        "SUB" => arithmetic(calc, "-"),
+        "MULT" => arithmetic(calc, "*"),
+        // the remainder is lost without the DIV
+        "DIV" if next.is_some_and(|x| x.opcode == "MVR") => return None,
+        "DIV" => arithmetic(calc, "/"),
+        "MOVE" | "MOVEL" if calc.factor1.is_empty() => {
+            let characters = character_fields(&source.cst.lines);
+            if !is_character(&calc.factor2, &characters) || !is_character(&calc.result, &characters)
+            {
+                return None;
+            }
+            let lengths = field_lengths(&source.cst.lines);
+            let padded = calc.extender.contains('p');
+            let same = operand_length(&calc.factor2, &lengths).is_some()
+                && operand_length(&calc.factor2, &lengths)
+                    == operand_length(&calc.result, &lengths);
+            if !padded && !same {
+                return None;
+            }
+            let opcode = match calc.opcode.as_str() {
+                "MOVE" => "EVALR",
+                _ => "EVAL",
+            };
+            return Some((
+                opcode.to_string(),
+                format!("{} = {}", calc.result, calc.factor2),
+            ));
+        }
+        _ => return None,
+    };
+    let opcode = format!("EVAL{}", half_adjust);
+    Some((opcode, format!("{} = {}", calc.result, expr)))
+}
+
+// Z-ADD, Z-SUB, ADD, SUB, MULT, DIV, MOVE and MOVEL to EVAL
+pub fn action(source: &Source, row: usize) -> Option<CodeAction> {
+    let Some(Srcline::C(CSrcline::Traditional { .. })) = source.cst.lines.get(row) else {
+        return None;
+    };
+    let calc = Calc::traditional(&source.lines[row]);
+    let next = match source.cst.lines.get(row + 1) {
+        Some(Srcline::C(CSrcline::Traditional { .. })) => {
+            Some(Calc::traditional(&source.lines[row + 1]))
+        }
+        _ => None,
+    };
+    // EVAL has no resulting indicators and can't define its result field
+    let indicators = calc.indicators.iter().any(|x| !x.is_empty());
+    if indicators || !calc.length.is_empty() || !calc.decimals.is_empty() {
+        return None;
+    }
+    if calc.result.is_empty() || calc.factor2.is_empty() {
+        return None;
+    }
+    let (opcode, expr) = eval(source, &calc, next.as_ref())?;
+    let mut lines = extf2(&source.lines[row], &opcode, &expr);
+    if !calc.comment.is_empty() {
+        lines[0] = format!("{:<80}{}", lines[0], calc.comment);
+    }
+    // EVAL doesn't truncate like the arithmetic opcodes do
+    let title = match caveat(&calc) {
+        Some(caveat) => format!("Convert {} to {}, {}", calc.opcode, opcode, caveat),
+        None => format!("Convert {} to {}", calc.opcode, opcode),
+    };
+    Some(CodeAction::new(
+        title,
+        vec![replace_lines(row, row + 1, &lines)],
+    ))
+}
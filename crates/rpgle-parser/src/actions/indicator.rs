@@ -0,0 +1,116 @@
+use super::{dspec, insert, CodeAction, Source, TextEdit};
+use crate::analysis::SymbolKind;
+use crate::cst::{CSrcline, Srcline};
+use crate::lint::{LintContext, NoNumberedIndicators, Rule};
+use std::collections::HashMap;
+
+// the based data structure named indicators are added to
+const OVERLAY: &str = "Indicators";
+const POINTER: &str = "IndPtr";
+
+// *IN50 and *IN(50) -> 50
+fn number(text: &str) -> Option<u32> {
+    let rest = text.to_uppercase();
+    let rest = rest.strip_prefix("*IN")?;
+    let digits = rest.trim_start_matches('(').trim_end_matches(')').trim();
+    match digits.parse::<u32>() {
+        Ok(x) if (1..=99).contains(&x) => Some(x),
+        _ => None,
+    }
+}
+
+fn subfield(source: &Source, name: &str, number: u32) -> TextEdit {
+    let pos = number.to_string();
+    let fixed = dspec(&format!(" {}", name), "", &pos, &pos, "N", "");
+    let free = vec![format!("         {} ind pos({});", name, pos)];
+    let ds = source
+        .definition(OVERLAY, SymbolKind::DataStructure)
+        .map(|row| {
+            let last = source
+                .symbols
+                .definitions
+                .iter()
+                .skip_while(|x| x.span.start.row <= row)
+                .take_while(|x| x.kind == SymbolKind::Subfield)
+                .last()
+                .map(|x| x.span.start.row)
+                .unwrap_or(row);
+            let mut at = last + 1;
+            while let Some(Srcline::DCont { .. }) = source.cst.lines.get(at) {
+                at += 1;
+            }
+            let free = matches!(source.cst.lines.get(row), Some(Srcline::C(_)));
+            (at, free)
+        });
+    match ds {
+        Some((at, true)) => insert(at, &free),
+        Some((at, false)) => insert(at, &fixed),
+        None => {
+            let mut block = dspec(POINTER, "S", "", "", "*", "INZ(%ADDR(*IN))");
+            block.extend(dspec(
+                OVERLAY,
+                "DS",
+                "",
+                "",
+                "",
+                &format!("BASED({})", POINTER),
+            ));
+            block.extend(fixed);
+            let free = vec![
+                format!("       dcl-s {} pointer inz(%addr(*in));", POINTER),
+                format!("       dcl-ds {} based({});", OVERLAY, POINTER),
+                free[0].clone(),
+                "       end-ds;".to_string(),
+            ];
+            source.declare(block, free)
+        }
+    }
+}
+
+/* *INxx to a named indicator, a subfield of a data structure based on the
+ * address of *IN so both names stay the same indicator.
+ */
+pub fn action(source: &Source, row: usize, col: usize) -> Option<CodeAction> {
+    let record_formats = HashMap::new();
+    let ctx = LintContext {
+        cst: &source.cst,
+        symbols: &source.symbols,
+        record_formats: &record_formats,
+    };
+    let found = NoNumberedIndicators
+        .check(&ctx)
+        .into_iter()
+        .map(|x| x.span)
+        .filter(|span| source.lines[span.start.row][span.start.col] == '*')
+        .map(|span| {
+            let text = source.text(span.start.row, span.start.col, span.end.col);
+            (span, number(&text))
+        })
+        .collect::<Vec<_>>();
+    let (_, target) = found
+        .iter()
+        .find(|(span, _)| span.start.row == row && span.start.col <= col && col < span.end.col)?;
+    let target = (*target)?;
+    let name = format!("Ind{:02}", target);
+    let mut edits = found
+        .iter()
+        .filter(|(_, x)| *x == Some(target))
+        .map(|(span, _)| {
+            // fixed columns keep their width
+            let width = span.end.col - span.start.col;
+            let new_text = match source.cst.lines[span.start.row] {
+                Srcline::C(CSrcline::Traditional { .. }) => format!("{:<1$}", name, width),
+                _ => name.clone(),
+            };
+            TextEdit {
+                span: *span,
+                new_text,
+            }
+        })
+        .collect::<Vec<TextEdit>>();
+    if !source.is_defined(&name) {
+        edits.push(subfield(source, &name, target));
+    }
+    let title = format!("Replace *IN{:02} with the named indicator {}", target, name);
+    Some(CodeAction::new(title, edits))
+}
@@ -0,0 +1,175 @@
+use super::{at, dspec, insert, replace_lines, CodeAction, Source, TextEdit};
+use crate::analysis::continued;
+use crate::convert::calc::Calc;
+use crate::cst::{CSrcline, Srcline};
+use crate::meta::Span;
+use std::ops::Range;
+
+// operations that take a key list as their search argument
+const KEYED: [&str; 6] = ["CHAIN", "DELETE", "READE", "READPE", "SETGT", "SETLL"];
+
+struct KeyList {
+    name: String,
+    // the KLIST line and its KFLD lines
+    rows: Range<usize>,
+    // (subfield, key field)
+    keys: Vec<(String, String)>,
+}
+
+fn calc(source: &Source, row: usize) -> Option<Calc> {
+    match source.cst.lines.get(row) {
+        Some(Srcline::C(CSrcline::Traditional { .. })) => {
+            Some(Calc::traditional(&source.lines[row]))
+        }
+        _ => None,
+    }
+}
+
+fn key_lists(source: &Source) -> Vec<KeyList> {
+    let mut out = vec![];
+    for row in 0..source.cst.lines.len() {
+        let Some(klist) = calc(source, row).filter(|x| x.opcode == "KLIST") else {
+            continue;
+        };
+        let mut end = row + 1;
+        let mut keys = vec![];
+        while let Some(kfld) = calc(source, end).filter(|x| x.opcode == "KFLD") {
+            let subfield = kfld.result.rsplit('.').next().unwrap_or_default();
+            keys.push((subfield.to_string(), kfld.result.clone()));
+            end += 1;
+        }
+        out.push(KeyList {
+            name: klist.factor1,
+            rows: row..end,
+            keys,
+        });
+    }
+    out
+}
+
+fn assignments(klist: &KeyList, indent: &str) -> Vec<String> {
+    klist
+        .keys
+        .iter()
+        .map(|(subfield, field)| format!("{}{}.{} = {};", indent, klist.name, subfield, field))
+        .collect()
+}
+
+// a keyed operation in a traditional calc spec becomes free-form, a fixed factor 1 can't take %KDS
+fn traditional(source: &Source, klist: &KeyList, row: usize) -> Option<TextEdit> {
+    let calc = calc(source, row)?;
+    let keyed = KEYED.contains(&calc.opcode.as_str());
+    let indicators = calc.indicators.iter().any(|x| !x.is_empty());
+    let conditioned = !calc.control_level.is_empty() || !calc.conditioning.is_empty();
+    if !keyed || indicators || conditioned || !calc.length.is_empty() {
+        return None;
+    }
+    if calc.factor1.to_uppercase() != klist.name.to_uppercase() {
+        return None;
+    }
+    let indent = format!("{}  ", source.lines[row][..5].iter().collect::<String>());
+    let mut lines = assignments(klist, &indent);
+    let operands = [calc.factor2.as_str(), calc.result.as_str()]
+        .iter()
+        .filter(|x| !x.is_empty())
+        .copied()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    lines.push(format!(
+        "{}{}{} %kds({}) {};",
+        indent,
+        calc.opcode.to_lowercase(),
+        calc.extender,
+        klist.name,
+        operands
+    ));
+    // sources that open /free blocks still need them
+    if source.free_directives() {
+        lines.insert(0, "      /free".to_string());
+        lines.push("      /end-free".to_string());
+    }
+    Some(replace_lines(row, row + 1, &lines))
+}
+
+// the key list as the search argument of a free-form keyed operation
+fn free(source: &Source, klist: &KeyList, row: usize, col: usize) -> Option<Vec<TextEdit>> {
+    if continued(&source.cst, row) {
+        return None;
+    }
+    let code = source.text(row, 7, 100).to_uppercase();
+    let mut words = code.split_whitespace();
+    let head = words.next()?.split('(').next().unwrap_or_default();
+    let argument = words.next()?.trim_end_matches(';');
+    if !KEYED.contains(&head) || argument != klist.name.to_uppercase() {
+        return None;
+    }
+    let len = klist.name.chars().count();
+    let first = source.lines[row][7..].iter().position(|c| *c != ' ')?;
+    let indent = source.lines[row][..7 + first].iter().collect::<String>();
+    let indent = indent.replace(|c: char| c != ' ', " ");
+    Some(vec![
+        insert(row, &assignments(klist, &indent)),
+        TextEdit {
+            span: Span {
+                start: at(row, col),
+                end: at(row, col + len),
+            },
+            new_text: format!("%kds({})", klist.name),
+        },
+    ])
+}
+
+/* A KLIST and its KFLD lines to a qualified data structure with a subfield
+ * LIKE each key field, searched with %KDS. The key fields are copied into
+ * it right before each keyed operation, like the KLIST read them.
+ */
+pub fn action(source: &Source, row: usize, col: usize) -> Option<CodeAction> {
+    let lists = key_lists(source);
+    let reference = source
+        .symbols
+        .references
+        .iter()
+        .find(|x| x.span.start.row == row && x.span.start.col <= col && col < x.span.end.col);
+    let klist = lists.iter().find(|x| {
+        x.rows.contains(&row) || reference.is_some_and(|r| r.name == x.name.to_uppercase())
+    })?;
+    if klist.keys.is_empty() || source.is_defined(&klist.name) {
+        return None;
+    }
+    let mut subfields = klist
+        .keys
+        .iter()
+        .map(|x| x.0.to_uppercase())
+        .collect::<Vec<_>>();
+    subfields.sort();
+    subfields.dedup();
+    if subfields.len() != klist.keys.len() {
+        return None;
+    }
+    let mut fixed = dspec(&klist.name, "DS", "", "", "", "QUALIFIED");
+    let mut free_decl = vec![format!("       dcl-ds {} qualified;", klist.name)];
+    for (subfield, field) in &klist.keys {
+        let like = format!("LIKE({})", field);
+        fixed.extend(dspec(&format!(" {}", subfield), "", "", "", "", &like));
+        free_decl.push(format!("         {} like({});", subfield, field));
+    }
+    free_decl.push("       end-ds;".to_string());
+    let mut edits = vec![source.declare(fixed, free_decl)];
+    edits.push(replace_lines(klist.rows.start, klist.rows.end, &[]));
+    let name = klist.name.to_uppercase();
+    for usage in source.symbols.references.iter().filter(|x| x.name == name) {
+        let (row, col) = (usage.span.start.row, usage.span.start.col);
+        if klist.rows.contains(&row) {
+            continue;
+        }
+        match source.cst.lines.get(row) {
+            Some(Srcline::C(CSrcline::Traditional { .. })) => {
+                edits.push(traditional(source, klist, row)?)
+            }
+            Some(Srcline::C(CSrcline::Free { .. })) => edits.extend(free(source, klist, row, col)?),
+            _ => return None,
+        }
+    }
+    let title = format!("Convert KLIST {} to a %KDS data structure", klist.name);
+    Some(CodeAction::new(title, edits))
+}
@@ -0,0 +1,408 @@
+mod eval;
+mod indicator;
+mod klist;
+mod prototype;
+
+use crate::analysis::{SymbolKind, SymbolTable};
+use crate::cst::{parse_cst, CSrcline, Srcline, CST};
+use crate::format::normalize_width;
+use crate::meta::{Position, Span};
+use serde::{Deserialize, Serialize};
+
+// Replace `span` with `new_text`, an empty span inserts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextEdit {
+    pub span: Span,
+    pub new_text: String,
+}
+
+// A named fix for the construct under the cursor, its edits never overlap
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeAction {
+    // ex: Convert Z-ADD to EVAL
+    pub title: String,
+    // sorted by position
+    pub edits: Vec<TextEdit>,
+}
+
+impl CodeAction {
+    // an insertion where another edit starts is folded into it
+    fn new(title: String, mut edits: Vec<TextEdit>) -> Self {
+        edits.sort_by_key(|x| (x.span.start.row, x.span.start.col));
+        let mut out: Vec<TextEdit> = vec![];
+        for mut edit in edits {
+            if let Some(last) = out.last() {
+                if last.span.start == edit.span.start && last.span.start == last.span.end {
+                    edit.new_text = format!("{}{}", last.new_text, edit.new_text);
+                    out.pop();
+                }
+            }
+            out.push(edit);
+        }
+        Self { title, edits: out }
+    }
+
+    // `input` with the edits applied, to preview the action
+    pub fn apply(&self, input: &str) -> String {
+        let mut lines = input
+            .split('\n')
+            .map(|x| x.chars().collect::<Vec<char>>())
+            .collect::<Vec<Vec<char>>>();
+        for edit in self.edits.iter().rev() {
+            let (start, end) = (edit.span.start, edit.span.end);
+            if start.row >= lines.len() {
+                continue;
+            }
+            let end_row = end.row.min(lines.len() - 1);
+            let head = &lines[start.row][..start.col.min(lines[start.row].len())];
+            let tail = &lines[end_row][end.col.min(lines[end_row].len())..];
+            let text = format!(
+                "{}{}{}",
+                head.iter().collect::<String>(),
+                edit.new_text,
+                tail.iter().collect::<String>()
+            );
+            let replacement = text
+                .split('\n')
+                .map(|x| x.chars().collect::<Vec<char>>())
+                .collect::<Vec<Vec<char>>>();
+            lines.splice(start.row..=end_row, replacement);
+        }
+        lines
+            .iter()
+            .map(|x| x.iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+// The source an action looks at, lines are padded to 100 columns
+struct Source {
+    cst: CST,
+    lines: Vec<Vec<char>>,
+    symbols: SymbolTable,
+}
+
+impl Source {
+    fn text(&self, row: usize, from: usize, to: usize) -> String {
+        self.lines[row][from..to]
+            .iter()
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+
+    fn is_defined(&self, name: &str) -> bool {
+        let name = name.to_uppercase();
+        self.symbols
+            .definitions
+            .iter()
+            .any(|x| x.name.to_uppercase() == name)
+    }
+
+    fn definition(&self, name: &str, kind: SymbolKind) -> Option<usize> {
+        let name = name.to_uppercase();
+        self.symbols
+            .definitions
+            .iter()
+            .find(|x| x.kind == kind && x.name.to_uppercase() == name)
+            .map(|x| x.span.start.row)
+    }
+
+    fn free_directives(&self) -> bool {
+        self.cst.lines.iter().enumerate().any(|(row, line)| {
+            matches!(line, Srcline::CompilerDirective { .. })
+                && self.text(row, 6, 100).to_uppercase().starts_with("/FREE")
+        })
+    }
+
+    // the first word of a free-form line, uppercased
+    fn free_head(&self, row: usize) -> Option<String> {
+        match self.cst.lines.get(row) {
+            Some(Srcline::C(CSrcline::Free { .. })) => {
+                let code = self.text(row, 7, 100).to_uppercase();
+                let head = code.split([' ', ';', '(']).next().unwrap_or_default();
+                Some(head.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /* Where new global declarations go, after the last one before the
+     * calculations, and whether they should be free-form like it.
+     */
+    fn declarations(&self) -> (usize, bool) {
+        let mut at = None;
+        let mut free = false;
+        let mut container = false;
+        for (row, line) in self.cst.lines.iter().enumerate() {
+            match line {
+                Srcline::H { .. }
+                | Srcline::F { .. }
+                | Srcline::FCont { .. }
+                | Srcline::D { .. }
+                | Srcline::DCont { .. } => {
+                    at = Some(row + 1);
+                    free = false;
+                }
+                Srcline::C(CSrcline::Free { .. }) => {
+                    let head = self.free_head(row).unwrap_or_default();
+                    let opens = ["DCL-DS", "DCL-PR", "DCL-PI"].contains(&head.as_str());
+                    let code = self.text(row, 7, 100).to_uppercase();
+                    if head.is_empty() || head.starts_with("//") {
+                        continue;
+                    } else if head == "DCL-PROC" {
+                        break;
+                    } else if head.starts_with("DCL-") || head == "CTL-OPT" || container {
+                        container = (container || opens)
+                            && !head.starts_with("END-")
+                            && !(opens && (code.contains("END-") || code.contains("LIKEDS")));
+                        at = Some(row + 1);
+                        free = true;
+                    } else {
+                        break;
+                    }
+                }
+                Srcline::C(_) => break,
+                _ => {}
+            }
+        }
+        match at {
+            Some(row) => (row, free),
+            None => {
+                let free = (0..self.lines.len())
+                    .any(|row| self.free_head(row).is_some_and(|x| x.starts_with("DCL-")));
+                (0, free)
+            }
+        }
+    }
+
+    // insert declarations in the style of the others
+    fn declare(&self, fixed: Vec<String>, free: Vec<String>) -> TextEdit {
+        let (row, is_free) = self.declarations();
+        let lines = match is_free {
+            true => free,
+            false => fixed,
+        };
+        insert(row, &lines)
+    }
+}
+
+fn at(row: usize, col: usize) -> Position {
+    Position::from((row, col))
+}
+
+fn insert(row: usize, lines: &[String]) -> TextEdit {
+    TextEdit {
+        span: Span {
+            start: at(row, 0),
+            end: at(row, 0),
+        },
+        new_text: lines.iter().map(|x| format!("{}\n", x)).collect(),
+    }
+}
+
+// replace the whole lines `start..end`
+fn replace_lines(start: usize, end: usize, lines: &[String]) -> TextEdit {
+    TextEdit {
+        span: Span {
+            start: at(start, 0),
+            end: at(end, 0),
+        },
+        new_text: lines.iter().map(|x| format!("{}\n", x)).collect(),
+    }
+}
+
+fn place(chars: &mut [char], from: usize, text: &str) {
+    for (i, c) in text.chars().enumerate() {
+        if let Some(x) = chars.get_mut(from + i) {
+            *x = c;
+        }
+    }
+}
+
+fn right(chars: &mut [char], to: usize, text: &str) {
+    let len = text.chars().count();
+    place(chars, to.saturating_sub(len), text);
+}
+
+/* A fixed-format D spec, `from` and `to` are right aligned and `datatype`
+ * goes in column 40. Names longer than the name columns continue with `...`.
+ */
+fn dspec(name: &str, deftype: &str, from: &str, to: &str, datatype: &str, kw: &str) -> Vec<String> {
+    let mut out = vec![];
+    let mut chars = vec![' '; 80];
+    chars[5] = 'D';
+    if name.trim_start().chars().count() > 14 {
+        let mut long = chars.clone();
+        place(&mut long, 7, &format!("{}...", name.trim_start()));
+        out.push(long.iter().collect::<String>().trim_end().to_string());
+    } else {
+        let indent = name.len() - name.trim_start().len();
+        place(&mut chars, 7 + indent, name.trim_start());
+    }
+    place(&mut chars, 23, deftype);
+    right(&mut chars, 32, from);
+    right(&mut chars, 39, to);
+    place(&mut chars, 39, datatype);
+    place(&mut chars, 43, kw);
+    out.push(chars.iter().collect::<String>().trim_end().to_string());
+    out
+}
+
+// a calc spec with an extended factor 2, longer expressions continue below
+fn extf2(prefix: &[char], opcode: &str, factor2: &str) -> Vec<String> {
+    let mut out = vec![];
+    let pieces = crate::convert::wrap(factor2, 80 - 35);
+    for (i, piece) in pieces.iter().enumerate() {
+        let mut chars = vec![' '; 80];
+        chars[5] = 'C';
+        if i == 0 {
+            place(&mut chars, 0, &prefix[..11].iter().collect::<String>());
+            place(&mut chars, 25, opcode);
+        }
+        place(&mut chars, 35, piece);
+        out.push(chars.iter().collect::<String>().trim_end().to_string());
+    }
+    out
+}
+
+/* Code actions for the construct at `row` and `col`, both zero based. Each
+ * one is only offered when it doesn't change what the program does.
+ */
+pub fn query_code_actions(input: &str, row: usize, col: usize) -> Vec<CodeAction> {
+    let normalized = normalize_width(input, 100);
+    let Ok(cst) = parse_cst(&normalized) else {
+        return vec![];
+    };
+    let symbols = SymbolTable::from(&cst);
+    let lines = normalized
+        .split('\n')
+        .map(|line| {
+            let mut chars = line.chars().collect::<Vec<char>>();
+            chars.resize(chars.len().max(100), ' ');
+            chars
+        })
+        .collect();
+    let source = Source {
+        cst,
+        lines,
+        symbols,
+    };
+    if row >= source.cst.lines.len() {
+        return vec![];
+    }
+    [
+        eval::action(&source, row),
+        indicator::action(&source, row, col),
+        prototype::action(&source, row),
+        klist::action(&source, row, col),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> String {
+        r#"
+     FCowEvt    UF A E           K DISK
+     D LastId          S              8  0
+     D Name            S             10
+     D Cmd             S            200
+     D Len             S             15  5
+     C     EvtKey        KLIST
+     C                   KFLD                    LastId
+     C                   KFLD                    Name
+     C     EvtKey        CHAIN     CowEvt
+     C     5             ADD       LastId        LastId
+     C                   MOVEL(P)  'ZEVT'        Name
+     C                   Z-ADD     1             LastId               50
+     C                   EVAL      *IN50 = *ON
+     C   50              SETOFF                                       50
+     C                   CALL      'QCMDEXC'
+     C                   PARM                    Cmd
+     C                   PARM                    Len
+      /free
+       if *in(50);
+         setll EvtKey CowEvt;
+       endif;
+      /end-free
+"#[1..]
+            .to_string()
+    }
+
+    // every action offered for the line, and the line once it's applied
+    fn actions(input: &str, row: usize, col: usize) -> Vec<String> {
+        query_code_actions(input, row, col)
+            .into_iter()
+            .map(|action| format!("{}\n{}", action.title, action.apply(input)))
+            .collect()
+    }
+
+    #[test]
+    fn test_code_actions_snapshot() {
+        let input = fixture();
+        let observed = [(9, 30), (10, 30), (12, 37), (14, 30), (5, 30), (18, 12)]
+            .iter()
+            .map(|(row, col)| actions(&input, *row, *col))
+            .collect::<Vec<Vec<String>>>();
+        insta::assert_yaml_snapshot!(observed);
+    }
+
+    #[test]
+    fn test_code_actions_not_offered() {
+        let input = fixture();
+        // resulting indicators have no EVAL equivalent
+        assert!(actions(&input, 11, 30).is_empty());
+        // the main declarations aren't calculations
+        assert!(actions(&input, 1, 10).is_empty());
+        assert!(actions(&input, 99, 0).is_empty());
+    }
+
+    #[test]
+    fn test_move_between_types() {
+        let input = r#"
+     D Count           S              8  0
+     D Name            S              8
+     D Code            S              8A
+     C                   MOVE      Count         Name
+     C                   MOVE      Name          Count
+     C                   MOVE      Code          Name
+     C                   MOVEL(P)  *BLANKS       Code
+"#[1..]
+            .to_string();
+        let titles = |row: usize| {
+            query_code_actions(&input, row, 30)
+                .into_iter()
+                .map(|x| x.title)
+                .collect::<Vec<String>>()
+        };
+        // a number moves by digits, EVAL wouldn't compile
+        assert!(titles(3).is_empty());
+        assert!(titles(4).is_empty());
+        assert_eq!(titles(5), vec!["Convert MOVE to EVALR"]);
+        assert_eq!(titles(6), vec!["Convert MOVEL to EVAL"]);
+    }
+
+    #[test]
+    fn test_apply() {
+        let action = CodeAction::new(
+            "test".to_string(),
+            vec![
+                insert(0, &["first".to_string()]),
+                TextEdit {
+                    span: Span {
+                        start: at(1, 1),
+                        end: at(2, 1),
+                    },
+                    new_text: "-".to_string(),
+                },
+            ],
+        );
+        assert_eq!(action.apply("abc\ndef\nghi\n"), "first\nabc\nd-hi\n");
+    }
+}
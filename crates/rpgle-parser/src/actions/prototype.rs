@@ -0,0 +1,108 @@
+use super::{dspec, extf2, replace_lines, CodeAction, Source};
+use crate::analysis::SymbolKind;
+use crate::convert::calc::Calc;
+use crate::cst::{CSrcline, Srcline};
+
+fn calc(source: &Source, row: usize) -> Option<Calc> {
+    match source.cst.lines.get(row) {
+        Some(Srcline::C(CSrcline::Traditional { .. })) => {
+            Some(Calc::traditional(&source.lines[row]))
+        }
+        _ => None,
+    }
+}
+
+// 'MYLIB/ZEVT' -> ZEVT
+fn program(factor2: &str) -> Option<String> {
+    let name = factor2.strip_prefix('\'')?.strip_suffix('\'')?;
+    let name = name.rsplit('/').next().unwrap_or_default().trim();
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '@' || c == '$' || c == '#')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || "@$#_".contains(c));
+    valid.then(|| name.to_string())
+}
+
+// the name of a prototype that already calls `program`
+fn existing(source: &Source, program: &str) -> Option<String> {
+    let extpgm = format!("EXTPGM('{}')", program.to_uppercase());
+    source
+        .symbols
+        .definitions
+        .iter()
+        .filter(|x| x.kind == SymbolKind::Prototype)
+        .find(|x| {
+            let mut row = x.span.start.row;
+            let mut text = source.text(row, 0, 100);
+            while let Some(Srcline::DCont { .. }) = source.cst.lines.get(row + 1) {
+                row += 1;
+                text.push_str(&source.text(row, 0, 100));
+            }
+            text.to_uppercase().replace(' ', "").contains(&extpgm)
+        })
+        .map(|x| x.name.clone())
+}
+
+/* A dynamic CALL and its PARM lines to a CALLP of an EXTPGM prototype, adding
+ * the prototype when there isn't one. Parameters are defined LIKE the fields
+ * that were passed.
+ */
+pub fn action(source: &Source, row: usize) -> Option<CodeAction> {
+    let mut start = row;
+    while start > 0 && calc(source, start).is_some_and(|x| x.opcode == "PARM") {
+        start -= 1;
+    }
+    let call = calc(source, start)?;
+    if call.opcode != "CALL" || !call.factor1.is_empty() || !call.result.is_empty() {
+        return None;
+    }
+    if call.indicators.iter().any(|x| !x.is_empty()) || !call.control_level.is_empty() {
+        return None;
+    }
+    let program = program(&call.factor2)?;
+    let mut parameters = vec![];
+    let mut end = start + 1;
+    while let Some(parm) = calc(source, end).filter(|x| x.opcode == "PARM") {
+        // values copied in and out, or a field defined here, have nowhere to go
+        let copied = !parm.factor1.is_empty() || !parm.factor2.is_empty();
+        if copied || !parm.length.is_empty() || !parm.conditioning.is_empty() {
+            return None;
+        }
+        parameters.push(parm.result);
+        end += 1;
+    }
+    let mut edits = vec![];
+    let name = match existing(source, &program) {
+        Some(name) => name,
+        None if source.is_defined(&program) => return None,
+        None => {
+            let extpgm = format!("EXTPGM('{}')", program);
+            let mut fixed = dspec(&program, "PR", "", "", "", &extpgm);
+            let mut free = vec![format!("       dcl-pr {0} extpgm('{0}');", program)];
+            // a parameter without a name would read as a keyword continuation
+            for parameter in &parameters {
+                let label = parameter.rsplit('.').next().unwrap_or_default();
+                let like = format!("LIKE({})", parameter);
+                fixed.extend(dspec(&format!(" {}", label), "", "", "", "", &like));
+                free.push(format!("         {} like({});", label, parameter));
+            }
+            free.push("       end-pr;".to_string());
+            edits.push(source.declare(fixed, free));
+            program.clone()
+        }
+    };
+    let target = match parameters.is_empty() {
+        true => name.clone(),
+        false => format!("{}({})", name, parameters.join(":")),
+    };
+    let lines = extf2(&source.lines[start], "CALLP", &target);
+    edits.push(replace_lines(start, end, &lines));
+    let title = match edits.len() {
+        1 => format!("Call {} through its EXTPGM prototype", program),
+        _ => format!("Add an EXTPGM prototype for {}", program),
+    };
+    Some(CodeAction::new(title, edits))
+}
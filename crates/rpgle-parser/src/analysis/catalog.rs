@@ -0,0 +1,2154 @@
+use crate::cst::{CSrcline, Srcline, CST};
+use crate::meta::{Position, Span};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Builtin {
+    pub name: &'static str,
+    // (name, type) of each parameter, the ones past `min_args` are optional
+    pub parameters: &'static [(&'static str, &'static str)],
+    pub min_args: usize,
+    // the last parameter repeats, ex: %MAX(item:item{:item...})
+    pub variadic: bool,
+    pub returns: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Opcode {
+    // a comparing family is named with `xx`, ex: IFxx for IFEQ and IFGT
+    pub name: &'static str,
+    // the allowed operation extenders, ex: HMR for EVAL(H)
+    pub extenders: &'static str,
+    // what each operand is for, empty when it isn't used
+    pub factor1: &'static str,
+    pub factor2: &'static str,
+    pub result: &'static str,
+    pub description: &'static str,
+}
+
+// A `%name` in an expression with the number of arguments it was given
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BuiltinCall {
+    // uppercased, with the `%`
+    pub name: String,
+    pub span: Span,
+    pub args: usize,
+}
+
+impl Builtin {
+    pub fn max_args(&self) -> Option<usize> {
+        match self.variadic {
+            true => None,
+            false => Some(self.parameters.len()),
+        }
+    }
+
+    pub fn accepts(&self, args: usize) -> bool {
+        args >= self.min_args && self.max_args().is_none_or(|max| args <= max)
+    }
+
+    // ex: 2 to 3 arguments
+    pub fn arity(&self) -> String {
+        let count = |n: usize| match n {
+            0 => "no arguments".to_string(),
+            1 => "1 argument".to_string(),
+            n => format!("{} arguments", n),
+        };
+        match self.max_args() {
+            None => format!("at least {}", count(self.min_args)),
+            Some(max) if max == self.min_args => count(max),
+            Some(max) => format!("{} to {}", self.min_args, count(max)),
+        }
+    }
+
+    // ex: %SUBST(string:start{:length}), optional parameters are braced
+    pub fn signature(&self) -> String {
+        if self.parameters.is_empty() {
+            return self.name.to_string();
+        }
+        let mut out = String::new();
+        let mut open = 0;
+        for (i, (name, _)) in self.parameters.iter().enumerate() {
+            let separator = if i == 0 { "" } else { ":" };
+            if i >= self.min_args {
+                out.push('{');
+                open += 1;
+            }
+            out.push_str(separator);
+            out.push_str(name);
+        }
+        if self.variadic {
+            out.push_str("{:...}");
+        }
+        out.push_str(&"}".repeat(open));
+        match self.min_args {
+            // the parens go away with the arguments, ex: %EOF{(file)}
+            0 => format!("{}{{({})}}", self.name, &out[1..out.len() - 1]),
+            _ => format!("{}({})", self.name, out),
+        }
+    }
+}
+
+const fn bif(
+    name: &'static str,
+    parameters: &'static [(&'static str, &'static str)],
+    min_args: usize,
+    variadic: bool,
+    returns: &'static str,
+    description: &'static str,
+) -> Builtin {
+    Builtin {
+        name,
+        parameters,
+        min_args,
+        variadic,
+        returns,
+        description,
+    }
+}
+
+const fn op(
+    name: &'static str,
+    extenders: &'static str,
+    factor1: &'static str,
+    factor2: &'static str,
+    result: &'static str,
+    description: &'static str,
+) -> Opcode {
+    Opcode {
+        name,
+        extenders,
+        factor1,
+        factor2,
+        result,
+        description,
+    }
+}
+
+const NUMERIC: &[(&str, &str)] = &[("numeric", "numeric")];
+const EXPRESSION: &[(&str, &str)] = &[("expression", "numeric or character")];
+const DURATION: &[(&str, &str)] = &[("number", "integer")];
+const FILE: &[(&str, &str)] = &[("file", "file or record format")];
+const PAIR: &[(&str, &str)] = &[("n", "numeric"), ("m", "numeric")];
+const BITS: &[(&str, &str)] = &[("expression", "integer or character"); 2];
+const LOOKUP: &[(&str, &str)] = &[
+    ("arg", "any"),
+    ("array", "array"),
+    ("start", "integer"),
+    ("elements", "integer"),
+];
+const TLOOKUP: &[(&str, &str)] = &[
+    ("arg", "any"),
+    ("search-table", "table"),
+    ("alt-table", "table"),
+];
+const CHECK: &[(&str, &str)] = &[
+    ("comparator", "string"),
+    ("base", "string"),
+    ("start", "integer"),
+];
+const SCAN: &[(&str, &str)] = &[
+    ("search", "string"),
+    ("source", "string"),
+    ("start", "integer"),
+    ("length", "integer"),
+];
+const CASE: &[(&str, &str)] = &[
+    ("string", "string"),
+    ("start", "integer"),
+    ("length", "integer"),
+];
+const TRIM: &[(&str, &str)] = &[("string", "string"), ("characters", "string")];
+const SIDE: &[(&str, &str)] = &[
+    ("string", "string"),
+    ("length", "integer"),
+    ("*NATURAL", "option"),
+];
+const ITEMS: &[(&str, &str)] = &[("item", "any"), ("item", "any")];
+const ARRAY: &[(&str, &str)] = &[
+    ("array", "array"),
+    ("start", "integer"),
+    ("elements", "integer"),
+];
+const DATETIME: &[(&str, &str)] = &[("expression", "any"), ("format", "format")];
+const VARIABLE: &[(&str, &str)] = &[("variable", "any")];
+const PARAMETER: &[(&str, &str)] = &[("parameter", "parameter")];
+const DOCUMENT: &[(&str, &str)] = &[("document", "string"), ("options", "string")];
+
+// The ILE RPG built-in functions, sorted by name
+pub const BUILTIN_CATALOG: [Builtin; 105] = [
+    bif(
+        "%ABS",
+        NUMERIC,
+        1,
+        false,
+        "numeric",
+        "Absolute value of a numeric expression",
+    ),
+    bif(
+        "%ADDR",
+        &[("variable", "any"), ("*DATA", "option")],
+        1,
+        false,
+        "pointer",
+        "Address of a variable, or of the data of a varying one with *DATA",
+    ),
+    bif(
+        "%ALLOC",
+        &[("size", "integer")],
+        1,
+        false,
+        "pointer",
+        "Allocates heap storage",
+    ),
+    bif(
+        "%BITAND",
+        BITS,
+        2,
+        true,
+        "same as the arguments",
+        "Bitwise AND of the arguments",
+    ),
+    bif(
+        "%BITNOT",
+        &[("expression", "integer or character")],
+        1,
+        false,
+        "same as the argument",
+        "Bitwise NOT of the argument",
+    ),
+    bif(
+        "%BITOR",
+        BITS,
+        2,
+        true,
+        "same as the arguments",
+        "Bitwise OR of the arguments",
+    ),
+    bif(
+        "%BITXOR",
+        BITS,
+        2,
+        false,
+        "same as the arguments",
+        "Bitwise exclusive OR of the arguments",
+    ),
+    bif(
+        "%CHAR",
+        &[("expression", "any"), ("format", "format or ccsid")],
+        1,
+        false,
+        "character",
+        "Converts a value to character",
+    ),
+    bif(
+        "%CHARCOUNT",
+        &[("expression", "string")],
+        1,
+        false,
+        "integer",
+        "Number of characters, not bytes, in a string",
+    ),
+    bif(
+        "%CHECK",
+        CHECK,
+        2,
+        false,
+        "integer",
+        "Position of the first character of base that isn't in comparator",
+    ),
+    bif(
+        "%CHECKR",
+        CHECK,
+        2,
+        false,
+        "integer",
+        "Position of the last character of base that isn't in comparator",
+    ),
+    bif(
+        "%CONCAT",
+        &[
+            ("separator", "string"),
+            ("item", "string"),
+            ("item", "string"),
+        ],
+        3,
+        true,
+        "string",
+        "Concatenates strings with a separator",
+    ),
+    bif(
+        "%CONCATARR",
+        &[("separator", "string"), ("array", "array")],
+        2,
+        false,
+        "string",
+        "Concatenates the elements of an array with a separator",
+    ),
+    bif(
+        "%DATA",
+        DOCUMENT,
+        1,
+        false,
+        "document",
+        "The document and options of DATA-INTO or DATA-GEN",
+    ),
+    bif(
+        "%DATE",
+        DATETIME,
+        0,
+        false,
+        "date",
+        "Converts to a date, the current date without arguments",
+    ),
+    bif(
+        "%DAYS",
+        DURATION,
+        1,
+        false,
+        "duration",
+        "A number of days for date arithmetic",
+    ),
+    bif(
+        "%DEC",
+        &[
+            ("expression", "any"),
+            ("digits", "integer"),
+            ("decimals", "integer"),
+        ],
+        1,
+        false,
+        "packed",
+        "Converts to packed decimal",
+    ),
+    bif(
+        "%DECH",
+        &[
+            ("expression", "numeric or character"),
+            ("digits", "integer"),
+            ("decimals", "integer"),
+        ],
+        3,
+        false,
+        "packed",
+        "Converts to packed decimal with half adjust",
+    ),
+    bif(
+        "%DECPOS",
+        NUMERIC,
+        1,
+        false,
+        "integer",
+        "Number of decimal positions of a numeric",
+    ),
+    bif(
+        "%DIFF",
+        &[
+            ("op1", "date, time or timestamp"),
+            ("op2", "date, time or timestamp"),
+            ("unit", "duration code"),
+            ("fraction", "integer"),
+        ],
+        3,
+        false,
+        "numeric",
+        "Difference between two dates, times or timestamps",
+    ),
+    bif(
+        "%DIV",
+        PAIR,
+        2,
+        false,
+        "integer",
+        "Integer quotient of a division",
+    ),
+    bif(
+        "%EDITC",
+        &[
+            ("numeric", "numeric"),
+            ("editcode", "character"),
+            ("currency", "*ASTFILL, *CURSYM or character"),
+        ],
+        2,
+        false,
+        "character",
+        "Edits a numeric with an edit code",
+    ),
+    bif(
+        "%EDITFLT",
+        NUMERIC,
+        1,
+        false,
+        "character",
+        "Converts a numeric to its external float representation",
+    ),
+    bif(
+        "%EDITW",
+        &[("numeric", "numeric"), ("editword", "character")],
+        2,
+        false,
+        "character",
+        "Edits a numeric with an edit word",
+    ),
+    bif(
+        "%ELEM",
+        &[
+            ("array", "array, table or multiple occurrence ds"),
+            ("*ALLOC", "option"),
+        ],
+        1,
+        false,
+        "integer",
+        "Number of elements or occurrences",
+    ),
+    bif(
+        "%EOF",
+        FILE,
+        0,
+        false,
+        "indicator",
+        "Whether the last read or positioning reached end of file",
+    ),
+    bif(
+        "%EQUAL",
+        FILE,
+        0,
+        false,
+        "indicator",
+        "Whether SETLL or LOOKUP found an exact match",
+    ),
+    bif(
+        "%ERROR",
+        &[],
+        0,
+        false,
+        "indicator",
+        "Whether the last operation with an E extender failed",
+    ),
+    bif(
+        "%FIELDS",
+        &[("name", "field")],
+        1,
+        true,
+        "field list",
+        "The fields UPDATE or a key list operates on",
+    ),
+    bif(
+        "%FLOAT",
+        EXPRESSION,
+        1,
+        false,
+        "float",
+        "Converts to floating point",
+    ),
+    bif(
+        "%FOUND",
+        FILE,
+        0,
+        false,
+        "indicator",
+        "Whether the last CHAIN, DELETE, SETGT, SETLL, CHECK, LOOKUP or SCAN found something",
+    ),
+    bif(
+        "%GEN",
+        DOCUMENT,
+        1,
+        false,
+        "generator",
+        "The generator and options of DATA-GEN",
+    ),
+    bif(
+        "%GRAPH",
+        &[("expression", "any"), ("ccsid", "integer")],
+        1,
+        false,
+        "graphic",
+        "Converts to graphic",
+    ),
+    bif(
+        "%HANDLER",
+        &[("procedure", "procedure"), ("communication-area", "any")],
+        2,
+        false,
+        "handler",
+        "The handler of XML-SAX or DATA-INTO",
+    ),
+    bif(
+        "%HIVAL",
+        VARIABLE,
+        1,
+        false,
+        "same as the argument",
+        "The highest value a variable can hold",
+    ),
+    bif(
+        "%HOURS",
+        DURATION,
+        1,
+        false,
+        "duration",
+        "A number of hours for time arithmetic",
+    ),
+    bif(
+        "%INT",
+        EXPRESSION,
+        1,
+        false,
+        "integer",
+        "Converts to integer",
+    ),
+    bif(
+        "%INTH",
+        EXPRESSION,
+        1,
+        false,
+        "integer",
+        "Converts to integer with half adjust",
+    ),
+    bif(
+        "%KDS",
+        &[("ds", "data structure"), ("keys", "integer")],
+        1,
+        false,
+        "key list",
+        "Uses the subfields of a data structure as a search argument",
+    ),
+    bif(
+        "%LEFT",
+        SIDE,
+        2,
+        false,
+        "string",
+        "The leftmost characters of a string",
+    ),
+    bif(
+        "%LEN",
+        &[("expression", "any"), ("*MAX", "option")],
+        1,
+        false,
+        "integer",
+        "Length of a value, or the current length of a varying one",
+    ),
+    bif(
+        "%LIST",
+        &[("item", "any")],
+        1,
+        true,
+        "array",
+        "A temporary array of the items",
+    ),
+    bif(
+        "%LOOKUP",
+        LOOKUP,
+        2,
+        false,
+        "integer",
+        "Index of the array element equal to arg",
+    ),
+    bif(
+        "%LOOKUPGE",
+        LOOKUP,
+        2,
+        false,
+        "integer",
+        "Index of the array element closest to and not less than arg",
+    ),
+    bif(
+        "%LOOKUPGT",
+        LOOKUP,
+        2,
+        false,
+        "integer",
+        "Index of the array element closest to and greater than arg",
+    ),
+    bif(
+        "%LOOKUPLE",
+        LOOKUP,
+        2,
+        false,
+        "integer",
+        "Index of the array element closest to and not greater than arg",
+    ),
+    bif(
+        "%LOOKUPLT",
+        LOOKUP,
+        2,
+        false,
+        "integer",
+        "Index of the array element closest to and less than arg",
+    ),
+    bif(
+        "%LOVAL",
+        VARIABLE,
+        1,
+        false,
+        "same as the argument",
+        "The lowest value a variable can hold",
+    ),
+    bif(
+        "%LOWER",
+        CASE,
+        1,
+        false,
+        "string",
+        "Converts a string, or a part of it, to lowercase",
+    ),
+    bif(
+        "%MAX",
+        ITEMS,
+        2,
+        true,
+        "same as the arguments",
+        "The largest of the arguments",
+    ),
+    bif(
+        "%MAXARR",
+        ARRAY,
+        1,
+        false,
+        "integer",
+        "Index of the largest array element",
+    ),
+    bif(
+        "%MIN",
+        ITEMS,
+        2,
+        true,
+        "same as the arguments",
+        "The smallest of the arguments",
+    ),
+    bif(
+        "%MINARR",
+        ARRAY,
+        1,
+        false,
+        "integer",
+        "Index of the smallest array element",
+    ),
+    bif(
+        "%MINUTES",
+        DURATION,
+        1,
+        false,
+        "duration",
+        "A number of minutes for time arithmetic",
+    ),
+    bif(
+        "%MONTHS",
+        DURATION,
+        1,
+        false,
+        "duration",
+        "A number of months for date arithmetic",
+    ),
+    bif(
+        "%MSECONDS",
+        DURATION,
+        1,
+        false,
+        "duration",
+        "A number of microseconds for time arithmetic",
+    ),
+    bif(
+        "%MSG",
+        &[
+            ("msgid", "character"),
+            ("msgfile", "character"),
+            ("replacement", "any"),
+        ],
+        2,
+        false,
+        "message",
+        "A message from a message file for SND-MSG",
+    ),
+    bif(
+        "%NULLIND",
+        &[("field", "null-capable field")],
+        1,
+        false,
+        "indicator",
+        "Gets or sets the null indicator of a field",
+    ),
+    bif(
+        "%OCCUR",
+        &[("ds", "multiple occurrence ds")],
+        1,
+        false,
+        "integer",
+        "Gets or sets the current occurrence of a data structure",
+    ),
+    bif(
+        "%OMITTED",
+        PARAMETER,
+        1,
+        false,
+        "indicator",
+        "Whether *OMIT was passed for a parameter",
+    ),
+    bif(
+        "%OPEN",
+        &[("file", "file")],
+        1,
+        false,
+        "indicator",
+        "Whether a file is open",
+    ),
+    bif(
+        "%PADDR",
+        &[("procedure", "procedure name or prototype")],
+        1,
+        false,
+        "procedure pointer",
+        "Address of a procedure",
+    ),
+    bif(
+        "%PARMNUM",
+        PARAMETER,
+        1,
+        false,
+        "integer",
+        "Position of a parameter in the parameter list",
+    ),
+    bif(
+        "%PARMS",
+        &[],
+        0,
+        false,
+        "integer",
+        "Number of parameters passed",
+    ),
+    bif(
+        "%PARSER",
+        DOCUMENT,
+        1,
+        false,
+        "parser",
+        "The parser and options of DATA-INTO",
+    ),
+    bif(
+        "%PASSED",
+        PARAMETER,
+        1,
+        false,
+        "indicator",
+        "Whether a parameter was passed and not omitted",
+    ),
+    bif(
+        "%PROC",
+        &[("*OWNER", "option")],
+        0,
+        false,
+        "character",
+        "Name of the current procedure",
+    ),
+    bif(
+        "%RANGE",
+        &[("lower", "any"), ("upper", "any")],
+        2,
+        false,
+        "range",
+        "A range for the IN operator",
+    ),
+    bif(
+        "%REALLOC",
+        &[("pointer", "pointer"), ("size", "integer")],
+        2,
+        false,
+        "pointer",
+        "Resizes heap storage",
+    ),
+    bif(
+        "%REM",
+        PAIR,
+        2,
+        false,
+        "integer",
+        "Remainder of an integer division",
+    ),
+    bif(
+        "%REPLACE",
+        &[
+            ("replacement", "string"),
+            ("source", "string"),
+            ("start", "integer"),
+            ("length", "integer"),
+        ],
+        2,
+        false,
+        "string",
+        "Replaces a part of a string",
+    ),
+    bif(
+        "%RIGHT",
+        SIDE,
+        2,
+        false,
+        "string",
+        "The rightmost characters of a string",
+    ),
+    bif(
+        "%SCAN",
+        SCAN,
+        2,
+        false,
+        "integer",
+        "Position of the first occurrence of search in source",
+    ),
+    bif(
+        "%SCANR",
+        SCAN,
+        2,
+        false,
+        "integer",
+        "Position of the last occurrence of search in source",
+    ),
+    bif(
+        "%SCANRPL",
+        &[
+            ("scan", "string"),
+            ("replacement", "string"),
+            ("source", "string"),
+            ("start", "integer"),
+            ("length", "integer"),
+        ],
+        3,
+        false,
+        "string",
+        "Replaces every occurrence of scan in source",
+    ),
+    bif(
+        "%SECONDS",
+        DURATION,
+        1,
+        false,
+        "duration",
+        "A number of seconds for time arithmetic",
+    ),
+    bif(
+        "%SHTDN",
+        &[],
+        0,
+        false,
+        "indicator",
+        "Whether the system operator asked for a shutdown",
+    ),
+    bif(
+        "%SIZE",
+        &[("variable", "any"), ("*ALL", "option")],
+        1,
+        false,
+        "integer",
+        "Number of bytes a variable occupies",
+    ),
+    bif(
+        "%SPLIT",
+        &[
+            ("string", "string"),
+            ("separators", "string"),
+            ("*ALLSEP", "option"),
+        ],
+        1,
+        false,
+        "array",
+        "A temporary array of the substrings",
+    ),
+    bif("%SQRT", NUMERIC, 1, false, "numeric", "Square root"),
+    bif(
+        "%STATUS",
+        FILE,
+        0,
+        false,
+        "integer",
+        "The last program or file status code",
+    ),
+    bif(
+        "%STR",
+        &[("pointer", "pointer"), ("max-length", "integer")],
+        1,
+        false,
+        "string",
+        "Gets or sets a null terminated string",
+    ),
+    bif("%SUBARR", ARRAY, 2, false, "array", "A part of an array"),
+    bif(
+        "%SUBDT",
+        &[
+            ("value", "date, time or timestamp"),
+            ("unit", "duration code"),
+            ("digits", "integer"),
+            ("decimals", "integer"),
+        ],
+        2,
+        false,
+        "integer",
+        "A part of a date, time or timestamp",
+    ),
+    bif(
+        "%SUBST",
+        &[
+            ("string", "string"),
+            ("start", "integer"),
+            ("length", "integer"),
+        ],
+        2,
+        false,
+        "string",
+        "A part of a string",
+    ),
+    bif(
+        "%TARGET",
+        &[("program", "character"), ("offset", "integer")],
+        1,
+        false,
+        "target",
+        "The target of SND-MSG",
+    ),
+    bif(
+        "%THIS",
+        &[],
+        0,
+        false,
+        "pointer",
+        "The class instance of a Java native method",
+    ),
+    bif(
+        "%TIME",
+        DATETIME,
+        0,
+        false,
+        "time",
+        "Converts to a time, the current time without arguments",
+    ),
+    bif(
+        "%TIMESTAMP",
+        &[
+            ("expression", "any"),
+            ("format", "format"),
+            ("fraction", "integer"),
+        ],
+        0,
+        false,
+        "timestamp",
+        "Converts to a timestamp, the current timestamp without arguments",
+    ),
+    bif(
+        "%TLOOKUP",
+        TLOOKUP,
+        2,
+        false,
+        "indicator",
+        "Whether a table element equals arg",
+    ),
+    bif(
+        "%TLOOKUPGE",
+        TLOOKUP,
+        2,
+        false,
+        "indicator",
+        "Whether a table element is not less than arg",
+    ),
+    bif(
+        "%TLOOKUPGT",
+        TLOOKUP,
+        2,
+        false,
+        "indicator",
+        "Whether a table element is greater than arg",
+    ),
+    bif(
+        "%TLOOKUPLE",
+        TLOOKUP,
+        2,
+        false,
+        "indicator",
+        "Whether a table element is not greater than arg",
+    ),
+    bif(
+        "%TLOOKUPLT",
+        TLOOKUP,
+        2,
+        false,
+        "indicator",
+        "Whether a table element is less than arg",
+    ),
+    bif(
+        "%TRIM",
+        TRIM,
+        1,
+        false,
+        "string",
+        "Removes leading and trailing blanks or characters",
+    ),
+    bif(
+        "%TRIML",
+        TRIM,
+        1,
+        false,
+        "string",
+        "Removes leading blanks or characters",
+    ),
+    bif(
+        "%TRIMR",
+        TRIM,
+        1,
+        false,
+        "string",
+        "Removes trailing blanks or characters",
+    ),
+    bif(
+        "%UCS2",
+        &[("expression", "any"), ("ccsid", "integer")],
+        1,
+        false,
+        "ucs2",
+        "Converts to UCS-2",
+    ),
+    bif(
+        "%UNS",
+        EXPRESSION,
+        1,
+        false,
+        "unsigned",
+        "Converts to unsigned integer",
+    ),
+    bif(
+        "%UNSH",
+        EXPRESSION,
+        1,
+        false,
+        "unsigned",
+        "Converts to unsigned integer with half adjust",
+    ),
+    bif(
+        "%UPPER",
+        CASE,
+        1,
+        false,
+        "string",
+        "Converts a string, or a part of it, to uppercase",
+    ),
+    bif(
+        "%XFOOT",
+        &[("array", "numeric array")],
+        1,
+        false,
+        "numeric",
+        "Sum of the elements of an array",
+    ),
+    bif(
+        "%XLATE",
+        &[
+            ("from", "string"),
+            ("to", "string"),
+            ("string", "string"),
+            ("start", "integer"),
+        ],
+        3,
+        false,
+        "string",
+        "Translates characters of from to the ones of to",
+    ),
+    bif(
+        "%XML",
+        DOCUMENT,
+        1,
+        false,
+        "document",
+        "The document and options of XML-INTO or XML-SAX",
+    ),
+    bif(
+        "%YEARS",
+        DURATION,
+        1,
+        false,
+        "duration",
+        "A number of years for date arithmetic",
+    ),
+];
+
+// The operations of both calc spec forms, sorted by name
+pub const OPCODE_CATALOG: [Opcode; 122] = [
+    op(
+        "ACQ",
+        "E",
+        "device name",
+        "WORKSTN file",
+        "",
+        "Acquires a program device for a multiple device file",
+    ),
+    op(
+        "ADD",
+        "H",
+        "addend (optional)",
+        "addend",
+        "sum",
+        "Adds factor 2 to factor 1, or to the result field",
+    ),
+    op(
+        "ADDDUR",
+        "E",
+        "date, time or timestamp (optional)",
+        "duration:code",
+        "date, time or timestamp",
+        "Adds a duration to a date, time or timestamp",
+    ),
+    op(
+        "ANDxx",
+        "",
+        "comparand",
+        "comparand",
+        "",
+        "Adds a condition to the IFxx, DOUxx, DOWxx or WHENxx above",
+    ),
+    op(
+        "BEGSR",
+        "",
+        "subroutine name",
+        "",
+        "",
+        "Starts a subroutine",
+    ),
+    op(
+        "BITOFF",
+        "",
+        "",
+        "bit numbers",
+        "character field",
+        "Sets bits off",
+    ),
+    op(
+        "BITON",
+        "",
+        "",
+        "bit numbers",
+        "character field",
+        "Sets bits on",
+    ),
+    op(
+        "CABxx",
+        "",
+        "comparand",
+        "comparand",
+        "label",
+        "Compares and branches to a TAG",
+    ),
+    op(
+        "CALL",
+        "E",
+        "",
+        "program name",
+        "PLIST name (optional)",
+        "Calls a program",
+    ),
+    op(
+        "CALLB",
+        "DE",
+        "",
+        "procedure name or pointer",
+        "PLIST name (optional)",
+        "Calls a bound procedure",
+    ),
+    op(
+        "CALLP",
+        "EMR",
+        "",
+        "prototyped call",
+        "",
+        "Calls a prototyped program or procedure",
+    ),
+    op(
+        "CASxx",
+        "",
+        "comparand",
+        "comparand",
+        "subroutine name",
+        "Compares and runs a subroutine",
+    ),
+    op(
+        "CAT",
+        "P",
+        "string (optional)",
+        "string:blanks",
+        "target",
+        "Concatenates two strings",
+    ),
+    op(
+        "CHAIN",
+        "NE",
+        "search argument",
+        "file or record format",
+        "data structure (optional)",
+        "Reads the record matching a key or relative record number",
+    ),
+    op(
+        "CHECK",
+        "E",
+        "comparator",
+        "base:start",
+        "position",
+        "Finds the first character of base not in comparator",
+    ),
+    op(
+        "CHECKR",
+        "E",
+        "comparator",
+        "base:start",
+        "position",
+        "Finds the last character of base not in comparator",
+    ),
+    op(
+        "CLEAR",
+        "",
+        "*NOKEY (optional)",
+        "*ALL (optional)",
+        "variable, structure or record format",
+        "Sets a variable to the default of its type",
+    ),
+    op("CLOSE", "E", "", "file or *ALL", "", "Closes files"),
+    op(
+        "COMMIT",
+        "E",
+        "boundary (optional)",
+        "",
+        "",
+        "Commits the pending database changes",
+    ),
+    op(
+        "COMP",
+        "",
+        "comparand",
+        "comparand",
+        "",
+        "Compares and sets the resulting indicators",
+    ),
+    op(
+        "DATA-GEN",
+        "EH",
+        "",
+        "source, %DATA and %GEN",
+        "",
+        "Generates a document from a variable",
+    ),
+    op(
+        "DATA-INTO",
+        "EH",
+        "",
+        "receiver or %HANDLER, %DATA and %PARSER",
+        "",
+        "Parses a document into a variable",
+    ),
+    op(
+        "DEALLOC",
+        "EN",
+        "",
+        "",
+        "pointer",
+        "Frees storage from %ALLOC",
+    ),
+    op(
+        "DEFINE",
+        "",
+        "*LIKE or *DTAARA",
+        "source field or data area",
+        "target field",
+        "Defines a field like another or a data area",
+    ),
+    op(
+        "DELETE",
+        "E",
+        "search argument (optional)",
+        "file or record format",
+        "",
+        "Deletes a record",
+    ),
+    op(
+        "DIV",
+        "H",
+        "dividend (optional)",
+        "divisor",
+        "quotient",
+        "Divides factor 1, or the result field, by factor 2",
+    ),
+    op(
+        "DO",
+        "",
+        "start (optional)",
+        "limit (optional)",
+        "index (optional)",
+        "Runs a group of operations a number of times",
+    ),
+    op(
+        "DOU",
+        "MR",
+        "",
+        "indicator expression",
+        "",
+        "Runs a group of operations until a condition is true",
+    ),
+    op(
+        "DOUxx",
+        "",
+        "comparand",
+        "comparand",
+        "",
+        "Runs a group of operations until a comparison is true",
+    ),
+    op(
+        "DOW",
+        "MR",
+        "",
+        "indicator expression",
+        "",
+        "Runs a group of operations while a condition is true",
+    ),
+    op(
+        "DOWxx",
+        "",
+        "comparand",
+        "comparand",
+        "",
+        "Runs a group of operations while a comparison is true",
+    ),
+    op(
+        "DSPLY",
+        "E",
+        "message",
+        "message queue (optional)",
+        "response (optional)",
+        "Displays a message and reads an optional response",
+    ),
+    op(
+        "DUMP",
+        "A",
+        "identifier (optional)",
+        "",
+        "",
+        "Writes a formatted dump of the program",
+    ),
+    op("ELSE", "", "", "", "", "Starts the false branch of an IF"),
+    op(
+        "ELSEIF",
+        "MR",
+        "",
+        "indicator expression",
+        "",
+        "Starts a branch of an IF with its own condition",
+    ),
+    op(
+        "END",
+        "",
+        "",
+        "increment (optional)",
+        "",
+        "Ends a DO, DOU, DOW, IF, SELECT or CASxx group",
+    ),
+    op("ENDCS", "", "", "", "", "Ends a CASxx group"),
+    op(
+        "ENDDO",
+        "",
+        "",
+        "increment (optional)",
+        "",
+        "Ends a DO, DOU or DOW group",
+    ),
+    op("ENDFOR", "", "", "", "", "Ends a FOR or FOR-EACH group"),
+    op("ENDIF", "", "", "", "", "Ends an IF group"),
+    op("ENDMON", "", "", "", "", "Ends a MONITOR group"),
+    op("ENDSL", "", "", "", "", "Ends a SELECT group"),
+    op(
+        "ENDSR",
+        "",
+        "label (optional)",
+        "return point (optional)",
+        "",
+        "Ends a subroutine",
+    ),
+    op(
+        "EVAL",
+        "HMR",
+        "",
+        "assignment",
+        "",
+        "Evaluates an expression and assigns the result",
+    ),
+    op(
+        "EVAL-CORR",
+        "",
+        "",
+        "target ds = source ds",
+        "",
+        "Assigns the subfields with the same names",
+    ),
+    op(
+        "EVALR",
+        "MR",
+        "",
+        "assignment",
+        "",
+        "Assigns a string right adjusted",
+    ),
+    op(
+        "EXCEPT",
+        "",
+        "",
+        "EXCPT name (optional)",
+        "",
+        "Writes exception output",
+    ),
+    op(
+        "EXFMT",
+        "E",
+        "",
+        "record format",
+        "data structure (optional)",
+        "Writes a display record then reads it back",
+    ),
+    op("EXSR", "", "", "subroutine name", "", "Runs a subroutine"),
+    op(
+        "EXTRCT",
+        "E",
+        "",
+        "date, time or timestamp:code",
+        "target",
+        "Extracts a part of a date, time or timestamp",
+    ),
+    op("FEOD", "EN", "", "file", "", "Forces end of data on a file"),
+    op(
+        "FOR",
+        "HMR",
+        "",
+        "index = start BY increment TO|DOWNTO limit",
+        "",
+        "Runs a group of operations a number of times",
+    ),
+    op(
+        "FOR-EACH",
+        "HMR",
+        "",
+        "item IN array or %LIST",
+        "",
+        "Runs a group of operations for each element",
+    ),
+    op(
+        "FORCE",
+        "",
+        "",
+        "file",
+        "",
+        "Selects the file the next cycle reads",
+    ),
+    op("GOTO", "", "", "label", "", "Branches to a TAG"),
+    op(
+        "IF",
+        "MR",
+        "",
+        "indicator expression",
+        "",
+        "Runs a group of operations when a condition is true",
+    ),
+    op(
+        "IFxx",
+        "",
+        "comparand",
+        "comparand",
+        "",
+        "Runs a group of operations when a comparison is true",
+    ),
+    op(
+        "IN",
+        "E",
+        "*LOCK (optional)",
+        "data area or *DTAARA",
+        "",
+        "Reads a data area",
+    ),
+    op(
+        "ITER",
+        "",
+        "",
+        "",
+        "",
+        "Goes on with the next iteration of a loop",
+    ),
+    op("KFLD", "", "", "", "key field", "Adds a field to a KLIST"),
+    op(
+        "KLIST",
+        "",
+        "KLIST name",
+        "",
+        "",
+        "Starts a list of key fields for file operations",
+    ),
+    op("LEAVE", "", "", "", "", "Leaves a loop"),
+    op("LEAVESR", "", "", "", "", "Leaves a subroutine"),
+    op(
+        "LOOKUP",
+        "",
+        "search argument",
+        "array or table",
+        "table (optional)",
+        "Searches an array or table for an element",
+    ),
+    op(
+        "MONITOR",
+        "",
+        "",
+        "",
+        "",
+        "Starts a group of operations with error handling",
+    ),
+    op(
+        "MOVE",
+        "P",
+        "data attributes (optional)",
+        "source",
+        "target",
+        "Moves factor 2 right adjusted to the result field",
+    ),
+    op(
+        "MOVEA",
+        "P",
+        "",
+        "source",
+        "target",
+        "Moves an array to or from a field, element by element",
+    ),
+    op(
+        "MOVEL",
+        "P",
+        "data attributes (optional)",
+        "source",
+        "target",
+        "Moves factor 2 left adjusted to the result field",
+    ),
+    op(
+        "MULT",
+        "H",
+        "multiplicand (optional)",
+        "multiplier",
+        "product",
+        "Multiplies factor 1, or the result field, by factor 2",
+    ),
+    op(
+        "MVR",
+        "",
+        "",
+        "",
+        "remainder",
+        "Moves the remainder of the DIV above",
+    ),
+    op(
+        "NEXT",
+        "E",
+        "program device",
+        "file",
+        "",
+        "Forces the next input from a program device",
+    ),
+    op(
+        "OCCUR",
+        "E",
+        "occurrence (optional)",
+        "multiple occurrence ds",
+        "occurrence (optional)",
+        "Sets or gets the occurrence of a data structure",
+    ),
+    op(
+        "ON-ERROR",
+        "",
+        "",
+        "status codes (optional)",
+        "",
+        "Handles the errors of a MONITOR group",
+    ),
+    op(
+        "ON-EXIT",
+        "",
+        "",
+        "abnormal end indicator (optional)",
+        "",
+        "Starts the code that runs when the procedure ends",
+    ),
+    op("OPEN", "E", "", "file", "", "Opens a file"),
+    op(
+        "ORxx",
+        "",
+        "comparand",
+        "comparand",
+        "",
+        "Adds an alternative condition to the IFxx, DOUxx, DOWxx or WHENxx above",
+    ),
+    op(
+        "OTHER",
+        "",
+        "",
+        "",
+        "",
+        "Starts the default branch of a SELECT",
+    ),
+    op(
+        "OUT",
+        "E",
+        "*LOCK (optional)",
+        "data area or *DTAARA",
+        "",
+        "Writes a data area",
+    ),
+    op(
+        "PARM",
+        "",
+        "target (optional)",
+        "source (optional)",
+        "parameter",
+        "Adds a parameter to a PLIST or CALL",
+    ),
+    op(
+        "PLIST",
+        "",
+        "PLIST name or *ENTRY",
+        "",
+        "",
+        "Starts a parameter list",
+    ),
+    op(
+        "POST",
+        "E",
+        "program device (optional)",
+        "file",
+        "INFDS (optional)",
+        "Puts device information in the file information data structure",
+    ),
+    op(
+        "READ",
+        "NE",
+        "",
+        "file or record format",
+        "data structure (optional)",
+        "Reads the next record",
+    ),
+    op(
+        "READC",
+        "E",
+        "",
+        "subfile record format",
+        "data structure (optional)",
+        "Reads the next changed subfile record",
+    ),
+    op(
+        "READE",
+        "NE",
+        "search argument",
+        "file or record format",
+        "data structure (optional)",
+        "Reads the next record with an equal key",
+    ),
+    op(
+        "READP",
+        "NE",
+        "",
+        "file or record format",
+        "data structure (optional)",
+        "Reads the prior record",
+    ),
+    op(
+        "READPE",
+        "NE",
+        "search argument",
+        "file or record format",
+        "data structure (optional)",
+        "Reads the prior record with an equal key",
+    ),
+    op(
+        "REL",
+        "E",
+        "program device",
+        "file",
+        "",
+        "Releases a program device",
+    ),
+    op(
+        "RESET",
+        "E",
+        "*NOKEY (optional)",
+        "*ALL (optional)",
+        "variable, structure or record format",
+        "Sets a variable back to its initial value",
+    ),
+    op(
+        "RETURN",
+        "HMR",
+        "",
+        "expression (optional)",
+        "",
+        "Returns from a procedure or program",
+    ),
+    op(
+        "ROLBK",
+        "E",
+        "",
+        "",
+        "",
+        "Rolls back the pending database changes",
+    ),
+    op(
+        "SCAN",
+        "E",
+        "search:length",
+        "base:start",
+        "position (optional)",
+        "Finds a string in another",
+    ),
+    op("SELECT", "", "", "", "", "Starts a group of WHEN branches"),
+    op(
+        "SETGT",
+        "E",
+        "search argument",
+        "file or record format",
+        "",
+        "Positions a file after the last record matching a key",
+    ),
+    op(
+        "SETLL",
+        "E",
+        "search argument",
+        "file or record format",
+        "",
+        "Positions a file at the first record matching a key",
+    ),
+    op(
+        "SETOFF",
+        "",
+        "",
+        "",
+        "",
+        "Sets the indicators of the resulting indicator columns off",
+    ),
+    op(
+        "SETON",
+        "",
+        "",
+        "",
+        "",
+        "Sets the indicators of the resulting indicator columns on",
+    ),
+    op(
+        "SHTDN",
+        "",
+        "",
+        "",
+        "",
+        "Checks whether the system operator asked for a shutdown",
+    ),
+    op(
+        "SND-MSG",
+        "E",
+        "",
+        "message type, message and %TARGET",
+        "",
+        "Sends a message to the job log or a program message queue",
+    ),
+    op("SORTA", "AD", "", "array", "", "Sorts an array"),
+    op("SQRT", "H", "", "value", "root", "Computes a square root"),
+    op(
+        "SUB",
+        "H",
+        "minuend (optional)",
+        "subtrahend",
+        "difference",
+        "Subtracts factor 2 from factor 1, or from the result field",
+    ),
+    op(
+        "SUBDUR",
+        "E",
+        "date, time or timestamp (optional)",
+        "date, time, timestamp or duration:code",
+        "duration or date, time or timestamp",
+        "Subtracts a duration or computes the one between two dates",
+    ),
+    op(
+        "SUBST",
+        "EP",
+        "length (optional)",
+        "base:start",
+        "target",
+        "Extracts a substring",
+    ),
+    op(
+        "TAG",
+        "",
+        "label",
+        "",
+        "",
+        "Declares a label for GOTO and CABxx",
+    ),
+    op(
+        "TEST",
+        "EDTZ",
+        "format (optional)",
+        "",
+        "date, time, timestamp or character",
+        "Checks a date, time or timestamp for validity",
+    ),
+    op(
+        "TESTB",
+        "",
+        "",
+        "bit numbers",
+        "character field",
+        "Tests bits",
+    ),
+    op(
+        "TESTN",
+        "",
+        "",
+        "",
+        "character field",
+        "Tests for zoned numeric characters",
+    ),
+    op(
+        "TESTZ",
+        "",
+        "",
+        "",
+        "character field",
+        "Tests the zone of the leftmost character",
+    ),
+    op(
+        "TIME",
+        "",
+        "",
+        "",
+        "target",
+        "Gets the system time and date",
+    ),
+    op(
+        "UNLOCK",
+        "E",
+        "",
+        "data area or file",
+        "",
+        "Unlocks a data area or releases a record lock",
+    ),
+    op(
+        "UPDATE",
+        "E",
+        "",
+        "file or record format",
+        "data structure or %FIELDS (optional)",
+        "Updates the last record read",
+    ),
+    op(
+        "WHEN",
+        "MR",
+        "",
+        "indicator expression",
+        "",
+        "Starts a branch of a SELECT",
+    ),
+    op(
+        "WHEN-IN",
+        "",
+        "",
+        "array, %LIST or %RANGE",
+        "",
+        "Starts a SELECT branch for a value in a list or range",
+    ),
+    op(
+        "WHEN-IS",
+        "",
+        "",
+        "value",
+        "",
+        "Starts a SELECT branch for a value",
+    ),
+    op(
+        "WHENxx",
+        "",
+        "comparand",
+        "comparand",
+        "",
+        "Starts a branch of a SELECT on a comparison",
+    ),
+    op(
+        "WRITE",
+        "E",
+        "",
+        "file or record format",
+        "data structure (optional)",
+        "Writes a new record",
+    ),
+    op(
+        "XFOOT",
+        "H",
+        "",
+        "numeric array",
+        "sum",
+        "Sums the elements of an array",
+    ),
+    op(
+        "XLATE",
+        "EP",
+        "from:to",
+        "source:start",
+        "target",
+        "Translates characters",
+    ),
+    op(
+        "XML-INTO",
+        "EH",
+        "",
+        "receiver or %HANDLER and %XML",
+        "",
+        "Parses an XML document into a variable",
+    ),
+    op(
+        "XML-SAX",
+        "E",
+        "",
+        "%HANDLER and %XML",
+        "",
+        "Parses an XML document with a handler procedure",
+    ),
+    op(
+        "Z-ADD",
+        "H",
+        "",
+        "value",
+        "target",
+        "Sets the result field to factor 2",
+    ),
+    op(
+        "Z-SUB",
+        "H",
+        "",
+        "value",
+        "target",
+        "Sets the result field to the negative of factor 2",
+    ),
+];
+
+const COMPARISONS: [&str; 6] = ["EQ", "NE", "GT", "LT", "GE", "LE"];
+
+// ex: %subst or %SUBST, with the `%`
+pub fn lookup_builtin(name: &str) -> Option<&'static Builtin> {
+    let upper = name.to_uppercase();
+    BUILTIN_CATALOG.iter().find(|x| x.name == upper)
+}
+
+// ex: CHAIN(E), IFEQ for IFxx, extenders are ignored
+pub fn lookup_opcode(name: &str) -> Option<&'static Opcode> {
+    let upper = name
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_uppercase();
+    if let Some(found) = OPCODE_CATALOG.iter().find(|x| x.name == upper) {
+        return Some(found);
+    }
+    let (head, tail) = upper.split_at(upper.len().saturating_sub(2));
+    if !COMPARISONS.contains(&tail) {
+        return None;
+    }
+    let family = format!("{}xx", head);
+    OPCODE_CATALOG.iter().find(|x| x.name == family)
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '@' || c == '$' || c == '#' || c == '_'
+}
+
+// an open paren, and the built-in it belongs to if any
+struct Frame {
+    builtin: Option<(String, Span)>,
+    colons: usize,
+    empty: bool,
+}
+
+/* Walks expression text char by char, a free-form statement can go on over
+ * several lines so the state is kept until `end`.
+ */
+#[derive(Default)]
+struct Scanner {
+    frames: Vec<Frame>,
+    quoted: bool,
+    out: Vec<BuiltinCall>,
+}
+
+impl Scanner {
+    fn line(&mut self, text: &str, row: usize, col: usize) {
+        let chars = text.chars().collect::<Vec<char>>();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            if self.quoted {
+                self.quoted = c != '\'';
+                i += 1;
+                continue;
+            }
+            if c == '/' && chars.get(i + 1) == Some(&'/') {
+                break;
+            }
+            if c != ' ' && c != ')' {
+                if let Some(frame) = self.frames.last_mut() {
+                    frame.empty = false;
+                }
+            }
+            match c {
+                '\'' => self.quoted = true,
+                ';' => self.end(),
+                ':' => {
+                    if let Some(frame) = self.frames.last_mut() {
+                        frame.colons += 1;
+                    }
+                }
+                '(' => self.frames.push(Frame {
+                    builtin: None,
+                    colons: 0,
+                    empty: true,
+                }),
+                ')' => {
+                    if let Some(Frame {
+                        builtin: Some((name, span)),
+                        colons,
+                        empty,
+                    }) = self.frames.pop()
+                    {
+                        let args = if empty { 0 } else { colons + 1 };
+                        self.out.push(BuiltinCall { name, span, args });
+                    }
+                }
+                '%' => {
+                    let len = chars[i + 1..]
+                        .iter()
+                        .take_while(|c| is_name_char(**c))
+                        .count();
+                    if len > 0 {
+                        let name = chars[i..i + 1 + len].iter().collect::<String>();
+                        let span = Span {
+                            start: Position::from((row, col + i)),
+                            end: Position::from((row, col + i + 1 + len)),
+                        };
+                        i += 1 + len;
+                        let next = chars[i..].iter().find(|c| **c != ' ');
+                        match next {
+                            Some('(') => {
+                                let skip = chars[i..].iter().take_while(|c| **c == ' ').count();
+                                i += skip + 1;
+                                self.frames.push(Frame {
+                                    builtin: Some((name.to_uppercase(), span)),
+                                    colons: 0,
+                                    empty: true,
+                                });
+                            }
+                            _ => self.out.push(BuiltinCall {
+                                name: name.to_uppercase(),
+                                span,
+                                args: 0,
+                            }),
+                        }
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    // unclosed calls are still being typed, they aren't reported
+    fn end(&mut self) {
+        self.frames.clear();
+        self.quoted = false;
+    }
+}
+
+/* Every built-in function call in free-form statements, extended factor 2
+ * expressions and D spec keywords, with its argument count.
+ */
+pub fn query_builtin_calls(cst: &CST) -> Vec<BuiltinCall> {
+    let mut scanner = Scanner::default();
+    for line in cst.lines.iter() {
+        let meta = match line {
+            Srcline::C(CSrcline::Free { code, .. }) => {
+                if let Some(code) = code.try_as() {
+                    let meta = &code.meta;
+                    scanner.line(&meta.text, meta.span.start.row, meta.span.start.col);
+                }
+                continue;
+            }
+            Srcline::C(CSrcline::ExtF2 { factor2, .. }) => factor2.try_as().map(|x| &x.meta),
+            Srcline::D { keywords, .. } | Srcline::DCont { keywords, .. } => {
+                keywords.try_as().map(|x| &x.meta)
+            }
+            _ => None,
+        };
+        if let Some(meta) = meta {
+            scanner.line(&meta.text, meta.span.start.row, meta.span.start.col);
+            scanner.end();
+        }
+    }
+    scanner
+        .out
+        .sort_by_key(|x| (x.span.start.row, x.span.start.col));
+    scanner.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::parse_cst;
+
+    #[test]
+    fn test_catalog_lookup() {
+        let subst = lookup_builtin("%subst").unwrap();
+        assert_eq!(subst.signature(), "%SUBST(string:start{:length})");
+        assert_eq!(subst.arity(), "2 to 3 arguments");
+        assert_eq!(lookup_builtin("%EOF").unwrap().signature(), "%EOF{(file)}");
+        assert_eq!(
+            lookup_builtin("%MAX").unwrap().signature(),
+            "%MAX(item:item{:...})"
+        );
+        assert!(lookup_builtin("%MAX").unwrap().accepts(5));
+        assert!(!lookup_builtin("%ERROR").unwrap().accepts(1));
+        assert!(lookup_builtin("%SUBSTR").is_none());
+        assert_eq!(lookup_opcode("chain(e)").unwrap().name, "CHAIN");
+        assert_eq!(lookup_opcode("IFEQ").unwrap().name, "IFxx");
+        assert!(lookup_opcode("IFXY").is_none());
+        // both tables are sorted so they read like the manual
+        assert!(BUILTIN_CATALOG.windows(2).all(|x| x[0].name < x[1].name));
+        assert!(OPCODE_CATALOG
+            .windows(2)
+            .all(|x| x[0].name.to_uppercase() < x[1].name.to_uppercase()));
+    }
+
+    #[test]
+    fn test_builtin_calls() {
+        let input = r#"
+     D Today           S               D   inz(%date())
+     C                   EVAL      Name = %subst(%trim(Name):1)
+      /free
+       if %eof or %found(CowEvt);
+         x = %scan('(:':
+                   Name : 2);
+       endif;
+      /end-free
+"#[1..]
+            .to_string();
+        let cst = parse_cst(&input).unwrap();
+        let observed = query_builtin_calls(&cst)
+            .into_iter()
+            .map(|x| (x.name, x.span.start.row, x.span.start.col, x.args))
+            .collect::<Vec<(String, usize, usize, usize)>>();
+        let expected = vec![
+            ("%DATE".to_string(), 0, 47, 0),
+            ("%SUBST".to_string(), 1, 42, 2),
+            ("%TRIM".to_string(), 1, 49, 1),
+            ("%EOF".to_string(), 3, 10, 0),
+            ("%FOUND".to_string(), 3, 18, 1),
+            ("%SCAN".to_string(), 4, 13, 3),
+        ];
+        assert_eq!(observed, expected);
+    }
+}
@@ -0,0 +1,429 @@
+use super::catalog::BUILTIN_CATALOG;
+use super::outline::{query_outline, OutlineItem, OutlineKind};
+use super::symbols::{Symbol, SymbolKind, SymbolTable};
+use crate::cst::{parse_cst, CSrcline, Srcline, CST};
+use crate::format::{strip_comment, OPCODES};
+use serde::{Deserialize, Serialize};
+
+// first column of free-form code
+const CODE: usize = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CompletionKind {
+    Variable,
+    Constant,
+    DataStructure,
+    // a subfield or an externally described field
+    Field,
+    Parameter,
+    File,
+    Procedure,
+    Subroutine,
+    Opcode,
+    Builtin,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    // type and length, ex: 9P 0, packed(9:2), or a BIF signature
+    pub detail: String,
+}
+
+// The candidates for a cursor, each replaces `start_col` up to the cursor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Completions {
+    pub start_col: usize,
+    pub items: Vec<Completion>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Context {
+    // the first word of a free-form statement
+    Statement,
+    // an operand, free-form or in a fixed factor column
+    Expression,
+    // the fixed-format operation column
+    Opcode,
+    // the operand of EXSR or CASxx
+    Subroutine,
+    // right after a `%`
+    Builtin,
+}
+
+// opcodes only found in fixed-format calc specs
+const FIXED_OPCODES: [&str; 44] = [
+    "ADD", "ADDDUR", "BITOFF", "BITON", "CALL", "CALLB", "CAT", "CHECK", "CHECKR", "COMP",
+    "DEFINE", "DIV", "DO", "END", "EXTRCT", "GOTO", "KFLD", "KLIST", "LOOKUP", "MOVE", "MOVEA",
+    "MOVEL", "MULT", "MVR", "OCCUR", "PARM", "PLIST", "SCAN", "SETOFF", "SETON", "SHTDN", "SQRT",
+    "SUB", "SUBDUR", "SUBST", "TAG", "TESTB", "TESTN", "TESTZ", "TIME", "XFOOT", "XLATE", "Z-ADD",
+    "Z-SUB",
+];
+
+// fixed-format opcodes with a comparison in them, ex: IFEQ, CASGT, WHENLE
+const COMPARING_OPCODES: [&str; 8] = ["AND", "CAB", "CAS", "DOU", "DOW", "IF", "OR", "WHEN"];
+const COMPARISONS: [&str; 6] = ["EQ", "NE", "GT", "LT", "GE", "LE"];
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '@' || c == '$' || c == '#' || c == '_'
+}
+
+// the text of a field without its padding
+fn trimmed(field: &impl ToString) -> String {
+    field.to_string().trim().to_string()
+}
+
+// keywords follow the case of what was typed so far
+fn keyword(word: &str, prefix: &str) -> String {
+    let lower = !prefix.is_empty() && prefix == prefix.to_lowercase();
+    match lower {
+        true => word.to_lowercase(),
+        false => word.to_string(),
+    }
+}
+
+fn fixed_opcodes() -> Vec<String> {
+    let mut out = FIXED_OPCODES
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>();
+    for head in COMPARING_OPCODES {
+        for cmp in COMPARISONS {
+            out.push(format!("{}{}", head, cmp));
+        }
+    }
+    // the rest can be used with an extended factor 2
+    for op in free_opcodes() {
+        if !["DCL-", "END-", "CTL-"].iter().any(|x| op.starts_with(x)) {
+            out.push(op);
+        }
+    }
+    out.sort();
+    out.dedup();
+    out
+}
+
+fn free_opcodes() -> Vec<String> {
+    OPCODES.iter().map(|x| x.to_string()).collect()
+}
+
+// Does the free-form statement before `row` go on past its line
+pub(crate) fn continued(cst: &CST, row: usize) -> bool {
+    for line in cst.lines[..row].iter().rev() {
+        let Srcline::C(CSrcline::Free { code, .. }) = line else {
+            return false;
+        };
+        let text = trimmed(code);
+        let statement = strip_comment(&text).trim_end();
+        if !statement.is_empty() {
+            return !statement.ends_with(';');
+        }
+    }
+    false
+}
+
+fn context(cst: &CST, chars: &[char], row: usize, start: usize) -> Option<Context> {
+    if start > 0 && chars.get(start - 1) == Some(&'%') {
+        return Some(Context::Builtin);
+    }
+    let text = |from: usize, to: usize| {
+        chars
+            .iter()
+            .take(to.min(chars.len()))
+            .skip(from)
+            .collect::<String>()
+            .trim()
+            .to_uppercase()
+    };
+    let fixed = match cst.lines.get(row) {
+        Some(Srcline::C(CSrcline::Free { .. })) => false,
+        Some(Srcline::C(_)) => true,
+        Some(Srcline::Idk { .. }) | None => match chars.get(5) {
+            Some('C') | Some('c') => chars.get(6) != Some(&'*'),
+            Some(' ') | None => false,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if fixed {
+        let operation = text(25, 35);
+        return match start {
+            0..=10 => None,
+            25..=34 => Some(Context::Opcode),
+            35..=48 if operation.starts_with("EXSR") => Some(Context::Subroutine),
+            49..=62 if operation.starts_with("CAS") => Some(Context::Subroutine),
+            _ => Some(Context::Expression),
+        };
+    }
+    if start < CODE {
+        return None;
+    }
+    let before = text(CODE, start);
+    let last = before
+        .rsplit([' ', ';', '('])
+        .find(|w| !w.is_empty())
+        .unwrap_or_default();
+    if last == "EXSR" && !before.ends_with(';') {
+        return Some(Context::Subroutine);
+    }
+    let starts = before.is_empty() || before.ends_with(';');
+    match starts && (before.ends_with(';') || !continued(cst, row)) {
+        true => Some(Context::Statement),
+        false => Some(Context::Expression),
+    }
+}
+
+fn flatten<'a>(items: &'a [OutlineItem], out: &mut Vec<&'a OutlineItem>) {
+    for item in items {
+        out.push(item);
+        flatten(&item.children, out);
+    }
+}
+
+// Type, length and keywords from the line a symbol is defined on
+fn detail(cst: &CST, symbol: &Symbol) -> String {
+    match cst.lines.get(symbol.span.start.row) {
+        Some(Srcline::D {
+            to_length,
+            datatype,
+            decimals,
+            keywords,
+            ..
+        }) => {
+            let size = format!("{}{}", trimmed(to_length), trimmed(datatype));
+            [size, trimmed(decimals), trimmed(keywords)]
+                .into_iter()
+                .filter(|x| !x.is_empty())
+                .collect::<Vec<String>>()
+                .join(" ")
+        }
+        Some(Srcline::C(CSrcline::Free { code, .. })) => {
+            let Some(code) = code.try_as() else {
+                return String::new();
+            };
+            let skip = symbol.span.end.col.saturating_sub(code.meta.span.start.col);
+            let rest = code.meta.text.chars().skip(skip).collect::<String>();
+            let statement = strip_comment(&rest);
+            statement
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        }
+        _ => String::new(),
+    }
+}
+
+fn symbols(cst: &CST, row: usize, subroutines: bool) -> Vec<Completion> {
+    let outline = query_outline(cst);
+    let mut items = vec![];
+    flatten(&outline, &mut items);
+    let bodies = |kind: OutlineKind| {
+        items
+            .iter()
+            .filter(|x| x.kind == kind)
+            .map(|x| (x.span.start.row, x.span.end.row))
+            .collect::<Vec<(usize, usize)>>()
+    };
+    let (procedures, prototypes) = (
+        bodies(OutlineKind::Procedure),
+        bodies(OutlineKind::Prototype),
+    );
+    let within = |row: usize, (start, end): &(usize, usize)| *start <= row && row <= *end;
+    let scope = |row: usize| procedures.iter().find(|p| within(row, p));
+    let current = scope(row);
+    let mut out = vec![];
+    for symbol in SymbolTable::from(cst).definitions.iter() {
+        let at = symbol.span.start.row;
+        if at == row || (scope(at).is_some() && scope(at) != current) {
+            continue;
+        }
+        let kind = match (symbol.kind, subroutines) {
+            (SymbolKind::Subroutine, true) => CompletionKind::Subroutine,
+            (_, true) | (SymbolKind::Subroutine, false) => continue,
+            (SymbolKind::Standalone, _) => CompletionKind::Variable,
+            (SymbolKind::Constant, _) => CompletionKind::Constant,
+            (SymbolKind::DataStructure, _) => CompletionKind::DataStructure,
+            (SymbolKind::Subfield, _) => CompletionKind::Field,
+            // prototype parameters only name the call's arguments
+            (SymbolKind::Parameter, _) if prototypes.iter().any(|p| within(at, p)) => continue,
+            (SymbolKind::Parameter, _) => CompletionKind::Parameter,
+            (SymbolKind::File { .. }, _) => CompletionKind::File,
+            (SymbolKind::Prototype, _) => CompletionKind::Procedure,
+            (SymbolKind::ProcedureInterface, _) | (SymbolKind::Tag, _) => continue,
+        };
+        out.push(Completion {
+            label: symbol.name.clone(),
+            kind,
+            detail: detail(cst, symbol),
+        });
+    }
+    if !subroutines {
+        for item in items.iter().filter(|x| x.kind == OutlineKind::Procedure) {
+            out.push(Completion {
+                label: item.name.clone(),
+                kind: CompletionKind::Procedure,
+                detail: String::new(),
+            });
+        }
+    }
+    out
+}
+
+/* Completion candidates for the cursor at `row` and `col`, both zero based.
+ * Which candidates depends on where the cursor is: opcodes in the fixed
+ * operation column, subroutines after EXSR, `%` builtins, and in free-form
+ * code the opcodes, in-scope names and the `external` fields of externally
+ * described files. Only candidates starting with the word typed so far are
+ * kept, ignoring case.
+ */
+pub fn query_completions(
+    input: &str,
+    row: usize,
+    col: usize,
+    external: &[Completion],
+) -> Completions {
+    let line = input.split('\n').nth(row).unwrap_or_default();
+    let chars = line.trim_end_matches('\r').chars().collect::<Vec<char>>();
+    let col = col.min(chars.len());
+    let start = col
+        - chars[..col]
+            .iter()
+            .rev()
+            .take_while(|c| is_name_char(**c))
+            .count();
+    let prefix = chars[start..col].iter().collect::<String>();
+    let empty = Completions {
+        start_col: start,
+        items: vec![],
+    };
+    let Ok(cst) = parse_cst(input) else {
+        return empty;
+    };
+    let Some(context) = context(&cst, &chars, row, start) else {
+        return empty;
+    };
+    let opcodes = |list: Vec<String>| {
+        list.into_iter().map(|op| Completion {
+            label: keyword(&op, &prefix),
+            kind: CompletionKind::Opcode,
+            detail: String::new(),
+        })
+    };
+    let mut items = vec![];
+    match context {
+        Context::Opcode => items.extend(opcodes(fixed_opcodes())),
+        Context::Subroutine => items.extend(symbols(&cst, row, true)),
+        Context::Builtin => items.extend(BUILTIN_CATALOG.iter().map(|bif| Completion {
+            label: keyword(&bif.name[1..], &prefix),
+            kind: CompletionKind::Builtin,
+            detail: bif.signature(),
+        })),
+        Context::Statement | Context::Expression => {
+            if context == Context::Statement {
+                items.extend(opcodes(free_opcodes()));
+            }
+            items.extend(symbols(&cst, row, false));
+            items.extend(external.iter().cloned());
+        }
+    }
+    let upper = prefix.to_uppercase();
+    let mut seen = vec![];
+    items.retain(|x| {
+        let label = x.label.to_uppercase();
+        let keep = label.starts_with(&upper) && !seen.contains(&label);
+        seen.push(label);
+        keep
+    });
+    Completions {
+        start_col: start,
+        items,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> String {
+        r#"
+     FCowEvt    UF A E           K DISK
+     D LastId          S              8  0
+     D Row             DS                  qualified
+     D  Id                            9  0
+     D QCmdExc         PR                  extpgm('QCMDEXC')
+     D  Command                    2000    const
+     C                   Z-ADD     1             LastId
+     C                   EXSR      $Init
+     C     $Init         BEGSR
+     C                   ENDSR
+      /free
+       dcl-proc Bump;
+         dcl-s Step packed(5:0);
+         La
+         Step = %
+       end-proc;
+       l
+       exsr $
+      /end-free
+"#[1..]
+            .to_string()
+    }
+
+    fn labels(completions: &Completions) -> Vec<String> {
+        completions.items.iter().map(|x| x.label.clone()).collect()
+    }
+
+    #[test]
+    fn test_completion_contexts() {
+        let input = fixture();
+        let external = vec![Completion {
+            label: "EVTNAME".to_string(),
+            kind: CompletionKind::Field,
+            detail: "EVTFMT 20A".to_string(),
+        }];
+        // fixed operation column
+        let ops = query_completions(&input, 6, 26, &[]);
+        assert_eq!(ops.start_col, 25);
+        assert_eq!(labels(&ops), vec!["Z-ADD", "Z-SUB"]);
+        // fixed EXSR operand
+        let subr = query_completions(&input, 7, 37, &[]);
+        assert_eq!(labels(&subr), vec!["$Init"]);
+        // statement start in free-form, opcodes and names
+        let names = query_completions(&input, 13, 11, &external);
+        assert_eq!(labels(&names), vec!["LastId"]);
+        assert_eq!(names.items[0].detail, "8 0");
+        // builtins
+        let bifs = query_completions(&input, 14, 17, &[]);
+        assert_eq!(bifs.start_col, 17);
+        assert!(labels(&bifs).contains(&"SUBST".to_string()));
+        // free-form EXSR operand
+        let subr = query_completions(&input, 17, 13, &[]);
+        assert_eq!(labels(&subr), vec!["$Init"]);
+        // locals of Bump are out of scope here, keywords follow the typed case
+        let mainline = query_completions(&input, 16, 8, &external);
+        assert_eq!(labels(&mainline), vec!["leave", "leavesr", "LastId"]);
+    }
+
+    #[test]
+    fn test_completion_details() {
+        let input = fixture();
+        let external = vec![Completion {
+            label: "EVTNAME".to_string(),
+            kind: CompletionKind::Field,
+            detail: "EVTFMT 20A".to_string(),
+        }];
+        let all = query_completions(&input, 13, 9, &external);
+        let find = |label: &str| all.items.iter().find(|x| x.label == label).cloned();
+        assert_eq!(find("Step").unwrap().detail, "packed(5:0)");
+        assert_eq!(find("Row").unwrap().detail, "qualified");
+        assert_eq!(find("Id").unwrap().kind, CompletionKind::Field);
+        assert_eq!(find("EVTNAME").unwrap().detail, "EVTFMT 20A");
+        assert_eq!(find("QCmdExc").unwrap().kind, CompletionKind::Procedure);
+        assert_eq!(find("Bump").unwrap().kind, CompletionKind::Procedure);
+        // prototype parameters aren't variables
+        assert!(find("Command").is_none());
+    }
+}
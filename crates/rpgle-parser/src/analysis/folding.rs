@@ -0,0 +1,177 @@
+use super::outline::{query_outline, OutlineItem};
+use crate::cst::{CSrcline, Srcline, CST};
+use crate::format::{effect, strip_comment, Effect};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FoldKind {
+    // a run of comment lines, ex: a banner above a subroutine
+    Comment,
+    // a declaration or routine body, ex: a DS and its subfields or BEGSR..ENDSR
+    Region,
+    // a control-flow block, ex: IF..ENDIF or SELECT..ENDSL
+    Block,
+}
+
+// Rows are zero based and inclusive, a fold always spans more than one row
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fold {
+    pub start_row: usize,
+    pub end_row: usize,
+    pub kind: FoldKind,
+}
+
+fn regions(items: &[OutlineItem], out: &mut Vec<Fold>) {
+    for item in items {
+        if item.span.end.row > item.span.start.row {
+            out.push(Fold {
+                start_row: item.span.start.row,
+                end_row: item.span.end.row,
+                kind: FoldKind::Region,
+            });
+        }
+        regions(&item.children, out);
+    }
+}
+
+fn comments(cst: &CST, out: &mut Vec<Fold>) {
+    let mut run: Option<(usize, usize)> = None;
+    for (row, line) in cst.lines.iter().enumerate() {
+        let comment = matches!(line, Srcline::Comment { .. });
+        run = match (comment, run) {
+            (true, Some((first, _))) => Some((first, row)),
+            (true, None) => Some((row, row)),
+            (false, Some((first, last))) => {
+                banner(first, last, out);
+                None
+            }
+            (false, None) => None,
+        };
+    }
+    if let Some((first, last)) = run {
+        banner(first, last, out);
+    }
+}
+
+fn banner(first: usize, last: usize, out: &mut Vec<Fold>) {
+    if last > first {
+        out.push(Fold {
+            start_row: first,
+            end_row: last,
+            kind: FoldKind::Comment,
+        });
+    }
+}
+
+/* Control-flow blocks of both calc spec forms, a free-form statement only
+ * counts once, on the line its opcode starts.
+ */
+fn blocks(cst: &CST, out: &mut Vec<Fold>) {
+    let mut open = vec![];
+    let mut continued = false;
+    for (row, line) in cst.lines.iter().enumerate() {
+        let found = match line {
+            Srcline::C(
+                CSrcline::Traditional { operation, .. } | CSrcline::ExtF2 { operation, .. },
+            ) => operation.try_as().map(|op| {
+                let opcode = op.value.split('(').next().unwrap_or_default();
+                effect(&opcode.trim().to_uppercase(), "")
+            }),
+            Srcline::C(CSrcline::Free { code, .. }) => code.try_as().and_then(|code| {
+                let statement = strip_comment(code.meta.text.trim()).trim_end();
+                if statement.is_empty() {
+                    return None;
+                }
+                let starts = !continued;
+                continued = !statement.ends_with(';');
+                let word = statement
+                    .split([' ', ';', '('])
+                    .next()
+                    .unwrap_or_default()
+                    .to_uppercase();
+                starts.then(|| effect(&word, statement))
+            }),
+            _ => None,
+        };
+        match found {
+            Some(Effect::Open(_)) => open.push(row),
+            Some(Effect::Close) => {
+                if let Some(start) = open.pop() {
+                    out.push(Fold {
+                        start_row: start,
+                        end_row: row,
+                        kind: FoldKind::Block,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/* Foldable ranges of the source: subroutines, procedures, DS groups with their
+ * subfields, PR/PI parameter lists, comment banners and nested control-flow
+ * blocks. Sorted by start row, outermost first, with no duplicate ranges.
+ */
+pub fn query_folds(cst: &CST) -> Vec<Fold> {
+    let mut out = vec![];
+    regions(&query_outline(cst), &mut out);
+    comments(cst, &mut out);
+    blocks(cst, &mut out);
+    // a free-form routine is both a region and a block, keep the region
+    let mut folds: Vec<Fold> = vec![];
+    for fold in out {
+        let same = |f: &Fold| f.start_row == fold.start_row && f.end_row == fold.end_row;
+        if !folds.iter().any(same) {
+            folds.push(fold);
+        }
+    }
+    folds.sort_by_key(|f| (f.start_row, std::cmp::Reverse(f.end_row)));
+    folds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::parse_cst;
+
+    #[test]
+    fn test_folds_snapshot() {
+        let input = r#"
+      * ---------------------------------
+      * Totals for the day
+      * ---------------------------------
+     D Row             DS                  qualified
+     D  Id                            9  0
+     D  Name                         20
+     D QCmdExc         PR                  extpgm('QCMDEXC')
+     D  Command                    2000    const
+     C     $Init         BEGSR
+     C     Id            IFEQ      0
+     C                   Z-ADD     1             Id
+     C                   ENDIF
+     C                   ENDSR
+     P Bump            B
+     D Bump            PI             9  0
+     D  Value                         9  0
+     C                   RETURN    Value + 1
+     P Bump            E
+      /free
+       dcl-proc Run;
+         select;
+           when Row.Id = 0;
+             dow not %eof(CowEvt)
+                 and Row.Id < 10;
+               Row.Id += 1;
+             enddo;
+           other;
+         endsl;
+       end-proc;
+      /end-free
+"#[1..]
+            .to_string();
+        let cst = parse_cst(&input).unwrap();
+        let folds = query_folds(&cst);
+        insta::assert_yaml_snapshot!(folds);
+    }
+}
@@ -0,0 +1,182 @@
+use super::catalog::{lookup_builtin, lookup_opcode, Builtin, Opcode};
+use super::completion::continued;
+use crate::cst::{parse_cst, CSrcline, Srcline};
+use crate::meta::{Position, Span};
+use serde::{Deserialize, Serialize};
+
+// first column of free-form code and the fixed-format operation columns
+const CODE: usize = 7;
+const OPERATION: usize = 25;
+const FACTOR2: usize = 35;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hover {
+    // the hovered word
+    pub span: Span,
+    // markdown, a signature line, a description and the operands
+    pub contents: String,
+}
+
+fn builtin_doc(bif: &Builtin) -> String {
+    let mut out = format!(
+        "`{}` → {}\n\n{}",
+        bif.signature(),
+        bif.returns,
+        bif.description
+    );
+    if !bif.parameters.is_empty() {
+        out.push('\n');
+    }
+    for (i, (name, datatype)) in bif.parameters.iter().enumerate() {
+        let optional = if i >= bif.min_args { " (optional)" } else { "" };
+        out.push_str(&format!("\n- {}: {}{}", name, datatype, optional));
+    }
+    out
+}
+
+fn opcode_doc(opcode: &Opcode) -> String {
+    let extenders = match opcode.extenders {
+        "" => String::new(),
+        x => format!("({})", x),
+    };
+    let mut out = format!("`{}{}`\n\n{}", opcode.name, extenders, opcode.description);
+    let operands = [
+        ("factor 1", opcode.factor1),
+        ("factor 2", opcode.factor2),
+        ("result", opcode.result),
+    ];
+    if operands.iter().any(|(_, x)| !x.is_empty()) {
+        out.push('\n');
+    }
+    for (operand, role) in operands.iter().filter(|(_, x)| !x.is_empty()) {
+        out.push_str(&format!("\n- {}: {}", operand, role));
+    }
+    out
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '@' || c == '$' || c == '#' || c == '_' || c == '-'
+}
+
+fn span(row: usize, start: usize, end: usize) -> Span {
+    Span {
+        start: Position::from((row, start)),
+        end: Position::from((row, end)),
+    }
+}
+
+/* Docs for the built-in function or operation code under the cursor at `row`
+ * and `col`, both zero based. Operation codes are only found in the fixed
+ * operation column and as the first word of a free-form statement.
+ */
+pub fn query_hover(input: &str, row: usize, col: usize) -> Option<Hover> {
+    let cst = parse_cst(input).ok()?;
+    let line = input.split('\n').nth(row)?;
+    let chars = line.trim_end_matches('\r').chars().collect::<Vec<char>>();
+    let fixed = matches!(
+        cst.lines.get(row),
+        Some(Srcline::C(
+            CSrcline::Traditional { .. } | CSrcline::ExtF2 { .. }
+        ))
+    );
+    if fixed && (OPERATION..FACTOR2).contains(&col) {
+        let field = chars
+            .iter()
+            .take(FACTOR2)
+            .skip(OPERATION)
+            .collect::<String>();
+        let opcode = lookup_opcode(field.trim())?;
+        let len = field.trim_end().chars().count();
+        return Some(Hover {
+            span: span(row, OPERATION, OPERATION + len),
+            contents: opcode_doc(opcode),
+        });
+    }
+    // the word around the cursor, a `%` under the cursor goes with the name after it
+    let at = if chars.get(col) == Some(&'%') {
+        col + 1
+    } else {
+        col
+    };
+    if !chars.get(at).is_some_and(|c| is_word_char(*c)) {
+        return None;
+    }
+    let start = chars[..at]
+        .iter()
+        .rposition(|c| !is_word_char(*c))
+        .map(|x| x + 1)
+        .unwrap_or(0);
+    let end = chars[at..]
+        .iter()
+        .position(|c| !is_word_char(*c))
+        .map(|x| x + at)
+        .unwrap_or(chars.len());
+    let word = chars[start..end].iter().collect::<String>();
+    if start > 0 && chars[start - 1] == '%' {
+        let name = word.split('-').next().unwrap_or_default();
+        let bif = lookup_builtin(&format!("%{}", name))?;
+        return Some(Hover {
+            span: span(row, start - 1, start + name.chars().count()),
+            contents: builtin_doc(bif),
+        });
+    }
+    let free = matches!(cst.lines.get(row), Some(Srcline::C(CSrcline::Free { .. })));
+    if !free || start < CODE {
+        return None;
+    }
+    let before = chars[CODE..start].iter().collect::<String>();
+    let before = before.trim();
+    let first = (before.is_empty() && !continued(&cst, row)) || before.ends_with(';');
+    let opcode = lookup_opcode(&word).filter(|_| first)?;
+    Some(Hover {
+        span: span(row, start, end),
+        contents: opcode_doc(opcode),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hover() {
+        let input = r#"
+     C     Key           CHAIN(E)  CowEvt
+     C                   IFEQ      *ON
+     C                   EVAL      Name = %subst(Name:2)
+      /free
+       read CowEvt;
+       x = %found(CowEvt) and %lenght(x) > 0;
+      /end-free
+"#[1..]
+            .to_string();
+        let hover = |row: usize, col: usize| query_hover(&input, row, col);
+        let chain = hover(0, 27).unwrap();
+        assert_eq!((chain.span.start.col, chain.span.end.col), (25, 33));
+        assert_eq!(
+            chain.contents,
+            "`CHAIN(NE)`\n\nReads the record matching a key or relative record number\n\n\
+             - factor 1: search argument\n\
+             - factor 2: file or record format\n\
+             - result: data structure (optional)"
+        );
+        assert!(hover(1, 26).unwrap().contents.starts_with("`IFxx`"));
+        let subst = hover(2, 43).unwrap();
+        assert_eq!((subst.span.start.col, subst.span.end.col), (42, 48));
+        assert_eq!(
+            subst.contents,
+            "`%SUBST(string:start{:length})` → string\n\nA part of a string\n\n\
+             - string: string\n\
+             - start: integer\n\
+             - length: integer (optional)"
+        );
+        assert!(hover(4, 8).unwrap().contents.starts_with("`READ(NE)`"));
+        assert!(hover(5, 11)
+            .unwrap()
+            .contents
+            .starts_with("`%FOUND{(file)}`"));
+        // a name isn't an opcode, and an unknown BIF has no docs
+        assert!(hover(4, 13).is_none());
+        assert!(hover(5, 31).is_none());
+    }
+}
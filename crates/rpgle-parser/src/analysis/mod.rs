@@ -0,0 +1,23 @@
+mod catalog;
+mod completion;
+mod folding;
+mod hover;
+mod outline;
+mod semantic;
+mod signature;
+mod symbols;
+mod unused;
+
+pub use catalog::{
+    lookup_builtin, lookup_opcode, query_builtin_calls, Builtin, BuiltinCall, Opcode,
+    BUILTIN_CATALOG, OPCODE_CATALOG,
+};
+pub(crate) use completion::continued;
+pub use completion::{query_completions, Completion, CompletionKind, Completions};
+pub use folding::{query_folds, Fold, FoldKind};
+pub use hover::{query_hover, Hover};
+pub use outline::{query_outline, OutlineItem, OutlineKind};
+pub use semantic::{query_semantic_highlights, SemanticKind};
+pub use signature::{query_signature_help, query_signatures, Parameter, Signature, SignatureHelp};
+pub use symbols::{Symbol, SymbolKind, SymbolTable};
+pub use unused::query_unused;
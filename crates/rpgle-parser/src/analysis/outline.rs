@@ -0,0 +1,381 @@
+use super::symbols::{scan_words, Word};
+use crate::cst::{CSrcline, Srcline, CST};
+use crate::field::{DefinitionType, FieldResult};
+use crate::meta::{Meta, Span};
+use serde::{Deserialize, Serialize};
+
+// every cst line is padded to this width
+const WIDTH: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OutlineKind {
+    // an H spec or ctl-opt statement
+    Options,
+    File,
+    DataStructure,
+    Subfield,
+    Prototype,
+    ProcedureInterface,
+    Parameter,
+    Procedure,
+    Subroutine,
+    Tag,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutlineItem {
+    pub name: String,
+    pub kind: OutlineKind,
+    // the whole body, ex: BEGSR through ENDSR or a DS through its last subfield
+    pub span: Span,
+    // just the name
+    pub selection: Span,
+    pub children: Vec<OutlineItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Body {
+    // DS/PR/PI members, fixed format bodies end at the first non-member line
+    Members { free: bool },
+    Procedure,
+    Subroutine,
+}
+
+struct Builder {
+    roots: Vec<OutlineItem>,
+    // open bodies, innermost last
+    open: Vec<(OutlineItem, Body)>,
+}
+
+fn rows(start: usize, end: usize) -> Span {
+    Span::from(((start, 0), (end, WIDTH)))
+}
+
+fn item(name: &str, kind: OutlineKind, span: Span, selection: Span) -> OutlineItem {
+    OutlineItem {
+        name: name.to_string(),
+        kind,
+        span,
+        selection,
+        children: vec![],
+    }
+}
+
+impl Builder {
+    fn push(&mut self, item: OutlineItem) {
+        match self.open.last_mut() {
+            Some((parent, body)) => {
+                // a fixed format member list grows with its members
+                if matches!(body, Body::Members { .. }) {
+                    parent.span.end = item.span.end;
+                }
+                parent.children.push(item);
+            }
+            None => self.roots.push(item),
+        }
+    }
+
+    fn open(&mut self, item: OutlineItem, body: Body) {
+        self.open.push((item, body));
+    }
+
+    // close the innermost body, `end` None keeps the end it has so far
+    fn close(&mut self, end: Option<usize>) {
+        if let Some((mut item, _)) = self.open.pop() {
+            if let Some(end) = end {
+                item.span.end = rows(end, end).end;
+            }
+            self.push(item);
+        }
+    }
+
+    // close bodies up to and including the innermost one of `body`
+    fn close_body(&mut self, body: Body, end: usize) {
+        if !self.open.iter().any(|(_, b)| *b == body) {
+            return;
+        }
+        while let Some((_, b)) = self.open.last() {
+            let done = *b == body;
+            self.close(Some(end));
+            if done {
+                break;
+            }
+        }
+    }
+
+    fn members(&self) -> Option<(OutlineKind, bool)> {
+        match self.open.last() {
+            Some((parent, Body::Members { free })) => {
+                let kind = match parent.kind {
+                    OutlineKind::DataStructure => OutlineKind::Subfield,
+                    _ => OutlineKind::Parameter,
+                };
+                Some((kind, *free))
+            }
+            _ => None,
+        }
+    }
+
+    fn dspec(&mut self, row: usize, end: usize, name: &Meta, deftype: Option<DefinitionType>) {
+        let text = name.text.trim().trim_end_matches("...");
+        let word = scan_words(name).into_iter().next();
+        let (name, selection) = match (&word, text.is_empty()) {
+            (Some(w), _) if !name.text.trim_end().ends_with("...") => (w.text.clone(), w.span),
+            (_, false) => (text.to_string(), name.span),
+            (_, true) => ("*N".to_string(), name.span),
+        };
+        let kind = match deftype {
+            Some(DefinitionType::DS) => OutlineKind::DataStructure,
+            Some(DefinitionType::PR) => OutlineKind::Prototype,
+            Some(DefinitionType::PI) => OutlineKind::ProcedureInterface,
+            Some(DefinitionType::Empty) => match self.members() {
+                Some((kind, false)) => {
+                    self.push(item(&name, kind, rows(row, end), selection));
+                    return;
+                }
+                _ => return,
+            },
+            _ => return,
+        };
+        self.open(
+            item(&name, kind, rows(row, end), selection),
+            Body::Members { free: false },
+        );
+    }
+
+    fn traditional(&mut self, row: usize, operation: &str, factor1: &Meta) {
+        let op = operation.to_uppercase();
+        let name = scan_words(factor1).into_iter().next();
+        match (op.as_str(), name) {
+            ("BEGSR", Some(w)) => self.open(
+                item(&w.text, OutlineKind::Subroutine, rows(row, row), w.span),
+                Body::Subroutine,
+            ),
+            ("ENDSR", _) => self.close_body(Body::Subroutine, row),
+            ("TAG", Some(w)) => self.push(item(&w.text, OutlineKind::Tag, rows(row, row), w.span)),
+            _ => {}
+        }
+    }
+
+    fn free(&mut self, row: usize, code: &Meta) {
+        let words = scan_words(code);
+        let Some(first) = words.first() else {
+            return;
+        };
+        let head = first.text.to_uppercase();
+        let second = words.get(1).filter(|w| !w.special);
+        let closes_on_same_line = words.iter().skip(1).any(|w| {
+            let t = w.text.to_uppercase();
+            t.starts_with("END-") || t == "LIKEDS" || t == "LIKEREC"
+        });
+        let named = |kind: OutlineKind, w: Option<&Word>| match w {
+            Some(w) => item(&w.text, kind, rows(row, row), w.span),
+            None => item("*N", kind, rows(row, row), first.span),
+        };
+        match head.as_str() {
+            // scan_words only keeps dcl-x and end-x whole
+            "CTL" if code.text.trim().to_uppercase().starts_with("CTL-OPT") => {
+                let options = code.text.trim()[7..].trim().trim_end_matches(';').trim();
+                let col = first.span.start.col;
+                let selection = Span::from(((row, col), (row, col + 7)));
+                self.push(item(
+                    options,
+                    OutlineKind::Options,
+                    rows(row, row),
+                    selection,
+                ));
+            }
+            "DCL-F" => self.push(named(OutlineKind::File, second)),
+            "DCL-DS" | "DCL-PR" | "DCL-PI" => {
+                let kind = match head.as_str() {
+                    "DCL-DS" => OutlineKind::DataStructure,
+                    "DCL-PR" => OutlineKind::Prototype,
+                    _ => OutlineKind::ProcedureInterface,
+                };
+                match closes_on_same_line {
+                    true => self.push(named(kind, second)),
+                    false => self.open(named(kind, second), Body::Members { free: true }),
+                }
+            }
+            "END-DS" | "END-PR" | "END-PI" => {
+                if let Some((_, Body::Members { free: true })) = self.open.last() {
+                    self.close(Some(row));
+                }
+            }
+            "DCL-PROC" => self.open(named(OutlineKind::Procedure, second), Body::Procedure),
+            "END-PROC" => self.close_body(Body::Procedure, row),
+            "BEGSR" => self.open(named(OutlineKind::Subroutine, second), Body::Subroutine),
+            "ENDSR" => self.close_body(Body::Subroutine, row),
+            "DCL-SUBF" | "DCL-PARM" => {
+                if let Some((kind, true)) = self.members() {
+                    self.push(named(kind, second));
+                }
+            }
+            _ => {
+                if let Some((kind, true)) = self.members() {
+                    self.push(named(kind, Some(first)));
+                }
+            }
+        }
+    }
+
+    // fixed format P specs aren't in the cst, ex: `P Main            B                   export`
+    fn pspec(&mut self, row: usize, idk: &Meta) {
+        let chars = idk.text.chars().collect::<Vec<char>>();
+        if chars.len() < 24 || !chars[5].eq_ignore_ascii_case(&'P') || chars[6] == '*' {
+            return;
+        }
+        let name = chars[6..21].iter().collect::<String>();
+        match chars[23].to_ascii_uppercase() {
+            'B' => {
+                let start = name.len() - name.trim_start().len();
+                let selection = Span::from(((row, 6 + start), (row, 6 + name.trim_end().len())));
+                let name = name.trim().trim_end_matches("...");
+                self.open(
+                    item(name, OutlineKind::Procedure, rows(row, row), selection),
+                    Body::Procedure,
+                );
+            }
+            'E' => self.close_body(Body::Procedure, row),
+            _ => {}
+        }
+    }
+}
+
+/* A hierarchical outline of the source: control options, files, data
+ * structures with their subfields, prototypes and interfaces with their
+ * parameters, procedures, subroutines and tags, each with the range of its body.
+ */
+pub fn query_outline(cst: &CST) -> Vec<OutlineItem> {
+    let mut builder = Builder {
+        roots: vec![],
+        open: vec![],
+    };
+    let lines = &cst.lines;
+    let mut row = 0;
+    while row < lines.len() {
+        // continuation lines belong to the spec before them
+        let mut end = row;
+        while let Some(Srcline::FCont { .. } | Srcline::DCont { .. }) = lines.get(end + 1) {
+            end += 1;
+        }
+        let member = matches!(
+            &lines[row],
+            Srcline::D { definition_type, .. }
+                if matches!(definition_type.try_as().map(|x| x.value), Some(DefinitionType::Empty))
+        );
+        let neutral = matches!(
+            &lines[row],
+            Srcline::Comment { .. } | Srcline::CompilerDirective { .. }
+        );
+        if !member && !neutral {
+            if let Some((_, Body::Members { free: false })) = builder.open.last() {
+                builder.close(None);
+            }
+        }
+        match &lines[row] {
+            Srcline::H { keywords, .. } => {
+                if let Some(kw) = keywords.try_as().map(|x| &x.meta) {
+                    let text = kw.text.trim();
+                    if !text.is_empty() {
+                        builder.push(item(text, OutlineKind::Options, rows(row, end), kw.span));
+                    }
+                }
+            }
+            Srcline::F { name, .. } => {
+                if let Some(w) = name
+                    .try_as()
+                    .and_then(|x| scan_words(&x.meta).into_iter().next())
+                {
+                    builder.push(item(&w.text, OutlineKind::File, rows(row, end), w.span));
+                }
+            }
+            Srcline::D {
+                name,
+                definition_type,
+                ..
+            } => {
+                if let Some(name) = name.try_as().map(|x| &x.meta) {
+                    let deftype = definition_type.try_as().map(|x| x.value);
+                    builder.dspec(row, end, name, deftype);
+                }
+            }
+            Srcline::C(CSrcline::Traditional {
+                factor1, operation, ..
+            }) => {
+                if let (Some(op), Some(f1)) =
+                    (operation.try_as(), factor1.try_as().map(|x| &x.meta))
+                {
+                    builder.traditional(row, &op.value, f1);
+                }
+            }
+            Srcline::C(CSrcline::Free { code, .. }) => {
+                if let Some(code) = code.try_as().map(|x| &x.meta) {
+                    builder.free(row, code);
+                }
+            }
+            Srcline::Idk {
+                idk: FieldResult::Idk(idk),
+            } => builder.pspec(row, &idk.meta),
+            _ => {}
+        }
+        row = end + 1;
+    }
+    // unterminated bodies run to the end of the source
+    let last = lines.len().saturating_sub(1);
+    while let Some((_, body)) = builder.open.last() {
+        let end = match body {
+            Body::Members { free: false } => None,
+            _ => Some(last),
+        };
+        builder.close(end);
+    }
+    builder.roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::parse_cst;
+
+    #[test]
+    fn test_outline_snapshot() {
+        let input = r#"
+     H option(*nodebugio:*srcstmt) dftactgrp(*no)
+     FCowEvt    UF A E           K DISK
+     F                                     rename(EVTFMT:VEVTFMT)
+     D Row             DS                  qualified
+     D  Id                            9  0
+     D  Name                         20
+     D LastId          S              8  0
+     D QCmdExc         PR                  extpgm('QCMDEXC')
+     D  Command                    2000    const
+     C                   EXSR      $Init
+     C     Done          TAG
+     C     $Init         BEGSR
+     C                   Z-ADD     1             LastId
+     C                   ENDSR
+     P Bump            B
+     D Bump            PI             9  0
+     D  Value                         9  0
+     C                   RETURN    Value + 1
+     P Bump            E
+      /free
+       ctl-opt main(Run);
+       dcl-ds Totals;
+         Count int(10);
+         dcl-subf Sum packed(9:2);
+       end-ds;
+       dcl-proc Run;
+         dcl-pi *n end-pi;
+         begsr $Tally;
+         endsr;
+       end-proc;
+      /end-free
+"#[1..]
+            .to_string();
+        let cst = parse_cst(&input).unwrap();
+        let outline = query_outline(&cst);
+        insta::assert_yaml_snapshot!(outline);
+    }
+}
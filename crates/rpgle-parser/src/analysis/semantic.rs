@@ -0,0 +1,326 @@
+use super::outline::{query_outline, OutlineItem, OutlineKind};
+use super::symbols::{CallKind, Symbol, SymbolKind, SymbolTable};
+use crate::cst::CST;
+use crate::format::is_opcode;
+use crate::meta::Span;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// declaration keywords, types and operators that scan as plain words
+const KEYWORDS: [&str; 78] = [
+    "ALIGN",
+    "ALT",
+    "ALTSEQ",
+    "AND",
+    "ASCEND",
+    "BASED",
+    "BINDEC",
+    "BLOCK",
+    "CCSID",
+    "CHAR",
+    "COMMIT",
+    "CONST",
+    "CTDATA",
+    "DATE",
+    "DATFMT",
+    "DESCEND",
+    "DIM",
+    "DISK",
+    "DTAARA",
+    "EXPORT",
+    "EXTDESC",
+    "EXTFILE",
+    "EXTFLD",
+    "EXTMBR",
+    "EXTNAME",
+    "EXTPGM",
+    "EXTPROC",
+    "FLOAT",
+    "FROMFILE",
+    "GRAPH",
+    "IMPORT",
+    "IND",
+    "INDDS",
+    "INFDS",
+    "INFSR",
+    "INT",
+    "INZ",
+    "KEYED",
+    "LIKE",
+    "LIKEDS",
+    "LIKEFILE",
+    "LIKEREC",
+    "NOOPT",
+    "NOT",
+    "NOPASS",
+    "OBJECT",
+    "OCCURS",
+    "OFLIND",
+    "OMIT",
+    "OPDESC",
+    "OPTIONS",
+    "OR",
+    "OVERLAY",
+    "PACKED",
+    "PERRCD",
+    "POINTER",
+    "POS",
+    "PREFIX",
+    "PRINTER",
+    "PROCPTR",
+    "QUALIFIED",
+    "RENAME",
+    "RTNPARM",
+    "SFILE",
+    "STATIC",
+    "TEMPLATE",
+    "TIME",
+    "TIMESTAMP",
+    "TOFILE",
+    "UCS2",
+    "UNS",
+    "USAGE",
+    "USROPN",
+    "VALUE",
+    "VARCHAR",
+    "VARYING",
+    "WORKSTN",
+    "ZONED",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SemanticKind {
+    LocalVariable,
+    GlobalVariable,
+    Constant,
+    Parameter,
+    DataStructure,
+    Subfield,
+    File,
+    RecordFormat,
+    ExternalField,
+    Subroutine,
+    Procedure,
+    Tag,
+    // a name that resolves to nothing, likely a typo
+    Undefined,
+}
+
+impl SemanticKind {
+    pub fn hl_group(&self) -> &'static str {
+        match self {
+            Self::LocalVariable => "@variable.local",
+            Self::GlobalVariable => "@variable.global",
+            Self::Constant => "@constant",
+            Self::Parameter => "@variable.parameter",
+            Self::DataStructure => "@variable.structure",
+            Self::Subfield => "@variable.member",
+            Self::File => "@module",
+            Self::RecordFormat => "@type.record",
+            Self::ExternalField => "@variable.member.external",
+            Self::Subroutine => "@function.subroutine",
+            Self::Procedure => "@function",
+            Self::Tag => "@label",
+            Self::Undefined => "@variable.undefined",
+        }
+    }
+}
+
+struct Resolver {
+    definitions: Vec<Symbol>,
+    // row ranges of procedure bodies, names declared inside are local to them
+    procedures: Vec<(usize, usize)>,
+    procedure_names: Vec<String>,
+    record_formats: Vec<String>,
+    fields: Vec<String>,
+}
+
+impl Resolver {
+    fn scope(&self, row: usize) -> Option<(usize, usize)> {
+        self.procedures
+            .iter()
+            .find(|(start, end)| *start <= row && row <= *end)
+            .copied()
+    }
+
+    fn kind(&self, symbol: &Symbol) -> SemanticKind {
+        match symbol.kind {
+            SymbolKind::Standalone => match self.scope(symbol.span.start.row) {
+                Some(_) => SemanticKind::LocalVariable,
+                None => SemanticKind::GlobalVariable,
+            },
+            SymbolKind::Constant => SemanticKind::Constant,
+            SymbolKind::DataStructure => SemanticKind::DataStructure,
+            SymbolKind::Subfield => SemanticKind::Subfield,
+            SymbolKind::Parameter => SemanticKind::Parameter,
+            SymbolKind::File { .. } => SemanticKind::File,
+            SymbolKind::Prototype | SymbolKind::ProcedureInterface => SemanticKind::Procedure,
+            SymbolKind::Subroutine => SemanticKind::Subroutine,
+            SymbolKind::Tag => SemanticKind::Tag,
+        }
+    }
+
+    // a definition in the same procedure hides a global one
+    fn lookup(&self, name: &str, row: usize) -> Option<&Symbol> {
+        let scope = self.scope(row);
+        let candidates = self
+            .definitions
+            .iter()
+            .filter(|x| x.name.to_uppercase() == name)
+            .collect::<Vec<&Symbol>>();
+        candidates
+            .iter()
+            .find(|x| scope.is_some() && self.scope(x.span.start.row) == scope)
+            .or(candidates
+                .iter()
+                .find(|x| self.scope(x.span.start.row).is_none()))
+            .copied()
+    }
+
+    fn reference(&self, name: &str, row: usize) -> Option<SemanticKind> {
+        if KEYWORDS.contains(&name) || is_opcode(name) || name.starts_with("END-") {
+            return None;
+        }
+        if let Some(symbol) = self.lookup(name, row) {
+            Some(self.kind(symbol))
+        } else if self.procedure_names.iter().any(|x| x == name) {
+            Some(SemanticKind::Procedure)
+        } else if self.record_formats.iter().any(|x| x == name) {
+            Some(SemanticKind::RecordFormat)
+        } else if self.fields.iter().any(|x| x == name) {
+            Some(SemanticKind::ExternalField)
+        } else {
+            Some(SemanticKind::Undefined)
+        }
+    }
+}
+
+fn procedures(items: &[OutlineItem], out: &mut Vec<(usize, usize, String)>) {
+    for item in items {
+        if item.kind == OutlineKind::Procedure {
+            let span = item.span;
+            out.push((span.start.row, span.end.row, item.name.to_uppercase()));
+        }
+        procedures(&item.children, out);
+    }
+}
+
+/* Classify every name of the source by what it refers to, with procedure
+ * scope. The record formats and fields of externally described files come
+ * from `formats` and `fields`, both keyed by uppercased file name, and are
+ * only known for files the source declares. Sorted by position.
+ */
+pub fn query_semantic_highlights(
+    cst: &CST,
+    formats: &HashMap<String, Vec<String>>,
+    fields: &HashMap<String, Vec<String>>,
+) -> Vec<(Span, SemanticKind)> {
+    let table = SymbolTable::from(cst);
+    let files = table
+        .definitions
+        .iter()
+        .filter(|x| x.kind == SymbolKind::File { external: true })
+        .map(|x| x.name.to_uppercase())
+        .collect::<Vec<String>>();
+    let external = |map: &HashMap<String, Vec<String>>| {
+        files
+            .iter()
+            .filter_map(|file| map.get(file))
+            .flatten()
+            .map(|x| x.trim().to_uppercase())
+            .collect::<Vec<String>>()
+    };
+    let mut record_formats = external(formats);
+    record_formats.extend(table.record_formats.iter().map(|(_, fmt)| fmt.clone()));
+    let mut bodies = vec![];
+    procedures(&query_outline(cst), &mut bodies);
+    let resolver = Resolver {
+        definitions: table.definitions.clone(),
+        procedures: bodies
+            .iter()
+            .map(|(start, end, _)| (*start, *end))
+            .collect(),
+        procedure_names: bodies.into_iter().map(|(_, _, name)| name).collect(),
+        record_formats,
+        fields: external(fields),
+    };
+    let mut out = vec![];
+    for symbol in &table.definitions {
+        out.push((symbol.span, resolver.kind(symbol)));
+    }
+    for call in &table.calls {
+        let target = table
+            .definitions
+            .iter()
+            .find(|x| x.name.to_uppercase() == call.target);
+        let kind = match (call.kind, target.map(|x| x.kind)) {
+            (CallKind::Exsr, Some(SymbolKind::Subroutine)) => SemanticKind::Subroutine,
+            (CallKind::Goto, Some(SymbolKind::Tag)) => SemanticKind::Tag,
+            _ => SemanticKind::Undefined,
+        };
+        out.push((call.span, kind));
+    }
+    for reference in &table.references {
+        let row = reference.span.start.row;
+        if let Some(kind) = resolver.reference(&reference.name, row) {
+            out.push((reference.span, kind));
+        }
+    }
+    // the main procedure interface also refers to itself
+    let mut seen = vec![];
+    out.retain(|(span, _)| match seen.contains(span) {
+        true => false,
+        false => {
+            seen.push(*span);
+            true
+        }
+    });
+    out.sort_by_key(|(span, _)| (span.start.row, span.start.col));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::parse_cst;
+
+    #[test]
+    fn test_semantic_snapshot() {
+        let input = r#"
+     FCowEvt    UF A E           K DISK
+     D LastId          S              8  0
+     D MaxId           C                   99999
+     D Row             DS                  qualified
+     D  Id                            9  0
+     C                   EXSR      $Init
+     C     $Init         BEGSR
+     C     EVTFMT        CHAIN     CowEvt
+     C                   Z-ADD     EVTID         LastId
+     C                   ENDSR
+      /free
+       dcl-proc Bump;
+         dcl-pi *n;
+           Step int(10) value;
+         end-pi;
+         dcl-s Total packed(9:0);
+         Total = LastId + Step + Row.Id + MaxId + Totl;
+       end-proc;
+      /end-free
+"#[1..]
+            .to_string();
+        let cst = parse_cst(&input).unwrap();
+        let mut formats = HashMap::new();
+        formats.insert("COWEVT".to_string(), vec!["EVTFMT".to_string()]);
+        let mut fields = HashMap::new();
+        fields.insert("COWEVT".to_string(), vec!["EVTID".to_string()]);
+        let observed = query_semantic_highlights(&cst, &formats, &fields)
+            .into_iter()
+            .map(|(span, kind)| {
+                let line = input.lines().nth(span.start.row).unwrap();
+                let name = &line[span.start.col..span.end.col];
+                format!("{} {:?}", name, kind)
+            })
+            .collect::<Vec<String>>();
+        insta::assert_yaml_snapshot!(observed);
+    }
+}
@@ -0,0 +1,373 @@
+use super::completion::continued;
+use super::outline::{query_outline, OutlineItem, OutlineKind};
+use crate::cst::{parse_cst, CSrcline, Srcline, CST};
+use crate::format::strip_comment;
+use serde::{Deserialize, Serialize};
+
+// first column of free-form code and of the extended factor 2
+const CODE: usize = 7;
+const FACTOR2: usize = 35;
+
+// keywords that say how a parameter is passed
+const PASSING: [&str; 3] = ["CONST", "VALUE", "OPTIONS"];
+
+// keywords of a prototype that aren't about its return value
+const LINKAGE: [&str; 4] = ["EXTPGM", "EXTPROC", "END-PR", "END-PI"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name: String,
+    // length and type or the type keyword, ex: 9P 0, char(20), likeds(Row)
+    pub datatype: String,
+    // CONST, VALUE and OPTIONS(...) as written
+    pub options: Vec<String>,
+}
+
+// A PR or PI with its parameter list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Signature {
+    pub name: String,
+    // the return value, ex: 9P 0 or int(10), empty when there is none
+    pub returns: String,
+    pub parameters: Vec<Parameter>,
+    pub row: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureHelp {
+    pub signature: Signature,
+    // zero based, can be past the last parameter while typing
+    pub active_parameter: usize,
+}
+
+impl Parameter {
+    // ex: Command 2000A CONST
+    pub fn label(&self) -> String {
+        let mut parts = vec![self.name.clone()];
+        parts.push(self.datatype.clone());
+        parts.extend(self.options.iter().cloned());
+        join(parts)
+    }
+}
+
+impl Signature {
+    // ex: QCmdExc(Command 2000A CONST : Length 15P 5 CONST)
+    pub fn label(&self) -> String {
+        let parameters = self
+            .parameters
+            .iter()
+            .map(Parameter::label)
+            .collect::<Vec<String>>()
+            .join(" : ");
+        join(vec![
+            format!("{}({})", self.name, parameters),
+            self.returns.clone(),
+        ])
+    }
+}
+
+fn join(parts: Vec<String>) -> String {
+    parts
+        .into_iter()
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// Split keywords at the top level, ex: `const options(*nopass: *omit)` is two
+fn split_keywords(text: &str) -> Vec<String> {
+    let mut out = vec![];
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '\'' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth -= 1,
+            _ => {}
+        }
+        if c == ' ' && depth == 0 && !quoted {
+            if !current.is_empty() {
+                out.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        out.push(current);
+    }
+    out
+}
+
+fn keyword_name(keyword: &str) -> String {
+    keyword
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_uppercase()
+}
+
+/* The type and keywords of a declaration from its lines, `end` included for
+ * fixed-format continuation lines. The name is skipped in free-form.
+ */
+fn declaration(cst: &CST, row: usize, end: usize, name_end: usize) -> (String, Vec<String>) {
+    let trimmed = |field: &dyn ToString| field.to_string().trim().to_string();
+    match cst.lines.get(row) {
+        Some(Srcline::D {
+            to_length,
+            datatype,
+            decimals,
+            keywords,
+            ..
+        }) => {
+            let size = join(vec![
+                format!("{}{}", trimmed(to_length), trimmed(datatype)),
+                trimmed(decimals),
+            ]);
+            let mut text = trimmed(keywords);
+            for line in cst.lines.iter().take(end + 1).skip(row + 1) {
+                if let Srcline::DCont { keywords, .. } = line {
+                    text.push(' ');
+                    text.push_str(&trimmed(keywords));
+                }
+            }
+            (size, split_keywords(&text))
+        }
+        Some(Srcline::C(CSrcline::Free { code, .. })) => {
+            let Some(code) = code.try_as() else {
+                return (String::new(), vec![]);
+            };
+            let skip = name_end.saturating_sub(code.meta.span.start.col);
+            let rest = code.meta.text.chars().skip(skip).collect::<String>();
+            let statement = strip_comment(&rest).split(';').next().unwrap_or_default();
+            let mut keywords = split_keywords(statement.trim());
+            // an unnamed `dcl-pi *n` is selected by its opcode
+            if keywords.first().is_some_and(|x| x.eq_ignore_ascii_case("*N")) {
+                keywords.remove(0);
+            }
+            // the type comes first in free-form, ex: char(20) const
+            match keywords.first().map(|x| keyword_name(x)) {
+                Some(first) if !PASSING.contains(&first.as_str()) => (keywords.remove(0), keywords),
+                _ => (String::new(), keywords),
+            }
+        }
+        _ => (String::new(), vec![]),
+    }
+}
+
+fn parameter(cst: &CST, item: &OutlineItem) -> Parameter {
+    let (size, keywords) = declaration(
+        cst,
+        item.span.start.row,
+        item.span.end.row,
+        item.selection.end.col,
+    );
+    let (options, others): (Vec<String>, Vec<String>) = keywords
+        .into_iter()
+        .partition(|x| PASSING.contains(&keyword_name(x).as_str()));
+    Parameter {
+        name: item.name.clone(),
+        datatype: join(std::iter::once(size).chain(others).collect()),
+        options,
+    }
+}
+
+fn signature(cst: &CST, item: &OutlineItem, procedure: Option<&str>) -> Signature {
+    let row = item.span.start.row;
+    let (size, keywords) = declaration(cst, row, row, item.selection.end.col);
+    let returns = std::iter::once(size)
+        .chain(
+            keywords
+                .into_iter()
+                .filter(|x| !LINKAGE.contains(&keyword_name(x).as_str())),
+        )
+        .collect();
+    // a free-form PI is often named *N after its procedure
+    let name = match (item.name.as_str(), procedure) {
+        ("*N" | "*n", Some(procedure)) => procedure.to_string(),
+        _ => item.name.clone(),
+    };
+    Signature {
+        name,
+        returns: join(returns),
+        parameters: item
+            .children
+            .iter()
+            .filter(|x| x.kind == OutlineKind::Parameter)
+            .map(|x| parameter(cst, x))
+            .collect(),
+        row,
+    }
+}
+
+fn collect(cst: &CST, items: &[OutlineItem], procedure: Option<&str>, out: &mut Vec<Signature>) {
+    for item in items {
+        match item.kind {
+            OutlineKind::Prototype | OutlineKind::ProcedureInterface => {
+                out.push(signature(cst, item, procedure))
+            }
+            OutlineKind::Procedure => collect(cst, &item.children, Some(&item.name), out),
+            _ => collect(cst, &item.children, procedure, out),
+        }
+    }
+}
+
+// Every prototype and procedure interface with its parameters
+pub fn query_signatures(cst: &CST) -> Vec<Signature> {
+    let mut out = vec![];
+    collect(cst, &query_outline(cst), None, &mut out);
+    out
+}
+
+// The statement text up to the cursor, free-form lines before it included
+fn statement(cst: &CST, chars: &[char], row: usize, col: usize) -> Option<String> {
+    let upto = |from: usize| {
+        chars
+            .iter()
+            .take(col.min(chars.len()))
+            .skip(from)
+            .collect::<String>()
+    };
+    match cst.lines.get(row)? {
+        Srcline::C(CSrcline::ExtF2 { .. }) if col >= FACTOR2 => Some(upto(FACTOR2)),
+        Srcline::C(CSrcline::Free { .. }) if col >= CODE => {
+            let mut text = upto(CODE);
+            let mut at = row;
+            while at > 0 && continued(cst, at) {
+                at -= 1;
+                if let Some(Srcline::C(CSrcline::Free { code, .. })) = cst.lines.get(at) {
+                    let code = code.to_string();
+                    text = format!("{} {}", strip_comment(&code).trim(), text);
+                }
+            }
+            Some(text)
+        }
+        _ => None,
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '@' || c == '$' || c == '#' || c == '_'
+}
+
+/* The prototype called by the innermost open call around the cursor at
+ * `row` and `col`, both zero based, and which of its parameters the cursor is
+ * on. Calls to built-in functions are looked through.
+ */
+pub fn query_signature_help(input: &str, row: usize, col: usize) -> Option<SignatureHelp> {
+    let cst = parse_cst(input).ok()?;
+    let line = input.split('\n').nth(row)?;
+    let chars = line.trim_end_matches('\r').chars().collect::<Vec<char>>();
+    let text = statement(&cst, &chars, row, col)?;
+    let chars = text.chars().collect::<Vec<char>>();
+    // open parens with the colons seen inside each so far
+    let mut open: Vec<(usize, usize)> = vec![];
+    let mut quoted = false;
+    for (i, c) in chars.iter().enumerate() {
+        match c {
+            '\'' => quoted = !quoted,
+            '(' if !quoted => open.push((i, 0)),
+            ')' if !quoted => {
+                open.pop();
+            }
+            ':' if !quoted => {
+                if let Some(last) = open.last_mut() {
+                    last.1 += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    let signatures = query_signatures(&cst);
+    for (paren, colons) in open.iter().rev() {
+        let before = chars[..*paren]
+            .iter()
+            .collect::<String>()
+            .trim_end()
+            .chars()
+            .collect::<Vec<char>>();
+        let len = before
+            .iter()
+            .rev()
+            .take_while(|c| is_name_char(**c))
+            .count();
+        let start = before.len() - len;
+        if len == 0 || (start > 0 && before[start - 1] == '%') {
+            continue;
+        }
+        let name = before[start..].iter().collect::<String>().to_uppercase();
+        if let Some(signature) = signatures.iter().find(|x| x.name.to_uppercase() == name) {
+            return Some(SignatureHelp {
+                signature: signature.clone(),
+                active_parameter: *colons,
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> String {
+        r#"
+     D QCmdExc         PR                  extpgm('QCMDEXC')
+     D  Command                    2000A   const
+     D  Length                       15P 5 const
+     D                                     options(*nopass)
+     C                   CALLP     QCmdExc(Cmd:
+      /free
+       dcl-pr Bump packed(9:0);
+         Value packed(9:0) value;
+         dcl-parm Step int(10) const options(*nopass);
+       end-pr;
+       dcl-proc Scale;
+         dcl-pi *n int(10);
+           Factor int(10) value;
+         end-pi;
+       end-proc;
+       x = Bump(%int(y):
+                Scale(
+      /end-free
+"#[1..]
+            .to_string()
+    }
+
+    #[test]
+    fn test_signatures_snapshot() {
+        let cst = parse_cst(&fixture()).unwrap();
+        insta::assert_yaml_snapshot!(query_signatures(&cst));
+    }
+
+    #[test]
+    fn test_signature_help() {
+        let input = fixture();
+        let help = query_signature_help(&input, 4, 47).unwrap();
+        assert_eq!(help.signature.name, "QCmdExc");
+        assert_eq!(help.active_parameter, 1);
+        assert_eq!(
+            help.signature.label(),
+            "QCmdExc(Command 2000A const : Length 15P 5 const options(*nopass))"
+        );
+        // a BIF call is looked through, the Bump call spans two lines
+        let help = query_signature_help(&input, 15, 21).unwrap();
+        assert_eq!(
+            (help.signature.name.as_str(), help.active_parameter),
+            ("Bump", 0)
+        );
+        let help = query_signature_help(&input, 16, 22).unwrap();
+        assert_eq!(
+            (help.signature.name.as_str(), help.active_parameter),
+            ("Scale", 0)
+        );
+        assert_eq!(
+            help.signature.label(),
+            "Scale(Factor int(10) value) int(10)"
+        );
+        assert!(query_signature_help(&input, 10, 20).is_none());
+    }
+}
@@ -0,0 +1,517 @@
+use crate::cst::{CSrcline, Srcline, CST};
+use crate::field::{DefinitionType, FieldResult, FileFormat};
+use crate::meta::{Meta, Position, Span};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SymbolKind {
+    Standalone,
+    Constant,
+    DataStructure,
+    Subfield,
+    Prototype,
+    ProcedureInterface,
+    Parameter,
+    File { external: bool },
+    Subroutine,
+    Tag,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    // span of the name itself, not the whole spec
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reference {
+    // uppercased, RPG names are case insensitive
+    pub name: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CallKind {
+    Exsr,
+    Goto,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Call {
+    // uppercased name of the enclosing subroutine, None for mainline code
+    pub from: Option<String>,
+    pub target: String,
+    pub kind: CallKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolTable {
+    pub definitions: Vec<Symbol>,
+    pub references: Vec<Reference>,
+    pub calls: Vec<Call>,
+    // (file, record format) pairs learned from RENAME keywords, uppercased
+    pub record_formats: Vec<(String, String)>,
+}
+
+impl SymbolTable {
+    pub fn is_referenced(&self, name: &str) -> bool {
+        let name = name.to_uppercase();
+        self.references.iter().any(|r| r.name == name)
+    }
+
+    pub fn calls_to(&self, name: &str) -> Vec<&Call> {
+        let name = name.to_uppercase();
+        self.calls.iter().filter(|c| c.target == name).collect()
+    }
+}
+
+// Words ---------------------------------------------------------------------------
+#[derive(Debug, Clone)]
+pub struct Word {
+    pub text: String,
+    pub span: Span,
+    // prefixed with `*` or `%`, ex: *INLR, *ON, %EOF
+    pub special: bool,
+    // immediately followed by `(`, ex: keyword or procedure call
+    pub call: bool,
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_alphabetic() || c == '@' || c == '$' || c == '#' || c == '_'
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '@' || c == '$' || c == '#' || c == '_'
+}
+
+// Cut a single line field into name-like words. String literals and `//`
+// comments are skipped, and `dcl-x`/`end-x` are kept whole so free-form
+// declarations read naturally.
+pub fn scan_words(meta: &Meta) -> Vec<Word> {
+    let chars = meta.text.chars().collect::<Vec<char>>();
+    let origin = meta.span.start;
+    let mut out = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\'' {
+            i += 1;
+            while i < chars.len() && chars[i] != '\'' {
+                i += 1;
+            }
+            i += 1;
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            break;
+        }
+        if c.is_numeric() {
+            while i < chars.len() && is_name_char(chars[i]) {
+                i += 1;
+            }
+            continue;
+        }
+        if !is_name_start(c) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && is_name_char(chars[i]) {
+            i += 1;
+        }
+        let head = chars[start..i].iter().collect::<String>().to_uppercase();
+        if (head == "DCL" || head == "END")
+            && chars.get(i) == Some(&'-')
+            && chars.get(i + 1).is_some_and(|c| c.is_alphabetic())
+        {
+            i += 1;
+            while i < chars.len() && is_name_char(chars[i]) {
+                i += 1;
+            }
+        }
+        let special = start > 0 && (chars[start - 1] == '*' || chars[start - 1] == '%');
+        let call = chars[i..].iter().find(|c| **c != ' ') == Some(&'(');
+        out.push(Word {
+            text: chars[start..i].iter().collect::<String>(),
+            span: Span {
+                start: Position::from((origin.row, origin.col + start)),
+                end: Position::from((origin.row, origin.col + i)),
+            },
+            special,
+            call,
+        });
+    }
+    out
+}
+
+// Table ---------------------------------------------------------------------------
+struct Builder {
+    table: SymbolTable,
+    container: Option<DefinitionType>,
+    // containers opened by D-specs end at the first non D-spec line
+    free_container: bool,
+    subroutine: Option<String>,
+}
+
+// *INZSR and *PSSR keep their `*`, they aren't the same as INZSR and PSSR
+fn subroutine_name(word: &Word) -> String {
+    match word.special {
+        true => format!("*{}", word.text.to_uppercase()),
+        false => word.text.to_uppercase(),
+    }
+}
+
+impl Builder {
+    fn begsr(&mut self, word: &Word) {
+        self.table.definitions.push(Symbol {
+            name: match word.special {
+                true => format!("*{}", word.text),
+                false => word.text.clone(),
+            },
+            kind: SymbolKind::Subroutine,
+            span: word.span,
+        });
+        self.subroutine = Some(subroutine_name(word));
+    }
+
+    fn define(&mut self, word: &Word, kind: SymbolKind) {
+        self.table.definitions.push(Symbol {
+            name: word.text.clone(),
+            kind,
+            span: word.span,
+        });
+    }
+
+    fn refer(&mut self, words: &[Word]) {
+        for w in words.iter().filter(|w| !w.special) {
+            self.table.references.push(Reference {
+                name: w.text.to_uppercase(),
+                span: w.span,
+            });
+        }
+    }
+
+    fn call(&mut self, word: &Word, kind: CallKind) {
+        self.table.calls.push(Call {
+            from: self.subroutine.clone(),
+            target: subroutine_name(word),
+            kind,
+            span: word.span,
+        });
+    }
+
+    fn member_kind(&self) -> Option<SymbolKind> {
+        match self.container {
+            Some(DefinitionType::DS) => Some(SymbolKind::Subfield),
+            Some(DefinitionType::PR) | Some(DefinitionType::PI) => Some(SymbolKind::Parameter),
+            _ => None,
+        }
+    }
+
+    fn dspec(&mut self, name: &Meta, deftype: Option<DefinitionType>, keywords: &[Word]) {
+        // long names continue with `...` onto the next line, YAGNI
+        let words = scan_words(name);
+        let kind = match deftype {
+            Some(DefinitionType::S) => Some(SymbolKind::Standalone),
+            Some(DefinitionType::C) => Some(SymbolKind::Constant),
+            Some(DefinitionType::DS) => Some(SymbolKind::DataStructure),
+            Some(DefinitionType::PR) => Some(SymbolKind::Prototype),
+            Some(DefinitionType::PI) => Some(SymbolKind::ProcedureInterface),
+            Some(DefinitionType::Empty) => self.member_kind(),
+            None => None,
+        };
+        match deftype {
+            Some(DefinitionType::DS) | Some(DefinitionType::PR) | Some(DefinitionType::PI) => {
+                self.container = deftype;
+                self.free_container = false;
+            }
+            Some(DefinitionType::Empty) => {}
+            _ => self.container = None,
+        }
+        if let (Some(word), Some(kind)) = (words.first(), kind) {
+            if !name.text.trim_end().ends_with("...") {
+                self.define(word, kind);
+                // the main procedure interface implies its own prototype
+                if kind == SymbolKind::ProcedureInterface {
+                    self.refer(&words[..1]);
+                }
+            }
+        }
+        // keyword names are not references, but their args can be
+        let args = keywords
+            .iter()
+            .filter(|w| !w.call)
+            .cloned()
+            .collect::<Vec<Word>>();
+        self.refer(&args);
+    }
+
+    fn fspec(&mut self, name: &Meta, external: bool, keywords: &[Word]) {
+        let words = scan_words(name);
+        if let Some(word) = words.first() {
+            self.define(word, SymbolKind::File { external });
+            let file = word.text.to_uppercase();
+            // RENAME(external:internal) tells us record format names for free
+            for (i, kw) in keywords.iter().enumerate() {
+                if kw.call && kw.text.to_uppercase() == "RENAME" {
+                    for fmt in keywords.iter().skip(i + 1).take(2) {
+                        self.table
+                            .record_formats
+                            .push((file.clone(), fmt.text.to_uppercase()));
+                    }
+                }
+            }
+        }
+    }
+
+    fn traditional(&mut self, operation: &str, factor1: &Meta, factor2: &Meta, result: &Meta) {
+        let op = operation.to_uppercase();
+        let f1 = scan_words(factor1);
+        let f2 = scan_words(factor2);
+        let rs = scan_words(result);
+        if op == "BEGSR" {
+            if let Some(w) = f1.first() {
+                self.begsr(w);
+            }
+        } else if op == "ENDSR" {
+            self.subroutine = None;
+        } else if op == "TAG" {
+            if let Some(w) = f1.first() {
+                self.define(w, SymbolKind::Tag);
+            }
+        } else if op == "EXSR" {
+            if let Some(w) = f2.first() {
+                self.call(w, CallKind::Exsr);
+            }
+        } else if op == "GOTO" {
+            if let Some(w) = f2.first() {
+                self.call(w, CallKind::Goto);
+            }
+            self.refer(&f1);
+        } else if op.starts_with("CAS") || op.starts_with("CAB") {
+            let kind = match op.starts_with("CAS") {
+                true => CallKind::Exsr,
+                false => CallKind::Goto,
+            };
+            if let Some(w) = rs.first() {
+                self.call(w, kind);
+            }
+            self.refer(&f1);
+            self.refer(&f2);
+        } else {
+            self.refer(&f1);
+            self.refer(&f2);
+            self.refer(&rs);
+        }
+    }
+
+    fn free(&mut self, code: &Meta) {
+        let words = scan_words(code);
+        let Some(first) = words.first() else {
+            return;
+        };
+        let head = first.text.to_uppercase();
+        let second = words.get(1);
+        let rest = if words.len() > 2 { &words[2..] } else { &[] };
+        let closes_on_same_line = words.iter().skip(1).any(|w| {
+            let t = w.text.to_uppercase();
+            t.starts_with("END-") || t == "LIKEDS" || t == "LIKEREC"
+        });
+        match head.as_str() {
+            "BEGSR" => {
+                if let Some(w) = second {
+                    self.begsr(w);
+                }
+            }
+            "ENDSR" => {
+                self.subroutine = None;
+                self.refer(&words[1..]);
+            }
+            "EXSR" => {
+                if let Some(w) = second {
+                    self.call(w, CallKind::Exsr);
+                }
+            }
+            "DCL-S" | "DCL-C" | "DCL-F" | "DCL-DS" | "DCL-PR" | "DCL-PI" => {
+                let (kind, container) = match head.as_str() {
+                    "DCL-S" => (SymbolKind::Standalone, None),
+                    "DCL-C" => (SymbolKind::Constant, None),
+                    "DCL-F" => (SymbolKind::File { external: true }, None),
+                    "DCL-DS" => (SymbolKind::DataStructure, Some(DefinitionType::DS)),
+                    "DCL-PR" => (SymbolKind::Prototype, Some(DefinitionType::PR)),
+                    _ => (SymbolKind::ProcedureInterface, Some(DefinitionType::PI)),
+                };
+                if let Some(w) = second.filter(|w| !w.special) {
+                    self.define(w, kind);
+                    if kind == SymbolKind::ProcedureInterface {
+                        self.refer(&words[1..2]);
+                    }
+                }
+                self.container = match closes_on_same_line {
+                    true => None,
+                    false => container,
+                };
+                self.free_container = true;
+                self.refer(rest);
+            }
+            "END-DS" | "END-PR" | "END-PI" => {
+                self.container = None;
+            }
+            "DCL-SUBF" | "DCL-PARM" => {
+                if let (Some(w), Some(kind)) = (second, self.member_kind()) {
+                    self.define(w, kind);
+                }
+                self.refer(rest);
+            }
+            _ => match self.member_kind() {
+                Some(kind) => {
+                    self.define(first, kind);
+                    self.refer(&words[1..]);
+                }
+                None => self.refer(&words),
+            },
+        }
+    }
+}
+
+fn keyword_words(keywords: &FieldResult<crate::field::RawKeywordsField>) -> Vec<Word> {
+    match keywords {
+        FieldResult::Ok(kw) => scan_words(&kw.meta),
+        FieldResult::Idk(_) => vec![],
+    }
+}
+
+impl From<&CST> for SymbolTable {
+    fn from(cst: &CST) -> Self {
+        let mut builder = Builder {
+            table: SymbolTable {
+                definitions: vec![],
+                references: vec![],
+                calls: vec![],
+                record_formats: vec![],
+            },
+            container: None,
+            free_container: false,
+            subroutine: None,
+        };
+        let lines = &cst.lines;
+        let mut idx = 0;
+        while idx < lines.len() {
+            // gather keywords from continuation lines up front
+            let mut conts = vec![];
+            let mut next = idx + 1;
+            while let Some(Srcline::FCont { keywords, .. } | Srcline::DCont { keywords, .. }) =
+                lines.get(next)
+            {
+                conts.append(&mut keyword_words(keywords));
+                next += 1;
+            }
+            let is_dspec = matches!(&lines[idx], Srcline::D { .. });
+            if !is_dspec && !builder.free_container {
+                builder.container = None;
+            }
+            match &lines[idx] {
+                Srcline::D {
+                    name,
+                    definition_type,
+                    keywords,
+                    ..
+                } => {
+                    let mut kws = keyword_words(keywords);
+                    kws.append(&mut conts);
+                    if let Some(name) = name.try_as().map(|x| &x.meta) {
+                        let deftype = definition_type.try_as().map(|x| x.value);
+                        builder.dspec(name, deftype, &kws);
+                    }
+                }
+                Srcline::F {
+                    name,
+                    file_format,
+                    keywords,
+                    ..
+                } => {
+                    let mut kws = keyword_words(keywords);
+                    kws.append(&mut conts);
+                    let external =
+                        !matches!(file_format.try_as().map(|x| x.value), Some(FileFormat::F));
+                    if let Some(name) = name.try_as().map(|x| &x.meta) {
+                        builder.fspec(name, external, &kws);
+                    }
+                }
+                Srcline::C(CSrcline::Traditional {
+                    factor1,
+                    operation,
+                    factor2,
+                    result,
+                    ..
+                }) => {
+                    if let (Some(op), Some(f1), Some(f2), Some(rs)) = (
+                        operation.try_as(),
+                        factor1.try_as().map(|x| &x.meta),
+                        factor2.try_as().map(|x| &x.meta),
+                        result.try_as().map(|x| &x.meta),
+                    ) {
+                        builder.traditional(&op.value, f1, f2, rs);
+                    }
+                }
+                Srcline::C(CSrcline::ExtF2 {
+                    factor1, factor2, ..
+                }) => {
+                    if let Some(f1) = factor1.try_as().map(|x| &x.meta) {
+                        builder.refer(&scan_words(f1));
+                    }
+                    if let Some(f2) = factor2.try_as().map(|x| &x.meta) {
+                        builder.refer(&scan_words(f2));
+                    }
+                }
+                Srcline::C(CSrcline::Free { code, .. }) => {
+                    if let Some(code) = code.try_as().map(|x| &x.meta) {
+                        builder.free(code);
+                    }
+                }
+                Srcline::Idk { .. }
+                | Srcline::Comment { .. }
+                | Srcline::CompilerDirective { .. }
+                | Srcline::H { .. }
+                | Srcline::FCont { .. }
+                | Srcline::DCont { .. } => {}
+            }
+            idx = match &lines[idx] {
+                Srcline::D { .. } | Srcline::F { .. } => next,
+                _ => idx + 1,
+            };
+        }
+        builder.table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_words() {
+        let meta = Meta::from((
+            Position::from((3, 7)),
+            "If *inlr = %eof(File) and Name = 'Not Me'; // comment"
+                .chars()
+                .collect::<Vec<char>>()
+                .as_slice(),
+        ));
+        let observed = scan_words(&meta)
+            .into_iter()
+            .filter(|w| !w.special)
+            .map(|w| (w.text, w.span.start.col, w.call))
+            .collect::<Vec<_>>();
+        let expected = vec![
+            ("If".to_string(), 7, false),
+            ("File".to_string(), 23, false),
+            ("and".to_string(), 29, false),
+            ("Name".to_string(), 33, false),
+        ];
+        assert_eq!(observed, expected);
+    }
+}
@@ -0,0 +1,196 @@
+use super::symbols::{CallKind, SymbolKind, SymbolTable};
+use crate::cst::CST;
+use crate::meta::diagnostic::{Diagnostic, DiagnosticLevel};
+use std::collections::{HashMap, HashSet};
+
+// the runtime calls these on its own, they never need an EXSR
+const IMPLICIT_SUBROUTINES: [&str; 2] = ["*INZSR", "*PSSR"];
+
+fn unused(span: crate::meta::Span, code: &str, msg: String) -> Diagnostic {
+    Diagnostic {
+        span,
+        level: DiagnosticLevel::Warning,
+        code: code.to_string(),
+        msg,
+    }
+}
+
+// Subroutines are live if mainline code can reach them through EXSR/CASxx.
+// A subroutine only called from dead subroutines is dead too.
+fn live_subroutines(table: &SymbolTable) -> HashSet<String> {
+    let mut live = HashSet::new();
+    let mut queue = table
+        .calls
+        .iter()
+        .filter(|c| c.kind == CallKind::Exsr && c.from.is_none())
+        .map(|c| c.target.clone())
+        .collect::<Vec<String>>();
+    queue.extend(IMPLICIT_SUBROUTINES.iter().map(|s| s.to_string()));
+    while let Some(name) = queue.pop() {
+        if live.insert(name.clone()) {
+            for call in table.calls.iter() {
+                if call.kind == CallKind::Exsr && call.from.as_ref() == Some(&name) {
+                    queue.push(call.target.clone());
+                }
+            }
+        }
+    }
+    live
+}
+
+/* Report definitions nothing refers to. `record_formats` maps an uppercased
+ * file name to its record format names (ex: from the DDS in the manifest) so
+ * externally described files that are only touched through their formats
+ * count as used. Without that knowledge, such files are skipped rather than
+ * guessed at.
+ */
+pub fn query_unused(cst: &CST, record_formats: &HashMap<String, Vec<String>>) -> Vec<Diagnostic> {
+    let table = SymbolTable::from(cst);
+    let live = live_subroutines(&table);
+    let mut out = vec![];
+    for def in table.definitions.iter() {
+        let upper = def.name.to_uppercase();
+        match def.kind {
+            SymbolKind::Standalone | SymbolKind::Constant | SymbolKind::Prototype => {
+                if table.is_referenced(&def.name) {
+                    continue;
+                }
+                let (code, what) = match def.kind {
+                    SymbolKind::Standalone => ("unused-field", "Standalone field"),
+                    SymbolKind::Constant => ("unused-constant", "Constant"),
+                    _ => ("unused-prototype", "Prototype"),
+                };
+                let msg = format!("{} {} is never referenced", what, def.name);
+                out.push(unused(def.span, code, msg));
+            }
+            SymbolKind::File { external } => {
+                let mut formats = table
+                    .record_formats
+                    .iter()
+                    .filter(|(file, _)| *file == upper)
+                    .map(|(_, fmt)| fmt.clone())
+                    .collect::<Vec<String>>();
+                if let Some(known) = record_formats.get(&upper) {
+                    formats.extend(known.iter().map(|f| f.to_uppercase()));
+                }
+                if external && formats.is_empty() && !record_formats.contains_key(&upper) {
+                    continue;
+                }
+                if table.is_referenced(&def.name) || formats.iter().any(|f| table.is_referenced(f))
+                {
+                    continue;
+                }
+                let msg = format!("File {} is never referenced", def.name);
+                out.push(unused(def.span, "unused-file", msg));
+            }
+            SymbolKind::Subroutine => {
+                if live.contains(&upper) {
+                    continue;
+                }
+                let msg = match table.calls_to(&def.name).is_empty() {
+                    true => format!("Subroutine {} is never called", def.name),
+                    false => format!(
+                        "Subroutine {} is only called from subroutines that never run",
+                        def.name
+                    ),
+                };
+                out.push(unused(def.span, "dead-subroutine", msg));
+            }
+            SymbolKind::Tag => {
+                let targeted = table
+                    .calls_to(&def.name)
+                    .iter()
+                    .any(|c| c.kind == CallKind::Goto);
+                if !targeted {
+                    let msg = format!("Tag {} is never the target of a GOTO or CABxx", def.name);
+                    out.push(unused(def.span, "unused-tag", msg));
+                }
+            }
+            SymbolKind::DataStructure
+            | SymbolKind::Subfield
+            | SymbolKind::ProcedureInterface
+            | SymbolKind::Parameter => {}
+        }
+    }
+    out.sort_by_key(|d| (d.span.start.row, d.span.start.col));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::parse_cst;
+
+    fn fixture() -> String {
+        r#"
+     FCowEvt    UF A E           K DISK
+     FCowEvtL2  IF   E           K DISK     Rename(EVTFMT:VEVTFMT)
+     FReport    O    F  132        PRINTER
+     D LastId          S              8  0
+     D Unused          S             10
+     D MaxId           C                   99999
+     D QCmdExc         PR                  EXTPGM('QCMDEXC')
+     D  Command                    2000
+     D  Length                       15  5
+       Exsr $SetLstId;
+       *inlr = *on;
+
+       Begsr $SetLstId;
+         SetLL *Loval CowEvtL2;
+         LastId = Vid;
+       Endsr;
+
+     C     $Orphan       BEGSR
+     C                   EXSR      $Zombie
+     C     Again         TAG
+     C                   ENDSR
+     C     $Zombie       BEGSR
+     C                   ENDSR                                                                      "#
+            [1..]
+            .to_string()
+    }
+
+    #[test]
+    fn test_unused_snapshot() {
+        let input = fixture();
+        let cst = parse_cst(&input).unwrap();
+        let observed = query_unused(&cst, &HashMap::new());
+        insta::assert_yaml_snapshot!(observed);
+    }
+
+    #[test]
+    fn test_unused_file_with_known_formats() {
+        let input = fixture();
+        let cst = parse_cst(&input).unwrap();
+        let mut formats = HashMap::new();
+        formats.insert("COWEVT".to_string(), vec!["EVTFMT".to_string()]);
+        let observed = query_unused(&cst, &formats)
+            .into_iter()
+            .filter(|d| d.code == "unused-file")
+            .map(|d| d.msg)
+            .collect::<Vec<String>>();
+        let expected = vec![
+            "File CowEvt is never referenced".to_string(),
+            "File Report is never referenced".to_string(),
+        ];
+        assert_eq!(observed, expected);
+    }
+
+    #[test]
+    fn test_implicit_subroutines() {
+        let input = r#"
+       *inlr = *on;
+
+     C     *INZSR        BEGSR
+     C                   EXSR      $Setup
+     C                   ENDSR
+       Begsr *pssr;
+       Endsr;
+       Begsr $Setup;
+       Endsr;
+"#[1..]
+            .to_string();
+        let cst = parse_cst(&input).unwrap();
+        assert_eq!(query_unused(&cst, &HashMap::new()), vec![]);
+    }
+}
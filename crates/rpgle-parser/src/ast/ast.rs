@@ -1,12 +1,25 @@
 // Public API for AST
+use super::emit::emit;
 use super::nvim::{highlight_ast, query_definition};
 use super::spec::{ast, ParseError, Spec};
+use super::trivia::{attach_trivia, Trivia};
 use crate::cst::{Srcline, CST};
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AST {
     pub specs: Vec<Spec>,
+    // the source lines of each spec, same order as `specs`
+    pub trivia: Vec<Trivia>,
+    // comments, directives and blank lines after the last spec
+    pub trailing: Vec<Srcline>,
+}
+
+impl Display for AST {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", emit(self))
+    }
 }
 
 type SpanShape = ((usize, usize), (usize, usize));
@@ -30,5 +43,10 @@ pub fn parse_ast(cst: &CST) -> Result<AST, ParseError> {
         .map(|line| line.clone())
         .collect::<Vec<Srcline>>();
     let (specs, _) = ast(&mut lines)?;
-    Ok(AST { specs })
+    let (trivia, trailing) = attach_trivia(&cst.lines);
+    Ok(AST {
+        specs,
+        trivia,
+        trailing,
+    })
 }
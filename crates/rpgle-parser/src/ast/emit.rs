@@ -0,0 +1,245 @@
+use super::ast::AST;
+use super::spec::Spec;
+use super::trivia::Trivia;
+use crate::field::{FieldBehavior, FieldResult};
+use crate::meta::Span;
+use std::fmt::Display;
+
+fn piece<T>(field: &FieldResult<T>) -> (Span, String)
+where
+    T: FieldBehavior + Display,
+{
+    (field.span(), field.to_string())
+}
+
+// what a spec says about its source, as text at a span
+fn pieces(spec: &Spec) -> Vec<(Span, String)> {
+    match spec {
+        Spec::H {
+            sequence,
+            form_type,
+            keywords,
+        } => {
+            let mut out = vec![piece(sequence), piece(form_type)];
+            if let FieldResult::Ok(kw) = keywords {
+                out.extend(kw.tokens.iter().map(|t| (t.meta.span, t.meta.text.clone())));
+            }
+            out
+        }
+        Spec::F {
+            sequence,
+            form_type,
+            name,
+            filetype,
+            file_designation,
+            endfile,
+            file_addition,
+            file_sequence,
+            file_format,
+            record_length,
+            limits_processing,
+            keylength,
+            record_address_type,
+            file_organization,
+            device,
+            reserved,
+            keywords,
+        } => {
+            let mut out = vec![
+                piece(sequence),
+                piece(form_type),
+                piece(name),
+                piece(filetype),
+                piece(file_designation),
+                piece(endfile),
+                piece(file_addition),
+                piece(file_sequence),
+                piece(file_format),
+                piece(record_length),
+                piece(limits_processing),
+                piece(keylength),
+                piece(record_address_type),
+                piece(file_organization),
+                piece(device),
+                piece(reserved),
+            ];
+            if let FieldResult::Ok(kw) = keywords {
+                out.extend(
+                    kw.tokens
+                        .iter()
+                        .flat_map(|t| t.metas.iter())
+                        .map(|m| (m.span, m.text.clone())),
+                );
+            }
+            out
+        }
+        Spec::D {
+            sequence,
+            form_type,
+            name,
+            external_description,
+            datastructure_type,
+            definition_type,
+            from_position,
+            to_length,
+            datatype,
+            decimals,
+            reserved,
+            keywords,
+        } => {
+            let mut out = vec![
+                piece(sequence),
+                piece(form_type),
+                piece(name),
+                piece(external_description),
+                piece(datastructure_type),
+                piece(definition_type),
+                piece(from_position),
+                piece(to_length),
+                piece(datatype),
+                piece(decimals),
+                piece(reserved),
+            ];
+            if let FieldResult::Ok(kw) = keywords {
+                out.extend(
+                    kw.tokens
+                        .iter()
+                        .flat_map(|t| t.metas.iter())
+                        .map(|m| (m.span, m.text.clone())),
+                );
+            }
+            out
+        }
+        Spec::C { code } => vec![piece(code)],
+    }
+}
+
+/* Write each piece over the spec's source lines at its span. Unchanged
+ * pieces leave the lines as they were, a shorter one is padded and a longer
+ * one pushes the rest of its line right. Pieces spanning lines are left out.
+ */
+fn paint(trivia: &Trivia, spec: &Spec) -> Vec<String> {
+    let mut lines = trivia
+        .lines
+        .iter()
+        .map(|x| x.to_string().chars().collect::<Vec<char>>())
+        .collect::<Vec<Vec<char>>>();
+    let mut pieces = pieces(spec)
+        .into_iter()
+        .filter(|(span, _)| span.start.row == span.end.row && span.start.row >= trivia.row)
+        .collect::<Vec<(Span, String)>>();
+    // right to left, so the columns of the pieces still to come don't move
+    pieces.sort_by_key(|(span, _)| (span.start.row, std::cmp::Reverse(span.start.col)));
+    for (span, text) in pieces {
+        let idx = span.start.row - trivia.row;
+        if idx >= lines.len() {
+            lines.resize(idx + 1, vec![]);
+        }
+        let line = &mut lines[idx];
+        if line.len() < span.end.col {
+            line.resize(span.end.col, ' ');
+        }
+        let mut chars = text.chars().collect::<Vec<char>>();
+        let width = span.end.col - span.start.col;
+        if chars.len() < width {
+            chars.resize(width, ' ');
+        }
+        line.splice(span.start.col..span.end.col, chars);
+    }
+    lines.iter().map(|x| x.iter().collect::<String>()).collect()
+}
+
+/* The source of an AST, its specs written back into the lines they came
+ * from along with the comments, directives and blank lines around them.
+ * Specs without trivia, like ones a transformation added, start new lines.
+ */
+pub fn emit(ast: &AST) -> String {
+    let mut out = vec![];
+    for (idx, spec) in ast.specs.iter().enumerate() {
+        let trivia = ast.trivia.get(idx).cloned().unwrap_or_else(|| Trivia {
+            leading: vec![],
+            row: spec_row(spec),
+            lines: vec![],
+        });
+        out.extend(trivia.leading.iter().map(|x| x.to_string()));
+        out.extend(paint(&trivia, spec));
+    }
+    out.extend(ast.trailing.iter().map(|x| x.to_string()));
+    out.join("\n")
+}
+
+fn spec_row(spec: &Spec) -> usize {
+    pieces(spec)
+        .iter()
+        .map(|(span, _)| span.start.row)
+        .min()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ast::{parse_ast, Spec};
+    use crate::cst::parse_cst;
+    use crate::field::FieldResult;
+    use crate::format::normalize_width;
+
+    fn fixture() -> String {
+        let input = r#"
+     H OPTION(*nodebugio:*srcstmt)
+     FCowEvtL2  IF   E           K DISK     Rename(EVTFMT:VEVTFMT)
+     F* the key is the cow id
+     F                                     Prefix(V)
+     D**********************************************************
+     D LastId          S              8  0
+     D QCmdExc         PR                  EXTPGM('QCMDEXC')
+     D  Command                    2000
+     D  Length                       15  5
+      /free
+       Exsr $SetLstId;
+       *inlr = *on;
+
+       Begsr $SetLstId;
+         SetLL *Loval CowEvtL2;
+       Endsr;
+      /end-free
+     C     $CrtBRNEVT    BegSr
+     C                   Z-ADD     1             LastId               50
+     C                   EVAL      LastId = LastId
+     C                                   + 1
+     C                   ENDSR
+     C* done"#;
+        normalize_width(&input[1..], 100)
+    }
+
+    #[test]
+    fn test_ast_round_trip() {
+        let input = fixture();
+        let cst = parse_cst(&input).unwrap();
+        let ast = parse_ast(&cst).unwrap();
+        assert_eq!(ast.to_string(), input);
+        // one Trivia per spec, the FCont and the comment before it stay with the F spec
+        assert_eq!(ast.trivia.len(), ast.specs.len());
+        assert_eq!(ast.trivia[1].lines.len(), 3);
+        assert_eq!(ast.trivia[2].leading.len(), 1);
+    }
+
+    #[test]
+    fn test_ast_emit_edit() {
+        let input = fixture();
+        let cst = parse_cst(&input).unwrap();
+        let mut ast = parse_ast(&cst).unwrap();
+        for spec in ast.specs.iter_mut() {
+            if let Spec::D {
+                name: FieldResult::Ok(name),
+                ..
+            } = spec
+            {
+                if name.meta.text.trim() == "LastId" {
+                    name.meta.text = name.meta.text.replace("LastId", "NextId");
+                }
+            }
+        }
+        let expected = input.replace("D LastId        ", "D NextId        ");
+        assert_eq!(ast.to_string(), expected);
+    }
+}
@@ -1,6 +1,9 @@
 mod ast;
+mod emit;
 mod nvim;
 mod spec;
+mod trivia;
 
 pub use ast::{parse_ast, AST};
 pub use spec::Spec;
+pub use trivia::Trivia;
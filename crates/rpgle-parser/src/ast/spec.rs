@@ -288,12 +288,15 @@ fn try_cspec_traditional(input: &[Srcline]) -> Option<(Spec, &[Srcline])> {
     idx += 1;
     let spec = match first {
         Srcline::C(CSrcline::Traditional {
-            factor1, operation, ..
+            factor1,
+            operation,
+            factor2,
+            ..
         }) => {
             // TDE: collect continuations
             let conts = vec![];
             // TDE: Op parser uses legacy style parser
-            let op = Op::from(((operation, factor1), conts.as_slice()));
+            let op = Op::from(((operation, factor1, factor2), conts.as_slice()));
             let fld = CodeField { op };
             let code = FieldResult::Ok(fld);
             let spec = Spec::C { code };
@@ -495,6 +498,25 @@ mod tests {
         insta::assert_yaml_snapshot!(observed);
     }
 
+    #[test]
+    fn test_cspec_traditional_03() {
+        // EXSR names the subroutine in factor 2, factor 1 is blank
+        let input = format!("{:<100}", "     C                   EXSR      $CrtEvts");
+        let cst = parse_cst(&input).unwrap();
+        let observed = try_cspec_traditional(&cst.lines).map(|x| x.0);
+        let Some(Spec::C { code }) = observed else {
+            panic!("expected a C spec");
+        };
+        let FieldResult::Ok(CodeField { op }) = code else {
+            panic!("expected an operation");
+        };
+        let Op::Exsr { name, meta, .. } = op else {
+            panic!("expected EXSR");
+        };
+        assert_eq!(name, "$CrtEvts");
+        assert_eq!(meta.span.start.col, 35);
+    }
+
     #[test]
     fn test_ast_snapshot() {
         let input = &r#"
@@ -0,0 +1,58 @@
+use crate::cst::{CSrcline, Srcline};
+use serde::{Deserialize, Serialize};
+
+// The source lines a spec came from, kept so the AST can be written back
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Trivia {
+    // comments, directives, blank and unparsed lines right before the spec
+    pub leading: Vec<Srcline>,
+    // row of the spec's first line
+    pub row: usize,
+    // the spec, its continuations and anything between them
+    pub lines: Vec<Srcline>,
+}
+
+// lines that start a spec, the ones `ast` doesn't set aside
+fn is_head(line: &Srcline) -> bool {
+    match line {
+        Srcline::H { .. }
+        | Srcline::F { .. }
+        | Srcline::D { .. }
+        | Srcline::C(CSrcline::Free { .. })
+        | Srcline::C(CSrcline::Traditional { .. }) => true,
+        Srcline::Idk { .. }
+        | Srcline::Comment { .. }
+        | Srcline::CompilerDirective { .. }
+        | Srcline::FCont { .. }
+        | Srcline::DCont { .. }
+        | Srcline::C(CSrcline::ExtF2 { .. }) => false,
+    }
+}
+
+/* Attach every line to the spec it belongs to, in source order, one Trivia
+ * per spec `ast` builds. Continuations fold into their spec like they do in
+ * `ast`, taking along the lines in between so nothing moves. Lines after
+ * the last spec are returned on their own.
+ */
+pub fn attach_trivia(input: &[Srcline]) -> (Vec<Trivia>, Vec<Srcline>) {
+    let mut out: Vec<Trivia> = vec![];
+    let mut pending = vec![];
+    for (row, line) in input.iter().enumerate() {
+        if is_head(line) {
+            out.push(Trivia {
+                leading: std::mem::take(&mut pending),
+                row,
+                lines: vec![line.clone()],
+            });
+            continue;
+        }
+        match (line, out.last_mut()) {
+            (Srcline::FCont { .. } | Srcline::DCont { .. }, Some(last)) => {
+                last.lines.append(&mut pending);
+                last.lines.push(line.clone());
+            }
+            _ => pending.push(line.clone()),
+        }
+    }
+    (out, pending)
+}
@@ -0,0 +1,303 @@
+// A calc spec cut into its columns. Traditional lines use the columns from
+// the manual, the extended factor 2 runs from column 36 to 80.
+#[derive(Debug, Clone, Default)]
+pub struct Calc {
+    pub control_level: String,
+    pub conditioning: String,
+    pub factor1: String,
+    // uppercased, without the extender
+    pub opcode: String,
+    // ex: "(e)", lowercased
+    pub extender: String,
+    pub factor2: String,
+    pub result: String,
+    pub length: String,
+    pub decimals: String,
+    pub indicators: [String; 3],
+    pub comment: String,
+}
+
+fn column(chars: &[char], from: usize, to: usize) -> String {
+    chars[from..to]
+        .iter()
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+impl Calc {
+    pub fn traditional(chars: &[char]) -> Self {
+        let mut calc = Self::extended(chars);
+        calc.factor2 = column(chars, 35, 49);
+        calc.result = column(chars, 49, 63);
+        calc.length = column(chars, 63, 68);
+        calc.decimals = column(chars, 68, 70);
+        calc.indicators = [
+            column(chars, 70, 72),
+            column(chars, 72, 74),
+            column(chars, 74, 76),
+        ];
+        calc.comment = column(chars, 80, 100);
+        calc
+    }
+
+    pub fn extended(chars: &[char]) -> Self {
+        let operation = column(chars, 25, 35);
+        let (opcode, extender) = match operation.find('(') {
+            Some(idx) => (&operation[..idx], operation[idx..].to_lowercase()),
+            None => (operation.as_str(), String::new()),
+        };
+        Self {
+            control_level: column(chars, 6, 8),
+            conditioning: column(chars, 8, 11),
+            factor1: column(chars, 11, 25),
+            opcode: opcode.trim().to_uppercase(),
+            extender,
+            factor2: column(chars, 35, 80),
+            ..Self::default()
+        }
+    }
+
+    fn op(&self) -> String {
+        format!("{}{}", self.opcode.to_lowercase(), self.extender)
+    }
+}
+
+const COMPARISONS: [(&str, &str); 6] = [
+    ("EQ", "="),
+    ("NE", "<>"),
+    ("GT", ">"),
+    ("LT", "<"),
+    ("GE", ">="),
+    ("LE", "<="),
+];
+
+// IFEQ -> ("IF", "="), ANDGT -> ("AND", ">")
+pub fn comparison(opcode: &str) -> Option<(&str, &'static str)> {
+    ["IF", "DOW", "DOU", "WHEN", "AND", "OR"]
+        .iter()
+        .find_map(|head| {
+            let rest = opcode.strip_prefix(head)?;
+            let symbol = COMPARISONS.iter().find(|(cmp, _)| *cmp == rest)?.1;
+            Some((&opcode[..head.len()], symbol))
+        })
+}
+
+// Lines that can't be converted no matter the opcode
+fn gate(calc: &Calc) -> Result<(), String> {
+    if !calc.control_level.is_empty() {
+        return Err("control level indicators have no free-form equivalent".to_string());
+    }
+    if !calc.conditioning.is_empty() {
+        return Err("conditioning indicators have no free-form equivalent".to_string());
+    }
+    if !calc.length.is_empty() || !calc.decimals.is_empty() {
+        return Err("the result field is defined here, declare it first".to_string());
+    }
+    let setting = calc.opcode == "SETON" || calc.opcode == "SETOFF";
+    if !setting && calc.indicators.iter().any(|i| !i.is_empty()) {
+        return Err("resulting indicators have no free-form equivalent".to_string());
+    }
+    Ok(())
+}
+
+fn counted(calc: &Calc) -> bool {
+    calc.opcode == "DO" && !calc.result.is_empty() && gate(calc).is_ok()
+}
+
+/* Block structure across the source, so a bare END can become the right
+ * ENDxx. Blocks opened by lines that stay fixed format are tracked too, mixing
+ * the two is fine.
+ */
+#[derive(Debug, Default)]
+pub struct Blocks {
+    closers: Vec<&'static str>,
+}
+
+impl Blocks {
+    // returns what a bare END on this line closes
+    pub fn track(&mut self, calc: &Calc) -> Option<&'static str> {
+        let opcode = calc.opcode.as_str();
+        let opened = match comparison(opcode).map(|(head, _)| head) {
+            Some("IF") => Some("endif"),
+            Some("DOW" | "DOU") => Some("enddo"),
+            _ => match opcode {
+                "IF" => Some("endif"),
+                "DOW" | "DOU" => Some("enddo"),
+                "DO" if counted(calc) => Some("endfor"),
+                "DO" => Some("enddo"),
+                "FOR" => Some("endfor"),
+                "SELECT" => Some("endsl"),
+                "MONITOR" => Some("endmon"),
+                _ if opcode.starts_with("CAS") && self.closers.last() != Some(&"endcs") => {
+                    Some("endcs")
+                }
+                _ => None,
+            },
+        };
+        if let Some(closer) = opened {
+            self.closers.push(closer);
+            return None;
+        }
+        match opcode {
+            "END" | "ENDIF" | "ENDDO" | "ENDFOR" | "ENDSL" | "ENDMON" | "ENDCS" => {
+                self.closers.pop()
+            }
+            _ => None,
+        }
+    }
+}
+
+fn operands(calc: &Calc) -> String {
+    [&calc.factor1, &calc.factor2, &calc.result]
+        .iter()
+        .filter(|x| !x.is_empty())
+        .map(|x| x.as_str())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn assign(calc: &Calc, expr: String) -> String {
+    match calc.extender.is_empty() {
+        true => format!("{} = {};", calc.result, expr),
+        false => format!("eval{} {} = {};", calc.extender, calc.result, expr),
+    }
+}
+
+fn arithmetic(calc: &Calc, symbol: &str) -> String {
+    match (calc.factor1.is_empty(), calc.extender.is_empty()) {
+        (true, true) => format!("{} {}= {};", calc.result, symbol, calc.factor2),
+        (true, false) => assign(calc, format!("{} {} {}", calc.result, symbol, calc.factor2)),
+        _ => assign(
+            calc,
+            format!("{} {} {}", calc.factor1, symbol, calc.factor2),
+        ),
+    }
+}
+
+// Fixed-format arithmetic drops the high-order digits that don't fit the
+// result, the same expression in free-form raises an error instead
+pub fn caveat(calc: &Calc) -> Option<String> {
+    match calc.opcode.as_str() {
+        "ADD" | "SUB" | "MULT" | "DIV" | "Z-ADD" | "Z-SUB" => Some(format!(
+            "{} truncated on overflow, this raises an error",
+            calc.opcode
+        )),
+        _ => None,
+    }
+}
+
+/* Free-form statements for a traditional calc spec. `conditions` are the
+ * ANDxx/ORxx lines continuing a compare and branch opcode, `closer` is what
+ * an END closes and `next` is the line after.
+ */
+pub fn traditional(
+    calc: &Calc,
+    conditions: &[Calc],
+    closer: Option<&str>,
+    next: Option<&Calc>,
+) -> Result<Vec<String>, String> {
+    gate(calc)?;
+    for condition in conditions {
+        gate(condition)?;
+    }
+    let opcode = calc.opcode.as_str();
+    let simple = |text: String| Ok(vec![text]);
+    if let Some((head, symbol)) = comparison(opcode) {
+        if head == "AND" || head == "OR" {
+            return Err("a condition without its IFxx/DOxxx/WHENxx".to_string());
+        }
+        let mut expr = format!("{} {} {}", calc.factor1, symbol, calc.factor2);
+        for condition in conditions {
+            let Some((joiner, symbol)) = comparison(&condition.opcode) else {
+                return Err("unexpected condition".to_string());
+            };
+            expr.push_str(&format!(
+                " {} {} {} {}",
+                joiner.to_lowercase(),
+                condition.factor1,
+                symbol,
+                condition.factor2
+            ));
+        }
+        return simple(format!("{} {};", head.to_lowercase(), expr));
+    }
+    match opcode {
+        "BEGSR" => simple(format!("begsr {};", calc.factor1)),
+        "ENDSR" if calc.factor1.is_empty() && calc.factor2.is_empty() => {
+            simple("endsr;".to_string())
+        }
+        "ENDSR" => Err("ENDSR labels and return points have no free-form equivalent".to_string()),
+        "EXSR" => simple(format!("exsr {};", calc.factor2)),
+        "ELSE" | "OTHER" | "SELECT" | "MONITOR" | "ENDIF" | "ENDDO" | "ENDSL" | "ENDFOR"
+        | "ENDMON" | "LEAVE" | "ITER" | "LEAVESR" => simple(format!("{};", calc.op())),
+        "END" => match closer {
+            Some("endcs") | None => Err("END of a CASxx group or unknown block".to_string()),
+            Some(closer) => simple(format!("{};", closer)),
+        },
+        "DO" if counted(calc) => {
+            let start = match calc.factor1.is_empty() {
+                true => "1",
+                false => calc.factor1.as_str(),
+            };
+            let limit = match calc.factor2.is_empty() {
+                true => "1",
+                false => calc.factor2.as_str(),
+            };
+            simple(format!("for {} = {} to {};", calc.result, start, limit))
+        }
+        "DO" => Err("DO without an index field has no free-form equivalent".to_string()),
+        "Z-ADD" => simple(assign(calc, calc.factor2.clone())),
+        "Z-SUB" => simple(assign(calc, format!("-{}", calc.factor2))),
+        "ADD" => simple(arithmetic(calc, "+")),
+        "SUB" => simple(arithmetic(calc, "-")),
+        "MULT" => simple(arithmetic(calc, "*")),
+        "DIV" if next.is_some_and(|n| n.opcode == "MVR") => {
+            Err("DIV followed by MVR needs %div and %rem".to_string())
+        }
+        "DIV" => simple(arithmetic(calc, "/")),
+        "SETON" | "SETOFF" => {
+            let value = match opcode {
+                "SETON" => "*on",
+                _ => "*off",
+            };
+            let out = calc
+                .indicators
+                .iter()
+                .filter(|i| !i.is_empty())
+                .map(|i| format!("*in{} = {};", i.to_lowercase(), value))
+                .collect::<Vec<String>>();
+            match out.is_empty() {
+                true => Err("SETON/SETOFF without indicators".to_string()),
+                false => Ok(out),
+            }
+        }
+        "ACQ" | "CHAIN" | "CLEAR" | "CLOSE" | "COMMIT" | "DEALLOC" | "DELETE" | "DSPLY"
+        | "DUMP" | "EXCEPT" | "EXFMT" | "FEOD" | "FORCE" | "IN" | "NEXT" | "OPEN" | "OUT"
+        | "POST" | "READ" | "READC" | "READE" | "READP" | "READPE" | "REL" | "RESET" | "RETURN"
+        | "ROLBK" | "SETGT" | "SETLL" | "SORTA" | "TEST" | "UNLOCK" | "UPDATE" | "WRITE" => {
+            let operands = operands(calc);
+            match operands.is_empty() {
+                true => simple(format!("{};", calc.op())),
+                false => simple(format!("{} {};", calc.op(), operands)),
+            }
+        }
+        "MOVE" | "MOVEL" | "MOVEA" => {
+            Err("MOVE semantics have no direct free-form equivalent".to_string())
+        }
+        "" => Err("no operation".to_string()),
+        _ => Err(format!("{} has no direct free-form equivalent", opcode)),
+    }
+}
+
+// Extended factor 2 opcodes already read like free-form
+pub fn extended(calc: &Calc, factor2: &str) -> Result<Vec<String>, String> {
+    gate(calc)?;
+    if !calc.factor1.is_empty() {
+        return Err("factor 1 has no free-form equivalent".to_string());
+    }
+    match calc.opcode.as_str() {
+        "EVAL" if calc.extender.is_empty() => Ok(vec![format!("{};", factor2)]),
+        _ => Ok(vec![format!("{} {};", calc.op(), factor2)]),
+    }
+}
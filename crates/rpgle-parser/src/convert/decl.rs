@@ -0,0 +1,503 @@
+use crate::ast::Spec;
+use crate::field::{
+    DatastructureType, Datatype, Decimals, DefinitionType, Device, ExternalDescription,
+    FieldResult, FileAddition, FileDesignation, FileFormat, Filetype, POS,
+};
+
+// Keywords -----------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyword {
+    pub name: String,
+    pub args: Option<String>,
+}
+
+impl Keyword {
+    fn render(&self) -> String {
+        match &self.args {
+            Some(args) => format!("{}({})", self.name, args),
+            None => self.name.clone(),
+        }
+    }
+}
+
+// Split a keyword area into keywords, parens and string literals stay whole
+pub fn split_keywords(text: &str) -> Vec<Keyword> {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == ' ' {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && chars[i] != ' ' && chars[i] != '(' {
+            i += 1;
+        }
+        let name = chars[start..i].iter().collect::<String>();
+        let mut args = None;
+        let mut j = i;
+        while j < chars.len() && chars[j] == ' ' {
+            j += 1;
+        }
+        if chars.get(j) == Some(&'(') {
+            let (mut depth, mut quoted) = (0, false);
+            let open = j;
+            while j < chars.len() {
+                match chars[j] {
+                    '\'' => quoted = !quoted,
+                    '(' if !quoted => depth += 1,
+                    ')' if !quoted => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+                j += 1;
+            }
+            args = Some(
+                chars[open + 1..j.min(chars.len())]
+                    .iter()
+                    .collect::<String>(),
+            );
+            i = j + 1;
+        }
+        out.push(Keyword { name, args });
+    }
+    out
+}
+
+fn take(keywords: &mut Vec<Keyword>, name: &str) -> Option<Keyword> {
+    let idx = keywords
+        .iter()
+        .position(|k| k.name.eq_ignore_ascii_case(name))?;
+    Some(keywords.remove(idx))
+}
+
+fn has(keywords: &[Keyword], name: &str) -> bool {
+    keywords.iter().any(|k| k.name.eq_ignore_ascii_case(name))
+}
+
+// fixed format takes object names bare, free format wants literals
+fn quote_names(args: &str) -> String {
+    args.split(':')
+        .map(|arg| {
+            let arg = arg.trim();
+            if arg.starts_with('\'') || arg.starts_with('*') {
+                arg.to_string()
+            } else {
+                format!("'{}'", arg.to_uppercase())
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(":")
+}
+
+fn free_keywords(keywords: &[Keyword]) -> Vec<Keyword> {
+    keywords
+        .iter()
+        .map(|kw| {
+            let upper = kw.name.to_uppercase();
+            match (&kw.args, upper.as_str()) {
+                (Some(args), "EXTNAME" | "EXTFLD") => Keyword {
+                    name: kw.name.clone(),
+                    args: Some(quote_names(args)),
+                },
+                (Some(args), "DTAARA") => match args.trim().strip_prefix("*VAR:") {
+                    Some(var) => Keyword {
+                        name: kw.name.clone(),
+                        args: Some(var.trim().to_string()),
+                    },
+                    None => Keyword {
+                        name: kw.name.clone(),
+                        args: Some(quote_names(args)),
+                    },
+                },
+                _ => kw.clone(),
+            }
+        })
+        .collect()
+}
+
+fn join(parts: Vec<String>) -> String {
+    parts
+        .into_iter()
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// H ------------------------------------------------------------------------------
+
+pub fn ctl_opt(keywords: &str) -> String {
+    format!("ctl-opt {};", keywords)
+}
+
+// F ------------------------------------------------------------------------------
+
+/* Build `dcl-f` from an F spec and the keywords of all its lines. Cycle
+ * files (primary, secondary, ...), end of file and limits processing have no
+ * free-form equivalent.
+ */
+pub fn dcl_f(spec: &Spec, keywords: &str) -> Result<String, String> {
+    let Spec::F {
+        name,
+        filetype,
+        file_designation,
+        endfile,
+        file_addition,
+        file_format,
+        record_length,
+        limits_processing,
+        keylength,
+        record_address_type,
+        file_organization,
+        device,
+        ..
+    } = spec
+    else {
+        return Err("not a file spec".to_string());
+    };
+    let unreadable = || "a fixed column can't be read".to_string();
+    let name = name.try_as().ok_or_else(unreadable)?.value.clone();
+    let filetype = &filetype.try_as().ok_or_else(unreadable)?.value;
+    let designation = &file_designation.try_as().ok_or_else(unreadable)?.value;
+    if !matches!(designation, FileDesignation::Empty | FileDesignation::F) {
+        return Err("the RPG cycle has no free-form equivalent".to_string());
+    }
+    if endfile.to_string().trim() != "" || limits_processing.to_string().trim() != "" {
+        return Err("end of file and limits processing have no free-form equivalent".to_string());
+    }
+    if file_organization.to_string().trim() != "" {
+        return Err("record address files have no free-form equivalent".to_string());
+    }
+    let addition = matches!(
+        file_addition.try_as().map(|x| &x.value),
+        Some(FileAddition::A)
+    );
+    let program_described = matches!(file_format.try_as().map(|x| &x.value), Some(FileFormat::F));
+    let length = record_length.to_string().trim().to_string();
+    let device = &device.try_as().ok_or_else(unreadable)?.value;
+    let (device, default_usage) = match device {
+        Device::DISK => ("disk", "*input"),
+        Device::PRINTER => ("printer", "*output"),
+        Device::WORKSTN => ("workstn", "*input:*output"),
+        Device::SEQ => ("seq", "*input"),
+        Device::SPECIAL => ("special", "*input"),
+    };
+    let device = match (program_described, device) {
+        (true, _) => format!("{}({})", device, length),
+        (false, "disk") => String::new(),
+        (false, _) => device.to_string(),
+    };
+    let usage = match (filetype, addition) {
+        (Filetype::I, false) => "*input",
+        (Filetype::I, true) => "*input:*output",
+        (Filetype::U, false) => "*update:*delete",
+        (Filetype::U, true) => "*update:*delete:*output",
+        (Filetype::O, _) => "*output",
+        (Filetype::C, _) => "*input:*output",
+        (Filetype::Empty, _) => return Err(unreadable()),
+    };
+    let usage = match usage == default_usage {
+        true => String::new(),
+        false => format!("usage({})", usage),
+    };
+    let keyed = match record_address_type
+        .to_string()
+        .trim()
+        .to_uppercase()
+        .as_str()
+    {
+        "" => String::new(),
+        "K" if program_described => format!("keyed(*char:{})", keylength.to_string().trim()),
+        "K" => "keyed".to_string(),
+        _ => return Err("record address types have no free-form equivalent".to_string()),
+    };
+    let keywords = free_keywords(&split_keywords(keywords))
+        .iter()
+        .map(|k| k.render())
+        .collect::<Vec<String>>()
+        .join(" ");
+    let decl = join(vec![
+        "dcl-f".to_string(),
+        name,
+        device,
+        usage,
+        keyed,
+        keywords,
+    ]);
+    Ok(format!("{};", decl))
+}
+
+// D ------------------------------------------------------------------------------
+
+// byte lengths from from/to positions are digits in free-form numeric types
+fn digits(datatype: &Datatype, bytes: u32) -> u32 {
+    match (datatype, bytes) {
+        (Datatype::P, _) => bytes * 2 - 1,
+        (Datatype::B, 2) => 4,
+        (Datatype::B, _) => 9,
+        (Datatype::I | Datatype::U, 1) => 3,
+        (Datatype::I | Datatype::U, 2) => 5,
+        (Datatype::I | Datatype::U, 4) => 10,
+        (Datatype::I | Datatype::U, _) => 20,
+        _ => bytes,
+    }
+}
+
+/* The free-form data type of a definition or None when a LIKE keyword
+ * supplies it. Keywords that turn into part of the type (VARYING, DATFMT,
+ * PROCPTR, ...) are taken out of `keywords`.
+ */
+pub fn free_type(
+    datatype: &Datatype,
+    length: Option<u32>,
+    decimals: Option<u32>,
+    in_ds: bool,
+    keywords: &mut Vec<Keyword>,
+) -> Result<Option<String>, String> {
+    let sized = |name: &str| match length {
+        Some(n) => Ok(format!("{}({})", name, n)),
+        None => Err(format!("{} needs a length", name)),
+    };
+    let numeric = |name: &str| match length {
+        Some(n) => Ok(format!("{}({}:{})", name, n, decimals.unwrap_or(0))),
+        None => Err(format!("{} needs a length", name)),
+    };
+    let varying = take(keywords, "VARYING");
+    let vary = |fixed: &str, var: &str| match (&varying, length) {
+        (Some(Keyword { args: Some(n), .. }), Some(len)) => Ok(format!("{}({}:{})", var, len, n)),
+        (Some(_), _) => sized(var),
+        (None, _) => sized(fixed),
+    };
+    let format = |keywords: &mut Vec<Keyword>, name: &str, kw: &str| match take(keywords, kw)
+        .and_then(|k| k.args)
+    {
+        Some(fmt) => format!("{}({})", name, fmt),
+        None => name.to_string(),
+    };
+    let like = has(keywords, "LIKE") || has(keywords, "LIKEDS") || has(keywords, "LIKEREC");
+    let out = match datatype {
+        Datatype::Empty if length.is_none() && (like || !in_ds) => return Ok(None),
+        Datatype::Empty if decimals.is_some() && in_ds => numeric("zoned")?,
+        Datatype::Empty if decimals.is_some() => numeric("packed")?,
+        Datatype::Empty => vary("char", "varchar")?,
+        Datatype::A => vary("char", "varchar")?,
+        Datatype::G => vary("graph", "vargraph")?,
+        Datatype::C => vary("ucs2", "varucs2")?,
+        Datatype::P => numeric("packed")?,
+        Datatype::S => numeric("zoned")?,
+        Datatype::B => numeric("bindec")?,
+        Datatype::I => sized("int")?,
+        Datatype::U => sized("uns")?,
+        Datatype::F => sized("float")?,
+        Datatype::N => "ind".to_string(),
+        Datatype::D => format(keywords, "date", "DATFMT"),
+        Datatype::T => format(keywords, "time", "TIMFMT"),
+        Datatype::Z => "timestamp".to_string(),
+        Datatype::Star => match take(keywords, "PROCPTR") {
+            Some(_) => "pointer(*proc)".to_string(),
+            None => "pointer".to_string(),
+        },
+        Datatype::O => return Err("object fields have no direct free-form type".to_string()),
+    };
+    Ok(Some(out))
+}
+
+fn pos(field: &FieldResult<crate::field::POSField>) -> Result<Option<u32>, String> {
+    match field.try_as().map(|x| &x.value) {
+        Some(POS::Value(n)) => Ok(Some(*n)),
+        Some(POS::Empty) => Ok(None),
+        None => Err("a length or position can't be read".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Container {
+    DS,
+    PR,
+    PI,
+}
+
+/* Convert one D spec (and the keywords of its continuation lines). Opens a
+ * container for DS/PR/PI, the caller closes it once the members run out.
+ */
+pub fn dcl(
+    spec: &Spec,
+    keywords: &str,
+    container: Option<&Container>,
+) -> Result<(String, Option<Container>), String> {
+    let Spec::D {
+        name,
+        external_description,
+        datastructure_type,
+        definition_type,
+        from_position,
+        to_length,
+        datatype,
+        decimals,
+        ..
+    } = spec
+    else {
+        return Err("not a definition spec".to_string());
+    };
+    let unreadable = || "a fixed column can't be read".to_string();
+    let name = name.try_as().ok_or_else(unreadable)?.value.clone();
+    if name.contains("...") {
+        return Err("continued names aren't converted".to_string());
+    }
+    let definition_type = &definition_type.try_as().ok_or_else(unreadable)?.value;
+    let datatype = &datatype.try_as().ok_or_else(unreadable)?.value;
+    let decimals = match decimals.try_as().map(|x| &x.value) {
+        Some(Decimals::Value(n)) => Some(*n),
+        Some(Decimals::Empty) => None,
+        None => return Err(unreadable()),
+    };
+    let from = pos(from_position)?;
+    let to = pos(to_length)?;
+    let mut keywords = free_keywords(&split_keywords(keywords));
+    let display = |name: &str| match name.is_empty() {
+        true => "*n".to_string(),
+        false => name.to_string(),
+    };
+    let rest = |keywords: &[Keyword]| {
+        keywords
+            .iter()
+            .map(|k| k.render())
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+    let typed = |keywords: &mut Vec<Keyword>, in_ds: bool| {
+        let length = match (from, to) {
+            (Some(from), Some(to)) if to >= from => Some(digits(datatype, to - from + 1)),
+            (Some(_), _) => return Err("positions out of order".to_string()),
+            (None, to) => to,
+        };
+        free_type(datatype, length, decimals, in_ds, keywords)
+    };
+    match definition_type {
+        DefinitionType::S => {
+            let ty = typed(&mut keywords, false)?.unwrap_or_default();
+            let decl = join(vec!["dcl-s".to_string(), name, ty, rest(&keywords)]);
+            Ok((format!("{};", decl), None))
+        }
+        DefinitionType::C => {
+            let value = match take(&mut keywords, "CONST") {
+                Some(Keyword {
+                    args: Some(value), ..
+                }) => value,
+                _ => keywords.iter().map(|k| k.render()).collect::<String>(),
+            };
+            Ok((format!("dcl-c {} {};", name, value.trim()), None))
+        }
+        DefinitionType::DS => {
+            let external = matches!(
+                external_description.try_as().map(|x| &x.value),
+                Some(ExternalDescription::E)
+            );
+            if external && !has(&keywords, "EXTNAME") {
+                keywords.insert(
+                    0,
+                    Keyword {
+                        name: "extname".to_string(),
+                        args: Some(format!("'{}'", name.to_uppercase())),
+                    },
+                );
+            }
+            match datastructure_type.try_as().map(|x| &x.value) {
+                Some(DatastructureType::S) => keywords.insert(
+                    0,
+                    Keyword {
+                        name: "psds".to_string(),
+                        args: None,
+                    },
+                ),
+                Some(DatastructureType::U) => keywords.insert(
+                    0,
+                    Keyword {
+                        name: "dtaara".to_string(),
+                        args: Some("*auto".to_string()),
+                    },
+                ),
+                _ => {}
+            }
+            let length = to.map(|n| format!("len({})", n));
+            let decl = join(vec![
+                "dcl-ds".to_string(),
+                display(&name),
+                length.unwrap_or_default(),
+                rest(&keywords),
+            ]);
+            Ok((format!("{};", decl), Some(Container::DS)))
+        }
+        DefinitionType::PR | DefinitionType::PI => {
+            let (head, container) = match definition_type {
+                DefinitionType::PR => ("dcl-pr", Container::PR),
+                _ => ("dcl-pi", Container::PI),
+            };
+            let ty = typed(&mut keywords, false)?.unwrap_or_default();
+            let decl = join(vec![head.to_string(), display(&name), ty, rest(&keywords)]);
+            Ok((format!("{};", decl), Some(container)))
+        }
+        DefinitionType::Empty => {
+            let in_ds = container == Some(&Container::DS);
+            let ty = typed(&mut keywords, in_ds)?.unwrap_or_default();
+            if in_ds {
+                if let Some(from) = from {
+                    keywords.push(Keyword {
+                        name: "pos".to_string(),
+                        args: Some(from.to_string()),
+                    });
+                }
+            }
+            let head = match (container, super::is_opcode(&name)) {
+                (Some(Container::DS), true) => "dcl-subf ",
+                (Some(_), true) => "dcl-parm ",
+                _ => "",
+            };
+            let member = join(vec![display(&name), ty, rest(&keywords)]);
+            match container {
+                Some(_) => Ok((format!("{}{};", head, member), None)),
+                None => Err("subfield outside of a data structure".to_string()),
+            }
+        }
+    }
+}
+
+pub fn end(container: &Container) -> String {
+    match container {
+        Container::DS => "end-ds;",
+        Container::PR => "end-pr;",
+        Container::PI => "end-pi;",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_keywords() {
+        let observed = split_keywords("EXTPGM('Q(X)') CONST DIM (10)");
+        let expected = vec![
+            Keyword {
+                name: "EXTPGM".to_string(),
+                args: Some("'Q(X)'".to_string()),
+            },
+            Keyword {
+                name: "CONST".to_string(),
+                args: None,
+            },
+            Keyword {
+                name: "DIM".to_string(),
+                args: Some("10".to_string()),
+            },
+        ];
+        assert_eq!(observed, expected);
+    }
+}
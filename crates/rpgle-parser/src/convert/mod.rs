@@ -0,0 +1,466 @@
+pub(crate) mod calc;
+mod decl;
+
+use crate::ast::{parse_ast, Spec};
+use crate::cst::{parse_cst, CSrcline, Srcline};
+use crate::field::{DefinitionType, FieldBehavior};
+use crate::format::{is_opcode, normalize_width, Nesting};
+use calc::{Blocks, Calc};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+// spaces per nesting level in converted code
+const INDENT: usize = 2;
+// converted statements are wrapped to stay clear of the comment columns, 81 to 100
+const RIGHT: usize = 80;
+// the narrowest a statement is wrapped to, however deep it's nested
+const MIN_WRAP: usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConvertError {
+    Parse(String),
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(msg) => write!(f, "Unable to parse source: {}", msg),
+        }
+    }
+}
+
+// Cut a statement into pieces of at most `width`, between words
+pub(crate) fn wrap(code: &str, width: usize) -> Vec<String> {
+    let mut out = vec![];
+    let mut rest = code.trim().chars().collect::<Vec<char>>();
+    while rest.len() > width {
+        let mut quoted = false;
+        let mut cut = None;
+        for (i, c) in rest.iter().enumerate().take(width + 1) {
+            match c {
+                '\'' => quoted = !quoted,
+                ' ' if !quoted => cut = Some(i),
+                _ => {}
+            }
+        }
+        let Some(cut) = cut else {
+            break;
+        };
+        let line = rest[..cut].iter().collect::<String>();
+        out.push(line.trim_end().to_string());
+        let next = rest[cut..].iter().collect::<String>();
+        rest = next.trim_start().chars().collect();
+    }
+    out.push(rest.into_iter().collect());
+    out
+}
+
+// Builds the converted source, indenting free-form lines as it goes
+#[derive(Default)]
+struct Emitter {
+    lines: Vec<String>,
+    nesting: Nesting,
+}
+
+impl Emitter {
+    fn keep(&mut self, chars: &[char], line: &Srcline) {
+        match line {
+            Srcline::C(CSrcline::Traditional { .. }) | Srcline::C(CSrcline::ExtF2 { .. }) => {
+                self.nesting.fixed(chars)
+            }
+            Srcline::C(CSrcline::Free { .. }) => {
+                self.nesting.free(&mut chars.to_vec(), 0);
+            }
+            _ => {}
+        }
+        self.lines.push(chars.iter().collect());
+    }
+
+    // the room left for code on the next line, after column 7 and its indent
+    fn width(&self) -> usize {
+        (RIGHT - 7)
+            .saturating_sub(self.nesting.level() * INDENT)
+            .max(MIN_WRAP)
+    }
+
+    /* One line at a time, a statement that opens a block pushes its own
+     * continuation lines deeper, so the rest is wrapped again each time.
+     */
+    fn free(&mut self, sequence: &[char], code: &str) {
+        let mut rest = code.trim().to_string();
+        while !rest.is_empty() {
+            let pieces = wrap(&rest, self.width());
+            let mut chars = sequence[..5].to_vec();
+            chars.extend([' ', ' ']);
+            chars.extend(pieces[0].chars());
+            chars.resize(chars.len().max(100), ' ');
+            self.nesting.free(&mut chars, INDENT);
+            self.lines.push(chars.iter().collect());
+            rest = pieces[1..].join(" ");
+        }
+    }
+
+    // flag lines left as they are, or converted with a caveat
+    fn note(&mut self, sequence: &[char], reason: &str) {
+        let prefix = "// idk: ";
+        for piece in wrap(reason, self.width() - prefix.len()) {
+            self.free(sequence, &format!("{}{}", prefix, piece));
+        }
+    }
+}
+
+fn start_row(spec: &Spec) -> Option<usize> {
+    match spec {
+        Spec::H { sequence, .. } | Spec::F { sequence, .. } | Spec::D { sequence, .. } => {
+            Some(sequence.span().start.row)
+        }
+        Spec::C { .. } => None,
+    }
+}
+
+// a line and the continuation lines after it
+fn group_end(lines: &[Srcline], start: usize) -> usize {
+    let mut end = start + 1;
+    while let Some(Srcline::FCont { .. } | Srcline::DCont { .. }) = lines.get(end) {
+        end += 1;
+    }
+    end
+}
+
+// keyword columns 44-80 of a group of lines
+fn keywords(lines: &[Vec<char>], rows: Range<usize>, from: usize) -> String {
+    lines[rows]
+        .iter()
+        .map(|chars| {
+            chars[from..80]
+                .iter()
+                .collect::<String>()
+                .trim()
+                .to_string()
+        })
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn is_member(line: Option<&Srcline>) -> bool {
+    matches!(
+        line,
+        Some(Srcline::D {
+            definition_type: crate::field::FieldResult::Ok(def),
+            ..
+        }) if def.value == DefinitionType::Empty
+    )
+}
+
+pub fn convert(input: &str) -> Result<String, ConvertError> {
+    convert_range(input, 0..usize::MAX)
+}
+
+/* Convert fixed format specs whose first line is in `rows` (0 based) to
+ * free-form, everything else is copied as is. Lines without a free-form
+ * equivalent are kept and flagged with a `// idk:` comment above them, so are
+ * converted ones that don't behave quite the same.
+ */
+pub fn convert_range(input: &str, rows: Range<usize>) -> Result<String, ConvertError> {
+    let normalized = normalize_width(input, 100);
+    let cst = parse_cst(&normalized).map_err(|e| ConvertError::Parse(format!("{:?}", e)))?;
+    let ast = parse_ast(&cst).map_err(|e| ConvertError::Parse(format!("{:?}", e)))?;
+    let specs = ast
+        .specs
+        .iter()
+        .filter_map(|spec| start_row(spec).map(|row| (row, spec)))
+        .collect::<HashMap<usize, &Spec>>();
+    let lines = normalized
+        .lines()
+        .map(|line| line.chars().collect::<Vec<char>>())
+        .collect::<Vec<Vec<char>>>();
+    let mut out = Emitter::default();
+    let mut blocks = Blocks::default();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &cst.lines[i];
+        let chars = &lines[i];
+        let selected = rows.contains(&i);
+        let end = group_end(&cst.lines, i);
+        let keep = |out: &mut Emitter, range: Range<usize>| {
+            for row in range {
+                out.keep(&lines[row], &cst.lines[row]);
+            }
+        };
+        match line {
+            Srcline::H { .. } if selected => {
+                out.free(chars, &decl::ctl_opt(&keywords(&lines, i..end, 6)))
+            }
+            Srcline::F { .. } if selected => {
+                let kw = keywords(&lines, i..end, 43);
+                match specs.get(&i).map(|spec| decl::dcl_f(spec, &kw)) {
+                    Some(Ok(text)) => out.free(chars, &text),
+                    Some(Err(reason)) => {
+                        out.note(chars, &reason);
+                        keep(&mut out, i..end);
+                    }
+                    None => keep(&mut out, i..end),
+                }
+            }
+            Srcline::D { .. } if selected => {
+                let mut converted = vec![];
+                let mut block_end = end;
+                let head = specs
+                    .get(&i)
+                    .ok_or_else(|| "a fixed column can't be read".to_string())
+                    .and_then(|spec| decl::dcl(spec, &keywords(&lines, i..end, 43), None));
+                let result = head.and_then(|(text, container)| {
+                    converted.push(text);
+                    let Some(container) = container else {
+                        return Ok(());
+                    };
+                    while is_member(cst.lines.get(block_end)) {
+                        let member_end = group_end(&cst.lines, block_end);
+                        let kw = keywords(&lines, block_end..member_end, 43);
+                        let spec = specs
+                            .get(&block_end)
+                            .ok_or_else(|| "a fixed column can't be read".to_string())?;
+                        converted.push(decl::dcl(spec, &kw, Some(&container))?.0);
+                        block_end = member_end;
+                    }
+                    converted.push(decl::end(&container));
+                    Ok(())
+                });
+                match result {
+                    Ok(_) => converted.iter().for_each(|text| out.free(chars, text)),
+                    Err(reason) => {
+                        // members of a block that didn't convert stay with it
+                        while is_member(cst.lines.get(block_end)) {
+                            block_end = group_end(&cst.lines, block_end);
+                        }
+                        out.note(chars, &reason);
+                        keep(&mut out, i..block_end);
+                    }
+                }
+                i = block_end;
+                continue;
+            }
+            Srcline::C(CSrcline::Traditional { .. }) => {
+                let calc = Calc::traditional(chars);
+                let closer = blocks.track(&calc);
+                if !selected {
+                    keep(&mut out, i..end);
+                    i = end;
+                    continue;
+                }
+                // ANDxx/ORxx lines continue the condition of the line above
+                let mut conditions = vec![];
+                let mut next = end;
+                while let Some(Srcline::C(CSrcline::Traditional { .. })) = cst.lines.get(next) {
+                    let condition = Calc::traditional(&lines[next]);
+                    match calc::comparison(&condition.opcode) {
+                        Some(("AND" | "OR", _)) => conditions.push(condition),
+                        _ => break,
+                    }
+                    next += 1;
+                }
+                let after = lines.get(next).map(|chars| Calc::traditional(chars));
+                match calc::traditional(&calc, &conditions, closer, after.as_ref()) {
+                    Ok(statements) => {
+                        if let Some(caveat) = calc::caveat(&calc) {
+                            out.note(chars, &caveat);
+                        }
+                        for (n, statement) in statements.iter().enumerate() {
+                            match n == statements.len() - 1 && !calc.comment.is_empty() {
+                                true => {
+                                    out.free(chars, &format!("{} // {}", statement, calc.comment))
+                                }
+                                false => out.free(chars, statement),
+                            }
+                        }
+                    }
+                    Err(reason) => {
+                        out.note(chars, &reason);
+                        keep(&mut out, i..next);
+                    }
+                }
+                i = next;
+                continue;
+            }
+            Srcline::C(CSrcline::ExtF2 { .. }) => {
+                let calc = Calc::extended(chars);
+                blocks.track(&calc);
+                // continued on lines with only an extended factor 2
+                let mut next = end;
+                let mut factor2 = calc.factor2.clone();
+                while let Some(chars) = lines.get(next) {
+                    let blank = chars[6..35].iter().all(|c| *c == ' ');
+                    if !chars[5].eq_ignore_ascii_case(&'C') || !blank {
+                        break;
+                    }
+                    factor2.push(' ');
+                    factor2.push_str(chars[35..80].iter().collect::<String>().trim());
+                    next += 1;
+                }
+                if !selected {
+                    keep(&mut out, i..next);
+                    i = next;
+                    continue;
+                }
+                match calc::extended(&calc, &factor2) {
+                    Ok(statements) => statements.iter().for_each(|s| out.free(chars, s)),
+                    Err(reason) => {
+                        out.note(chars, &reason);
+                        keep(&mut out, i..next);
+                    }
+                }
+                i = next;
+                continue;
+            }
+            Srcline::Comment { .. } if selected => {
+                let text = chars[7..80].iter().collect::<String>();
+                match text.trim().chars().count() <= out.width() {
+                    true => out.free(chars, &format!("// {}", text.trim())),
+                    false => out.keep(chars, line),
+                }
+            }
+            // free-form code doesn't need /free and /end-free
+            Srcline::CompilerDirective { directive, .. } if selected => {
+                let text = directive.to_string().trim().to_uppercase();
+                if text != "/FREE" && text != "/END-FREE" {
+                    out.keep(chars, line);
+                }
+            }
+            _ => keep(&mut out, i..end),
+        }
+        i = end;
+    }
+    let mut output = out.lines.join("\n");
+    if normalized.ends_with('\n') {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> String {
+        r#"
+     H OPTION(*nodebugio:*srcstmt)
+     FCowEvt    UF A E           K DISK
+     FReport    O    F  132        PRINTER
+     D LastId          S              8  0
+     D Name            S             20A   VARYING
+     D MaxId           C                   CONST(99999)
+     D Row             DS                  QUALIFIED
+     D  Id                            8P 0
+     D  Desc                         30
+     D QCmdExc         PR                  EXTPGM('QCMDEXC')
+     D  Command                    2000    CONST
+     D  Length                       15P 5 CONST
+      * set up the run
+     C                   EXSR      $SetLstId
+     C     LastId        IFGT      MaxId
+     C     Name          OREQ      *BLANKS
+     C                   Z-ADD     1             LastId
+     C                   ELSE
+     C                   ADD       1             LastId
+     C                   END
+     C                   MOVE      LastId        Name
+     C   50              SETON                                        LR
+     C                   SETON                                        LR
+     C                   EVAL      Name = 'done'
+     C     $SetLstId     BEGSR
+     C     *LOVAL        SETLL     CowEvt
+     C                   READ      CowEvt
+     C                   ENDSR
+"#[1..]
+            .to_string()
+    }
+
+    #[test]
+    fn test_convert_snapshot() {
+        let observed = convert(&fixture()).unwrap();
+        insta::assert_yaml_snapshot!(observed);
+    }
+
+    #[test]
+    fn test_convert_range() {
+        // only the Z-ADD line, it gets a note
+        let observed = convert_range(&fixture(), 16..17).unwrap();
+        let lines = observed.lines().collect::<Vec<&str>>();
+        assert!(lines[16].trim().starts_with("// idk: Z-ADD"));
+        assert_eq!(lines[17].trim(), "LastId = 1;");
+        assert_eq!(
+            lines[3].trim_end(),
+            "     D LastId          S              8  0"
+        );
+        assert_eq!(lines.len(), fixture().lines().count() + 1);
+    }
+
+    #[test]
+    fn test_convert_arithmetic() {
+        // fixed-format truncates, free-form doesn't, so each gets a note
+        let input = r#"
+     C                   MULT      2             Total
+     C     Total         DIV       3             Share
+     C                   EVAL      Total = Total * 2
+"#[1..]
+            .to_string();
+        let observed = convert(&input).unwrap();
+        let lines = observed.lines().map(|x| x.trim()).collect::<Vec<&str>>();
+        let expected = vec![
+            "// idk: MULT truncated on overflow, this raises an error",
+            "Total *= 2;",
+            "// idk: DIV truncated on overflow, this raises an error",
+            "Share = Total / 3;",
+            "Total = Total * 2;",
+        ];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn test_convert_nested() {
+        // twelve levels deep, every line still ends before the comment columns
+        let mut input = String::new();
+        for _ in 0..12 {
+            input.push_str("     C     LastId        IFGT      MaxId\n");
+        }
+        input
+            .push_str("     C                   EVAL      Desc = 'A cow moved to a farm' + Name\n");
+        input.push_str("     C                   MULT      2             Total\n");
+        for _ in 0..12 {
+            input.push_str("     C                   ENDIF\n");
+        }
+        let observed = convert(&input).unwrap();
+        let lines = observed.lines().collect::<Vec<&str>>();
+        let notes = lines[13..15]
+            .iter()
+            .map(|x| x.trim())
+            .collect::<Vec<&str>>();
+        let expected = vec![
+            "// idk: MULT truncated on overflow, this raises",
+            "// idk: an error",
+        ];
+        assert_eq!(notes, expected);
+        for line in lines {
+            assert!(line.trim_end().chars().count() <= 80, "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_wrap() {
+        let observed = wrap("dcl-pr QCmdExc extpgm('QCMDEXC  X');", 20);
+        let expected = vec!["dcl-pr QCmdExc", "extpgm('QCMDEXC  X');"];
+        assert_eq!(observed, expected);
+    }
+
+    #[test]
+    fn test_wrap_counts_chars() {
+        // 20 characters but 26 bytes, it fits
+        let observed = wrap("Name = 'äöüäöü' + x;", 20);
+        assert_eq!(observed, vec!["Name = 'äöüäöü' + x;"]);
+        // the last blank within 12 characters, not 12 bytes
+        let observed = wrap("Name = 'äöü' + Other;", 12);
+        assert_eq!(observed, vec!["Name = 'äöü'", "+ Other;"]);
+    }
+}
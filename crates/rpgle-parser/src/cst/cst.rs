@@ -46,11 +46,13 @@ pub fn parse_cst(input: &str) -> Result<CST, ParseError> {
     // check all lines are 100 chars long so we can safely convert to [char;100]
     // return early if not all meet this condition
     let mut padded_lines: Vec<[char; 100]> = vec![];
-    for line in input.split("\n") {
+    let count = input.split("\n").count();
+    for (idx, line) in input.split("\n").enumerate() {
         if line.chars().count() == 100 {
             let rs: [char; 100] = line.chars().collect::<Vec<char>>().try_into().unwrap();
             padded_lines.push(rs);
-        } else if line.chars().count() == 0 {
+        } else if line.chars().count() == 0 && idx == count - 1 {
+            // nothing after the last newline, blank lines keep their row
             continue;
         } else if line.chars().count() < 100 {
             let mut rs: [char; 100] = std::iter::repeat(' ')
@@ -84,6 +86,8 @@ pub fn parse_cst(input: &str) -> Result<CST, ParseError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cst::CSrcline;
+    use crate::field::{FieldBehavior, FieldResult};
     use insta;
     use std::env;
 
@@ -161,6 +165,54 @@ mod tests {
         assert_eq!(observed, expected);
     }
 
+    // where a field starts and what's in it
+    fn at<T: FieldBehavior + Display>(field: &FieldResult<T>) -> (usize, String) {
+        (field.span().start.col, field.to_string().trim().to_string())
+    }
+
+    #[test]
+    fn test_cspec_columns() {
+        // every field of a traditional C-spec filled to its documented positions
+        let input = format!(
+            "{:<100}",
+            "     C  N01Total         MULT      Factor2Longest Share            5 2010203    why"
+        );
+        let cst = parse_cst(input.as_str()).unwrap();
+        let Some(Srcline::C(CSrcline::Traditional {
+            factor2,
+            result,
+            result_length,
+            decimals,
+            resulting_indicators,
+            comments,
+            ..
+        })) = cst.lines.first()
+        else {
+            panic!("not a traditional C-spec: {:?}", cst.lines);
+        };
+        let observed = vec![
+            at(factor2),
+            at(result),
+            at(result_length),
+            at(decimals),
+            at(resulting_indicators),
+            at(comments),
+        ];
+        let expected = vec![
+            (35, "Factor2Longest"),
+            (49, "Share"),
+            (63, "5"),
+            (68, "2"),
+            (70, "010203"),
+            (76, "why"),
+        ];
+        let expected = expected
+            .into_iter()
+            .map(|(col, text)| (col, text.to_string()));
+        assert_eq!(observed, expected.collect::<Vec<_>>());
+        assert_eq!(cst.to_string(), input);
+    }
+
     fn nonascii_fixture() -> String {
         r#"
      D FOOOO           S              4    Inz('****')                                              
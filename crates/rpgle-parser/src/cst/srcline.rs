@@ -519,23 +519,20 @@ fn try_cline_traditional(row: usize, chars: &[char; 100]) -> Option<Srcline> {
         indicators: FieldResult::from((Position::from((row, 8)), pluck::<100, 8, 3, 89>(chars))),
         factor1: FieldResult::from((Position::from((row, 11)), pluck::<100, 11, 14, 75>(chars))),
         operation: FieldResult::from((Position::from((row, 25)), pluck::<100, 25, 10, 65>(chars))),
-        factor2: FieldResult::from((
-            // this doesn't match the documentation, but matches every program I can find
-            // ...wtf...wtf...wtf...wtf....
-            Position::from((row, 35)),
-            pluck::<100, 35, 13, 52>(chars),
-        )),
+        // positions 36-49 like the documentation, a 13 wide factor 2 read the same on every
+        // program with nothing in 49, but lost what's there and read 68 twice
+        factor2: FieldResult::from((Position::from((row, 35)), pluck::<100, 35, 14, 51>(chars))),
         result: FieldResult::from((Position::from((row, 49)), pluck::<100, 49, 14, 37>(chars))),
         result_length: FieldResult::from((
             Position::from((row, 63)),
             pluck::<100, 63, 5, 32>(chars),
         )),
-        decimals: FieldResult::from((Position::from((row, 67)), pluck::<100, 67, 2, 31>(chars))),
+        decimals: FieldResult::from((Position::from((row, 68)), pluck::<100, 68, 2, 30>(chars))),
         resulting_indicators: FieldResult::from((
-            Position::from((row, 69)),
-            pluck::<100, 69, 5, 26>(chars),
+            Position::from((row, 70)),
+            pluck::<100, 70, 6, 24>(chars),
         )),
-        comments: FieldResult::from((Position::from((row, 74)), pluck::<100, 74, 26, 0>(chars))),
+        comments: FieldResult::from((Position::from((row, 76)), pluck::<100, 76, 24, 0>(chars))),
     });
     Some(line)
 }
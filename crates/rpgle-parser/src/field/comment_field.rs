@@ -47,8 +47,8 @@ impl From<(Position, &[char; 94])> for FieldResult<CommentField> {
 }
 
 // for cspec inline comment
-impl From<(Position, &[char; 26])> for FieldResult<CommentField> {
-    fn from(value: (Position, &[char; 26])) -> Self {
+impl From<(Position, &[char; 24])> for FieldResult<CommentField> {
+    fn from(value: (Position, &[char; 24])) -> Self {
         let chars = value.1;
         let meta = Meta::from((value.0, chars.as_slice()));
         match chars[0] {
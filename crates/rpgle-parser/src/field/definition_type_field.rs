@@ -60,7 +60,7 @@ impl From<(Position, &[char; 2])> for FieldResult<DefinitionTypeField> {
             "DS" => Some(DefinitionType::DS),
             "PR" => Some(DefinitionType::PR),
             "PI" => Some(DefinitionType::PI),
-            "SI" => Some(DefinitionType::S),
+            "S " => Some(DefinitionType::S),
             _ => None,
         };
         if let Some(x) = maybe {
@@ -85,3 +85,24 @@ impl FieldBehavior for DefinitionTypeField {
         vec![(self.span(), "@type.definition".to_string())]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standalone() {
+        // a standalone field is `S` and a blank, `SI` isn't a definition type
+        let p = Position::empty();
+        let rs: FieldResult<DefinitionTypeField> = FieldResult::from((p, &['s', ' ']));
+        assert!(matches!(
+            rs,
+            FieldResult::Ok(DefinitionTypeField {
+                value: DefinitionType::S,
+                ..
+            })
+        ));
+        let rs: FieldResult<DefinitionTypeField> = FieldResult::from((p, &['S', 'I']));
+        assert!(matches!(rs, FieldResult::Idk(IdkField { .. })));
+    }
+}
@@ -19,13 +19,13 @@ impl Display for RawFactor2Field {
     }
 }
 
-impl From<(Position, &[char; 13])> for FieldResult<RawFactor2Field> {
-    fn from(value: (Position, &[char; 13])) -> Self {
+impl From<(Position, &[char; 14])> for FieldResult<RawFactor2Field> {
+    fn from(value: (Position, &[char; 14])) -> Self {
         let pos = value.0;
         let chars = value.1;
         let meta = Meta::from((pos, chars.as_slice()));
         let value = NonEmpty::from_vec(chars.iter().map(|c| *c).collect::<Vec<char>>())
-            .expect("&[char; 13] is guaranteed to be nonempty");
+            .expect("&[char; 14] is guaranteed to be nonempty");
         Self::Ok(RawFactor2Field { value, meta })
     }
 }
@@ -26,8 +26,8 @@ impl From<(Position, &[char; 3])> for FieldResult<IndicatorsField> {
     }
 }
 
-impl From<(Position, &[char; 5])> for FieldResult<IndicatorsField> {
-    fn from(value: (Position, &[char; 5])) -> Self {
+impl From<(Position, &[char; 6])> for FieldResult<IndicatorsField> {
+    fn from(value: (Position, &[char; 6])) -> Self {
         let chars = value.1;
         let meta = Meta::from((value.0, chars.as_slice()));
         let value = chars.iter().filter(|c| **c != ' ').collect::<String>();
@@ -39,21 +39,21 @@ pub use code_field::{CodeField, RawCodeField};
 pub use comment_field::CommentField;
 pub use compiler_directive_field::CompilerDirectiveField;
 pub use control_level_field::ControlLevelField;
-pub use datastructure_type_field::DatastructureTypeField;
-pub use datatype_field::DatatypeField;
-pub use decimals_field::DecimalsField;
-pub use definition_type_field::DefinitionTypeField;
-pub use device_field::DeviceField;
+pub use datastructure_type_field::{DatastructureType, DatastructureTypeField};
+pub use datatype_field::{Datatype, DatatypeField};
+pub use decimals_field::{Decimals, DecimalsField};
+pub use definition_type_field::{DefinitionType, DefinitionTypeField};
+pub use device_field::{Device, DeviceField};
 pub use endfile_field::EndfileField;
-pub use external_description_field::ExternalDescriptionField;
+pub use external_description_field::{ExternalDescription, ExternalDescriptionField};
 pub use factor1_field::Factor1Field;
 pub use factor2_field::RawFactor2Field;
-pub use file_addition_field::FileAdditionField;
-pub use file_designation_field::FileDesignationField;
-pub use file_format_field::FileFormatField;
+pub use file_addition_field::{FileAddition, FileAdditionField};
+pub use file_designation_field::{FileDesignation, FileDesignationField};
+pub use file_format_field::{FileFormat, FileFormatField};
 pub use file_organization_field::FileOrganizationField;
 pub use file_sequence_field::FileSequenceField;
-pub use filetype_field::FiletypeField;
+pub use filetype_field::{Filetype, FiletypeField};
 pub use formtype_field::FormtypeField;
 pub use idk_field::IdkField;
 pub use indicators_field::IndicatorsField;
@@ -63,11 +63,11 @@ pub use limits_processing_field::LimitsProcessingField;
 pub use name_field::NameField;
 pub use nothing_field::NothingField;
 pub use operation_field::{has_extf2_optoken, OperationField};
-pub use position_field::POSField;
+pub use position_field::{POSField, POS};
 pub use record_address_type_field::RecordAddressTypeField;
 pub use record_length_field::RecordLengthField;
 pub use reserved_field::ReservedField;
 pub use result::{FieldBehavior, FieldResult}; // used by each field type
 pub use result_field::ResultField;
-pub use result_length_field::ResultLengthField;
+pub use result_length_field::{ResultLength, ResultLengthField};
 pub use sequence_field::SequenceField;
@@ -0,0 +1,308 @@
+use super::verify::same_kind;
+use crate::cst::{parse_cst, CSrcline, Srcline};
+use crate::field::FieldResult;
+
+// the furthest a field may have drifted and still be moved back
+pub const MAX_DRIFT: isize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Right,
+    // stays where it is, ex: names indented under their data structure
+    Keep,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Any,
+    Name,
+    External,
+    DatastructureType,
+    DefinitionType,
+    Number,
+    Datatype,
+    Opcode,
+    Blank,
+}
+
+impl Kind {
+    fn accepts(&self, text: &str) -> bool {
+        let upper = text.to_uppercase();
+        let name_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '#' | '@' | '$');
+        match self {
+            Self::Any => true,
+            Self::Name => upper.chars().all(name_char),
+            Self::External => upper == "E",
+            Self::DatastructureType => upper == "S" || upper == "U",
+            Self::DefinitionType => ["S", "C", "DS", "PR", "PI"].contains(&upper.as_str()),
+            Self::Number => {
+                let digits = upper.trim_start_matches(['+', '-']);
+                !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+            }
+            Self::Datatype => upper.len() == 1 && upper.chars().all(|c| c.is_ascii_alphabetic()),
+            Self::Opcode => upper
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '(' | ')')),
+            Self::Blank => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    start: usize,
+    len: usize,
+    align: Align,
+    kind: Kind,
+}
+
+const fn slot(start: usize, len: usize, align: Align, kind: Kind) -> Slot {
+    Slot {
+        start,
+        len,
+        align,
+        kind,
+    }
+}
+
+// columns 7-43
+const D_SLOTS: [Slot; 9] = [
+    slot(6, 15, Align::Keep, Kind::Name),
+    slot(21, 1, Align::Left, Kind::External),
+    slot(22, 1, Align::Left, Kind::DatastructureType),
+    slot(23, 2, Align::Left, Kind::DefinitionType),
+    slot(25, 7, Align::Right, Kind::Number),
+    slot(32, 7, Align::Right, Kind::Number),
+    slot(39, 1, Align::Left, Kind::Datatype),
+    slot(40, 2, Align::Right, Kind::Number),
+    slot(42, 1, Align::Left, Kind::Blank),
+];
+
+// columns 7-63, past that the CST and the manual disagree so it's left alone
+const C_SLOTS: [Slot; 6] = [
+    slot(6, 2, Align::Keep, Kind::Any),
+    slot(8, 3, Align::Keep, Kind::Any),
+    slot(11, 14, Align::Left, Kind::Any),
+    slot(25, 10, Align::Left, Kind::Opcode),
+    slot(35, 14, Align::Left, Kind::Any),
+    slot(49, 14, Align::Left, Kind::Any),
+];
+
+#[derive(Debug, Clone)]
+struct Token {
+    start: usize,
+    text: Vec<char>,
+}
+
+// blank separated tokens, string literals stay whole
+fn tokenize(chars: &[char], from: usize, to: usize) -> Vec<Token> {
+    let mut out = vec![];
+    let mut i = from;
+    while i < to {
+        if chars[i] == ' ' {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut quoted = false;
+        while i < to && (quoted || chars[i] != ' ') {
+            if chars[i] == '\'' {
+                quoted = !quoted;
+            }
+            i += 1;
+        }
+        out.push(Token {
+            start,
+            text: chars[start..i].to_vec(),
+        });
+    }
+    out
+}
+
+// the slot each token falls entirely inside, in order
+fn place(tokens: &[Token], slots: &[Slot]) -> Option<Vec<usize>> {
+    let mut out: Vec<usize> = vec![];
+    for token in tokens.iter() {
+        let text = token.text.iter().collect::<String>();
+        let end = token.start + token.text.len();
+        let idx = slots.iter().position(|s| {
+            token.start >= s.start && end <= s.start + s.len && s.kind.accepts(&text)
+        })?;
+        if out.last().is_some_and(|last| *last >= idx) {
+            return None;
+        }
+        out.push(idx);
+    }
+    Some(out)
+}
+
+// a line is drifted when its tokens straddle field boundaries. Try moving
+// the tail of the line back by the smallest amount that puts every token
+// inside a field, the fewer tokens moved the better.
+fn layout(
+    chars: &[char],
+    slots: &[Slot],
+    valid: impl Fn(&[(Token, Slot)]) -> bool,
+) -> Option<Vec<(Token, Slot)>> {
+    let from = slots.first()?.start;
+    let last = slots.last()?;
+    let to = last.start + last.len;
+    // a token running across the end of the region can't be moved whole
+    if chars[to - 1] != ' ' && chars.get(to).is_some_and(|c| *c != ' ') {
+        return None;
+    }
+    let tokens = tokenize(chars, from, to);
+    let mut shifts = vec![0];
+    for k in 1..=MAX_DRIFT {
+        shifts.push(-k);
+        shifts.push(k);
+    }
+    for k in shifts {
+        let splits = if k == 0 { 0..1 } else { 0..tokens.len() };
+        for split in splits.rev() {
+            let mut moved = tokens.clone();
+            let mut fits = true;
+            for token in moved[split..].iter_mut() {
+                let start = token.start as isize + k;
+                if start < from as isize {
+                    fits = false;
+                }
+                token.start = start.max(0) as usize;
+            }
+            if !fits {
+                continue;
+            }
+            let Some(placed) = place(&moved, slots) else {
+                continue;
+            };
+            let pairs = moved
+                .into_iter()
+                .zip(placed.into_iter().map(|idx| slots[idx]))
+                .collect::<Vec<(Token, Slot)>>();
+            if valid(&pairs) {
+                return Some(pairs);
+            }
+        }
+    }
+    None
+}
+
+fn render(chars: &mut [char], slots: &[Slot], pairs: &[(Token, Slot)]) -> bool {
+    let from = slots[0].start;
+    let to = slots[slots.len() - 1].start + slots[slots.len() - 1].len;
+    let mut out = vec![' '; to - from];
+    for (token, slot) in pairs.iter() {
+        let start = match slot.align {
+            Align::Left => slot.start,
+            Align::Right => slot.start + slot.len - token.text.len(),
+            Align::Keep => token.start,
+        };
+        for (i, c) in token.text.iter().enumerate() {
+            out[start - from + i] = *c;
+        }
+    }
+    if out == chars[from..to] {
+        return false;
+    }
+    let before = parse_line(chars);
+    let mut after = chars.to_vec();
+    after[from..to].copy_from_slice(&out);
+    // never trade a field the parser understood for one it doesn't
+    let better = match (before, parse_line(&after)) {
+        (Some(before), Some(after)) => {
+            same_kind(&before, &after) && idk_fields(&after) <= idk_fields(&before)
+        }
+        _ => false,
+    };
+    if better {
+        chars.copy_from_slice(&after);
+    }
+    better
+}
+
+fn parse_line(chars: &[char]) -> Option<Srcline> {
+    let text = chars.iter().collect::<String>();
+    parse_cst(&text).ok()?.lines.into_iter().next()
+}
+
+fn idk_fields(line: &Srcline) -> usize {
+    fn idk<T>(field: &FieldResult<T>) -> usize {
+        matches!(field, FieldResult::Idk(_)) as usize
+    }
+    match line {
+        Srcline::D {
+            name,
+            external_description,
+            datastructure_type,
+            definition_type,
+            from_position,
+            to_length,
+            datatype,
+            decimals,
+            reserved,
+            ..
+        } => {
+            idk(name)
+                + idk(external_description)
+                + idk(datastructure_type)
+                + idk(definition_type)
+                + idk(from_position)
+                + idk(to_length)
+                + idk(datatype)
+                + idk(decimals)
+                + idk(reserved)
+        }
+        Srcline::C(CSrcline::Traditional {
+            control_level,
+            indicators,
+            factor1,
+            operation,
+            factor2,
+            result,
+            ..
+        }) => {
+            idk(control_level)
+                + idk(indicators)
+                + idk(factor1)
+                + idk(operation)
+                + idk(factor2)
+                + idk(result)
+        }
+        _ => 0,
+    }
+}
+
+// An external or data structure type marker only makes sense on a DS,
+// without one it's a definition type that slid left.
+fn valid_d(pairs: &[(Token, Slot)]) -> bool {
+    let is_ds = pairs.iter().any(|(token, slot)| {
+        slot.kind == Kind::DefinitionType
+            && token.text.iter().collect::<String>().to_uppercase() == "DS"
+    });
+    is_ds
+        || !pairs
+            .iter()
+            .any(|(_, slot)| slot.kind == Kind::External || slot.kind == Kind::DatastructureType)
+}
+
+// Lines with a name continued by `...` run past the name column on purpose
+pub fn realign_d(chars: &mut [char]) -> bool {
+    let name = chars[6..43].iter().collect::<String>();
+    if name.contains("...") {
+        return false;
+    }
+    match layout(chars, &D_SLOTS, valid_d) {
+        Some(pairs) => render(chars, &D_SLOTS, &pairs),
+        None => false,
+    }
+}
+
+// The operation has to be there, everything else is optional
+pub fn realign_c(chars: &mut [char]) -> bool {
+    let valid = |pairs: &[(Token, Slot)]| pairs.iter().any(|(_, slot)| slot.kind == Kind::Opcode);
+    match layout(chars, &C_SLOTS, valid) {
+        Some(pairs) => render(chars, &C_SLOTS, &pairs),
+        None => false,
+    }
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Case {
+    Keep,
+    Upper,
+    Lower,
+}
+
+// ascii only so a line never changes length
+pub fn recase(chars: &mut [char], range: Range<usize>, case: Case) {
+    for c in chars[range].iter_mut() {
+        *c = match case {
+            Case::Keep => *c,
+            Case::Upper => c.to_ascii_uppercase(),
+            Case::Lower => c.to_ascii_lowercase(),
+        };
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '#' | '@' | '$')
+}
+
+// Keywords are the words outside of any parens, ex: the EXTPGM in
+// `EXTPGM('QCMDEXC')` or the CONST in `CONST VALUE`. Arguments are names and
+// literals and are left alone. Columns 81-100 are comments.
+pub fn recase_keywords(chars: &mut [char], from: usize, case: Case) {
+    if case == Case::Keep {
+        return;
+    }
+    let end = chars.len().min(80);
+    let mut depth = 0;
+    let mut i = from;
+    while i < end {
+        let c = chars[i];
+        if c == '\'' {
+            i += 1;
+            while i < end && chars[i] != '\'' {
+                i += 1;
+            }
+            i += 1;
+            continue;
+        }
+        match c {
+            '(' => depth += 1,
+            ')' => depth = usize::saturating_sub(depth, 1),
+            _ => {}
+        }
+        if !is_name_char(c) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < end && is_name_char(chars[i]) {
+            i += 1;
+        }
+        let special = start > 0 && matches!(chars[start - 1], '*' | '%');
+        if depth == 0 && !special && c.is_ascii_alphabetic() {
+            recase(chars, start..i, case);
+        }
+    }
+}
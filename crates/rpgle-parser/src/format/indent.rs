@@ -0,0 +1,271 @@
+use std::ops::Range;
+
+// free-form code lives in columns 8-100
+const CODE: usize = 7;
+
+// Free-form opcodes, the first word of a statement. Anything else in that
+// spot is an assignment or a procedure call.
+pub(crate) const OPCODES: [&str; 82] = [
+    "ACQ",
+    "BEGSR",
+    "CALLP",
+    "CHAIN",
+    "CLEAR",
+    "CLOSE",
+    "COMMIT",
+    "CTL-OPT",
+    "DATA-GEN",
+    "DATA-INTO",
+    "DCL-C",
+    "DCL-DS",
+    "DCL-F",
+    "DCL-PARM",
+    "DCL-PI",
+    "DCL-PR",
+    "DCL-PROC",
+    "DCL-S",
+    "DCL-SUBF",
+    "DEALLOC",
+    "DELETE",
+    "DOU",
+    "DOW",
+    "DSPLY",
+    "DUMP",
+    "ELSE",
+    "ELSEIF",
+    "END-DS",
+    "END-PI",
+    "END-PR",
+    "END-PROC",
+    "ENDDO",
+    "ENDFOR",
+    "ENDIF",
+    "ENDMON",
+    "ENDSL",
+    "ENDSR",
+    "EVAL",
+    "EVAL-CORR",
+    "EVALR",
+    "EXCEPT",
+    "EXFMT",
+    "EXSR",
+    "FEOD",
+    "FOR",
+    "FOR-EACH",
+    "FORCE",
+    "IF",
+    "IN",
+    "ITER",
+    "LEAVE",
+    "LEAVESR",
+    "MONITOR",
+    "NEXT",
+    "ON-ERROR",
+    "ON-EXIT",
+    "OPEN",
+    "OTHER",
+    "OUT",
+    "POST",
+    "READ",
+    "READC",
+    "READE",
+    "READP",
+    "READPE",
+    "REL",
+    "RESET",
+    "RETURN",
+    "ROLBK",
+    "SELECT",
+    "SETGT",
+    "SETLL",
+    "SND-MSG",
+    "SORTA",
+    "TEST",
+    "UNLOCK",
+    "UPDATE",
+    "WHEN",
+    "WHEN-IN",
+    "WHEN-IS",
+    "WRITE",
+    "XML-INTO",
+];
+
+pub fn is_opcode(word: &str) -> bool {
+    OPCODES.contains(&word.to_uppercase().as_str())
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Effect {
+    // opens a block, the body is this many levels deeper
+    Open(usize),
+    Close,
+    // ELSE, WHEN and friends sit one level out from the body around them
+    Middle,
+    Nothing,
+}
+
+// SELECT indents its WHENs and their bodies one more level again
+pub(crate) fn effect(opcode: &str, statement: &str) -> Effect {
+    let upper = statement.to_uppercase();
+    match opcode {
+        "SELECT" => Effect::Open(2),
+        "IF" | "DO" | "DOW" | "DOU" | "FOR" | "FOR-EACH" | "MONITOR" | "BEGSR" | "DCL-PROC" => {
+            Effect::Open(1)
+        }
+        "DCL-DS" if upper.contains("LIKEDS") || upper.contains("LIKEREC") => Effect::Nothing,
+        "DCL-DS" if upper.contains("END-DS") => Effect::Nothing,
+        "DCL-PR" if upper.contains("END-PR") => Effect::Nothing,
+        "DCL-PI" if upper.contains("END-PI") => Effect::Nothing,
+        "DCL-DS" | "DCL-PR" | "DCL-PI" => Effect::Open(1),
+        "ELSE" | "ELSEIF" | "WHEN" | "WHEN-IN" | "WHEN-IS" | "OTHER" | "ON-ERROR" | "ON-EXIT" => {
+            Effect::Middle
+        }
+        "END" | "ENDIF" | "ENDDO" | "ENDFOR" | "ENDSL" | "ENDMON" | "ENDSR" | "END-PROC"
+        | "END-DS" | "END-PR" | "END-PI" => Effect::Close,
+        // traditional forms with a comparison in the opcode, ex: IFEQ, DOWLT, WHENGE
+        _ => {
+            let comparisons = ["EQ", "NE", "GT", "LT", "GE", "LE"];
+            let conditional = |head: &str| {
+                opcode
+                    .strip_prefix(head)
+                    .is_some_and(|rest| comparisons.contains(&rest))
+            };
+            if conditional("IF") || conditional("DOW") || conditional("DOU") {
+                Effect::Open(1)
+            } else if conditional("WHEN") {
+                Effect::Middle
+            } else {
+                Effect::Nothing
+            }
+        }
+    }
+}
+
+// The statement text without a trailing `//` comment
+pub(crate) fn strip_comment(code: &str) -> &str {
+    let mut quoted = false;
+    let mut prev = ' ';
+    for (i, c) in code.char_indices() {
+        if c == '\'' {
+            quoted = !quoted;
+        }
+        if !quoted && c == '/' && prev == '/' {
+            return &code[..i - 1];
+        }
+        prev = c;
+    }
+    code
+}
+
+/* Tracks block nesting across a source. Traditional calc specs can't be
+ * indented but still open and close blocks, so free-form code inside a
+ * fixed-format BEGSR is indented like any other.
+ */
+#[derive(Debug, Default)]
+pub struct Nesting {
+    // levels opened by each block still open
+    blocks: Vec<usize>,
+    // the last free-form statement hasn't hit its `;` yet
+    continued: bool,
+}
+
+impl Nesting {
+    fn depth(&self) -> usize {
+        self.blocks.iter().sum()
+    }
+
+    // the level the next line sits at, before its own opcode opens or closes a block
+    pub fn level(&self) -> usize {
+        self.depth() + usize::from(self.continued)
+    }
+
+    // apply an effect, returns the nesting level the line itself sits at
+    fn apply(&mut self, effect: Effect) -> usize {
+        match effect {
+            Effect::Open(levels) => {
+                let depth = self.depth();
+                self.blocks.push(levels);
+                depth
+            }
+            Effect::Close => {
+                self.blocks.pop();
+                self.depth()
+            }
+            Effect::Middle => self.depth().saturating_sub(1),
+            Effect::Nothing => self.depth(),
+        }
+    }
+
+    pub fn fixed(&mut self, chars: &[char]) {
+        let operation = chars[25..35].iter().collect::<String>();
+        let opcode = operation
+            .split('(')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_uppercase();
+        self.apply(effect(&opcode, ""));
+    }
+
+    /* Re-indent a free-form line to its nesting level, `indent` spaces per
+     * level. Continuation lines of a statement sit one level deeper. Lines
+     * that would no longer fit are left where they are. Returns the columns of
+     * the opcode when the line starts with one.
+     */
+    pub fn free(&mut self, chars: &mut [char], indent: usize) -> Option<Range<usize>> {
+        let code = chars[CODE..].iter().collect::<String>();
+        let text = code.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let statement = strip_comment(text).trim_end();
+        let mut opcode = None;
+        let level = if statement.is_empty() {
+            self.depth()
+        } else if self.continued {
+            self.depth() + 1
+        } else {
+            let word = statement
+                .split([' ', ';', '('])
+                .next()
+                .unwrap_or_default()
+                .to_uppercase();
+            if OPCODES.contains(&word.as_str()) {
+                opcode = Some(word.chars().count());
+            }
+            self.apply(effect(&word, statement))
+        };
+        if !statement.is_empty() {
+            self.continued = !statement.ends_with(';');
+        }
+        let mut start = CODE + code.chars().take_while(|c| *c == ' ').count();
+        let width = text.chars().count();
+        if indent > 0 && level * indent + width <= chars.len() - CODE {
+            start = CODE + level * indent;
+            for c in chars[CODE..].iter_mut() {
+                *c = ' ';
+            }
+            for (i, c) in text.chars().enumerate() {
+                chars[start + i] = c;
+            }
+        }
+        opcode.map(|len| start..start + len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effect() {
+        assert_eq!(effect("IFEQ", ""), Effect::Open(1));
+        assert_eq!(effect("WHENGT", ""), Effect::Middle);
+        assert_eq!(effect("IFS", ""), Effect::Nothing);
+        assert_eq!(
+            effect("DCL-DS", "dcl-ds Row likeds(Tmpl);"),
+            Effect::Nothing
+        );
+        assert_eq!(effect("DCL-DS", "dcl-ds Row qualified;"), Effect::Open(1));
+    }
+}
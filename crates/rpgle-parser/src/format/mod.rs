@@ -0,0 +1,231 @@
+mod align;
+mod case;
+mod indent;
+mod verify;
+
+use crate::cst::{parse_cst, CSrcline, Srcline};
+use align::{realign_c, realign_d};
+use case::{recase, recase_keywords};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use verify::verify;
+
+pub use case::Case;
+pub(crate) use indent::{effect, is_opcode, strip_comment, Effect, Nesting, OPCODES};
+
+// every rpgle line is exactly this wide once formatted
+const WIDTH: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatOptions {
+    // free-form opcodes, declarations and the traditional operation column
+    pub opcode_case: Case,
+    // H/F/D spec keywords, ex: EXTPGM, RENAME, INZ
+    pub keyword_case: Case,
+    // spaces per nesting level in free-form code, 0 leaves indentation alone
+    pub indent: usize,
+    // move fixed-format fields that drifted out of their columns back in
+    pub realign: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            opcode_case: Case::Keep,
+            keyword_case: Case::Keep,
+            indent: 2,
+            realign: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatError {
+    // the input couldn't be parsed at all, nothing was changed
+    Parse(String),
+    // the formatted output doesn't reparse to the same program, nothing was changed
+    NotEquivalent(String),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(msg) => write!(f, "Unable to parse source: {}", msg),
+            Self::NotEquivalent(msg) => write!(f, "Formatting would change the program: {}", msg),
+        }
+    }
+}
+
+/* Pad every line to `width` and coerce overlong lines. Lines that are only
+ * long because of trailing blanks are trimmed. Anything else is assumed to be
+ * several records run together (ex: a member fetched over ODBC) and is cut
+ * into `width` sized lines. Control characters become blanks.
+ *
+ * RPG uses a width of 100, DDS uses 80.
+ */
+pub fn normalize_width(input: &str, width: usize) -> String {
+    if input.is_empty() {
+        return String::new();
+    }
+    let body = input.strip_suffix('\n').unwrap_or(input);
+    let mut out = vec![];
+    for line in body.split('\n') {
+        let chars = line
+            .trim_end_matches('\r')
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect::<Vec<char>>();
+        let mut rest = chars.as_slice();
+        loop {
+            if rest.len() <= width || rest[width..].iter().all(|c| *c == ' ') {
+                let mut text = rest.iter().take(width).collect::<String>();
+                text.push_str(&" ".repeat(width - rest.len().min(width)));
+                out.push(text);
+                break;
+            }
+            out.push(rest[..width].iter().collect::<String>());
+            rest = &rest[width..];
+        }
+    }
+    let mut text = out.join("\n");
+    if input.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
+
+/* Format rpgle source. Only whitespace and letter case are ever touched, and
+ * the result is reparsed and compared against the input before it's returned,
+ * so a formatting bug surfaces as an error instead of a changed program.
+ */
+pub fn format(input: &str, options: &FormatOptions) -> Result<String, FormatError> {
+    let normalized = normalize_width(input, WIDTH);
+    let cst = parse_cst(&normalized).map_err(|e| FormatError::Parse(format!("{:?}", e)))?;
+    let mut lines = normalized
+        .lines()
+        .map(|line| line.chars().collect::<Vec<char>>())
+        .collect::<Vec<Vec<char>>>();
+    let mut nesting = Nesting::default();
+    for (line, chars) in cst.lines.iter().zip(lines.iter_mut()) {
+        match line {
+            Srcline::H { .. } => recase_keywords(chars, 6, options.keyword_case),
+            Srcline::F { .. } | Srcline::FCont { .. } | Srcline::DCont { .. } => {
+                recase_keywords(chars, 43, options.keyword_case)
+            }
+            Srcline::D { .. } => {
+                if options.realign {
+                    realign_d(chars);
+                }
+                recase_keywords(chars, 43, options.keyword_case);
+            }
+            Srcline::C(CSrcline::Traditional { .. }) => {
+                if options.realign {
+                    realign_c(chars);
+                }
+                recase(chars, 25..35, options.opcode_case);
+                nesting.fixed(chars);
+            }
+            Srcline::C(CSrcline::ExtF2 { .. }) => {
+                recase(chars, 25..35, options.opcode_case);
+                nesting.fixed(chars);
+            }
+            Srcline::C(CSrcline::Free { .. }) => {
+                if let Some(opcode) = nesting.free(chars, options.indent) {
+                    recase(chars, opcode, options.opcode_case);
+                }
+            }
+            Srcline::Idk { .. } | Srcline::Comment { .. } | Srcline::CompilerDirective { .. } => {}
+        }
+    }
+    let mut output = lines
+        .iter()
+        .map(|chars| chars.iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n");
+    if normalized.ends_with('\n') {
+        output.push('\n');
+    }
+    verify(&normalized, &output)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> String {
+        r#"
+     H option(*nodebugio:*srcstmt)
+     FCowEvtL2  IF   E           K DISK     rename(EVTFMT:VEVTFMT)
+     D LastId         S              8  0
+     D Count           S             5   0
+     D QCmdExc         PR                  extpgm('QCMDEXC')
+     D  Command                    2000
+      /free
+       exsr $SetLstId;
+       *inlr = *on;
+
+       begsr $SetLstId;
+       setll *loval CowEvtL2;
+          if not %eof;
+       read CowEvtL2;
+       // keep the last id
+           LastId = Vid;
+       else;
+       LastId = 1;
+       endif;
+       select;
+       when LastId > 1;
+       QCmdExc('DLYJOB DLY(1)':
+       13);
+       other;
+       Count = 0;
+       endsl;
+       endsr;
+      /end-free
+     C     $Chk          begsr
+     C                   IF        Count > 0
+       Count = 0;
+     C                   ENDIF
+     C                   eval      Count = 1
+     C                    Z-ADD     1             Count
+     C                   endsr
+"#[1..]
+            .to_string()
+    }
+
+    #[test]
+    fn test_normalize_width() {
+        let input = format!("{}{}\n", "A".repeat(100), "B".repeat(20));
+        let observed = normalize_width(&input, 100);
+        let expected = format!(
+            "{}\n{}{}\n",
+            "A".repeat(100),
+            "B".repeat(20),
+            " ".repeat(80)
+        );
+        assert_eq!(observed, expected);
+        let observed = normalize_width(&format!("A\t{}\r\n", " ".repeat(120)), 80);
+        assert_eq!(observed, format!("A{}\n", " ".repeat(79)));
+    }
+
+    #[test]
+    fn test_format_snapshot() {
+        let options = FormatOptions {
+            opcode_case: Case::Upper,
+            keyword_case: Case::Upper,
+            ..FormatOptions::default()
+        };
+        let observed = format(&fixture(), &options).unwrap();
+        insta::assert_yaml_snapshot!(observed);
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        let options = FormatOptions::default();
+        let once = format(&fixture(), &options).unwrap();
+        let twice = format(&once, &options).unwrap();
+        assert_eq!(once, twice);
+    }
+}
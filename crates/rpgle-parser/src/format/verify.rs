@@ -0,0 +1,349 @@
+use super::align::MAX_DRIFT;
+use super::FormatError;
+use crate::ast::parse_ast;
+use crate::cst::{parse_cst, CSrcline, Srcline};
+use crate::field::{DefinitionType, FieldBehavior, FieldResult};
+use std::fmt::Display;
+use std::mem::discriminant;
+
+// What formatting can't change: everything without blanks and in one case.
+// String literals are kept exactly.
+fn significant(text: &str) -> String {
+    let mut out = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        if c == '\'' {
+            quoted = !quoted;
+        }
+        if quoted || c == '\'' {
+            out.push(c);
+        } else if c != ' ' {
+            out.push(c.to_ascii_uppercase());
+        }
+    }
+    out
+}
+
+// A field of a line, the columns it covers and what's in it
+#[derive(Debug)]
+struct Cell {
+    start: usize,
+    end: usize,
+    value: String,
+    // the parser understood what's in it, where it is
+    settled: bool,
+}
+
+fn cell<T: FieldBehavior + Display>(field: &FieldResult<T>) -> Cell {
+    let span = field.span();
+    Cell {
+        start: span.start.col,
+        end: span.end.col,
+        value: significant(&field.to_string()),
+        settled: field.is_ok(),
+    }
+}
+
+fn cells(line: &Srcline) -> Vec<Cell> {
+    match line {
+        Srcline::Idk { idk } => vec![cell(idk)],
+        Srcline::Comment {
+            sequence,
+            form_type,
+            comment,
+        } => vec![cell(sequence), cell(form_type), cell(comment)],
+        Srcline::CompilerDirective {
+            sequence,
+            form_type,
+            directive,
+        } => vec![cell(sequence), cell(form_type), cell(directive)],
+        Srcline::H {
+            sequence,
+            form_type,
+            keywords,
+        } => vec![cell(sequence), cell(form_type), cell(keywords)],
+        Srcline::F {
+            sequence,
+            form_type,
+            name,
+            filetype,
+            file_designation,
+            endfile,
+            file_addition,
+            file_sequence,
+            file_format,
+            record_length,
+            limits_processing,
+            keylength,
+            record_address_type,
+            file_organization,
+            device,
+            reserved,
+            keywords,
+        } => vec![
+            cell(sequence),
+            cell(form_type),
+            cell(name),
+            cell(filetype),
+            cell(file_designation),
+            cell(endfile),
+            cell(file_addition),
+            cell(file_sequence),
+            cell(file_format),
+            cell(record_length),
+            cell(limits_processing),
+            cell(keylength),
+            cell(record_address_type),
+            cell(file_organization),
+            cell(device),
+            cell(reserved),
+            cell(keywords),
+        ],
+        Srcline::FCont {
+            sequence,
+            form_type,
+            nothing,
+            keywords,
+        }
+        | Srcline::DCont {
+            sequence,
+            form_type,
+            nothing,
+            keywords,
+        } => vec![
+            cell(sequence),
+            cell(form_type),
+            cell(nothing),
+            cell(keywords),
+        ],
+        Srcline::D {
+            sequence,
+            form_type,
+            name,
+            external_description,
+            datastructure_type,
+            definition_type,
+            from_position,
+            to_length,
+            datatype,
+            decimals,
+            reserved,
+            keywords,
+        } => {
+            // the external and data structure type columns only mean something on a DS
+            let ds = matches!(definition_type, FieldResult::Ok(x) if x.value == DefinitionType::DS);
+            let mut external_description = cell(external_description);
+            let mut datastructure_type = cell(datastructure_type);
+            external_description.settled &= ds || external_description.value.is_empty();
+            datastructure_type.settled &= ds || datastructure_type.value.is_empty();
+            vec![
+                cell(sequence),
+                cell(form_type),
+                cell(name),
+                external_description,
+                datastructure_type,
+                cell(definition_type),
+                cell(from_position),
+                cell(to_length),
+                cell(datatype),
+                cell(decimals),
+                cell(reserved),
+                cell(keywords),
+            ]
+        }
+        Srcline::C(CSrcline::Traditional {
+            nothing,
+            form_type,
+            control_level,
+            indicators,
+            factor1,
+            operation,
+            factor2,
+            result,
+            result_length,
+            decimals,
+            resulting_indicators,
+            comments,
+        }) => vec![
+            cell(nothing),
+            cell(form_type),
+            cell(control_level),
+            cell(indicators),
+            cell(factor1),
+            cell(operation),
+            cell(factor2),
+            cell(result),
+            cell(result_length),
+            cell(decimals),
+            cell(resulting_indicators),
+            cell(comments),
+        ],
+        Srcline::C(CSrcline::ExtF2 {
+            nothing,
+            form_type,
+            control_level,
+            indicators,
+            factor1,
+            operation,
+            factor2,
+        }) => vec![
+            cell(nothing),
+            cell(form_type),
+            cell(control_level),
+            cell(indicators),
+            cell(factor1),
+            cell(operation),
+            cell(factor2),
+        ],
+        Srcline::C(CSrcline::Free { nothing, code }) => vec![cell(nothing), cell(code)],
+    }
+}
+
+// blank separated tokens with their columns, string literals stay whole
+fn tokens(text: &str) -> Vec<(usize, usize, String)> {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == ' ' {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let mut quoted = false;
+        while i < chars.len() && (quoted || chars[i] != ' ') {
+            if chars[i] == '\'' {
+                quoted = !quoted;
+            }
+            i += 1;
+        }
+        let token = chars[start..i].iter().collect::<String>();
+        out.push((start, i, significant(&token)));
+    }
+    out
+}
+
+pub fn same_kind(a: &Srcline, b: &Srcline) -> bool {
+    match (a, b) {
+        (Srcline::C(a), Srcline::C(b)) => discriminant::<CSrcline>(a) == discriminant(b),
+        _ => discriminant(a) == discriminant(b),
+    }
+}
+
+/* Every field of the line holds what it held before. A drifted line, one
+ * with a token running across fields or in a field the parser didn't
+ * understand, may have its tokens moved back by realigning. Each has to land
+ * whole in a field it ran into, or in one the parser understands at most
+ * MAX_DRIFT columns away.
+ */
+fn same_fields(a: &Srcline, b: &Srcline, x: &str, y: &str) -> bool {
+    let (before, after) = (cells(a), cells(b));
+    if before.len() != after.len() {
+        return false;
+    }
+    if before
+        .iter()
+        .zip(after.iter())
+        .all(|(a, b)| a.value == b.value)
+    {
+        return true;
+    }
+    let (moved_from, moved_to) = (tokens(x), tokens(y));
+    if moved_from.len() != moved_to.len() {
+        return false;
+    }
+    let overlapping = |token: &(usize, usize, String)| {
+        before
+            .iter()
+            .filter(|c| c.start < token.1 && token.0 < c.end)
+            .collect::<Vec<&Cell>>()
+    };
+    let drifted = moved_from.iter().any(|token| {
+        let cells = overlapping(token);
+        cells.len() > 1 || cells.iter().any(|c| !c.settled)
+    });
+    moved_from.iter().zip(moved_to.iter()).all(|(from, to)| {
+        let Some(idx) = after.iter().position(|c| c.start <= to.0 && to.1 <= c.end) else {
+            return false;
+        };
+        let stayed = before[idx].start < from.1 && from.0 < before[idx].end;
+        let shift = from.0.abs_diff(to.0) as isize;
+        let settled = after[idx].settled;
+        from.2 == to.2 && (stayed || (drifted && settled && shift <= MAX_DRIFT))
+    })
+}
+
+/* The formatted source has to parse into the same lines, each with the same
+ * fields holding the same values, and into the same sequence of specs. An
+ * input the AST can't be built from only gets the line level check.
+ */
+pub fn verify(before: &str, after: &str) -> Result<(), FormatError> {
+    let parse = |input: &str| parse_cst(input).map_err(|e| FormatError::Parse(format!("{:?}", e)));
+    let (cst_before, cst_after) = (parse(before)?, parse(after)?);
+    if cst_before.lines.len() != cst_after.lines.len() {
+        let msg = "the number of lines changed".to_string();
+        return Err(FormatError::NotEquivalent(msg));
+    }
+    let rows = cst_before.lines.iter().zip(cst_after.lines.iter());
+    let texts = before.lines().zip(after.lines());
+    for (row, ((a, b), (x, y))) in rows.zip(texts).enumerate() {
+        if !same_kind(a, b) || !same_fields(a, b, x, y) {
+            let msg = format!("line {} changed meaning", row + 1);
+            return Err(FormatError::NotEquivalent(msg));
+        }
+    }
+    let Ok(ast_before) = parse_ast(&cst_before) else {
+        return Ok(());
+    };
+    let Ok(ast_after) = parse_ast(&cst_after) else {
+        let msg = "the output no longer builds an AST".to_string();
+        return Err(FormatError::NotEquivalent(msg));
+    };
+    let same_specs = ast_before.specs.len() == ast_after.specs.len()
+        && ast_before
+            .specs
+            .iter()
+            .zip(ast_after.specs.iter())
+            .all(|(a, b)| discriminant(a) == discriminant(b));
+    if !same_specs {
+        let msg = "the output parses into different specs".to_string();
+        return Err(FormatError::NotEquivalent(msg));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a line with each token at its column
+    fn line(tokens: &[(usize, &str)]) -> String {
+        let mut chars = vec![' '; 100];
+        for (col, token) in tokens.iter() {
+            for (i, c) in token.chars().enumerate() {
+                chars[col + i] = c;
+            }
+        }
+        format!("{}\n", chars.into_iter().collect::<String>())
+    }
+
+    #[test]
+    fn test_verify_fields() {
+        let calc = |factor2: usize, result: usize| {
+            line(&[(5, "C"), (25, "Z-ADD"), (factor2, "1"), (result, "Count")])
+        };
+        let before = calc(35, 49);
+        // drifted back into its columns
+        let drifted = line(&[(5, "C"), (24, "Z-ADD"), (34, "1"), (48, "Count")]);
+        assert!(verify(&drifted, &before).is_ok());
+        // the result moved into the length, the factor 2 into the result
+        assert!(verify(&before, &calc(35, 63)).is_err());
+        assert!(verify(&before, &calc(49, 55)).is_err());
+        // a data structure type on a line that isn't a DS is a definition type that slid left
+        let dspec = |tokens: &[(usize, &str)]| line(&[&[(5, "D"), (7, "LastId")], tokens].concat());
+        let before = dspec(&[(22, "S"), (37, "8"), (40, "0")]);
+        assert!(verify(&before, &dspec(&[(23, "S"), (38, "8"), (41, "0")])).is_ok());
+        // the decimals moved into the reserved column
+        assert!(verify(&before, &dspec(&[(23, "S"), (38, "8"), (42, "0")])).is_err());
+    }
+}
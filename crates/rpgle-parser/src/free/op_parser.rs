@@ -371,6 +371,7 @@ impl<'a> From<RawF2ResultInput<'a>> for Op {
 type TraditionalResultFields<'a> = (
     &'a FieldResult<OperationField>,
     &'a FieldResult<Factor1Field>,
+    &'a FieldResult<RawFactor2Field>,
 );
 type TraditionalResultInput<'a> = (
     TraditionalResultFields<'a>,
@@ -380,6 +381,7 @@ impl<'a> From<TraditionalResultInput<'a>> for Op {
     fn from(value: TraditionalResultInput) -> Self {
         let operation = value.0 .0;
         let factor1 = value.0 .1;
+        let factor2 = value.0 .2;
         match operation {
             FieldResult::Ok(opfield) => {
                 if opfield.value.to_uppercase() == "BEGSR" {
@@ -402,15 +404,16 @@ impl<'a> From<TraditionalResultInput<'a>> for Op {
                         highlights: vec![],
                     }
                 } else if opfield.value.to_uppercase() == "EXSR" {
-                    match factor1 {
-                        FieldResult::Ok(f1) => Op::Exsr {
-                            name: f1.value.clone(),
-                            meta: f1.meta.clone(),
+                    // the subroutine name lives in factor 2
+                    match factor2 {
+                        FieldResult::Ok(f2) => Op::Exsr {
+                            name: f2.value.iter().filter(|c| **c != ' ').collect::<String>(),
+                            meta: f2.meta.clone(),
                             highlights: vec![],
                         },
                         FieldResult::Idk(idk) => Op::Idk {
                             meta: idk.meta.clone(),
-                            error: "BAD F1".to_string(),
+                            error: "BAD F2".to_string(),
                             tokens: vec![],
                             highlights: vec![],
                         },
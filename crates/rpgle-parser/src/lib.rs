@@ -1,11 +1,28 @@
+mod actions;
+mod analysis;
 mod ast;
+mod convert;
 mod cst;
 mod field;
+mod format;
 mod free;
+mod lint;
 mod meta;
 
-pub use ast::{parse_ast, Spec, AST};
+pub use actions::{query_code_actions, CodeAction, TextEdit};
+pub use analysis::{
+    lookup_builtin, lookup_opcode, query_builtin_calls, query_completions, query_folds,
+    query_hover, query_outline, query_semantic_highlights, query_signature_help, query_signatures,
+    query_unused, Builtin, BuiltinCall, Completion, CompletionKind, Completions, Fold, FoldKind,
+    Hover, Opcode, OutlineItem, OutlineKind, Parameter, SemanticKind, Signature, SignatureHelp,
+    Symbol, SymbolKind, SymbolTable, BUILTIN_CATALOG, OPCODE_CATALOG,
+};
+pub use ast::{parse_ast, Spec, Trivia, AST};
+pub use convert::{convert, convert_range, ConvertError};
 pub use cst::{parse_cst, CST};
 pub use field::FieldResult;
+pub use format::{format, normalize_width, Case, FormatError, FormatOptions};
 pub use free::{Op, TokenKind};
+pub use lint::{LintConfig, LintContext, Linter, Rule, Severity};
+pub use meta::diagnostic::{Diagnostic, DiagnosticLevel};
 pub use meta::Span;
@@ -0,0 +1,178 @@
+use super::rules::builtin_rules;
+use super::suppress::Suppressions;
+use crate::analysis::SymbolTable;
+use crate::cst::CST;
+use crate::meta::diagnostic::{Diagnostic, DiagnosticLevel};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Everything a rule gets to look at. Built once per lint run and shared.
+pub struct LintContext<'a> {
+    pub cst: &'a CST,
+    pub symbols: &'a SymbolTable,
+    // uppercased file name -> record format names, see `query_unused`
+    pub record_formats: &'a HashMap<String, Vec<String>>,
+}
+
+pub trait Rule {
+    // stable id used in config files and suppression comments, ex: "no-goto"
+    fn id(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn default_level(&self) -> DiagnosticLevel;
+    // the engine overwrites the level of returned diagnostics from config
+    fn check(&self, ctx: &LintContext) -> Vec<Diagnostic>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+/* Per project rule settings, usually read from json:
+ *
+ * { "rules": { "no-goto": "error", "no-numbered-indicators": "off" } }
+ *
+ * Keys can be a rule id or a more specific diagnostic code, the code wins.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LintConfig {
+    #[serde(default)]
+    pub rules: HashMap<String, Severity>,
+}
+
+impl LintConfig {
+    fn level(&self, rule: &dyn Rule, code: &str) -> Option<DiagnosticLevel> {
+        let severity = self.rules.get(code).or_else(|| self.rules.get(rule.id()));
+        match severity {
+            Some(Severity::Off) => None,
+            Some(Severity::Info) => Some(DiagnosticLevel::Info),
+            Some(Severity::Warning) => Some(DiagnosticLevel::Warning),
+            Some(Severity::Error) => Some(DiagnosticLevel::Error),
+            None => Some(rule.default_level()),
+        }
+    }
+}
+
+pub struct Linter {
+    pub config: LintConfig,
+    pub rules: Vec<Box<dyn Rule>>,
+}
+
+impl Linter {
+    pub fn new(config: LintConfig) -> Self {
+        Self {
+            config,
+            rules: builtin_rules(),
+        }
+    }
+
+    pub fn register(&mut self, rule: Box<dyn Rule>) {
+        self.rules.push(rule);
+    }
+
+    pub fn lint(
+        &self,
+        cst: &CST,
+        record_formats: &HashMap<String, Vec<String>>,
+    ) -> Vec<Diagnostic> {
+        let symbols = SymbolTable::from(cst);
+        let ctx = LintContext {
+            cst,
+            symbols: &symbols,
+            record_formats,
+        };
+        let suppressions = Suppressions::from(cst);
+        let mut out = vec![];
+        for rule in self.rules.iter() {
+            for mut diag in rule.check(&ctx) {
+                let Some(level) = self.config.level(rule.as_ref(), &diag.code) else {
+                    continue;
+                };
+                let row = diag.span.start.row;
+                if suppressions.is_suppressed(row, rule.id(), &diag.code) {
+                    continue;
+                }
+                diag.level = level;
+                out.push(diag);
+            }
+        }
+        out.sort_by_key(|d| (d.span.start.row, d.span.start.col));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::parse_cst;
+
+    fn fixture() -> String {
+        r#"
+     D Count           S              5  0
+     C                   MOVE      *IN50         Flag              1
+     C                   GOTO      Done
+      * idk-disable-next-line no-goto
+     C                   GOTO      Done
+     C                   GOTO      Done                                     idk-disable-line
+     C     Done          TAG
+       Count = 1;                                                                                   "#
+            [1..]
+            .to_string()
+    }
+
+    fn codes(diags: &[Diagnostic]) -> Vec<(usize, String)> {
+        diags
+            .iter()
+            .map(|d| (d.span.start.row, d.code.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_suppressions() {
+        let cst = parse_cst(&fixture()).unwrap();
+        let linter = Linter::new(LintConfig::default());
+        let observed = codes(&linter.lint(&cst, &HashMap::new()));
+        let expected = vec![
+            (1, "no-numbered-indicators".to_string()),
+            (2, "no-goto".to_string()),
+        ];
+        assert_eq!(observed, expected);
+    }
+
+    #[test]
+    fn test_suppression_in_string() {
+        // the `//` is inside a literal, it doesn't start a comment
+        let input = r#"
+       Msg = 'a//idk-disable no-numbered-indicators ' + Id;
+       *In50 = *On;                                                                                 "#
+            [1..]
+            .to_string();
+        let cst = parse_cst(&input).unwrap();
+        let linter = Linter::new(LintConfig::default());
+        let observed = codes(&linter.lint(&cst, &HashMap::new()));
+        let expected = vec![(1, "no-numbered-indicators".to_string())];
+        assert_eq!(observed, expected);
+    }
+
+    #[test]
+    fn test_config_levels() {
+        let cst = parse_cst(&fixture()).unwrap();
+        let mut config = LintConfig::default();
+        config
+            .rules
+            .insert("no-numbered-indicators".to_string(), Severity::Off);
+        config.rules.insert("no-goto".to_string(), Severity::Error);
+        let linter = Linter::new(config);
+        let observed = linter
+            .lint(&cst, &HashMap::new())
+            .into_iter()
+            .map(|d| (d.code, d.level))
+            .collect::<Vec<_>>();
+        let expected = vec![("no-goto".to_string(), DiagnosticLevel::Error)];
+        assert_eq!(observed, expected);
+    }
+}
@@ -0,0 +1,6 @@
+mod engine;
+mod rules;
+mod suppress;
+
+pub use engine::{LintConfig, LintContext, Linter, Rule, Severity};
+pub(crate) use rules::{field_lengths, operand_length, NoNumberedIndicators};
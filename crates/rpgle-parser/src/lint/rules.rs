@@ -0,0 +1,494 @@
+use super::engine::{LintContext, Rule};
+use crate::analysis::{lookup_builtin, query_builtin_calls, query_unused};
+use crate::cst::{CSrcline, Srcline};
+use crate::field::{FieldResult, ResultLength, POS};
+use crate::format::strip_comment;
+use crate::meta::diagnostic::{Diagnostic, DiagnosticLevel};
+use crate::meta::{Meta, Position, Span};
+use std::collections::HashMap;
+
+pub fn builtin_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(NoGoto),
+        Box::new(NoNumberedIndicators),
+        Box::new(PreferExtpgm),
+        Box::new(MoveTruncation),
+        Box::new(Unused),
+        Box::new(UnknownBuiltin),
+        Box::new(BuiltinArity),
+    ]
+}
+
+fn diagnostic(rule: &dyn Rule, span: Span, msg: String) -> Diagnostic {
+    Diagnostic {
+        span,
+        level: rule.default_level(),
+        code: rule.id().to_string(),
+        msg,
+    }
+}
+
+// narrow a fixed width field down to its non blank text
+fn trimmed_span(meta: &Meta) -> Span {
+    let chars = meta.text.chars().collect::<Vec<char>>();
+    let first = chars.iter().position(|c| *c != ' ').unwrap_or(0);
+    let last = chars
+        .iter()
+        .rposition(|c| *c != ' ')
+        .map(|x| x + 1)
+        .unwrap_or(chars.len());
+    let row = meta.span.start.row;
+    let col = meta.span.start.col;
+    Span {
+        start: Position::from((row, col + first)),
+        end: Position::from((row, col + last)),
+    }
+}
+
+// base opcode without extenders, ex: MOVEL(P) -> MOVEL
+fn opcode(value: &str) -> String {
+    value
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_uppercase()
+}
+
+// iterate (opcode, line) for every traditional calc spec
+fn traditional_ops<'a>(
+    lines: &'a [Srcline],
+) -> impl Iterator<Item = (String, &'a Meta, &'a CSrcline)> + 'a {
+    lines.iter().filter_map(|line| match line {
+        Srcline::C(
+            cline @ CSrcline::Traditional {
+                operation: FieldResult::Ok(op),
+                ..
+            },
+        ) => Some((opcode(&op.value), &op.meta, cline)),
+        _ => None,
+    })
+}
+
+// GOTO ----------------------------------------------------------------------------
+pub struct NoGoto;
+
+impl Rule for NoGoto {
+    fn id(&self) -> &'static str {
+        "no-goto"
+    }
+
+    fn description(&self) -> &'static str {
+        "GOTO and CABxx jump to TAG labels, use structured operations instead"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn check(&self, ctx: &LintContext) -> Vec<Diagnostic> {
+        traditional_ops(&ctx.cst.lines)
+            .filter(|(op, _, _)| op == "GOTO" || op.starts_with("CAB"))
+            .map(|(op, meta, _)| {
+                let msg = format!("{} jumps to a TAG, use structured operations instead", op);
+                diagnostic(self, trimmed_span(meta), msg)
+            })
+            .collect()
+    }
+}
+
+// *INxx ---------------------------------------------------------------------------
+pub struct NoNumberedIndicators;
+
+fn has_digit_pair(value: &str) -> bool {
+    let chars = value.chars().collect::<Vec<char>>();
+    chars
+        .windows(2)
+        .any(|w| w[0].is_ascii_digit() && w[1].is_ascii_digit())
+}
+
+// spans of *INxx and *IN(xx) in a field, skipping string literals
+fn numbered_indicators(meta: &Meta) -> Vec<Span> {
+    let chars = meta.text.chars().collect::<Vec<char>>();
+    let upper = meta.text.to_uppercase().chars().collect::<Vec<char>>();
+    let mut out = vec![];
+    let mut quoted = false;
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '\'' {
+            quoted = !quoted;
+        }
+        if !quoted && upper[i..].starts_with(&['*', 'I', 'N']) {
+            let rest = &upper[i + 3..];
+            let len = if rest.len() > 1 && rest[0].is_ascii_digit() && rest[1].is_ascii_digit() {
+                Some(5)
+            } else if rest.first() == Some(&'(') {
+                rest.iter().position(|c| *c == ')').map(|x| x + 4)
+            } else {
+                None
+            };
+            if let Some(len) = len {
+                let row = meta.span.start.row;
+                let col = meta.span.start.col;
+                out.push(Span {
+                    start: Position::from((row, col + i)),
+                    end: Position::from((row, col + i + len)),
+                });
+                i += len;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+impl Rule for NoNumberedIndicators {
+    fn id(&self) -> &'static str {
+        "no-numbered-indicators"
+    }
+
+    fn description(&self) -> &'static str {
+        "Numbered indicators hide intent, use named indicators or %FOUND/%EOF style BIFs"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn check(&self, ctx: &LintContext) -> Vec<Diagnostic> {
+        let mut spans = vec![];
+        for line in ctx.cst.lines.iter() {
+            match line {
+                Srcline::C(CSrcline::Traditional {
+                    indicators,
+                    factor1,
+                    factor2,
+                    result,
+                    resulting_indicators,
+                    ..
+                }) => {
+                    for ind in [indicators, resulting_indicators] {
+                        if let FieldResult::Ok(fld) = ind {
+                            if has_digit_pair(&fld.value) {
+                                spans.push(trimmed_span(&fld.meta));
+                            }
+                        }
+                    }
+                    if let FieldResult::Ok(fld) = factor1 {
+                        spans.append(&mut numbered_indicators(&fld.meta));
+                    }
+                    if let FieldResult::Ok(fld) = factor2 {
+                        spans.append(&mut numbered_indicators(&fld.meta));
+                    }
+                    if let FieldResult::Ok(fld) = result {
+                        spans.append(&mut numbered_indicators(&fld.meta));
+                    }
+                }
+                Srcline::C(CSrcline::ExtF2 {
+                    indicators,
+                    factor2,
+                    ..
+                }) => {
+                    if let FieldResult::Ok(fld) = indicators {
+                        if has_digit_pair(&fld.value) {
+                            spans.push(trimmed_span(&fld.meta));
+                        }
+                    }
+                    if let FieldResult::Ok(fld) = factor2 {
+                        spans.append(&mut numbered_indicators(&fld.meta));
+                    }
+                }
+                Srcline::C(CSrcline::Free {
+                    code: FieldResult::Ok(fld),
+                    ..
+                }) => {
+                    let mut found = numbered_indicators(&fld.meta);
+                    // ignore anything inside a trailing comment
+                    let statement = strip_comment(&fld.meta.text);
+                    let limit = fld.meta.span.start.col + statement.chars().count();
+                    found.retain(|s| s.start.col < limit);
+                    spans.append(&mut found);
+                }
+                _ => {}
+            }
+        }
+        spans
+            .into_iter()
+            .map(|span| {
+                let msg = "Numbered indicator, use a named indicator instead".to_string();
+                diagnostic(self, span, msg)
+            })
+            .collect()
+    }
+}
+
+// CALL ----------------------------------------------------------------------------
+pub struct PreferExtpgm;
+
+impl Rule for PreferExtpgm {
+    fn id(&self) -> &'static str {
+        "prefer-extpgm"
+    }
+
+    fn description(&self) -> &'static str {
+        "Dynamic CALL/PARM skips parameter checking, prototype the program with EXTPGM"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn check(&self, ctx: &LintContext) -> Vec<Diagnostic> {
+        traditional_ops(&ctx.cst.lines)
+            .filter(|(op, _, _)| op == "CALL")
+            .map(|(_, meta, _)| {
+                let msg = "CALL is unchecked, call an EXTPGM prototype with CALLP".to_string();
+                diagnostic(self, trimmed_span(meta), msg)
+            })
+            .collect()
+    }
+}
+
+// MOVE ----------------------------------------------------------------------------
+pub struct MoveTruncation;
+
+// declared lengths from D-specs and calc specs that define their result field
+pub(crate) fn field_lengths(lines: &[Srcline]) -> HashMap<String, u32> {
+    let mut out = HashMap::new();
+    for line in lines.iter() {
+        match line {
+            Srcline::D {
+                name: FieldResult::Ok(name),
+                from_position: FieldResult::Ok(from),
+                to_length: FieldResult::Ok(to),
+                ..
+            } => {
+                let len = match (from.value, to.value) {
+                    (POS::Empty, POS::Value(len)) => Some(len),
+                    (POS::Value(from), POS::Value(to)) if to >= from => Some(to - from + 1),
+                    _ => None,
+                };
+                if let (Some(len), false) = (len, name.value.is_empty()) {
+                    out.insert(name.value.to_uppercase(), len);
+                }
+            }
+            Srcline::C(CSrcline::Traditional {
+                result: FieldResult::Ok(result),
+                result_length: FieldResult::Ok(length),
+                ..
+            }) => {
+                if let ResultLength::Value(len) = length.value {
+                    out.entry(result.value.to_uppercase()).or_insert(len);
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+pub(crate) fn operand_length(operand: &str, lengths: &HashMap<String, u32>) -> Option<u32> {
+    let operand = operand.trim();
+    if operand.len() > 1 && operand.starts_with('\'') && operand.ends_with('\'') {
+        let inner = operand[1..operand.len() - 1].replace("''", "'");
+        return Some(inner.chars().count() as u32);
+    }
+    if !operand.is_empty() && operand.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Some(operand.chars().filter(|c| c.is_ascii_digit()).count() as u32);
+    }
+    lengths.get(&operand.to_uppercase()).copied()
+}
+
+impl Rule for MoveTruncation {
+    fn id(&self) -> &'static str {
+        "move-truncation"
+    }
+
+    fn description(&self) -> &'static str {
+        "MOVE/MOVEL into a shorter result field silently drops data"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn check(&self, ctx: &LintContext) -> Vec<Diagnostic> {
+        let lengths = field_lengths(&ctx.cst.lines);
+        let mut out = vec![];
+        for (op, _, cline) in traditional_ops(&ctx.cst.lines) {
+            if op != "MOVE" && op != "MOVEL" {
+                continue;
+            }
+            let CSrcline::Traditional {
+                factor2: FieldResult::Ok(factor2),
+                result: FieldResult::Ok(result),
+                result_length,
+                ..
+            } = cline
+            else {
+                continue;
+            };
+            let source = factor2.value.iter().collect::<String>();
+            let target = match result_length {
+                FieldResult::Ok(fld) => match fld.value {
+                    ResultLength::Value(len) => Some(len),
+                    ResultLength::Empty => lengths.get(&result.value.to_uppercase()).copied(),
+                },
+                FieldResult::Idk(_) => None,
+            };
+            if let (Some(from), Some(to)) = (operand_length(&source, &lengths), target) {
+                if from > to {
+                    let lost = match op.as_str() {
+                        "MOVE" => "leftmost",
+                        _ => "rightmost",
+                    };
+                    let msg = format!(
+                        "{} of {} long {} into {} long {} drops the {} {}",
+                        op,
+                        from,
+                        source.trim(),
+                        to,
+                        result.value,
+                        lost,
+                        from - to
+                    );
+                    out.push(diagnostic(self, trimmed_span(&result.meta), msg));
+                }
+            }
+        }
+        out
+    }
+}
+
+// unused --------------------------------------------------------------------------
+// wraps `query_unused`, which reports under more specific codes like unused-field
+pub struct Unused;
+
+impl Rule for Unused {
+    fn id(&self) -> &'static str {
+        "unused"
+    }
+
+    fn description(&self) -> &'static str {
+        "Definitions, subroutines and tags nothing refers to"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Warning
+    }
+
+    fn check(&self, ctx: &LintContext) -> Vec<Diagnostic> {
+        query_unused(ctx.cst, ctx.record_formats)
+    }
+}
+
+// %names ---------------------------------------------------------------------------
+pub struct UnknownBuiltin;
+
+impl Rule for UnknownBuiltin {
+    fn id(&self) -> &'static str {
+        "unknown-builtin"
+    }
+
+    fn description(&self) -> &'static str {
+        "A %name that isn't an ILE RPG built-in function"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Error
+    }
+
+    fn check(&self, ctx: &LintContext) -> Vec<Diagnostic> {
+        query_builtin_calls(ctx.cst)
+            .into_iter()
+            .filter(|call| lookup_builtin(&call.name).is_none())
+            .map(|call| {
+                let msg = format!("{} is not a built-in function", call.name);
+                diagnostic(self, call.span, msg)
+            })
+            .collect()
+    }
+}
+
+pub struct BuiltinArity;
+
+impl Rule for BuiltinArity {
+    fn id(&self) -> &'static str {
+        "builtin-arity"
+    }
+
+    fn description(&self) -> &'static str {
+        "A built-in function called with the wrong number of arguments"
+    }
+
+    fn default_level(&self) -> DiagnosticLevel {
+        DiagnosticLevel::Error
+    }
+
+    fn check(&self, ctx: &LintContext) -> Vec<Diagnostic> {
+        let mut out = vec![];
+        for call in query_builtin_calls(ctx.cst) {
+            let Some(bif) = lookup_builtin(&call.name) else {
+                continue;
+            };
+            if !bif.accepts(call.args) {
+                let msg = format!(
+                    "{} takes {}, found {}",
+                    bif.signature(),
+                    bif.arity(),
+                    call.args
+                );
+                out.push(diagnostic(self, call.span, msg));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::SymbolTable;
+    use crate::cst::parse_cst;
+
+    #[test]
+    fn test_rules_snapshot() {
+        let input = &r#"
+     D Short           S              4
+     D Long            S             10
+     C     *IN03         IFEQ      *ON
+     C                   MOVE      Long          Short
+     C                   MOVEL     'ABCDEF'      Short
+     C                   MOVE      'AB'          Short
+     C                   CALL      'PGM1'                               50
+     C                   ENDIF
+     C                   CABEQ     *ON           Done
+     C     Done          TAG
+       *In(12) = *Off; // *IN99 is fine in a comment                                               
+       Short = %subst(Long) + %lenght(Long) + %trim(Long);                                          "#
+            [1..];
+        let cst = parse_cst(input).unwrap();
+        let symbols = SymbolTable::from(&cst);
+        let formats = HashMap::new();
+        let ctx = LintContext {
+            cst: &cst,
+            symbols: &symbols,
+            record_formats: &formats,
+        };
+        let rules: Vec<Box<dyn Rule>> = vec![
+            Box::new(NoGoto),
+            Box::new(NoNumberedIndicators),
+            Box::new(PreferExtpgm),
+            Box::new(MoveTruncation),
+            Box::new(UnknownBuiltin),
+            Box::new(BuiltinArity),
+        ];
+        let observed = rules
+            .iter()
+            .flat_map(|r| r.check(&ctx))
+            .map(|d| d.to_string())
+            .collect::<Vec<String>>();
+        insta::assert_yaml_snapshot!(observed);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::cst::{CSrcline, Srcline, CST};
+use crate::field::{FieldBehavior, FieldResult};
+use crate::format::strip_comment;
+
+/* Inline suppression comments. Any comment the CST knows about can carry one:
+ * `*` comment lines, `//` comments in free code and the comment columns of
+ * traditional calc specs.
+ *
+ *   idk-disable-next-line [ids]   the following line
+ *   idk-disable-line [ids]        the line the comment is on
+ *   idk-disable [ids]             every line until a matching idk-enable
+ *   idk-enable [ids]
+ *
+ * Ids are rule ids or diagnostic codes separated by spaces or commas. No ids
+ * means every rule.
+ */
+#[derive(Debug, Clone, PartialEq)]
+enum Directive {
+    NextLine,
+    Line,
+    Disable,
+    Enable,
+}
+
+#[derive(Debug, Clone)]
+struct Suppression {
+    start: usize,
+    // inclusive, None means to the end of the source
+    end: Option<usize>,
+    ids: Vec<String>,
+}
+
+impl Suppression {
+    fn covers(&self, row: usize, rule: &str, code: &str) -> bool {
+        let in_range = row >= self.start && self.end.is_none_or(|end| row <= end);
+        let matches = self.ids.is_empty() || self.ids.iter().any(|id| id == rule || id == code);
+        in_range && matches
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Suppressions {
+    items: Vec<Suppression>,
+}
+
+impl Suppressions {
+    pub fn is_suppressed(&self, row: usize, rule: &str, code: &str) -> bool {
+        self.items.iter().any(|s| s.covers(row, rule, code))
+    }
+}
+
+fn parse_directive(comment: &str) -> Option<(Directive, Vec<String>)> {
+    // longest prefix first, they share a stem
+    let directives = [
+        ("idk-disable-next-line", Directive::NextLine),
+        ("idk-disable-line", Directive::Line),
+        ("idk-disable", Directive::Disable),
+        ("idk-enable", Directive::Enable),
+    ];
+    let lower = comment.to_lowercase();
+    for (prefix, directive) in directives {
+        if let Some(idx) = lower.find(prefix) {
+            let ids = lower[idx + prefix.len()..]
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|x| !x.is_empty())
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>();
+            return Some((directive, ids));
+        }
+    }
+    None
+}
+
+fn comment_text(line: &Srcline) -> Option<String> {
+    match line {
+        Srcline::Comment { comment, .. } => Some(comment.to_string()),
+        Srcline::C(CSrcline::Traditional { comments, .. }) => Some(comments.to_string()),
+        Srcline::C(CSrcline::Free {
+            code: FieldResult::Ok(code),
+            ..
+        }) => {
+            // a `//` inside a string literal doesn't start a comment
+            let statement = strip_comment(&code.meta.text);
+            let comment = &code.meta.text[statement.len()..];
+            (!comment.is_empty()).then(|| comment.to_string())
+        }
+        _ => None,
+    }
+}
+
+fn row_of(line: &Srcline) -> Option<usize> {
+    match line {
+        Srcline::Comment { sequence, .. } => Some(sequence.span().start.row),
+        Srcline::C(CSrcline::Traditional { form_type, .. }) => Some(form_type.span().start.row),
+        Srcline::C(CSrcline::Free { nothing, .. }) => Some(nothing.span().start.row),
+        _ => None,
+    }
+}
+
+impl From<&CST> for Suppressions {
+    fn from(cst: &CST) -> Self {
+        let mut items: Vec<Suppression> = vec![];
+        for line in cst.lines.iter() {
+            let (Some(text), Some(row)) = (comment_text(line), row_of(line)) else {
+                continue;
+            };
+            let Some((directive, ids)) = parse_directive(&text) else {
+                continue;
+            };
+            match directive {
+                Directive::NextLine => items.push(Suppression {
+                    start: row + 1,
+                    end: Some(row + 1),
+                    ids,
+                }),
+                Directive::Line => items.push(Suppression {
+                    start: row,
+                    end: Some(row),
+                    ids,
+                }),
+                Directive::Disable => items.push(Suppression {
+                    start: row,
+                    end: None,
+                    ids,
+                }),
+                Directive::Enable => {
+                    for open in items.iter_mut().filter(|s| s.end.is_none()) {
+                        if ids.is_empty() || open.ids == ids {
+                            open.end = Some(row);
+                        }
+                    }
+                }
+            }
+        }
+        Self { items }
+    }
+}
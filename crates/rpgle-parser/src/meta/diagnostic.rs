@@ -1,15 +1,45 @@
+use super::span::Span;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum DiagnosticLevel {
     Info,
     Warning,
     Error,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl fmt::Display for DiagnosticLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Diagnostic {
-    pub span: String,
+    pub span: Span,
     pub level: DiagnosticLevel,
+    // stable identifier for the check that produced this, ex: "unused-field"
+    pub code: String,
     pub msg: String,
 }
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // 1-based row:col so it lines up with editors and compiler listings
+        let s = format!(
+            "{}:{}: {}[{}] {}",
+            self.span.start.row + 1,
+            self.span.start.col + 1,
+            self.level,
+            self.code,
+            self.msg,
+        );
+        write!(f, "{}", s)
+    }
+}
@@ -1,27 +1,12 @@
-// based on: https://github.com/llogiq/partition/blob/master/src/lib.rs
-// except I don't want mutable outputs
+// Stable, so both sides keep their original order. Specs are built from the
+// kept lines and have to come out in source order.
 #[inline]
 pub fn partition_index<T, P>(data: &mut [T], predicate: P) -> usize
 where
     P: Fn(&T) -> bool,
 {
-    let len = data.len();
-    if len == 0 {
-        return 0;
-    }
-    let (mut l, mut r) = (0, len - 1);
-    loop {
-        while l < len && predicate(&data[l]) {
-            l += 1;
-        }
-        while r > 0 && !predicate(&data[r]) {
-            r -= 1;
-        }
-        if l >= r {
-            return l;
-        }
-        data.swap(l, r);
-    }
+    data.sort_by_key(|x| !predicate(x));
+    data.iter().take_while(|x| predicate(x)).count()
 }
 
 /* Slice partitioning. Split a slice into (keep, ignore) slices
@@ -36,3 +21,16 @@ where
     let idx = partition_index(data, predicate);
     return data.split_at(idx);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_is_stable() {
+        let mut data = [1, 2, 3, 4, 5, 6, 7];
+        let (keep, ignore) = partition(&mut data, |x| x % 2 == 0);
+        assert_eq!(keep, &[2, 4, 6]);
+        assert_eq!(ignore, &[1, 3, 5, 7]);
+    }
+}
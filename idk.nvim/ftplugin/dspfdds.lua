@@ -0,0 +1,6 @@
+local idk = require("idk")
+vim.api.nvim_create_user_command("DspfddsHighlight", idk.highlight_dspfdds, {})
+vim.api.nvim_create_user_command("DspfddsOutline", idk.outline, {})
+vim.api.nvim_create_user_command("DspfddsOutlineList", idk.outline_loclist, {})
+idk.highlight_dspfdds()
+idk.setup_folding()
@@ -0,0 +1,6 @@
+local idk = require("idk")
+vim.api.nvim_create_user_command("LfddsHighlight", idk.highlight_lfdds, {})
+vim.api.nvim_create_user_command("LfddsOutline", idk.outline, {})
+vim.api.nvim_create_user_command("LfddsOutlineList", idk.outline_loclist, {})
+idk.highlight_lfdds()
+idk.setup_folding()
@@ -1,6 +1,10 @@
 local idk = require("idk")
 vim.api.nvim_create_user_command("PfddsHighlight", idk.highlight_pfdds, {})
+vim.api.nvim_create_user_command("PfddsOutline", idk.outline, {})
+vim.api.nvim_create_user_command("PfddsOutlineList", idk.outline_loclist, {})
+vim.api.nvim_create_user_command("PfddsHover", idk.hover, {})
 idk.highlight_pfdds()
+idk.setup_folding()
 
 -- searching
 vim.cmd([[set ignorecase]])
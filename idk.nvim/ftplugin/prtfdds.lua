@@ -0,0 +1,6 @@
+local idk = require("idk")
+vim.api.nvim_create_user_command("PrtfddsHighlight", idk.highlight_prtfdds, {})
+vim.api.nvim_create_user_command("PrtfddsOutline", idk.outline, {})
+vim.api.nvim_create_user_command("PrtfddsOutlineList", idk.outline_loclist, {})
+idk.highlight_prtfdds()
+idk.setup_folding()
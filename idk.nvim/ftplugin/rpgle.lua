@@ -1,10 +1,23 @@
 local idk = require("idk")
 idk.setup_rpgle()
 vim.api.nvim_create_user_command("RpgleRunTools", idk.run_rpgle_tools, {})
+vim.api.nvim_create_user_command("RpgleDiagnostics", idk.diagnose_rpgle, {})
+vim.api.nvim_create_user_command("RpgleOutline", idk.outline, {})
+vim.api.nvim_create_user_command("RpgleOutlineList", idk.outline_loclist, {})
+vim.api.nvim_create_user_command("RpgleSignatureHelp", idk.signature_help, {})
+vim.keymap.set("i", "<C-s>", idk.signature_help, { buffer = true })
+vim.api.nvim_create_user_command("RpgleHover", idk.hover, {})
+vim.keymap.set("n", "K", idk.hover, { buffer = true })
+vim.api.nvim_create_user_command("RpgleCodeActions", idk.code_actions, {})
+vim.keymap.set("n", "gra", idk.code_actions, { buffer = true })
+vim.api.nvim_create_user_command("RpgleToFree", idk.convert_rpgle, { range = "%" })
 -- vim.api.nvim_create_user_command("RpgleHighlight", idk.highlight_rpgle, {})
 -- vim.api.nvim_create_user_command("RpgleMarkSpecs", idk.mark_rpgle, {})
 idk.highlight_rpgle()
 idk.mark_rpgle()
+idk.setup_folding()
+vim.bo.omnifunc = "v:lua.require'idk'.omnifunc"
+idk.diagnose_rpgle()
 
 -- searching
 vim.cmd([[set ignorecase]])
@@ -0,0 +1,6 @@
+local idk = require("idk")
+vim.api.nvim_create_user_command("SqlddlHighlight", idk.highlight_sqlddl, {})
+vim.api.nvim_create_user_command("SqlddlOutline", idk.outline, {})
+vim.api.nvim_create_user_command("SqlddlOutlineList", idk.outline_loclist, {})
+idk.highlight_sqlddl()
+idk.setup_folding()
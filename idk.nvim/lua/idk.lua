@@ -37,6 +37,9 @@ M.setup_rpgle = function(args)
   rpgle_formatter.setup()
   vim.bo[0].tagfunc = "v:lua.require'idk'.tfunc"
   vim.opt.iskeyword:append("$")
+  -- the semantic groups fall back to their parent, ex: @variable.local to
+  -- @variable, but a name that resolves to nothing should stand out
+  vim.api.nvim_set_hl(0, "@variable.undefined", { link = "DiagnosticUnderlineError", default = true })
 end
 
 M.tfunc = tfunc
@@ -50,13 +53,153 @@ M.format_rpgle = function()
   rpgle_formatter.format_all()
 end
 
+M.convert_rpgle = function(opts)
+  local outcome = libidk.convert_rpgle(opts.line1 - 1, opts.line2)
+  if not outcome.ok then
+    vim.notify(outcome.msg, vim.log.levels.ERROR)
+  end
+end
+
+local function outline_text(item)
+  return string.rep("  ", item.depth) .. item.name .. " [" .. item.kind .. "]"
+end
+
+-- pick an outline entry of the current buffer and jump to it
+M.outline = function()
+  vim.ui.select(libidk.outline(), {
+    prompt = "Outline",
+    format_item = outline_text,
+  }, function(item)
+    if item then
+      vim.api.nvim_win_set_cursor(0, { item.lnum + 1, item.col })
+    end
+  end)
+end
+
+-- the outline of the current buffer in the location list, as a sidebar
+M.outline_loclist = function()
+  local bufnr = vim.api.nvim_get_current_buf()
+  local entries = {}
+  for _, item in ipairs(libidk.outline()) do
+    table.insert(entries, {
+      bufnr = bufnr,
+      lnum = item.lnum + 1,
+      col = item.col + 1,
+      end_lnum = item.end_lnum + 1,
+      text = outline_text(item),
+    })
+  end
+  vim.fn.setloclist(0, {}, " ", { title = "Outline", items = entries })
+  vim.cmd("lopen")
+end
+
+-- candidates of the last findstart call, vim removes the typed word before asking for them
+local completions = nil
+
+-- 'omnifunc' provider, ex: omnifunc=v:lua.require'idk'.omnifunc
+M.omnifunc = function(findstart, base)
+  if findstart == 1 then
+    local cursor = vim.api.nvim_win_get_cursor(0)
+    completions = libidk.complete_rpgle(cursor[1] - 1, cursor[2])
+    return completions.start_col
+  end
+  return completions and completions.items or {}
+end
+
+-- the prototype of the call under the cursor in a float, the active parameter in bold
+M.signature_help = function()
+  local cursor = vim.api.nvim_win_get_cursor(0)
+  local help = libidk.signature_help(cursor[1] - 1, cursor[2])
+  if not help then
+    return
+  end
+  local lines = { help.label, "" }
+  for i, parameter in ipairs(help.parameters) do
+    if i == help.active then
+      table.insert(lines, "- **" .. parameter .. "**")
+    else
+      table.insert(lines, "- " .. parameter)
+    end
+  end
+  vim.lsp.util.open_floating_preview(lines, "markdown", { focusable = false })
+end
+
+-- docs for the built-in function or opcode under the cursor in a float
+M.hover = function()
+  local cursor = vim.api.nvim_win_get_cursor(0)
+  local item = libidk.hover(cursor[1] - 1, cursor[2])
+  if not item then
+    return
+  end
+  local lines = vim.split(item.contents, "\n")
+  vim.lsp.util.open_floating_preview(lines, "markdown", { focus_id = "idk-hover" })
+end
+
+-- pick one of the code actions under the cursor with vim.ui.select and apply it
+M.code_actions = function()
+  local cursor = vim.api.nvim_win_get_cursor(0)
+  local actions = libidk.code_actions(cursor[1] - 1, cursor[2])
+  if #actions == 0 then
+    vim.notify("No code actions here", vim.log.levels.INFO)
+    return
+  end
+  local format_item = function(action)
+    return action.title
+  end
+  vim.ui.select(actions, { prompt = "Code actions", format_item = format_item }, function(action)
+    if not action then
+      return
+    end
+    -- last edit first so the positions of the others still hold
+    for i = #action.edits, 1, -1 do
+      local edit = action.edits[i]
+      local lines = edit.lines
+      if edit.start_col == 0 and edit.end_col == 0 and lines[#lines] == "" then
+        -- whole lines, also works for the last line of the buffer
+        table.remove(lines)
+        vim.api.nvim_buf_set_lines(0, edit.start_row, edit.end_row, false, lines)
+      else
+        vim.api.nvim_buf_set_text(0, edit.start_row, edit.start_col, edit.end_row, edit.end_col, lines)
+      end
+    end
+  end)
+end
+
+-- fold levels per buffer, rebuilt when the buffer changes
+local fold_cache = {}
+
+-- 'foldexpr' provider, ex: foldexpr=v:lua.require'idk'.foldexpr(v:lnum)
+M.foldexpr = function(lnum)
+  local bufnr = vim.api.nvim_get_current_buf()
+  local tick = vim.api.nvim_buf_get_changedtick(bufnr)
+  local cached = fold_cache[bufnr]
+  if not cached or cached.tick ~= tick then
+    cached = { tick = tick, levels = libidk.fold_levels() }
+    fold_cache[bufnr] = cached
+  end
+  return cached.levels[lnum] or "0"
+end
+
+-- fold by subroutines, procedures, declarations and blocks in the current window
+M.setup_folding = function()
+  vim.wo.foldmethod = "expr"
+  vim.wo.foldexpr = "v:lua.require'idk'.foldexpr(v:lnum)"
+  vim.wo.foldlevel = 99
+end
+
 M.mark_rpgle = function()
   rpgle_marker.set_marks()
 end
 
+M.diagnose_rpgle = function()
+  local ns = vim.api.nvim_create_namespace("RPGLEDiagnostics")
+  vim.diagnostic.set(ns, 0, libidk.diagnostics_rpgle())
+end
+
 M.run_rpgle_tools = function()
   rpgle_formatter.format_all()
   libidk.highlight_rpgle()
+  M.diagnose_rpgle()
 end
 
 M.json_dump_current_buffer = function(path)
@@ -72,4 +215,24 @@ M.highlight_pfdds = function()
   libidk.highlight_pfdds()
 end
 
+-- lfdds specific
+M.highlight_lfdds = function()
+  libidk.highlight_lfdds()
+end
+
+-- dspfdds specific
+M.highlight_dspfdds = function()
+  libidk.highlight_dspfdds()
+end
+
+-- prtfdds specific
+M.highlight_prtfdds = function()
+  libidk.highlight_prtfdds()
+end
+
+-- sqlddl specific
+M.highlight_sqlddl = function()
+  libidk.highlight_sqlddl()
+end
+
 return M
@@ -1,3 +1,5 @@
+local libidk = require("libidk")
+
 local Formatter = function(buf)
   local self = { buf = buf }
 
@@ -48,10 +50,16 @@ local Formatter = function(buf)
 
   function self.format_all()
     local pos = vim.api.nvim_win_get_cursor(0)
-    self.remove_nonprintable()
-    local lines_in_file = vim.api.nvim_buf_line_count(self.buf)
-    for line = 0, lines_in_file - 1 do
-      self.format_line(line)
+    -- the native formatter refuses anything it can't prove is safe,
+    -- fall back on padding and trimming so the buffer still parses
+    local outcome = libidk.format_rpgle()
+    if not outcome.ok then
+      vim.notify(outcome.msg or "Unable to format buffer", vim.log.levels.WARN)
+      self.remove_nonprintable()
+      local lines_in_file = vim.api.nvim_buf_line_count(self.buf)
+      for line = 0, lines_in_file - 1 do
+        self.format_line(line)
+      end
     end
     vim.api.nvim_win_set_cursor(0, pos);
   end